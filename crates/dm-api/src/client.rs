@@ -1,9 +1,12 @@
 use crate::{
-    raw::RawResponse, ContentCount, ContentQuery, Credentials, DmApiError, DownloadByteRange,
+    error::is_retriable_status,
+    governor::{is_throttling_status, retry_after},
+    raw::RawResponse,
+    ContentCount, ContentQuery, Credentials, DlsiteEndpoints, DmApiError, DownloadByteRange,
     DownloadFile, DownloadFileKind, DownloadPlan, DownloadResolution, DownloadStreamRequest,
-    DownloadUnavailableReason, PublicWork, Purchase, Result, SerialDownloadPage, SerialNumber,
-    SessionSnapshot, SessionStatus, SplitDownloadPage, SplitDownloadPart, Work, WorkId,
-    WorksResponse, DEFAULT_WORKS_BATCH_LIMIT,
+    DownloadUnavailableReason, Language, ProxyConfig, PublicWork, Purchase, RequestGovernor,
+    Result, SerialDownloadPage, SerialNumber, SessionSnapshot, SessionStatus, SplitDownloadPage,
+    SplitDownloadPart, Work, WorkId, WorksResponse, DEFAULT_WORKS_BATCH_LIMIT,
 };
 use bytes::Bytes;
 use cookie_store::CookieStore;
@@ -11,7 +14,7 @@ use futures_core::Stream;
 use reqwest::{
     header::{HeaderMap, HeaderValue, CONTENT_TYPE, LOCATION, RANGE},
     redirect::Policy,
-    Client, Response, StatusCode,
+    Client, RequestBuilder, Response, StatusCode,
 };
 use reqwest_cookie_store::CookieStoreMutex;
 use serde::de::DeserializeOwned;
@@ -20,27 +23,24 @@ use std::{collections::BTreeMap, io::BufWriter, sync::Arc};
 use tokio::sync::Mutex;
 use url::Url;
 
-const LOGIN_URL: &str = "https://login.dlsite.com/login";
-const LOGIN_SKIP_URL: &str = "https://www.dlsite.com/home/login/=/skip_register/1";
-const LOGIN_FINISH_URL: &str = "https://www.dlsite.com/home/login/finish";
-const CONTENT_COUNT_URL: &str = "https://play.dlsite.com/api/v3/content/count";
-const CONTENT_SALES_URL: &str = "https://play.dlsite.com/api/v3/content/sales";
-const CONTENT_WORKS_URL: &str = "https://play.dlsite.com/api/v3/content/works";
-const DOWNLOAD_URL: &str = "https://play.dlsite.com/api/v3/download";
-const PUBLIC_PRODUCT_URL: &str = "https://www.dlsite.com/home/api/=/product.json";
-const HOME_SERIAL_URL: &str = "https://www.dlsite.com/home/serial/=/product_id/";
 const MAX_DOWNLOAD_REDIRECTS: usize = 8;
 const DOWNLOAD_PAGE_BODY_LIMIT: usize = 512 * 1024;
 
 #[derive(Debug, Clone)]
 pub struct DlsiteClientConfig {
     pub user_agent: String,
+    pub endpoints: DlsiteEndpoints,
+    pub governor: RequestGovernor,
+    pub proxy: Option<ProxyConfig>,
 }
 
 impl Default for DlsiteClientConfig {
     fn default() -> Self {
         Self {
             user_agent: concat!("dlsite-manager/", env!("CARGO_PKG_VERSION")).to_owned(),
+            endpoints: DlsiteEndpoints::default(),
+            governor: RequestGovernor::default(),
+            proxy: None,
         }
     }
 }
@@ -49,44 +49,77 @@ impl Default for DlsiteClientConfig {
 pub struct DlsiteClient {
     http: Client,
     cookie_store: Arc<CookieStoreMutex>,
+    endpoints: Arc<DlsiteEndpoints>,
+    governor: RequestGovernor,
+    user_agent: Arc<str>,
+    proxy: Option<Arc<ProxyConfig>>,
     works_batch_limit: Arc<Mutex<Option<usize>>>,
 }
 
 impl DlsiteClient {
     pub fn new(config: DlsiteClientConfig) -> Result<Self> {
         let cookie_store = Arc::new(CookieStoreMutex::new(CookieStore::default()));
-        let http = Client::builder()
+        let mut builder = Client::builder()
             .cookie_provider(cookie_store.clone())
             .redirect(Policy::none())
-            .user_agent(config.user_agent)
-            .build()?;
+            .user_agent(config.user_agent.as_str());
+
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(proxy.to_reqwest()?);
+        }
 
         Ok(Self {
-            http,
+            http: builder.build()?,
             cookie_store,
+            endpoints: Arc::new(config.endpoints),
+            governor: config.governor,
+            user_agent: config.user_agent.into(),
+            proxy: config.proxy.map(Arc::new),
             works_batch_limit: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// Builds a fresh client with the same endpoints and governor but a
+    /// different proxy. The session is not carried over.
+    pub fn with_proxy(&self, proxy: Option<ProxyConfig>) -> Result<Self> {
+        Self::new(DlsiteClientConfig {
+            user_agent: self.user_agent.to_string(),
+            endpoints: (*self.endpoints).clone(),
+            governor: self.governor.clone(),
+            proxy,
+        })
+    }
+
+    pub fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_deref()
+    }
+
+    pub fn endpoints(&self) -> &DlsiteEndpoints {
+        &self.endpoints
+    }
+
+    pub fn governor(&self) -> &RequestGovernor {
+        &self.governor
+    }
+
     pub async fn login(&self, credentials: &Credentials) -> Result<SessionSnapshot> {
-        self.http
-            .get(LOGIN_URL)
-            .query(&[("user", "self")])
-            .send()
+        let login_url = self.endpoints.login_url()?;
+
+        self.send(self.http.get(login_url.clone()).query(&[("user", "self")]))
             .await?
             .error_for_status()?;
 
         let xsrf_token = self.xsrf_token()?;
 
         let auth_res = self
-            .http
-            .post(LOGIN_URL)
-            .form(&[
-                ("login_id", credentials.username.as_str()),
-                ("password", credentials.password.as_str()),
-                ("_token", xsrf_token.as_str()),
-            ])
-            .send()
+            .send_with_retries(
+                self.http.post(login_url.clone()).form(&[
+                    ("login_id", credentials.username.as_str()),
+                    ("password", credentials.password.as_str()),
+                    ("_token", xsrf_token.as_str()),
+                ]),
+                RetryScope::Unprocessed,
+            )
             .await?;
 
         if auth_res.status() != StatusCode::FOUND {
@@ -108,7 +141,7 @@ impl DlsiteClient {
             });
         }
 
-        let login_res = self.http.get(LOGIN_URL).send().await?;
+        let login_res = self.send(self.http.get(login_url)).await?;
         let login_res_status = login_res.status();
         let login_res_endpoint = login_res.url().clone();
         let login_res_body = response_text_snippet(login_res).await;
@@ -128,15 +161,15 @@ impl DlsiteClient {
             });
         }
 
-        let skip_location = self.redirect_location_from_get(LOGIN_SKIP_URL).await?;
+        let skip_location = self
+            .redirect_location_from_get(self.endpoints.login_skip_url()?)
+            .await?;
         let oauth_request_location = self.redirect_location_from_get(skip_location).await?;
 
         self.redirect_location_from_get(oauth_request_location)
             .await?;
 
-        self.http
-            .get(LOGIN_FINISH_URL)
-            .send()
+        self.send(self.http.get(self.endpoints.login_finish_url()?))
             .await?
             .error_for_status()?;
 
@@ -179,8 +212,21 @@ impl DlsiteClient {
         Ok(())
     }
 
+    /// Drops every cookie so the next request starts a fresh session.
+    pub fn clear_session(&self) -> Result<()> {
+        let mut guard = self
+            .cookie_store
+            .lock()
+            .map_err(|_| DmApiError::CookieStore("cookie store mutex is poisoned".to_owned()))?;
+
+        *guard = CookieStore::default();
+        Ok(())
+    }
+
     pub async fn validate_session(&self) -> Result<SessionStatus> {
-        let res = self.http.get(CONTENT_COUNT_URL).send().await?;
+        let res = self
+            .send(self.http.get(self.endpoints.content_count_url()?))
+            .await?;
 
         match res.status() {
             StatusCode::OK => {
@@ -204,28 +250,28 @@ impl DlsiteClient {
     }
 
     pub async fn content_count(&self, query: ContentQuery) -> Result<ContentCount> {
-        let endpoint = Url::parse(CONTENT_COUNT_URL)?;
+        let endpoint = self.endpoints.content_count_url()?;
         let mut request = self.http.get(endpoint.clone());
 
         if let Some(last) = query.last {
             request = request.query(&[("last", last)]);
         }
 
-        let count = parse_json_response(request.send().await?).await?;
+        let count = parse_json_response(self.send(request).await?).await?;
         self.cache_limits_from_count(&count).await;
 
         Ok(count)
     }
 
     pub async fn sales(&self, query: ContentQuery) -> Result<Vec<Purchase>> {
-        let endpoint = Url::parse(CONTENT_SALES_URL)?;
+        let endpoint = self.endpoints.content_sales_url()?;
         let mut request = self.http.get(endpoint.clone());
 
         if let Some(last) = query.last {
             request = request.query(&[("last", last)]);
         }
 
-        parse_json_response(request.send().await?).await
+        parse_json_response(self.send(request).await?).await
     }
 
     pub async fn works(&self, ids: &[WorkId]) -> Result<Vec<Work>> {
@@ -253,13 +299,13 @@ impl DlsiteClient {
     }
 
     pub async fn works_batch(&self, ids: &[WorkId]) -> Result<Vec<Work>> {
-        let endpoint = Url::parse(CONTENT_WORKS_URL)?;
+        let endpoint = self.endpoints.content_works_url()?;
         let ids = ids
             .iter()
             .map(|id| id.as_ref().to_owned())
             .collect::<Vec<_>>();
 
-        let res = self.http.post(endpoint).json(&ids).send().await?;
+        let res = self.send(self.http.post(endpoint).json(&ids)).await?;
         let status = res.status();
 
         if status == StatusCode::UNAUTHORIZED {
@@ -299,15 +345,41 @@ impl DlsiteClient {
     }
 
     pub async fn public_work(&self, id: &WorkId) -> Result<Option<PublicWork>> {
-        let endpoint = Url::parse(PUBLIC_PRODUCT_URL)?;
-        let products = parse_json_response::<Vec<PublicWork>>(
-            self.http
-                .get(endpoint)
-                .query(&[("workno", id.as_ref())])
-                .send()
-                .await?,
-        )
-        .await?;
+        self.public_work_in_locale(id, None).await
+    }
+
+    /// Looks up public product metadata as DLsite presents it in `language`.
+    ///
+    /// DLsite falls back to Japanese text when no translation exists.
+    pub async fn localized_public_works(
+        &self,
+        ids: &[WorkId],
+        language: &Language,
+    ) -> Result<Vec<PublicWork>> {
+        let mut works = Vec::new();
+
+        for id in ids {
+            if let Some(work) = self.public_work_in_locale(id, Some(language)).await? {
+                works.push(work);
+            }
+        }
+
+        Ok(works)
+    }
+
+    async fn public_work_in_locale(
+        &self,
+        id: &WorkId,
+        language: Option<&Language>,
+    ) -> Result<Option<PublicWork>> {
+        let endpoint = self.endpoints.public_product_url()?;
+        let mut request = self.http.get(endpoint).query(&[("workno", id.as_ref())]);
+
+        if let Some(language) = language {
+            request = request.query(&[("locale", language.code())]);
+        }
+
+        let products = parse_json_response::<Vec<PublicWork>>(self.send(request).await?).await?;
 
         Ok(products
             .into_iter()
@@ -331,12 +403,12 @@ impl DlsiteClient {
         ids: &[WorkId],
         body_limit: usize,
     ) -> Result<RawResponse> {
-        let endpoint = Url::parse(CONTENT_WORKS_URL)?;
+        let endpoint = self.endpoints.content_works_url()?;
         let ids = ids
             .iter()
             .map(|id| id.as_ref().to_owned())
             .collect::<Vec<_>>();
-        let res = self.http.post(endpoint).json(&ids).send().await?;
+        let res = self.send(self.http.post(endpoint).json(&ids)).await?;
 
         RawResponse::from_response_with_body_limit(res, body_limit).await
     }
@@ -347,7 +419,7 @@ impl DlsiteClient {
 
     pub async fn probe_download(&self, work_id: &WorkId) -> Result<DownloadProbe> {
         let initial = self.raw_download_probe(work_id).await?;
-        let resolution = download_resolution_from_raw_response(&initial);
+        let resolution = download_resolution_from_raw_response(&initial, &self.endpoints);
 
         Ok(DownloadProbe {
             work_id: work_id.clone(),
@@ -429,7 +501,7 @@ impl DlsiteClient {
                 builder = builder.header(RANGE, range.header_value());
             }
 
-            let res = builder.send().await?;
+            let res = self.send(builder).await?;
             let status = res.status();
 
             if status.is_redirection() {
@@ -460,7 +532,7 @@ impl DlsiteClient {
         &self,
         work_id: &WorkId,
     ) -> Result<Option<SerialDownloadPage>> {
-        let location = serial_page_url(work_id)?;
+        let location = self.endpoints.serial_page_url(work_id)?;
         let raw = self
             .raw_get_with_body_limit(location.clone(), DOWNLOAD_PAGE_BODY_LIMIT)
             .await?;
@@ -469,7 +541,7 @@ impl DlsiteClient {
             return Ok(None);
         }
 
-        parse_serial_download_page_from_raw(location, raw).map(Some)
+        parse_serial_download_page_from_raw(location, raw, &self.endpoints).map(Some)
     }
 
     pub async fn split_download_page(&self, location: Url) -> Result<SplitDownloadPage> {
@@ -477,7 +549,7 @@ impl DlsiteClient {
             .raw_get_with_body_limit(location.clone(), DOWNLOAD_PAGE_BODY_LIMIT)
             .await?;
         let body = raw.body_snippet.as_deref().unwrap_or_default();
-        let parts = parse_split_download_parts(&location, body);
+        let parts = parse_split_download_parts(&location, body, &self.endpoints);
 
         if parts.is_empty() {
             return Err(DmApiError::DownloadPageLinkNotFound {
@@ -497,7 +569,7 @@ impl DlsiteClient {
             .raw_get_with_body_limit(location.clone(), DOWNLOAD_PAGE_BODY_LIMIT)
             .await?;
 
-        parse_serial_download_page_from_raw(location, raw)
+        parse_serial_download_page_from_raw(location, raw, &self.endpoints)
     }
 
     pub async fn raw_download_probe(&self, work_id: &WorkId) -> Result<RawResponse> {
@@ -510,10 +582,11 @@ impl DlsiteClient {
         body_limit: usize,
     ) -> Result<RawResponse> {
         let res = self
-            .http
-            .get(DOWNLOAD_URL)
-            .query(&[("workno", work_id.as_ref())])
-            .send()
+            .send(
+                self.http
+                    .get(self.endpoints.download_url()?)
+                    .query(&[("workno", work_id.as_ref())]),
+            )
             .await?;
 
         RawResponse::from_response_with_body_limit(res, body_limit).await
@@ -528,7 +601,7 @@ impl DlsiteClient {
         url: Url,
         body_limit: usize,
     ) -> Result<RawResponse> {
-        let res = self.http.get(url).send().await?;
+        let res = self.send(self.http.get(url)).await?;
 
         RawResponse::from_response_with_body_limit(res, body_limit).await
     }
@@ -550,6 +623,64 @@ impl DlsiteClient {
         }
     }
 
+    /// Sends the request, repeating it on the failures [`DmApiError::is_retriable`] accepts.
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        self.send_with_retries(request, RetryScope::Retriable).await
+    }
+
+    async fn send_with_retries(
+        &self,
+        request: RequestBuilder,
+        scope: RetryScope,
+    ) -> Result<Response> {
+        let request = request.build()?;
+        let max_retries = self.governor.config().max_retries;
+        let mut attempt = 0;
+
+        loop {
+            let url = request.url().clone();
+            self.governor.acquire(&url).await;
+
+            let Some(attempt_request) = request.try_clone() else {
+                return Ok(self.http.execute(request).await?);
+            };
+
+            match self.http.execute(attempt_request).await {
+                Ok(res) if scope.retries_status(res.status()) => {
+                    let status = res.status();
+
+                    if !is_throttling_status(status) {
+                        if attempt >= max_retries {
+                            return Ok(res);
+                        }
+
+                        tokio::time::sleep(self.governor.backoff(attempt)).await;
+                    } else {
+                        let retry_after = retry_after(&res);
+
+                        if attempt >= max_retries {
+                            return Err(DmApiError::RateLimited {
+                                endpoint: res.url().clone(),
+                                status,
+                                retry_after,
+                            });
+                        }
+
+                        let delay = self.governor.retry_delay(attempt, retry_after);
+                        self.governor.block_host(&url, delay).await;
+                    }
+                }
+                Ok(res) => return Ok(res),
+                Err(err) if attempt < max_retries && scope.retries_error(&err) => {
+                    tokio::time::sleep(self.governor.backoff(attempt)).await;
+                }
+                Err(err) => return Err(err.into()),
+            }
+
+            attempt += 1;
+        }
+    }
+
     fn xsrf_token(&self) -> Result<String> {
         let guard = self
             .cookie_store
@@ -557,13 +688,13 @@ impl DlsiteClient {
             .map_err(|_| DmApiError::CookieStore("cookie store mutex is poisoned".to_owned()))?;
 
         guard
-            .get("login.dlsite.com", "/", "XSRF-TOKEN")
+            .get(self.endpoints.login_cookie_domain(), "/", "XSRF-TOKEN")
             .map(|cookie| cookie.value().to_owned())
             .ok_or(DmApiError::XsrfTokenNotFound)
     }
 
     async fn redirect_location_from_get(&self, url: impl reqwest::IntoUrl) -> Result<Url> {
-        let res = self.http.get(url).send().await?;
+        let res = self.send(self.http.get(url)).await?;
         let status = res.status();
 
         if !status.is_redirection() {
@@ -583,6 +714,7 @@ impl DlsiteClient {
 fn parse_serial_download_page_from_raw(
     location: Url,
     raw: RawResponse,
+    endpoints: &DlsiteEndpoints,
 ) -> Result<SerialDownloadPage> {
     if !(200..=299).contains(&raw.status) {
         return Err(DmApiError::UnexpectedStatus {
@@ -595,7 +727,7 @@ fn parse_serial_download_page_from_raw(
     let body = raw.body_snippet.as_deref().unwrap_or_default();
     let serial_numbers = parse_serial_numbers(body);
 
-    if let Some(stream_request) = parse_serial_download_link(&location, body) {
+    if let Some(stream_request) = parse_serial_download_link(&location, body, endpoints) {
         return Ok(SerialDownloadPage {
             page_url: raw.url,
             serial_numbers,
@@ -663,10 +795,13 @@ impl DownloadStream {
     }
 }
 
-fn download_resolution_from_raw_response(raw: &RawResponse) -> DownloadResolution {
+fn download_resolution_from_raw_response(
+    raw: &RawResponse,
+    endpoints: &DlsiteEndpoints,
+) -> DownloadResolution {
     match raw.status {
         300..=399 => match raw.location.clone() {
-            Some(location) => DownloadResolution::from_redirect_location(location),
+            Some(location) => DownloadResolution::from_redirect_location(location, endpoints),
             None => DownloadResolution::Unavailable {
                 reason: DownloadUnavailableReason::UnexpectedStatus {
                     status: raw.status,
@@ -689,8 +824,12 @@ fn download_resolution_from_raw_response(raw: &RawResponse) -> DownloadResolutio
     }
 }
 
-fn parse_split_download_parts(page_url: &Url, body: &str) -> Vec<SplitDownloadPart> {
-    let mut parts = extract_download_links(page_url, body)
+fn parse_split_download_parts(
+    page_url: &Url,
+    body: &str,
+    endpoints: &DlsiteEndpoints,
+) -> Vec<SplitDownloadPart> {
+    let mut parts = extract_download_links(page_url, body, endpoints)
         .into_iter()
         .filter_map(|url| {
             let number = download_part_number(&url)?;
@@ -712,12 +851,16 @@ fn parse_split_download_parts(page_url: &Url, body: &str) -> Vec<SplitDownloadPa
     parts
 }
 
-fn parse_serial_download_link(page_url: &Url, body: &str) -> Option<DownloadStreamRequest> {
-    extract_download_links(page_url, body)
+fn parse_serial_download_link(
+    page_url: &Url,
+    body: &str,
+    endpoints: &DlsiteEndpoints,
+) -> Option<DownloadStreamRequest> {
+    extract_download_links(page_url, body, endpoints)
         .into_iter()
         .find(|url| {
             matches!(
-                DownloadResolution::from_redirect_location(url.clone()),
+                DownloadResolution::from_redirect_location(url.clone(), endpoints),
                 DownloadResolution::Direct { .. }
             )
         })
@@ -810,16 +953,14 @@ fn decode_basic_html_entities(value: &str) -> String {
         .replace("&#039;", "'")
 }
 
-fn extract_download_links(page_url: &Url, body: &str) -> Vec<Url> {
+fn extract_download_links(page_url: &Url, body: &str, endpoints: &DlsiteEndpoints) -> Vec<Url> {
     let mut links = extract_quoted_values(body)
         .into_iter()
         .filter_map(|value| page_url.join(&value).ok())
         .filter(|url| {
-            let host = url.host_str().unwrap_or_default();
-            let path = url.path();
-            host == "www.dlsite.com"
-                && path.starts_with("/home/download")
-                && path.contains("product_id")
+            endpoints.download_page_path(url).is_some_and(|path| {
+                path.starts_with("/home/download") && path.contains("product_id")
+            })
         })
         .collect::<Vec<_>>();
 
@@ -862,10 +1003,6 @@ fn download_part_number(url: &Url) -> Option<u32> {
     None
 }
 
-fn serial_page_url(work_id: &WorkId) -> Result<Url> {
-    Url::parse(&format!("{HOME_SERIAL_URL}{work_id}.html")).map_err(Into::into)
-}
-
 async fn parse_json_response<T>(res: Response) -> Result<T>
 where
     T: DeserializeOwned,
@@ -962,6 +1099,35 @@ fn header_value_to_usize(value: &HeaderValue) -> Option<usize> {
     value.to_str().ok()?.parse().ok()
 }
 
+pub(crate) fn is_transient_request_error(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_body()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RetryScope {
+    /// Every failure [`DmApiError::is_retriable`] accepts.
+    Retriable,
+    /// Only failures the server can't have acted on: refused connections and 429 responses. A
+    /// repeated login form after a timeout could log in twice or count towards a lockout.
+    Unprocessed,
+}
+
+impl RetryScope {
+    fn retries_status(self, status: StatusCode) -> bool {
+        match self {
+            Self::Retriable => is_retriable_status(status),
+            Self::Unprocessed => status == StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    fn retries_error(self, err: &reqwest::Error) -> bool {
+        match self {
+            Self::Retriable => is_transient_request_error(err),
+            Self::Unprocessed => err.is_connect(),
+        }
+    }
+}
+
 pub(crate) fn redirect_location(res: &Response) -> Result<Url> {
     let endpoint = res.url().clone();
     let location = res
@@ -1014,6 +1180,24 @@ mod tests {
     use super::*;
     use reqwest::header::HeaderValue;
 
+    #[test]
+    fn retries_statuses_within_the_retry_scope() {
+        for status in [
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::REQUEST_TIMEOUT,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::GATEWAY_TIMEOUT,
+        ] {
+            assert!(RetryScope::Retriable.retries_status(status), "{status}");
+        }
+        assert!(!RetryScope::Retriable.retries_status(StatusCode::NOT_FOUND));
+        assert!(RetryScope::Unprocessed.retries_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!RetryScope::Unprocessed.retries_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!RetryScope::Unprocessed.retries_status(StatusCode::GATEWAY_TIMEOUT));
+    }
+
     #[test]
     fn detects_batch_limit_from_headers() {
         let mut headers = HeaderMap::new();
@@ -1041,7 +1225,7 @@ mod tests {
     #[test]
     fn classifies_raw_download_responses() {
         let raw = RawResponse {
-            url: Url::parse(DOWNLOAD_URL).unwrap(),
+            url: DlsiteEndpoints::default().download_url().unwrap(),
             status: 302,
             headers: BTreeMap::new(),
             location: Some(
@@ -1053,7 +1237,7 @@ mod tests {
         };
 
         assert!(matches!(
-            download_resolution_from_raw_response(&raw),
+            download_resolution_from_raw_response(&raw, &DlsiteEndpoints::default()),
             DownloadResolution::Direct { .. }
         ));
 
@@ -1064,7 +1248,7 @@ mod tests {
         };
 
         assert!(matches!(
-            download_resolution_from_raw_response(&raw),
+            download_resolution_from_raw_response(&raw, &DlsiteEndpoints::default()),
             DownloadResolution::Unavailable {
                 reason: DownloadUnavailableReason::NotAuthorized
             }
@@ -1082,7 +1266,7 @@ mod tests {
             <a href="/home/download/split/=/product_id/RJ123456.html">self</a>
         "#;
 
-        let parts = parse_split_download_parts(&page_url, body);
+        let parts = parse_split_download_parts(&page_url, body, &DlsiteEndpoints::default());
 
         assert_eq!(parts.len(), 2);
         assert_eq!(parts[0].number, 1);
@@ -1099,7 +1283,8 @@ mod tests {
             </p>
         "#;
 
-        let request = parse_serial_download_link(&page_url, body).unwrap();
+        let request =
+            parse_serial_download_link(&page_url, body, &DlsiteEndpoints::default()).unwrap();
 
         assert_eq!(
             request.url.as_str(),
@@ -1130,16 +1315,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn builds_serial_page_url() {
-        let url = serial_page_url(&WorkId::from("RJ123456")).unwrap();
-
-        assert_eq!(
-            url.as_str(),
-            "https://www.dlsite.com/home/serial/=/product_id/RJ123456.html"
-        );
-    }
-
     #[test]
     fn detects_absent_optional_serial_page_statuses() {
         assert!(is_absent_optional_serial_page_status(302));
@@ -1150,7 +1325,7 @@ mod tests {
 
     #[test]
     fn parses_public_product_response_shape() {
-        let endpoint = Url::parse(PUBLIC_PRODUCT_URL).unwrap();
+        let endpoint = DlsiteEndpoints::default().public_product_url().unwrap();
         let body = r#"[
             {
                 "workno": "RJ01553954",
@@ -0,0 +1,198 @@
+use crate::{Result, WorkId};
+use url::Url;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DlsiteEndpoints {
+    pub login: Url,
+    pub play_api: Url,
+    pub home: Url,
+    /// Hosts that serve download pages and files; redirects anywhere else are not followed.
+    pub download: Vec<Url>,
+}
+
+impl DlsiteEndpoints {
+    pub fn with_base_url(base_url: Url) -> Self {
+        Self {
+            login: base_url.clone(),
+            play_api: base_url.clone(),
+            home: base_url.clone(),
+            download: vec![base_url],
+        }
+    }
+
+    pub fn login_url(&self) -> Result<Url> {
+        join(&self.login, "login")
+    }
+
+    pub fn login_skip_url(&self) -> Result<Url> {
+        join(&self.home, "home/login/=/skip_register/1")
+    }
+
+    pub fn login_finish_url(&self) -> Result<Url> {
+        join(&self.home, "home/login/finish")
+    }
+
+    pub fn content_count_url(&self) -> Result<Url> {
+        join(&self.play_api, "api/v3/content/count")
+    }
+
+    pub fn content_sales_url(&self) -> Result<Url> {
+        join(&self.play_api, "api/v3/content/sales")
+    }
+
+    pub fn content_works_url(&self) -> Result<Url> {
+        join(&self.play_api, "api/v3/content/works")
+    }
+
+    pub fn download_url(&self) -> Result<Url> {
+        join(&self.play_api, "api/v3/download")
+    }
+
+    pub fn public_product_url(&self) -> Result<Url> {
+        join(&self.home, "home/api/=/product.json")
+    }
+
+    pub fn serial_page_url(&self, work_id: &WorkId) -> Result<Url> {
+        join(
+            &self.home,
+            &format!("home/serial/=/product_id/{work_id}.html"),
+        )
+    }
+
+    pub fn login_cookie_domain(&self) -> &str {
+        self.login.host_str().unwrap_or_default()
+    }
+
+    /// Returns the path of `url` relative to the first download host it belongs to, rooted at `/`.
+    pub fn download_page_path<'a>(&self, url: &'a Url) -> Option<&'a str> {
+        self.download
+            .iter()
+            .find_map(|base| relative_path(base, url))
+    }
+}
+
+impl Default for DlsiteEndpoints {
+    fn default() -> Self {
+        Self {
+            login: Url::parse("https://login.dlsite.com/").expect("valid login base URL"),
+            play_api: Url::parse("https://play.dlsite.com/").expect("valid play API base URL"),
+            home: Url::parse("https://www.dlsite.com/").expect("valid home base URL"),
+            download: vec![Url::parse("https://www.dlsite.com/").expect("valid download base URL")],
+        }
+    }
+}
+
+fn join(base: &Url, path: &str) -> Result<Url> {
+    let mut base = base.clone();
+
+    if !base.path().ends_with('/') {
+        let path = format!("{}/", base.path());
+        base.set_path(&path);
+    }
+
+    base.join(path).map_err(Into::into)
+}
+
+/// Matches on host and explicit port only, so a redirect that drops to plain HTTP still counts.
+fn relative_path<'a>(base: &Url, url: &'a Url) -> Option<&'a str> {
+    if base.host_str() != url.host_str() || base.port() != url.port() {
+        return None;
+    }
+
+    let prefix = base.path().trim_end_matches('/');
+    let path = url.path().strip_prefix(prefix)?;
+
+    path.starts_with('/').then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_default_dlsite_urls() {
+        let endpoints = DlsiteEndpoints::default();
+
+        assert_eq!(
+            endpoints.login_url().unwrap().as_str(),
+            "https://login.dlsite.com/login"
+        );
+        assert_eq!(
+            endpoints.content_works_url().unwrap().as_str(),
+            "https://play.dlsite.com/api/v3/content/works"
+        );
+        assert_eq!(
+            endpoints
+                .serial_page_url(&WorkId::from("RJ123456"))
+                .unwrap()
+                .as_str(),
+            "https://www.dlsite.com/home/serial/=/product_id/RJ123456.html"
+        );
+        assert_eq!(endpoints.login_cookie_domain(), "login.dlsite.com");
+    }
+
+    #[test]
+    fn keeps_base_path_prefix_for_mirrors() {
+        let endpoints =
+            DlsiteEndpoints::with_base_url(Url::parse("http://127.0.0.1:8080/mirror").unwrap());
+        let page =
+            Url::parse("http://127.0.0.1:8080/mirror/home/download/=/product_id/RJ1.html").unwrap();
+
+        assert_eq!(
+            endpoints.download_url().unwrap().as_str(),
+            "http://127.0.0.1:8080/mirror/api/v3/download"
+        );
+        assert_eq!(
+            endpoints.download_page_path(&page),
+            Some("/home/download/=/product_id/RJ1.html")
+        );
+        assert_eq!(
+            endpoints.download_page_path(
+                &Url::parse("http://127.0.0.1:9090/mirror/home/download/").unwrap()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn recognizes_every_configured_download_host() {
+        let mut endpoints = DlsiteEndpoints::default();
+        endpoints
+            .download
+            .push(Url::parse("https://cdn.example.test/dlsite/").unwrap());
+
+        assert_eq!(
+            endpoints.download_page_path(
+                &Url::parse("https://www.dlsite.com/home/download/=/product_id/RJ1.html").unwrap()
+            ),
+            Some("/home/download/=/product_id/RJ1.html")
+        );
+        assert_eq!(
+            endpoints.download_page_path(
+                &Url::parse(
+                    "https://cdn.example.test/dlsite/home/download/split/=/product_id/RJ1.html"
+                )
+                .unwrap()
+            ),
+            Some("/home/download/split/=/product_id/RJ1.html")
+        );
+        assert_eq!(
+            endpoints.download_page_path(
+                &Url::parse("https://other.example.test/home/download/").unwrap()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn ignores_the_scheme_of_download_hosts() {
+        let endpoints = DlsiteEndpoints::default();
+
+        assert_eq!(
+            endpoints.download_page_path(
+                &Url::parse("http://www.dlsite.com/home/download/=/product_id/RJ1.html").unwrap()
+            ),
+            Some("/home/download/=/product_id/RJ1.html")
+        );
+    }
+}
@@ -1,4 +1,6 @@
+use crate::DownloadUnavailableReason;
 use reqwest::StatusCode;
+use std::time::Duration;
 use url::Url;
 
 pub type Result<T> = std::result::Result<T, DmApiError>;
@@ -26,6 +28,12 @@ pub enum DmApiError {
         status: StatusCode,
         body_snippet: Option<String>,
     },
+    #[error("rate limited by {endpoint} with status {status}; retry after: {retry_after:?}")]
+    RateLimited {
+        endpoint: Url,
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
     #[error("unexpected API response from {endpoint}")]
     UnexpectedResponse {
         endpoint: Url,
@@ -58,6 +66,8 @@ pub enum DmApiError {
         limit: usize,
         body_snippet: Option<String>,
     },
+    #[error("unsupported proxy {url}: {reason}")]
+    UnsupportedProxy { url: Url, reason: &'static str },
     #[error("cookie store error: {0}")]
     CookieStore(String),
     #[error("HTTP request failed")]
@@ -67,3 +77,90 @@ pub enum DmApiError {
     #[error("JSON operation failed")]
     Json(#[from] serde_json::Error),
 }
+
+impl DmApiError {
+    /// Whether the same request may succeed later without any change on our side.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } => true,
+            Self::UnexpectedStatus { status, .. } => is_retriable_status(*status),
+            Self::DownloadUnavailable {
+                reason: DownloadUnavailableReason::UnexpectedStatus { status, .. },
+                ..
+            } => StatusCode::from_u16(*status).is_ok_and(is_retriable_status),
+            Self::UnexpectedResponse { source, .. } | Self::Request(source) => {
+                crate::client::is_transient_request_error(source)
+            }
+            Self::InvalidCredentials
+            | Self::NotAuthorized
+            | Self::XsrfTokenNotFound
+            | Self::LocationHeaderMissing { .. }
+            | Self::InvalidLocationHeader { .. }
+            | Self::UnexpectedJson { .. }
+            | Self::RedirectLimitExceeded { .. }
+            | Self::DownloadPageLinkNotFound { .. }
+            | Self::DownloadUnavailable { .. }
+            | Self::DownloadUnknownRedirect { .. }
+            | Self::BatchLimitExceeded { .. }
+            | Self::UnsupportedProxy { .. }
+            | Self::CookieStore(_)
+            | Self::Url(_)
+            | Self::Json(_) => false,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn is_retriable_status(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorkId;
+
+    #[test]
+    fn classifies_retriable_and_permanent_errors() {
+        let endpoint = Url::parse("https://play.dlsite.com/api/v3/content/works").unwrap();
+        let unexpected = |status| DmApiError::UnexpectedStatus {
+            endpoint: endpoint.clone(),
+            status,
+            body_snippet: None,
+        };
+
+        assert!(DmApiError::RateLimited {
+            endpoint: endpoint.clone(),
+            status: StatusCode::TOO_MANY_REQUESTS,
+            retry_after: Some(Duration::from_secs(5)),
+        }
+        .is_retriable());
+        assert!(unexpected(StatusCode::BAD_GATEWAY).is_retriable());
+        assert!(unexpected(StatusCode::REQUEST_TIMEOUT).is_retriable());
+        assert!(!unexpected(StatusCode::BAD_REQUEST).is_retriable());
+        assert!(!unexpected(StatusCode::FORBIDDEN).is_retriable());
+        assert!(!DmApiError::InvalidCredentials.is_retriable());
+        assert!(!DmApiError::NotAuthorized.is_retriable());
+        assert!(DmApiError::DownloadUnavailable {
+            work_id: WorkId::from("RJ123456"),
+            reason: DownloadUnavailableReason::UnexpectedStatus {
+                status: 503,
+                body_snippet: None,
+            },
+        }
+        .is_retriable());
+        assert!(!DmApiError::DownloadUnavailable {
+            work_id: WorkId::from("RJ123456"),
+            reason: DownloadUnavailableReason::NotFound,
+        }
+        .is_retriable());
+    }
+}
@@ -0,0 +1,272 @@
+use chrono::{DateTime, Utc};
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{sync::Mutex, time::Instant};
+use url::Url;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestGovernorConfig {
+    /// Sustained request rate per host; `None` disables the token bucket.
+    pub requests_per_second: Option<f64>,
+    pub burst: u32,
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Upper bound for a server-provided `Retry-After` wait.
+    pub max_retry_after: Duration,
+}
+
+impl Default for RequestGovernorConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: Some(4.0),
+            burst: 8,
+            max_retries: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_retry_after: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Paces requests per host and decides how long to wait before retrying.
+///
+/// Clones share the same buckets, so one governor can be handed to every
+/// client that talks to the same DLsite hosts.
+#[derive(Clone)]
+pub struct RequestGovernor {
+    config: Arc<RequestGovernorConfig>,
+    hosts: Arc<Mutex<HashMap<String, HostState>>>,
+}
+
+#[derive(Debug)]
+struct HostState {
+    tokens: f64,
+    refilled_at: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl RequestGovernor {
+    pub fn new(config: RequestGovernorConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            hosts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn config(&self) -> &RequestGovernorConfig {
+        &self.config
+    }
+
+    pub async fn acquire(&self, url: &Url) {
+        let host = host_key(url);
+
+        loop {
+            let wait = {
+                let mut hosts = self.hosts.lock().await;
+                let now = Instant::now();
+                let burst = f64::from(self.config.burst.max(1));
+                let state = hosts.entry(host.clone()).or_insert_with(|| HostState {
+                    tokens: burst,
+                    refilled_at: now,
+                    blocked_until: None,
+                });
+
+                match state.blocked_until {
+                    Some(until) if until > now => until - now,
+                    _ => {
+                        state.blocked_until = None;
+
+                        let Some(rate) = self
+                            .config
+                            .requests_per_second
+                            .filter(|rate| rate.is_finite() && *rate > 0.0)
+                        else {
+                            return;
+                        };
+
+                        let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+                        state.tokens = (state.tokens + elapsed * rate).min(burst);
+                        state.refilled_at = now;
+
+                        if state.tokens >= 1.0 {
+                            state.tokens -= 1.0;
+                            return;
+                        }
+
+                        Duration::from_secs_f64((1.0 - state.tokens) / rate)
+                    }
+                }
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Holds back every request to the host of `url` for `delay`.
+    pub async fn block_host(&self, url: &Url, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut hosts = self.hosts.lock().await;
+        let state = hosts.entry(host_key(url)).or_insert_with(|| HostState {
+            tokens: 0.0,
+            refilled_at: Instant::now(),
+            blocked_until: None,
+        });
+
+        if state.blocked_until.is_none_or(|blocked| blocked < until) {
+            state.blocked_until = Some(until);
+        }
+    }
+
+    /// Exponential backoff with jitter for the zero-based `attempt`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.max_backoff);
+        let half = ceiling / 2;
+
+        half + half.mul_f64(jitter_fraction())
+    }
+
+    pub fn retry_delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        match retry_after {
+            Some(retry_after) => retry_after.min(self.config.max_retry_after),
+            None => self.backoff(attempt),
+        }
+    }
+}
+
+impl Default for RequestGovernor {
+    fn default() -> Self {
+        Self::new(RequestGovernorConfig::default())
+    }
+}
+
+impl fmt::Debug for RequestGovernor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestGovernor")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) fn is_throttling_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+pub(crate) fn retry_after(res: &Response) -> Option<Duration> {
+    let value = res.headers().get(RETRY_AFTER)?.to_str().ok()?;
+
+    parse_retry_after(value, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let at = DateTime::parse_from_rfc2822(value)
+        .ok()?
+        .with_timezone(&Utc);
+
+    Some((at - now).to_std().unwrap_or_default())
+}
+
+fn host_key(url: &Url) -> String {
+    match url.port_or_known_default() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_owned(),
+    }
+}
+
+fn jitter_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or_default();
+    let mixed = u64::from(nanos).wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 11;
+
+    mixed as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Thu, 01 Jan 2026 00:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 31 Dec 2025 23:59:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn backs_off_exponentially_within_bounds() {
+        let governor = RequestGovernor::new(RequestGovernorConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(400),
+            ..RequestGovernorConfig::default()
+        });
+
+        for (attempt, ceiling) in [(0, 100), (1, 200), (2, 400), (5, 400)] {
+            let delay = governor.backoff(attempt);
+            let ceiling = Duration::from_millis(ceiling);
+
+            assert!(
+                delay >= ceiling / 2 && delay <= ceiling,
+                "{attempt}: {delay:?}"
+            );
+        }
+
+        assert_eq!(
+            governor.retry_delay(0, Some(Duration::from_secs(600))),
+            governor.config().max_retry_after
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paces_requests_per_host_after_burst() {
+        let governor = RequestGovernor::new(RequestGovernorConfig {
+            requests_per_second: Some(2.0),
+            burst: 2,
+            ..RequestGovernorConfig::default()
+        });
+        let play = Url::parse("https://play.dlsite.com/api/v3/content/count").unwrap();
+        let home = Url::parse("https://www.dlsite.com/home/api/=/product.json").unwrap();
+        let start = Instant::now();
+
+        governor.acquire(&play).await;
+        governor.acquire(&play).await;
+        governor.acquire(&home).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        governor.acquire(&play).await;
+        assert!(start.elapsed() >= Duration::from_millis(500));
+
+        governor.block_host(&home, Duration::from_secs(3)).await;
+        governor.acquire(&home).await;
+        assert!(start.elapsed() >= Duration::from_millis(3500));
+    }
+}
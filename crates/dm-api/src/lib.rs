@@ -1,8 +1,14 @@
 mod client;
+mod endpoints;
 mod error;
+mod governor;
 mod model;
+mod proxy;
 pub mod raw;
 
 pub use client::{DlsiteClient, DlsiteClientConfig, DownloadProbe, DownloadStream};
+pub use endpoints::DlsiteEndpoints;
 pub use error::{DmApiError, Result};
+pub use governor::{RequestGovernor, RequestGovernorConfig};
 pub use model::*;
+pub use proxy::ProxyConfig;
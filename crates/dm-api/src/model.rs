@@ -1,3 +1,4 @@
+use crate::DlsiteEndpoints;
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
@@ -294,6 +295,18 @@ impl PublicWork {
     pub fn image_main_url(&self) -> Option<&str> {
         self.image_main.as_ref().and_then(PublicWorkImage::url)
     }
+
+    pub fn genre_names(&self) -> Vec<&str> {
+        self.extra
+            .get("genres")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|genre| genre.get("name").and_then(Value::as_str))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -340,23 +353,20 @@ pub enum DownloadResolution {
 }
 
 impl DownloadResolution {
-    pub fn from_redirect_location(location: Url) -> Self {
-        let host = location.host_str().unwrap_or_default();
-        let path = location.path();
+    pub fn from_redirect_location(location: Url, endpoints: &DlsiteEndpoints) -> Self {
+        let Some(path) = endpoints.download_page_path(&location) else {
+            return Self::UnknownRedirect { location };
+        };
 
-        if host == "www.dlsite.com"
-            && (path.starts_with("/home/download/split") || path.starts_with("/home/split"))
-        {
+        if path.starts_with("/home/download/split") || path.starts_with("/home/split") {
             return Self::Split { location };
         }
 
-        if host == "www.dlsite.com"
-            && (path.starts_with("/home/download/serial") || path.starts_with("/home/serial"))
-        {
+        if path.starts_with("/home/download/serial") || path.starts_with("/home/serial") {
             return Self::SerialRequired { location };
         }
 
-        if host == "www.dlsite.com" && path.starts_with("/home/download") {
+        if path.starts_with("/home/download") {
             return Self::Direct {
                 stream_request: DownloadStreamRequest { url: location },
             };
@@ -547,17 +557,18 @@ mod tests {
 
     #[test]
     fn classifies_download_redirects() {
+        let endpoints = DlsiteEndpoints::default();
         let direct =
             Url::parse("https://www.dlsite.com/home/download/=/product_id/RJ123456.html").unwrap();
         assert!(matches!(
-            DownloadResolution::from_redirect_location(direct),
+            DownloadResolution::from_redirect_location(direct, &endpoints),
             DownloadResolution::Direct { .. }
         ));
 
         let split =
             Url::parse("https://www.dlsite.com/home/split/=/product_id/RJ123456.html").unwrap();
         assert!(matches!(
-            DownloadResolution::from_redirect_location(split),
+            DownloadResolution::from_redirect_location(split, &endpoints),
             DownloadResolution::Split { .. }
         ));
 
@@ -565,14 +576,14 @@ mod tests {
             Url::parse("https://www.dlsite.com/home/download/split/=/product_id/RJ123456.html")
                 .unwrap();
         assert!(matches!(
-            DownloadResolution::from_redirect_location(split_download),
+            DownloadResolution::from_redirect_location(split_download, &endpoints),
             DownloadResolution::Split { .. }
         ));
 
         let serial =
             Url::parse("https://www.dlsite.com/home/serial/=/product_id/RJ123456.html").unwrap();
         assert!(matches!(
-            DownloadResolution::from_redirect_location(serial),
+            DownloadResolution::from_redirect_location(serial, &endpoints),
             DownloadResolution::SerialRequired { .. }
         ));
 
@@ -580,9 +591,23 @@ mod tests {
             Url::parse("https://www.dlsite.com/home/download/serial/=/product_id/RJ123456.html")
                 .unwrap();
         assert!(matches!(
-            DownloadResolution::from_redirect_location(serial_download),
+            DownloadResolution::from_redirect_location(serial_download, &endpoints),
             DownloadResolution::SerialRequired { .. }
         ));
+
+        let fake_direct =
+            Url::parse("http://127.0.0.1:8080/home/download/=/product_id/RJ123456.html").unwrap();
+        assert!(matches!(
+            DownloadResolution::from_redirect_location(fake_direct.clone(), &endpoints),
+            DownloadResolution::UnknownRedirect { .. }
+        ));
+        assert!(matches!(
+            DownloadResolution::from_redirect_location(
+                fake_direct,
+                &DlsiteEndpoints::with_base_url(Url::parse("http://127.0.0.1:8080/").unwrap())
+            ),
+            DownloadResolution::Direct { .. }
+        ));
     }
 
     #[test]
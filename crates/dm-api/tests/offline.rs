@@ -0,0 +1,236 @@
+use dm_api::{
+    ContentQuery, Credentials, DlsiteClient, DlsiteClientConfig, DlsiteEndpoints, DmApiError,
+    DownloadFileKind, DownloadResolution, RequestGovernor, RequestGovernorConfig, SerialNumber,
+    WorkId,
+};
+use dm_fake_dlsite::{
+    FakeAccount, FakeDlsite, FakeDlsiteConfig, FakeDownload, FakeEndpoint, FakeFile, FakePurchase,
+    FakeSerialNumber, FakeWork, Fault, FaultRule,
+};
+use std::{error::Error, time::Duration};
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+#[tokio::test]
+async fn logs_in_and_syncs_against_configured_endpoints() -> TestResult {
+    let fake = FakeDlsite::start(FakeDlsiteConfig {
+        works_batch_limit: Some(2),
+        page_limit: None,
+        ..fake_config()
+    })
+    .await?;
+    let client = fake_client(&fake)?;
+
+    let snapshot = client.login(&credentials()).await?;
+    let count = client.content_count(ContentQuery::default()).await?;
+    let sales = client.sales(ContentQuery::default()).await?;
+    let works = client
+        .works(
+            &sales
+                .iter()
+                .map(|purchase| purchase.id.clone())
+                .collect::<Vec<_>>(),
+        )
+        .await?;
+
+    assert_eq!(count.user, 3);
+    assert_eq!(
+        works
+            .iter()
+            .map(|work| work.id.as_ref())
+            .collect::<Vec<_>>(),
+        vec!["RJ000001", "RJ000002", "VJ000003"]
+    );
+
+    let reused = fake_client(&fake)?;
+    reused.import_session(&snapshot)?;
+    assert_eq!(reused.content_count(ContentQuery::default()).await?.user, 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_invalid_credentials_from_configured_login_host() -> TestResult {
+    let fake = FakeDlsite::start(fake_config()).await?;
+    let client = fake_client(&fake)?;
+
+    let error = client
+        .login(&Credentials::new("fake-user", "wrong-password"))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, DmApiError::InvalidCredentials));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn waits_out_throttling_before_giving_up() -> TestResult {
+    let fake = FakeDlsite::start(fake_config()).await?;
+    let client = fake_client(&fake)?;
+    let ids = [WorkId::from("RJ000001")];
+    client.login(&credentials()).await?;
+    fake.push_fault(FaultRule {
+        times: Some(2),
+        ..FaultRule::new(
+            FakeEndpoint::ContentWorks,
+            Fault::Throttled {
+                status: 429,
+                retry_after_seconds: Some(0),
+            },
+        )
+    });
+
+    assert_eq!(client.works(&ids).await?.len(), 1);
+    assert_eq!(
+        fake.requests_to(FakeEndpoint::ContentWorks)
+            .iter()
+            .map(|request| request.status)
+            .collect::<Vec<_>>(),
+        vec![429, 429, 200]
+    );
+
+    fake.push_fault(FaultRule::new(
+        FakeEndpoint::ContentWorks,
+        Fault::Throttled {
+            status: 503,
+            retry_after_seconds: Some(0),
+        },
+    ));
+    let error = client.works(&ids).await.unwrap_err();
+
+    assert!(matches!(error, DmApiError::RateLimited { .. }));
+    assert!(error.is_retriable());
+    assert_eq!(error.retry_after(), Some(Duration::ZERO));
+    assert_eq!(fake.requests_to(FakeEndpoint::ContentWorks).len(), 3 + 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retries_server_errors_on_reads_but_not_on_the_login_form() -> TestResult {
+    let fake = FakeDlsite::start(fake_config()).await?;
+    let client = fake_client(&fake)?;
+    let ids = [WorkId::from("RJ000001")];
+    client.login(&credentials()).await?;
+    fake.push_fault(FaultRule {
+        times: Some(1),
+        ..FaultRule::new(FakeEndpoint::ContentWorks, Fault::Status(502))
+    });
+
+    assert_eq!(client.works(&ids).await?.len(), 1);
+    assert_eq!(
+        fake.requests_to(FakeEndpoint::ContentWorks)
+            .iter()
+            .map(|request| request.status)
+            .collect::<Vec<_>>(),
+        vec![502, 200]
+    );
+
+    let client = fake_client(&fake)?;
+    fake.push_fault(FaultRule {
+        method: Some("POST".to_owned()),
+        ..FaultRule::new(FakeEndpoint::Login, Fault::Status(504))
+    });
+    let error = client.login(&credentials()).await.unwrap_err();
+
+    assert!(error.is_retriable());
+    assert_eq!(
+        fake.requests_to(FakeEndpoint::Login)
+            .iter()
+            .filter(|request| request.method == "POST")
+            .map(|request| request.status)
+            .collect::<Vec<_>>(),
+        vec![302, 504]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn classifies_download_redirects_on_configured_hosts() -> TestResult {
+    let fake = FakeDlsite::start(fake_config()).await?;
+    let client = fake_client(&fake)?;
+    client.login(&credentials()).await?;
+
+    assert!(matches!(
+        client.resolve_download(&WorkId::from("RJ000001")).await?,
+        DownloadResolution::Direct { .. }
+    ));
+
+    let split = client.download_plan(&WorkId::from("RJ000002")).await?;
+    assert_eq!(
+        split
+            .files
+            .iter()
+            .map(|file| file.kind.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            DownloadFileKind::SplitPart { number: 1 },
+            DownloadFileKind::SplitPart { number: 2 },
+        ]
+    );
+    assert!(split
+        .files
+        .iter()
+        .all(|file| file.stream_request.url.origin() == fake.base_url().origin()));
+
+    let serial = client.download_plan(&WorkId::from("VJ000003")).await?;
+    assert_eq!(
+        serial.serial_numbers,
+        vec![SerialNumber {
+            label: "シリアル番号".to_owned(),
+            value: "ABCD-1234-EFGH".to_owned(),
+        }]
+    );
+    assert_eq!(serial.files.len(), 1);
+
+    Ok(())
+}
+
+fn fake_config() -> FakeDlsiteConfig {
+    let mut direct = FakeWork::new("RJ000001", "Direct Work", "Maker");
+    direct.download = Some(FakeDownload::Direct {
+        file: FakeFile::new("RJ000001.zip", vec![0; 32]),
+    });
+    let mut split = FakeWork::new("RJ000002", "Split Work", "Maker");
+    split.download = Some(FakeDownload::Split {
+        parts: vec![
+            FakeFile::new("RJ000002.part1.exe", vec![1; 32]),
+            FakeFile::new("RJ000002.part2.rar", vec![2; 32]),
+        ],
+    });
+    let mut serial = FakeWork::new("VJ000003", "Serial Work", "Maker");
+    serial.download = Some(FakeDownload::Serial {
+        file: FakeFile::new("VJ000003.zip", vec![3; 32]),
+        serial_numbers: vec![FakeSerialNumber::new("シリアル番号", "ABCD-1234-EFGH")],
+    });
+    let mut account = FakeAccount::new("fake-user", "fake-password");
+    account.purchases = ["RJ000001", "RJ000002", "VJ000003"]
+        .into_iter()
+        .map(|work_id| FakePurchase::new(work_id, "2026-01-02T00:00:00+09:00"))
+        .collect();
+
+    FakeDlsiteConfig {
+        accounts: vec![account],
+        works: vec![direct, split, serial],
+        ..FakeDlsiteConfig::default()
+    }
+}
+
+fn fake_client(fake: &FakeDlsite) -> dm_api::Result<DlsiteClient> {
+    DlsiteClient::new(DlsiteClientConfig {
+        endpoints: DlsiteEndpoints::with_base_url(fake.base_url().clone()),
+        governor: RequestGovernor::new(RequestGovernorConfig {
+            requests_per_second: None,
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(10),
+            ..RequestGovernorConfig::default()
+        }),
+        ..DlsiteClientConfig::default()
+    })
+}
+
+fn credentials() -> Credentials {
+    Credentials::new("fake-user", "fake-password")
+}
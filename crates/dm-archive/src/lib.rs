@@ -1,12 +1,18 @@
 use std::{
-    fs, io,
+    fmt, fs,
+    io::{self, Read, Seek, SeekFrom, Write},
     path::{Component, Path, PathBuf},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ArchiveExtractOptions {
     pub flatten_single_root: bool,
     pub remove_sources: bool,
+    pub name_encoding: ArchiveNameEncoding,
+    /// Tried in order on encrypted ZIP and RAR archives.
+    pub passwords: Vec<ArchivePassword>,
+    /// Unset leaves archives found among the extracted files packed.
+    pub nested: Option<NestedArchiveOptions>,
 }
 
 impl Default for ArchiveExtractOptions {
@@ -14,15 +20,104 @@ impl Default for ArchiveExtractOptions {
         Self {
             flatten_single_root: true,
             remove_sources: true,
+            name_encoding: ArchiveNameEncoding::Auto,
+            passwords: Vec::new(),
+            nested: None,
+        }
+    }
+}
+
+/// Unpacking of archives that turn up among the extracted files, such as per-language versions
+/// shipped as ZIPs inside the work's ZIP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NestedArchiveOptions {
+    /// Levels of archives inside archives to unpack; deeper ones are reported and left packed.
+    pub max_depth: u32,
+    /// Uncompressed bytes all inner archives together may unpack to. RAR sets, whose size can't
+    /// be read up front, are checked against their packed size.
+    pub max_unpacked_bytes: u64,
+    pub keep_inner_archives: bool,
+}
+
+impl Default for NestedArchiveOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 2,
+            max_unpacked_bytes: 64 * 1024 * 1024 * 1024,
+            keep_inner_archives: false,
         }
     }
 }
 
+#[derive(Clone, PartialEq, Eq)]
+pub struct ArchivePassword(String);
+
+impl ArchivePassword {
+    pub fn new(password: impl Into<String>) -> Self {
+        Self(password.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ArchivePassword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ArchivePassword(<redacted>)")
+    }
+}
+
+/// How ZIP entry names stored without the UTF-8 flag are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArchiveNameEncoding {
+    /// Picks one encoding per archive from how well its names read as UTF-8, CP932 and CP437.
+    #[default]
+    Auto,
+    Utf8,
+    /// Shift_JIS as written by Japanese Windows.
+    Cp932,
+    /// The ZIP default, and what the `zip` crate assumes.
+    Cp437,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ArchiveExtraction {
     pub output_dir: PathBuf,
     pub extracted_paths: Vec<PathBuf>,
     pub removed_sources: Vec<PathBuf>,
+    /// Encoding the ZIP entry names were read with; `None` for other archive kinds.
+    pub name_encoding: Option<ArchiveNameEncoding>,
+    /// Entries whose names are not valid in `name_encoding`, extracted under their CP437 reading.
+    pub fallback_names: Vec<ArchiveFallbackName>,
+    /// Archives found among the extracted files when nested unpacking is on.
+    pub nested: Vec<NestedArchive>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedArchive {
+    /// The inner archive's files, in volume order.
+    pub sources: Vec<PathBuf>,
+    pub outcome: NestedArchiveOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NestedArchiveOutcome {
+    /// Unpacked into a folder named after the archive, next to it.
+    Extracted(ArchiveExtraction),
+    /// Left packed because it sits deeper than `max_depth`.
+    DepthLimit,
+    /// Left packed because it would have taken the unpacked total past `max_unpacked_bytes`.
+    SizeLimit { required: u64, remaining: u64 },
+    /// Left packed because unpacking it failed, for example on a missing password or corrupt
+    /// data; `error` describes why.
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveFallbackName {
+    pub raw_name: Vec<u8>,
+    pub extracted_name: String,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -33,16 +128,37 @@ pub enum ArchiveError {
     Zip(#[from] zip::result::ZipError),
     #[error("RAR error")]
     Rar(#[from] unrar_ng::error::UnrarError),
+    #[error("7z error")]
+    SevenZip(#[from] sevenz_rust::Error),
     #[error("unsafe archive entry path: {entry}")]
     UnsafeArchiveEntry { entry: String },
     #[error("archive extraction target already exists: {path}")]
     TargetAlreadyExists { path: PathBuf },
     #[error("archive plan is not extractable yet: {kind}")]
     UnsupportedPlan { kind: &'static str },
+    #[error("streamed ZIP entry does not match the central directory: {entry}")]
+    StreamedEntryMismatch { entry: String },
+    #[error("archive volume is missing: {volume}")]
+    MissingArchiveVolume { volume: PathBuf },
+    #[error("archive volume {volume} is {actual} bytes, expected {expected}")]
+    ArchiveVolumeSizeMismatch {
+        volume: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+    #[error("archive is encrypted and needs a password: {archive}")]
+    PasswordRequired { archive: PathBuf },
+    #[error("none of the {tried} passwords opens archive {archive}")]
+    WrongPassword { archive: PathBuf, tried: usize },
+    #[error("archive uses AES encryption, which can't be unpacked: {archive}")]
+    UnsupportedEncryption { archive: PathBuf },
 }
 
 pub type Result<T> = std::result::Result<T, ArchiveError>;
 
+const STAGING_DIR_PREFIX: &str = ".dm-archive-";
+const ZIP_AES_EXTRA_FIELD_ID: u16 = 0x9901;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ArchivePlan {
     KeepArchives {
@@ -51,10 +167,22 @@ pub enum ArchivePlan {
     SingleZip {
         archive: PathBuf,
     },
+    SevenZip {
+        archive: PathBuf,
+    },
     LegacySplitRar {
         first_part: PathBuf,
         parts: Vec<PathBuf>,
     },
+    /// `name.part1.rar`, `name.part2.rar`, … or `name.rar`, `name.r00`, …, in volume order.
+    MultiVolumeRar {
+        first_volume: PathBuf,
+        volumes: Vec<PathBuf>,
+    },
+    /// `name.z01`, …, `name.zip` or `name.zip.001`, `name.zip.002`, …, in volume order.
+    SplitZip {
+        volumes: Vec<PathBuf>,
+    },
 }
 
 impl ArchivePlan {
@@ -65,8 +193,11 @@ impl ArchivePlan {
     pub fn source_files(&self) -> Vec<&Path> {
         match self {
             Self::KeepArchives { files } => files.iter().map(PathBuf::as_path).collect(),
-            Self::SingleZip { archive } => vec![archive.as_path()],
+            Self::SingleZip { archive } | Self::SevenZip { archive } => vec![archive.as_path()],
             Self::LegacySplitRar { parts, .. } => parts.iter().map(PathBuf::as_path).collect(),
+            Self::MultiVolumeRar { volumes, .. } | Self::SplitZip { volumes } => {
+                volumes.iter().map(PathBuf::as_path).collect()
+            }
         }
     }
 }
@@ -78,30 +209,336 @@ pub fn plan_archive_handling(files: impl IntoIterator<Item = PathBuf>) -> Archiv
         [archive] if has_extension(archive, "zip") => ArchivePlan::SingleZip {
             archive: archive.clone(),
         },
+        [archive] if has_extension(archive, "7z") => ArchivePlan::SevenZip {
+            archive: archive.clone(),
+        },
         [first_part, ..] if has_extension(first_part, "exe") => ArchivePlan::LegacySplitRar {
             first_part: first_part.clone(),
             parts: files,
         },
-        _ => ArchivePlan::KeepArchives { files },
+        _ => plan_archive_volumes(files),
+    }
+}
+
+/// Whether the file name looks like one volume of a multi-volume RAR or split ZIP set.
+pub fn is_archive_volume(path: &Path) -> bool {
+    archive_volume(path).is_some()
+}
+
+fn plan_archive_volumes(files: Vec<PathBuf>) -> ArchivePlan {
+    let Some((layout, volumes)) = ordered_archive_volumes(&files) else {
+        return ArchivePlan::KeepArchives { files };
+    };
+
+    match layout {
+        VolumeLayout::RarParts | VolumeLayout::RarSeries => ArchivePlan::MultiVolumeRar {
+            first_volume: volumes[0].clone(),
+            volumes,
+        },
+        VolumeLayout::ZipSpanned | VolumeLayout::ZipSplit => ArchivePlan::SplitZip { volumes },
     }
 }
 
+/// Total uncompressed size of the entries the plan would extract.
+///
+/// Returns `None` when the size can't be read up front; legacy split RAR parts are only listed
+/// once the first part has been renamed during extraction.
+pub fn uncompressed_size(plan: &ArchivePlan) -> Result<Option<u64>> {
+    match plan {
+        ArchivePlan::KeepArchives { .. } => Ok(Some(0)),
+        ArchivePlan::SingleZip { archive } => {
+            let mut zip = zip::ZipArchive::new(fs::File::open(archive)?)?;
+            let mut total = 0u64;
+
+            for index in 0..zip.len() {
+                total = total.saturating_add(zip.by_index_raw(index)?.size());
+            }
+
+            Ok(Some(total))
+        }
+        ArchivePlan::SevenZip { archive } => match sevenz_rust::Archive::open(archive) {
+            Ok(archive) => {
+                Ok(Some(archive.files.iter().fold(0u64, |total, entry| {
+                    total.saturating_add(entry.size())
+                })))
+            }
+            // Encrypted headers can only be listed with the password.
+            Err(err) if is_seven_zip_password_error(&err) => Ok(None),
+            Err(err) => Err(err.into()),
+        },
+        ArchivePlan::LegacySplitRar { .. } => Ok(None),
+        ArchivePlan::MultiVolumeRar { volumes, .. } => {
+            verify_archive_volumes(volumes)?;
+            Ok(None)
+        }
+        ArchivePlan::SplitZip { volumes } => {
+            verify_archive_volumes(volumes)?;
+            let mut zip = zip::ZipArchive::new(ZipVolumeReader::open(volumes)?)?;
+            let mut total = 0u64;
+
+            for index in 0..zip.len() {
+                total = total.saturating_add(zip.by_index_raw(index)?.size());
+            }
+
+            Ok(Some(total))
+        }
+    }
+}
+
+/// Extracts the plan, then any archives found among its files when `options.nested` is set.
+///
+/// An inner archive that fails to unpack is reported as [`NestedArchiveOutcome::Failed`] and left
+/// packed; the others are still unpacked.
 pub fn extract_archive_plan(
     plan: &ArchivePlan,
     output_dir: impl AsRef<Path>,
     options: ArchiveExtractOptions,
+) -> Result<ArchiveExtraction> {
+    let mut extraction = extract_archive_plan_once(plan, output_dir.as_ref(), options.clone())?;
+
+    extract_nested_archives(&mut extraction, &options)?;
+
+    Ok(extraction)
+}
+
+fn extract_archive_plan_once(
+    plan: &ArchivePlan,
+    output_dir: &Path,
+    options: ArchiveExtractOptions,
 ) -> Result<ArchiveExtraction> {
     match plan {
         ArchivePlan::KeepArchives { .. } => Ok(ArchiveExtraction {
-            output_dir: output_dir.as_ref().to_owned(),
+            output_dir: output_dir.to_owned(),
             extracted_paths: Vec::new(),
             removed_sources: Vec::new(),
+            name_encoding: None,
+            fallback_names: Vec::new(),
+            nested: Vec::new(),
         }),
         ArchivePlan::SingleZip { archive } => extract_single_zip(archive, output_dir, options),
+        ArchivePlan::SevenZip { archive } => extract_seven_zip(archive, output_dir, options),
         ArchivePlan::LegacySplitRar { first_part, parts } => {
             extract_legacy_split_rar(first_part, parts, output_dir, options)
         }
+        ArchivePlan::MultiVolumeRar {
+            first_volume,
+            volumes,
+        } => extract_multi_volume_rar(first_volume, volumes, output_dir, options),
+        ArchivePlan::SplitZip { volumes } => extract_split_zip(volumes, output_dir, options),
+    }
+}
+
+fn extract_nested_archives(
+    extraction: &mut ArchiveExtraction,
+    options: &ArchiveExtractOptions,
+) -> Result<()> {
+    let Some(nested) = options.nested else {
+        return Ok(());
+    };
+    let inner_options = ArchiveExtractOptions {
+        remove_sources: !nested.keep_inner_archives,
+        nested: None,
+        ..options.clone()
+    };
+    let mut remaining = nested.max_unpacked_bytes;
+
+    extract_nested_level(extraction, &inner_options, nested.max_depth, &mut remaining)
+}
+
+fn extract_nested_level(
+    extraction: &mut ArchiveExtraction,
+    options: &ArchiveExtractOptions,
+    depth_left: u32,
+    remaining: &mut u64,
+) -> Result<()> {
+    for sources in nested_archive_sets(&extraction.extracted_paths)? {
+        let plan = plan_nested_archive(sources.clone());
+
+        if !plan.is_unpackable() {
+            continue;
+        }
+
+        let outcome = if depth_left == 0 {
+            NestedArchiveOutcome::DepthLimit
+        } else {
+            extract_nested_archive(&plan, &sources, options, depth_left, remaining).unwrap_or_else(
+                |err| NestedArchiveOutcome::Failed {
+                    error: error_message(&err),
+                },
+            )
+        };
+
+        extraction.nested.push(NestedArchive { sources, outcome });
+    }
+
+    Ok(())
+}
+
+fn extract_nested_archive(
+    plan: &ArchivePlan,
+    sources: &[PathBuf],
+    options: &ArchiveExtractOptions,
+    depth_left: u32,
+    remaining: &mut u64,
+) -> Result<NestedArchiveOutcome> {
+    let required = match uncompressed_size(plan)? {
+        Some(size) => size,
+        None => files_size(sources)?,
+    };
+
+    if required > *remaining {
+        return Ok(NestedArchiveOutcome::SizeLimit {
+            required,
+            remaining: *remaining,
+        });
+    }
+
+    let output_dir = nested_output_dir(plan)?;
+    let mut inner = match extract_archive_plan_once(plan, &output_dir, options.clone()) {
+        Ok(inner) => inner,
+        Err(err) => {
+            // Only removes the folder when the failed attempt left it empty.
+            fs::remove_dir(&output_dir).ok();
+            return Err(err);
+        }
+    };
+    let mut unpacked = Vec::new();
+
+    for path in &inner.extracted_paths {
+        collect_files(path, &mut unpacked)?;
+    }
+
+    *remaining = remaining.saturating_sub(files_size(&unpacked)?);
+    extract_nested_level(&mut inner, options, depth_left - 1, remaining)?;
+
+    Ok(NestedArchiveOutcome::Extracted(inner))
+}
+
+/// Like [`plan_archive_handling`], but also unpacks a lone `.rar`, which inside a work is a
+/// whole archive rather than a volume awaiting its siblings.
+fn plan_nested_archive(sources: Vec<PathBuf>) -> ArchivePlan {
+    match sources.as_slice() {
+        [archive] if has_extension(archive, "rar") => ArchivePlan::MultiVolumeRar {
+            first_volume: archive.clone(),
+            volumes: sources,
+        },
+        _ => plan_archive_handling(sources),
+    }
+}
+
+/// The error and its sources, since most variants only name the failing library.
+fn error_message(err: &ArchiveError) -> String {
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(err);
+
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+
+    message
+}
+
+/// Archive files under `paths`, one set per ZIP or 7z file and per RAR or split ZIP volume set.
+/// Executables are left alone, since inside a work they are usually programs.
+fn nested_archive_sets(paths: &[PathBuf]) -> Result<Vec<Vec<PathBuf>>> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        collect_files(path, &mut files)?;
+    }
+
+    files.sort();
+
+    let mut volume_sets: Vec<(PathBuf, VolumeLayout, String, Vec<PathBuf>)> = Vec::new();
+    let mut sets = Vec::new();
+
+    for file in files {
+        if has_extension(&file, "7z") {
+            sets.push(vec![file]);
+            continue;
+        }
+
+        let Some(volume) = archive_volume(&file) else {
+            continue;
+        };
+        let directory = file.parent().unwrap_or_else(|| Path::new("")).to_owned();
+
+        match volume_sets
+            .iter_mut()
+            .find(|(set_directory, layout, base, _)| {
+                *set_directory == directory && *layout == volume.layout && *base == volume.base
+            }) {
+            Some((_, _, _, volumes)) => volumes.push(file),
+            None => volume_sets.push((directory, volume.layout, volume.base, vec![file])),
+        }
+    }
+
+    sets.extend(volume_sets.into_iter().map(|(_, _, _, volumes)| volumes));
+    sets.sort();
+
+    Ok(sets)
+}
+
+/// Folder next to the inner archive named after it, without its volume or archive extension.
+/// A number is appended when that name is already taken, as by `EN.zip` next to `EN.7z`.
+fn nested_output_dir(plan: &ArchivePlan) -> Result<PathBuf> {
+    let first = plan.source_files()[0];
+    let base = match archive_volume(first) {
+        Some(volume) => first.with_file_name(volume.base),
+        None => first.with_extension(""),
+    };
+
+    if !base.try_exists()? {
+        return Ok(base);
+    }
+
+    let name = base
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    for index in 2..1000 {
+        let candidate = base.with_file_name(format!("{name} ({index})"));
+
+        if !candidate.try_exists()? {
+            return Ok(candidate);
+        }
+    }
+
+    Err(ArchiveError::TargetAlreadyExists { path: base })
+}
+
+/// Regular files at or below `path`; symlinks and staging directories are skipped.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+
+    if metadata.is_file() {
+        files.push(path.to_owned());
+    } else if metadata.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+
+            if entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(STAGING_DIR_PREFIX))
+            {
+                continue;
+            }
+
+            collect_files(&entry.path(), files)?;
+        }
     }
+
+    Ok(())
+}
+
+fn files_size(files: &[PathBuf]) -> Result<u64> {
+    files.iter().try_fold(0u64, |total, file| {
+        Ok(total.saturating_add(fs::metadata(file)?.len()))
+    })
 }
 
 pub fn extract_single_zip(
@@ -124,6 +561,69 @@ pub fn extract_single_zip(
     result
 }
 
+pub fn extract_seven_zip(
+    archive: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    options: ArchiveExtractOptions,
+) -> Result<ArchiveExtraction> {
+    let archive = archive.as_ref();
+    let output_dir = output_dir.as_ref();
+
+    fs::create_dir_all(output_dir)?;
+
+    let staging_dir = create_staging_dir(output_dir)?;
+    let result = extract_seven_zip_inner(archive, output_dir, &staging_dir, options);
+
+    if result.is_err() {
+        fs::remove_dir_all(&staging_dir).ok();
+    }
+
+    result
+}
+
+/// Checks that no volume is missing and that every volume but the last has the same size, then
+/// lets unrar follow the set from its first volume.
+pub fn extract_multi_volume_rar(
+    first_volume: impl AsRef<Path>,
+    volumes: &[PathBuf],
+    output_dir: impl AsRef<Path>,
+    options: ArchiveExtractOptions,
+) -> Result<ArchiveExtraction> {
+    let output_dir = output_dir.as_ref();
+
+    verify_archive_volumes(volumes)?;
+    fs::create_dir_all(output_dir)?;
+
+    let mut extraction = extract_rar_volumes(first_volume.as_ref(), volumes, output_dir, &options)?;
+
+    if options.remove_sources {
+        extraction.removed_sources = remove_archive_sources(volumes)?;
+    }
+
+    Ok(extraction)
+}
+
+/// Checks the volumes like [`extract_multi_volume_rar`], then reads them as one ZIP archive.
+pub fn extract_split_zip(
+    volumes: &[PathBuf],
+    output_dir: impl AsRef<Path>,
+    options: ArchiveExtractOptions,
+) -> Result<ArchiveExtraction> {
+    let output_dir = output_dir.as_ref();
+
+    verify_archive_volumes(volumes)?;
+    fs::create_dir_all(output_dir)?;
+
+    let staging_dir = create_staging_dir(output_dir)?;
+    let result = extract_split_zip_inner(volumes, output_dir, &staging_dir, options);
+
+    if result.is_err() {
+        fs::remove_dir_all(&staging_dir).ok();
+    }
+
+    result
+}
+
 pub fn extract_legacy_split_rar(
     first_part: impl AsRef<Path>,
     parts: &[PathBuf],
@@ -144,8 +644,7 @@ pub fn extract_legacy_split_rar(
 
     fs::rename(first_part, &temporary_rar_path)?;
 
-    let extraction_result =
-        extract_legacy_split_rar_renamed(&temporary_rar_path, parts, output_dir, options);
+    let extraction_result = extract_rar_volumes(&temporary_rar_path, parts, output_dir, &options);
     let restore_result = restore_legacy_split_first_part(&temporary_rar_path, first_part);
 
     match (extraction_result, restore_result) {
@@ -161,17 +660,40 @@ pub fn extract_legacy_split_rar(
     }
 }
 
-fn extract_legacy_split_rar_renamed(
-    temporary_rar_path: &Path,
+/// Tries the archive without a password first, then each candidate, until unrar stops asking for
+/// one.
+fn extract_rar_volumes(
+    first_volume: &Path,
     parts: &[PathBuf],
     output_dir: &Path,
-    options: ArchiveExtractOptions,
+    options: &ArchiveExtractOptions,
 ) -> Result<ArchiveExtraction> {
-    validate_legacy_split_rar_entries(temporary_rar_path)?;
+    let candidates = std::iter::once(None).chain(options.passwords.iter().map(Some));
+
+    for password in candidates {
+        match extract_rar_volumes_with_password(first_volume, password, parts, output_dir, options)
+        {
+            Err(ArchiveError::Rar(err)) if is_rar_password_error(&err) => {}
+            result => return result,
+        }
+    }
+
+    Err(password_error(first_volume, &options.passwords))
+}
+
+fn extract_rar_volumes_with_password(
+    first_volume: &Path,
+    password: Option<&ArchivePassword>,
+    parts: &[PathBuf],
+    output_dir: &Path,
+    options: &ArchiveExtractOptions,
+) -> Result<ArchiveExtraction> {
+    validate_rar_entries(first_volume, password)?;
 
     let staging_dir = create_staging_dir(output_dir)?;
-    let result = extract_legacy_split_rar_inner(
-        temporary_rar_path,
+    let result = extract_rar_volumes_inner(
+        first_volume,
+        password,
         parts,
         output_dir,
         &staging_dir,
@@ -185,16 +707,19 @@ fn extract_legacy_split_rar_renamed(
     result
 }
 
-fn extract_legacy_split_rar_inner(
-    temporary_rar_path: &Path,
+fn extract_rar_volumes_inner(
+    first_volume: &Path,
+    password: Option<&ArchivePassword>,
     _parts: &[PathBuf],
     output_dir: &Path,
     staging_dir: &Path,
-    options: ArchiveExtractOptions,
+    options: &ArchiveExtractOptions,
 ) -> Result<ArchiveExtraction> {
-    unrar_ng::Archive::new(temporary_rar_path)
-        .open_for_processing()?
-        .extract_all(staging_dir)?;
+    let archive = match password {
+        Some(password) => unrar_ng::Archive::with_password(first_volume, password.as_str()),
+        None => unrar_ng::Archive::new(first_volume),
+    };
+    archive.open_for_processing()?.extract_all(staging_dir)?;
 
     let content_root = content_root(staging_dir, options.flatten_single_root)?;
     let extracted_paths = move_extracted_contents(&content_root, output_dir)?;
@@ -205,11 +730,19 @@ fn extract_legacy_split_rar_inner(
         output_dir: output_dir.to_owned(),
         extracted_paths,
         removed_sources: Vec::new(),
+        name_encoding: None,
+        fallback_names: Vec::new(),
+        nested: Vec::new(),
     })
 }
 
-fn validate_legacy_split_rar_entries(temporary_rar_path: &Path) -> Result<()> {
-    for entry in unrar_ng::Archive::new(temporary_rar_path).open_for_listing_split()? {
+fn validate_rar_entries(first_volume: &Path, password: Option<&ArchivePassword>) -> Result<()> {
+    let archive = match password {
+        Some(password) => unrar_ng::Archive::with_password(first_volume, password.as_str()),
+        None => unrar_ng::Archive::new(first_volume),
+    };
+
+    for entry in archive.open_for_listing_split()? {
         let entry = entry?;
         validate_archive_entry_path(&entry.filename)?;
     }
@@ -217,6 +750,27 @@ fn validate_legacy_split_rar_entries(temporary_rar_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Only unrar's own password codes count; bad data is reported as corruption, not retried.
+fn is_rar_password_error(err: &unrar_ng::error::UnrarError) -> bool {
+    matches!(
+        err.code,
+        unrar_ng::error::Code::MissingPassword | unrar_ng::error::Code::BadPassword
+    )
+}
+
+fn password_error(archive: &Path, passwords: &[ArchivePassword]) -> ArchiveError {
+    if passwords.is_empty() {
+        ArchiveError::PasswordRequired {
+            archive: archive.to_owned(),
+        }
+    } else {
+        ArchiveError::WrongPassword {
+            archive: archive.to_owned(),
+            tried: passwords.len(),
+        }
+    }
+}
+
 fn restore_legacy_split_first_part(temporary_rar_path: &Path, first_part: &Path) -> io::Result<()> {
     if temporary_rar_path.try_exists()? {
         fs::rename(temporary_rar_path, first_part)?;
@@ -250,23 +804,61 @@ fn extract_single_zip_inner(
     options: ArchiveExtractOptions,
 ) -> Result<ArchiveExtraction> {
     let archive_file = fs::File::open(archive)?;
-    let mut zip = zip::ZipArchive::new(archive_file)?;
+    let names = extract_zip_entries(
+        &mut zip::ZipArchive::new(archive_file)?,
+        archive,
+        staging_dir,
+        &options,
+    )?;
 
-    for index in 0..zip.len() {
-        let mut entry = zip.by_index(index)?;
-        let entry_path = safe_archive_entry_path(staging_dir, entry.name())?;
+    let content_root = content_root(staging_dir, options.flatten_single_root)?;
+    let extracted_paths = move_extracted_contents(&content_root, output_dir)?;
 
-        if entry.is_dir() {
-            fs::create_dir_all(&entry_path)?;
-            continue;
-        }
+    fs::remove_dir_all(staging_dir).ok();
 
-        if let Some(parent) = entry_path.parent() {
-            fs::create_dir_all(parent)?;
+    let removed_sources = if options.remove_sources {
+        fs::remove_file(archive)?;
+        vec![archive.to_owned()]
+    } else {
+        Vec::new()
+    };
+
+    Ok(ArchiveExtraction {
+        output_dir: output_dir.to_owned(),
+        extracted_paths,
+        removed_sources,
+        name_encoding: Some(names.encoding),
+        fallback_names: names.fallback_names,
+        nested: Vec::new(),
+    })
+}
+
+/// Tries the archive without a password first, then each candidate, like [`extract_rar_volumes`].
+fn extract_seven_zip_inner(
+    archive: &Path,
+    output_dir: &Path,
+    staging_dir: &Path,
+    options: ArchiveExtractOptions,
+) -> Result<ArchiveExtraction> {
+    let candidates = std::iter::once(None).chain(options.passwords.iter().map(Some));
+    let mut opened = false;
+
+    for password in candidates {
+        match write_seven_zip_entries(archive, staging_dir, password) {
+            Err(ArchiveError::SevenZip(err)) if is_seven_zip_password_error(&err) => {
+                fs::remove_dir_all(staging_dir)?;
+                fs::create_dir(staging_dir)?;
+            }
+            result => {
+                result?;
+                opened = true;
+                break;
+            }
         }
+    }
 
-        let mut output_file = fs::File::create(&entry_path)?;
-        io::copy(&mut entry, &mut output_file)?;
+    if !opened {
+        return Err(password_error(archive, &options.passwords));
     }
 
     let content_root = content_root(staging_dir, options.flatten_single_root)?;
@@ -285,244 +877,1812 @@ fn extract_single_zip_inner(
         output_dir: output_dir.to_owned(),
         extracted_paths,
         removed_sources,
+        name_encoding: None,
+        fallback_names: Vec::new(),
+        nested: Vec::new(),
     })
 }
 
-fn content_root(staging_dir: &Path, flatten_single_root: bool) -> Result<PathBuf> {
-    if !flatten_single_root {
-        return Ok(staging_dir.to_owned());
-    }
+fn write_seven_zip_entries(
+    archive: &Path,
+    staging_dir: &Path,
+    password: Option<&ArchivePassword>,
+) -> Result<()> {
+    let password = password.map_or_else(sevenz_rust::Password::empty, |password| {
+        sevenz_rust::Password::from(password.as_str())
+    });
+    let mut reader = sevenz_rust::SevenZReader::open(archive, password)?;
+    // The entry callback can only return 7z errors, so ours are carried out beside it.
+    let mut entry_error = None;
+
+    reader.for_each_entries(|entry, data| {
+        if entry_error.is_some() {
+            return Ok(false);
+        }
 
-    let entries = fs::read_dir(staging_dir)?.collect::<io::Result<Vec<_>>>()?;
+        match write_seven_zip_entry(staging_dir, entry, data) {
+            Ok(()) => Ok(true),
+            // Handed back so 7z can tell a wrong password from corrupt data.
+            Err(ArchiveError::SevenZip(err)) => Err(err),
+            Err(err) => {
+                entry_error = Some(err);
+                Ok(false)
+            }
+        }
+    })?;
+
+    match entry_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+fn write_seven_zip_entry(
+    staging_dir: &Path,
+    entry: &sevenz_rust::SevenZArchiveEntry,
+    data: &mut dyn Read,
+) -> Result<()> {
+    let entry_path = safe_archive_entry_path(staging_dir, entry.name())?;
+
+    if entry.is_directory() {
+        fs::create_dir_all(&entry_path)?;
+        return Ok(());
+    }
+
+    if let Some(parent) = entry_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut output_file = fs::File::create(&entry_path)?;
+    let mut buffer = vec![0; 64 * 1024];
+
+    loop {
+        // Read failures come from decoding, so they stay 7z errors.
+        let read = data.read(&mut buffer).map_err(sevenz_rust::Error::io)?;
+
+        if read == 0 {
+            break;
+        }
+
+        output_file.write_all(&buffer[..read])?;
+    }
+
+    Ok(())
+}
+
+fn is_seven_zip_password_error(err: &sevenz_rust::Error) -> bool {
+    matches!(
+        err,
+        sevenz_rust::Error::PasswordRequired | sevenz_rust::Error::MaybeBadPassword(_)
+    )
+}
+
+fn extract_split_zip_inner(
+    volumes: &[PathBuf],
+    output_dir: &Path,
+    staging_dir: &Path,
+    options: ArchiveExtractOptions,
+) -> Result<ArchiveExtraction> {
+    let names = extract_zip_entries(
+        &mut zip::ZipArchive::new(ZipVolumeReader::open(volumes)?)?,
+        &volumes[0],
+        staging_dir,
+        &options,
+    )?;
+
+    let content_root = content_root(staging_dir, options.flatten_single_root)?;
+    let extracted_paths = move_extracted_contents(&content_root, output_dir)?;
+
+    fs::remove_dir_all(staging_dir).ok();
+
+    let removed_sources = if options.remove_sources {
+        remove_archive_sources(volumes)?
+    } else {
+        Vec::new()
+    };
+
+    Ok(ArchiveExtraction {
+        output_dir: output_dir.to_owned(),
+        extracted_paths,
+        removed_sources,
+        name_encoding: Some(names.encoding),
+        fallback_names: names.fallback_names,
+        nested: Vec::new(),
+    })
+}
+
+fn extract_zip_entries<R: Read + Seek>(
+    zip: &mut zip::ZipArchive<R>,
+    archive: &Path,
+    staging_dir: &Path,
+    options: &ArchiveExtractOptions,
+) -> Result<ZipEntryNames> {
+    let names = ZipEntryNames::read(zip, options.name_encoding)?;
+    let password = zip_password(zip, archive, &options.passwords)?;
+
+    for (index, name) in names.names.iter().enumerate() {
+        let mut entry = match password {
+            Some(password) => zip.by_index_decrypt(index, password.as_str().as_bytes())?,
+            None => zip.by_index(index)?,
+        };
+        let entry_path = safe_archive_entry_path(staging_dir, name)?;
+
+        if entry.is_dir() {
+            fs::create_dir_all(&entry_path)?;
+            continue;
+        }
+
+        if let Some(parent) = entry_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut output_file = fs::File::create(&entry_path)?;
+        io::copy(&mut entry, &mut output_file)?;
+    }
+
+    Ok(names)
+}
+
+/// The first candidate that decrypts the archive's smallest non-empty encrypted entry with a
+/// matching CRC, or `None` when nothing is encrypted.
+///
+/// ZipCrypto's one-byte header check lets about one wrong password in 256 through, so passing it
+/// alone doesn't pick a candidate.
+fn zip_password<'a, R: Read + Seek>(
+    zip: &mut zip::ZipArchive<R>,
+    archive: &Path,
+    passwords: &'a [ArchivePassword],
+) -> Result<Option<&'a ArchivePassword>> {
+    let mut encrypted = Vec::new();
+
+    for index in 0..zip.len() {
+        let entry = zip.by_index_raw(index)?;
+
+        if !entry.encrypted() {
+            continue;
+        }
+
+        if is_zip_aes_entry(entry.extra_data()) {
+            return Err(ArchiveError::UnsupportedEncryption {
+                archive: archive.to_owned(),
+            });
+        }
+
+        encrypted.push((entry.size() == 0, entry.size(), index));
+    }
+
+    let Some((_empty, _size, index)) = encrypted.into_iter().min() else {
+        return Ok(None);
+    };
+
+    for password in passwords {
+        match zip.by_index_decrypt(index, password.as_str().as_bytes()) {
+            Ok(mut entry) => {
+                if io::copy(&mut entry, &mut io::sink()).is_ok() {
+                    return Ok(Some(password));
+                }
+            }
+            Err(zip::result::ZipError::InvalidPassword) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Err(password_error(archive, passwords))
+}
+
+/// Whether the entry's extra data carries the WinZip AES header; only ZipCrypto is supported.
+fn is_zip_aes_entry(extra_data: Option<&[u8]>) -> bool {
+    let mut fields = extra_data.unwrap_or_default();
+
+    while let [id_low, id_high, len_low, len_high, rest @ ..] = fields {
+        if u16::from_le_bytes([*id_low, *id_high]) == ZIP_AES_EXTRA_FIELD_ID {
+            return true;
+        }
+
+        fields = rest
+            .get(usize::from(u16::from_le_bytes([*len_low, *len_high]))..)
+            .unwrap_or_default();
+    }
+
+    false
+}
+
+/// Entry names of one ZIP archive, decoded with the encoding chosen for the whole archive.
+#[derive(Debug)]
+struct ZipEntryNames {
+    encoding: ArchiveNameEncoding,
+    names: Vec<String>,
+    fallback_names: Vec<ArchiveFallbackName>,
+}
+
+impl ZipEntryNames {
+    fn read<R: Read + Seek>(
+        zip: &mut zip::ZipArchive<R>,
+        requested: ArchiveNameEncoding,
+    ) -> Result<Self> {
+        let stored = (0..zip.len())
+            .map(|index| {
+                let entry = zip.by_index_raw(index)?;
+                Ok((entry.name().to_owned(), entry.name_raw().to_vec()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let encoding = match requested {
+            ArchiveNameEncoding::Auto => detect_name_encoding(
+                stored
+                    .iter()
+                    .map(|(name, raw_name)| (name.as_str(), raw_name.as_slice())),
+            ),
+            encoding => encoding,
+        };
+        let mut names = Vec::with_capacity(stored.len());
+        let mut fallback_names = Vec::new();
+
+        for (name, raw_name) in stored {
+            match decode_entry_name(&name, &raw_name, encoding) {
+                Some(decoded) => names.push(decoded),
+                None => {
+                    fallback_names.push(ArchiveFallbackName {
+                        raw_name,
+                        extracted_name: name.clone(),
+                    });
+                    names.push(name);
+                }
+            }
+        }
+
+        Ok(Self {
+            encoding,
+            names,
+            fallback_names,
+        })
+    }
+}
+
+/// Whether the `zip` crate already read the name as Unicode. It decodes names without the UTF-8
+/// flag as CP437, which changes every non-ASCII byte, so only flagged or ASCII names come back
+/// unchanged.
+fn is_unicode_entry_name(name: &str, raw_name: &[u8]) -> bool {
+    name.as_bytes() == raw_name
+}
+
+/// Reads a stored entry name, given the `zip` crate's CP437 reading of it. Returns `None` when
+/// the bytes are not valid in `encoding`.
+fn decode_entry_name(name: &str, raw_name: &[u8], encoding: ArchiveNameEncoding) -> Option<String> {
+    if is_unicode_entry_name(name, raw_name) {
+        return Some(name.to_owned());
+    }
+
+    match encoding {
+        ArchiveNameEncoding::Utf8 => String::from_utf8(raw_name.to_vec()).ok(),
+        ArchiveNameEncoding::Cp932 => encoding_rs::SHIFT_JIS
+            .decode_without_bom_handling_and_without_replacement(raw_name)
+            .map(String::from),
+        ArchiveNameEncoding::Auto | ArchiveNameEncoding::Cp437 => Some(name.to_owned()),
+    }
+}
+
+/// Scores every candidate over the names that lack the UTF-8 flag and keeps the best, preferring
+/// UTF-8, then CP932, on ties. Archives without such names read as UTF-8.
+fn detect_name_encoding<'a>(
+    names: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> ArchiveNameEncoding {
+    let names = names
+        .into_iter()
+        .filter(|(name, raw_name)| !is_unicode_entry_name(name, raw_name))
+        .collect::<Vec<_>>();
+
+    if names.is_empty() {
+        return ArchiveNameEncoding::Utf8;
+    }
+
+    let mut best = (ArchiveNameEncoding::Cp437, None);
+
+    for encoding in [
+        ArchiveNameEncoding::Utf8,
+        ArchiveNameEncoding::Cp932,
+        ArchiveNameEncoding::Cp437,
+    ] {
+        let score = names
+            .iter()
+            .map(|(name, raw_name)| name_encoding_score(name, raw_name, encoding))
+            .sum::<Option<i64>>();
+
+        if score.is_some() && score > best.1 {
+            best = (encoding, score);
+        }
+    }
+
+    best.0
+}
+
+/// How plausible one name reads in `encoding`, or `None` when it doesn't decode or decodes to
+/// control characters.
+///
+/// Japanese text scores highest. Accented Latin letters score a little, so CP437 still wins for
+/// European names, while the box drawing and symbols a wrong code page produces cost points.
+/// Valid UTF-8 gets a bonus per multi-byte sequence byte, since other encodings rarely happen to
+/// form it.
+fn name_encoding_score(name: &str, raw_name: &[u8], encoding: ArchiveNameEncoding) -> Option<i64> {
+    let decoded = decode_entry_name(name, raw_name, encoding)?;
+    let mut score = 0i64;
+
+    for character in decoded.chars() {
+        score += match character {
+            character if character.is_control() || character == char::REPLACEMENT_CHARACTER => {
+                return None;
+            }
+            character if character.is_ascii() => 0,
+            '\u{3000}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{FF01}'..='\u{FF5E}' => 3,
+            '\u{FF61}'..='\u{FF9F}' => 1,
+            '\u{00C0}'..='\u{024F}' if character != '×' && character != '÷' => 1,
+            _ => -1,
+        };
+    }
+
+    if encoding == ArchiveNameEncoding::Utf8 {
+        score += 2 * raw_name.iter().filter(|byte| !byte.is_ascii()).count() as i64;
+    }
+
+    Some(score)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VolumeLayout {
+    /// `name.part1.rar`, `name.part2.rar`, …
+    RarParts,
+    /// `name.rar`, `name.r00`, `name.r01`, …
+    RarSeries,
+    /// `name.z01`, `name.z02`, …, `name.zip`, where the ZIP holds the central directory.
+    ZipSpanned,
+    /// `name.zip.001`, `name.zip.002`, …, cut from one ZIP file at fixed sizes.
+    ZipSplit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ArchiveVolume {
+    layout: VolumeLayout,
+    base: String,
+    /// Position in the set; the `.zip` of a spanned set is numbered `u32::MAX` so it sorts last.
+    number: u32,
+    digits: usize,
+}
+
+impl ArchiveVolume {
+    fn first_number(layout: VolumeLayout) -> u32 {
+        match layout {
+            VolumeLayout::RarSeries => 0,
+            VolumeLayout::RarParts | VolumeLayout::ZipSpanned | VolumeLayout::ZipSplit => 1,
+        }
+    }
+
+    /// File name of the volume numbered `number` in this volume's set.
+    fn sibling_name(&self, number: u32) -> String {
+        let base = &self.base;
+        let digits = self.digits;
+
+        match self.layout {
+            VolumeLayout::RarParts => format!("{base}.part{number:0digits$}.rar"),
+            VolumeLayout::RarSeries if number == 0 => format!("{base}.rar"),
+            VolumeLayout::RarSeries => format!("{base}.r{:0digits$}", number - 1),
+            VolumeLayout::ZipSpanned if number == u32::MAX => format!("{base}.zip"),
+            VolumeLayout::ZipSpanned => format!("{base}.z{number:0digits$}"),
+            VolumeLayout::ZipSplit => format!("{base}.zip.{number:0digits$}"),
+        }
+    }
+}
+
+fn archive_volume(path: &Path) -> Option<ArchiveVolume> {
+    let file_name = path.file_name()?.to_str()?;
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    let extension = extension.to_ascii_lowercase();
+    let volume = |layout, base: &str, number, digits| {
+        Some(ArchiveVolume {
+            layout,
+            base: base.to_owned(),
+            number,
+            digits,
+        })
+    };
+
+    if extension == "rar" {
+        return match stem.rsplit_once('.') {
+            Some((base, part))
+                if part
+                    .get(..4)
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case("part")) =>
+            {
+                let digits = &part[4..];
+                let number = parse_volume_digits(digits).filter(|number| *number > 0)?;
+                volume(VolumeLayout::RarParts, base, number, digits.len())
+            }
+            _ => volume(VolumeLayout::RarSeries, stem, 0, 2),
+        };
+    }
+
+    if extension == "zip" {
+        return volume(VolumeLayout::ZipSpanned, stem, u32::MAX, 2);
+    }
+
+    if let Some(digits) = extension.strip_prefix('r') {
+        let number = parse_volume_digits(digits)?;
+        return volume(VolumeLayout::RarSeries, stem, number + 1, digits.len());
+    }
+
+    if let Some(digits) = extension.strip_prefix('z') {
+        let number = parse_volume_digits(digits).filter(|number| *number > 0)?;
+        return volume(VolumeLayout::ZipSpanned, stem, number, digits.len());
+    }
+
+    let number = parse_volume_digits(&extension).filter(|number| *number > 0)?;
+    let (base, zip_extension) = stem.rsplit_once('.')?;
+
+    if !zip_extension.eq_ignore_ascii_case("zip") {
+        return None;
+    }
+
+    volume(VolumeLayout::ZipSplit, base, number, extension.len())
+}
+
+fn parse_volume_digits(digits: &str) -> Option<u32> {
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    digits.parse().ok()
+}
+
+/// Sorts two or more files of one volume set into reading order, or returns `None` when they are
+/// not all volumes of the same set.
+fn ordered_archive_volumes(files: &[PathBuf]) -> Option<(VolumeLayout, Vec<PathBuf>)> {
+    if files.len() < 2 {
+        return None;
+    }
+
+    let mut volumes = files
+        .iter()
+        .map(|path| Some((archive_volume(path)?, path.clone())))
+        .collect::<Option<Vec<_>>>()?;
+    let (first, _) = &volumes[0];
+    let (layout, base) = (first.layout, first.base.clone());
+
+    if volumes
+        .iter()
+        .any(|(volume, _)| volume.layout != layout || volume.base != base)
+    {
+        return None;
+    }
+
+    volumes.sort_by_key(|(volume, _)| volume.number);
+
+    if volumes
+        .windows(2)
+        .any(|pair| pair[0].0.number == pair[1].0.number)
+    {
+        return None;
+    }
+
+    Some((layout, volumes.into_iter().map(|(_, path)| path).collect()))
+}
+
+/// Fails when a volume is missing from the numbering or when any volume but the last differs in
+/// size from the first, which is how a truncated or mixed-up set shows.
+fn verify_archive_volumes(volumes: &[PathBuf]) -> Result<()> {
+    let parsed = volumes
+        .iter()
+        .map(|path| archive_volume(path))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(ArchiveError::UnsupportedPlan {
+            kind: "unrecognized archive volume",
+        })?;
+    let (Some(first), Some(first_path)) = (parsed.first(), volumes.first()) else {
+        return Ok(());
+    };
+    let directory = first_path.parent().unwrap_or_else(|| Path::new(""));
+    let missing = |number| ArchiveError::MissingArchiveVolume {
+        volume: directory.join(first.sibling_name(number)),
+    };
+    let numbered = parsed.iter().take_while(|volume| volume.number != u32::MAX);
+
+    for (expected, volume) in (ArchiveVolume::first_number(first.layout)..).zip(numbered) {
+        if volume.number != expected {
+            return Err(missing(expected));
+        }
+    }
+
+    if first.layout == VolumeLayout::ZipSpanned
+        && parsed.last().map(|volume| volume.number) != Some(u32::MAX)
+    {
+        return Err(missing(u32::MAX));
+    }
+
+    let mut expected_size = None;
+
+    for (index, volume) in volumes.iter().enumerate() {
+        let size = match fs::metadata(volume) {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(ArchiveError::MissingArchiveVolume {
+                    volume: volume.clone(),
+                });
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let expected = *expected_size.get_or_insert(size);
+
+        if index + 1 < volumes.len() && size != expected {
+            return Err(ArchiveError::ArchiveVolumeSizeMismatch {
+                volume: volume.clone(),
+                expected,
+                actual: size,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+const ZIP_CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const ZIP_END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const ZIP_CENTRAL_HEADER_LEN: usize = 46;
+const ZIP_END_OF_CENTRAL_DIRECTORY_LEN: usize = 22;
+
+/// Presents the volumes of a split ZIP as one seekable archive.
+///
+/// Volumes are read back to back. For a spanned set, whose central directory counts offsets
+/// from the start of each volume, the directory is served from a copy rewritten to count from
+/// the start of the first volume.
+struct ZipVolumeReader {
+    volumes: Vec<(fs::File, u64)>,
+    /// Length served from the volumes; the rewritten directory, if any, follows it.
+    data_len: u64,
+    tail: Vec<u8>,
+    position: u64,
+}
+
+impl ZipVolumeReader {
+    fn open(volumes: &[PathBuf]) -> Result<Self> {
+        let volumes = volumes
+            .iter()
+            .map(|path| {
+                let file = fs::File::open(path)?;
+                let len = file.metadata()?.len();
+                Ok((file, len))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let data_len = volumes.iter().map(|(_file, len)| len).sum();
+        let mut reader = Self {
+            volumes,
+            data_len,
+            tail: Vec::new(),
+            position: 0,
+        };
+
+        if reader.volumes.len() > 1 {
+            reader.rewrite_spanned_directory()?;
+        }
+
+        Ok(reader)
+    }
+
+    fn rewrite_spanned_directory(&mut self) -> Result<()> {
+        let volume_starts = self
+            .volumes
+            .iter()
+            .scan(0u64, |start, (_file, len)| {
+                let volume_start = *start;
+                *start += len;
+                Some(volume_start)
+            })
+            .collect::<Vec<_>>();
+        let last_start = volume_starts[volume_starts.len() - 1];
+        let search_len = (self.data_len - last_start)
+            .min(u64::from(u16::MAX) + ZIP_END_OF_CENTRAL_DIRECTORY_LEN as u64);
+        let mut search = vec![0; search_len as usize];
+
+        self.seek(SeekFrom::Start(self.data_len - search_len))?;
+        self.read_exact(&mut search)?;
+
+        let eocd_start = search
+            .len()
+            .checked_sub(ZIP_END_OF_CENTRAL_DIRECTORY_LEN)
+            .and_then(|last| {
+                (0..=last).rev().find(|&index| {
+                    read_u32_le(&search, index) == ZIP_END_OF_CENTRAL_DIRECTORY_SIGNATURE
+                })
+            })
+            .ok_or(zip::result::ZipError::InvalidArchive(
+                "Could not find central directory end",
+            ))?;
+        let mut eocd = search[eocd_start..].to_vec();
+        let disk = usize::from(read_u16_le(&eocd, 4));
+        let directory_disk = usize::from(read_u16_le(&eocd, 6));
+        let directory_size = read_u32_le(&eocd, 12);
+        let directory_offset = read_u32_le(&eocd, 16);
+
+        // A set cut from one ZIP file keeps a plain single-disk directory.
+        if disk == 0 {
+            return Ok(());
+        }
+        if disk + 1 != self.volumes.len() || directory_disk > disk {
+            return Err(zip::result::ZipError::InvalidArchive(
+                "Split ZIP volume count does not match its central directory",
+            )
+            .into());
+        }
+        if directory_size == u32::MAX || directory_offset == u32::MAX {
+            return Err(ArchiveError::UnsupportedPlan {
+                kind: "ZIP64 split zip",
+            });
+        }
+
+        let directory_start = volume_starts[directory_disk] + u64::from(directory_offset);
+        let mut directory = vec![0; directory_size as usize];
+
+        self.seek(SeekFrom::Start(directory_start))?;
+        self.read_exact(&mut directory)?;
+
+        let mut index = 0;
+        let mut entries = 0u16;
+
+        while index < directory.len() {
+            if directory.len() - index < ZIP_CENTRAL_HEADER_LEN
+                || read_u32_le(&directory, index) != ZIP_CENTRAL_HEADER_SIGNATURE
+            {
+                return Err(
+                    zip::result::ZipError::InvalidArchive("Invalid central directory").into(),
+                );
+            }
+
+            let entry_disk = read_u16_le(&directory, index + 34);
+            let local_offset = read_u32_le(&directory, index + 42);
+            let absolute_offset = volume_starts
+                .get(usize::from(entry_disk))
+                .map(|start| start + u64::from(local_offset))
+                .filter(|_| entry_disk != u16::MAX && local_offset != u32::MAX)
+                .and_then(|offset| u32::try_from(offset).ok())
+                .ok_or(ArchiveError::UnsupportedPlan {
+                    kind: "ZIP64 split zip",
+                })?;
+
+            directory[index + 34..index + 36].copy_from_slice(&0u16.to_le_bytes());
+            directory[index + 42..index + 46].copy_from_slice(&absolute_offset.to_le_bytes());
+            index += ZIP_CENTRAL_HEADER_LEN
+                + usize::from(read_u16_le(&directory, index + 28))
+                + usize::from(read_u16_le(&directory, index + 30))
+                + usize::from(read_u16_le(&directory, index + 32));
+            entries = entries.saturating_add(1);
+        }
+
+        let directory_offset =
+            u32::try_from(directory_start).map_err(|_| ArchiveError::UnsupportedPlan {
+                kind: "ZIP64 split zip",
+            })?;
+
+        eocd[4..6].copy_from_slice(&0u16.to_le_bytes());
+        eocd[6..8].copy_from_slice(&0u16.to_le_bytes());
+        eocd[8..10].copy_from_slice(&entries.to_le_bytes());
+        eocd[10..12].copy_from_slice(&entries.to_le_bytes());
+        eocd[16..20].copy_from_slice(&directory_offset.to_le_bytes());
+
+        directory.extend_from_slice(&eocd);
+        self.data_len = directory_start;
+        self.tail = directory;
+        self.position = 0;
+
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        self.data_len + self.tail.len() as u64
+    }
+}
+
+impl Read for ZipVolumeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.data_len {
+            let offset = usize::try_from(self.position - self.data_len)
+                .unwrap_or(usize::MAX)
+                .min(self.tail.len());
+            let read = (&self.tail[offset..]).read(buf)?;
+            self.position += read as u64;
+            return Ok(read);
+        }
+
+        let mut volume_start = 0;
+
+        for (file, len) in &mut self.volumes {
+            let volume_end = (volume_start + *len).min(self.data_len);
+
+            if self.position < volume_end {
+                let available = usize::try_from(volume_end - self.position).unwrap_or(usize::MAX);
+                let limit = buf.len().min(available);
+
+                file.seek(SeekFrom::Start(self.position - volume_start))?;
+                let read = file.read(&mut buf[..limit])?;
+                self.position += read as u64;
+                return Ok(read);
+            }
+
+            volume_start += *len;
+        }
+
+        Ok(0)
+    }
+}
+
+impl Seek for ZipVolumeReader {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let target = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.len().checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the archive",
+            )
+        })?;
+
+        self.position = target;
+        Ok(target)
+    }
+}
+
+fn read_u16_le(bytes: &[u8], index: usize) -> u16 {
+    u16::from_le_bytes([bytes[index], bytes[index + 1]])
+}
+
+fn read_u32_le(bytes: &[u8], index: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[index],
+        bytes[index + 1],
+        bytes[index + 2],
+        bytes[index + 3],
+    ])
+}
+
+/// Extracts a ZIP archive front to back from its local file headers, so entries can be written
+/// while the rest of the archive is still arriving.
+///
+/// Entries go to a staging directory inside the output directory, which is removed again unless
+/// [`ZipStreamExtractor::finish`] succeeds.
+///
+/// With [`ArchiveNameEncoding::Auto`] each name is judged on its own while streaming; when that
+/// disagrees with the choice for the whole archive, [`ZipStreamExtractor::finish`] reports a
+/// mismatch so the archive gets extracted again from disk.
+#[derive(Debug)]
+pub struct ZipStreamExtractor {
+    output_dir: PathBuf,
+    staging_dir: Option<PathBuf>,
+    name_encoding: ArchiveNameEncoding,
+    /// Decoded name and size of every streamed entry.
+    entries: Vec<(String, u64)>,
+}
+
+impl ZipStreamExtractor {
+    pub fn new(output_dir: impl AsRef<Path>, name_encoding: ArchiveNameEncoding) -> Result<Self> {
+        let output_dir = output_dir.as_ref();
+
+        fs::create_dir_all(output_dir)?;
+
+        Ok(Self {
+            output_dir: output_dir.to_owned(),
+            staging_dir: Some(create_staging_dir(output_dir)?),
+            name_encoding,
+            entries: Vec::new(),
+        })
+    }
+
+    /// Reads entries until the central directory starts.
+    ///
+    /// Encrypted entries and entries that only record their size after their data can't be read
+    /// this way and fail with [`ArchiveError::Zip`]; such archives need [`extract_single_zip`].
+    pub fn extract(&mut self, mut reader: impl Read) -> Result<()> {
+        let staging_dir = self.staging_dir()?.to_owned();
+
+        while let Some(mut entry) = zip::read::read_zipfile_from_stream(&mut reader)? {
+            let encoding = match self.name_encoding {
+                ArchiveNameEncoding::Auto => {
+                    detect_name_encoding([(entry.name(), entry.name_raw())])
+                }
+                encoding => encoding,
+            };
+            let name = decode_entry_name(entry.name(), entry.name_raw(), encoding)
+                .unwrap_or_else(|| entry.name().to_owned());
+            let entry_path = safe_archive_entry_path(&staging_dir, &name)?;
+
+            if entry.is_dir() {
+                fs::create_dir_all(&entry_path)?;
+            } else {
+                if let Some(parent) = entry_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                let mut output_file = fs::File::create(&entry_path)?;
+                io::copy(&mut entry, &mut output_file)?;
+            }
+
+            self.entries.push((name, entry.size()));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the streamed entries against the complete archive's central directory, then moves
+    /// them into the output directory the same way [`extract_single_zip`] does.
+    ///
+    /// Fails with [`ArchiveError::StreamedEntryMismatch`] before anything is moved when the
+    /// archive holds entries the stream didn't.
+    pub fn finish(
+        mut self,
+        archive: impl AsRef<Path>,
+        options: ArchiveExtractOptions,
+    ) -> Result<ArchiveExtraction> {
+        let archive = archive.as_ref();
+        let staging_dir = self.staging_dir()?.to_owned();
+        let names = self.verify_central_directory(archive)?;
+
+        let content_root = content_root(&staging_dir, options.flatten_single_root)?;
+        let extracted_paths = move_extracted_contents(&content_root, &self.output_dir)?;
+
+        fs::remove_dir_all(&staging_dir).ok();
+        self.staging_dir = None;
+
+        let mut extraction = ArchiveExtraction {
+            output_dir: self.output_dir.clone(),
+            extracted_paths,
+            removed_sources: Vec::new(),
+            name_encoding: Some(names.encoding),
+            fallback_names: names.fallback_names,
+            nested: Vec::new(),
+        };
+
+        extract_nested_archives(&mut extraction, &options)?;
+
+        // Removed last so a failed finish can still fall back to unpacking the archive from disk.
+        if options.remove_sources {
+            fs::remove_file(archive)?;
+            extraction.removed_sources.push(archive.to_owned());
+        }
+
+        Ok(extraction)
+    }
+
+    fn verify_central_directory(&self, archive: &Path) -> Result<ZipEntryNames> {
+        let mut zip = zip::ZipArchive::new(fs::File::open(archive)?)?;
+        let names = ZipEntryNames::read(&mut zip, self.name_encoding)?;
+
+        for index in 0..zip.len().max(self.entries.len()) {
+            let expected = if index < zip.len() {
+                let entry = zip.by_index_raw(index)?;
+                Some((names.names[index].clone(), entry.size()))
+            } else {
+                None
+            };
+            let streamed = self.entries.get(index);
+
+            if expected.as_ref() != streamed {
+                let entry = expected
+                    .or_else(|| streamed.cloned())
+                    .map(|(name, _size)| name)
+                    .unwrap_or_default();
+
+                return Err(ArchiveError::StreamedEntryMismatch { entry });
+            }
+        }
+
+        Ok(names)
+    }
+
+    fn staging_dir(&self) -> Result<&Path> {
+        self.staging_dir.as_deref().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "archive staging directory is gone").into()
+        })
+    }
+}
+
+impl Drop for ZipStreamExtractor {
+    fn drop(&mut self) {
+        if let Some(staging_dir) = self.staging_dir.take() {
+            fs::remove_dir_all(staging_dir).ok();
+        }
+    }
+}
+
+fn content_root(staging_dir: &Path, flatten_single_root: bool) -> Result<PathBuf> {
+    if !flatten_single_root {
+        return Ok(staging_dir.to_owned());
+    }
+
+    let entries = fs::read_dir(staging_dir)?.collect::<io::Result<Vec<_>>>()?;
+
+    if entries.len() == 1 && entries[0].file_type()?.is_dir() {
+        return Ok(entries[0].path());
+    }
+
+    Ok(staging_dir.to_owned())
+}
+
+fn move_extracted_contents(content_root: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(content_root)?.collect::<io::Result<Vec<_>>>()?;
+    let mut extracted_paths = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let target_path = output_dir.join(entry.file_name());
+
+        if target_path.try_exists()? {
+            return Err(ArchiveError::TargetAlreadyExists { path: target_path });
+        }
+
+        fs::rename(entry.path(), &target_path)?;
+        extracted_paths.push(target_path);
+    }
+
+    Ok(extracted_paths)
+}
+
+fn safe_archive_entry_path(base: &Path, entry: &str) -> Result<PathBuf> {
+    validate_archive_entry_str(entry)?;
+
+    Ok(base.join(relative_archive_entry_path(Path::new(entry), entry)?))
+}
+
+fn validate_archive_entry_path(path: &Path) -> Result<()> {
+    let entry = path.to_string_lossy();
+    validate_archive_entry_str(&entry)?;
+    relative_archive_entry_path(path, &entry)?;
+
+    Ok(())
+}
+
+fn validate_archive_entry_str(entry: &str) -> Result<()> {
+    if entry.contains('\\') || entry.contains('\0') {
+        return Err(ArchiveError::UnsafeArchiveEntry {
+            entry: entry.to_owned(),
+        });
+    }
+
+    Ok(())
+}
+
+fn relative_archive_entry_path(path: &Path, original_entry: &str) -> Result<PathBuf> {
+    let mut relative = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => {
+                return Err(ArchiveError::UnsafeArchiveEntry {
+                    entry: original_entry.to_owned(),
+                });
+            }
+        }
+    }
+
+    if relative.as_os_str().is_empty() {
+        return Err(ArchiveError::UnsafeArchiveEntry {
+            entry: original_entry.to_owned(),
+        });
+    }
+
+    Ok(relative)
+}
+
+/// Removes staging directories an interrupted extraction left inside `output_dir`.
+pub fn remove_stale_staging_dirs(output_dir: impl AsRef<Path>) -> Result<()> {
+    let entries = match fs::read_dir(output_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+
+    for entry in entries {
+        let entry = entry?;
+
+        if entry.file_type()?.is_dir()
+            && entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(STAGING_DIR_PREFIX))
+        {
+            fs::remove_dir_all(entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn create_staging_dir(output_dir: &Path) -> Result<PathBuf> {
+    for index in 0..1000 {
+        let staging_dir = output_dir.join(format!("{STAGING_DIR_PREFIX}{index}"));
+
+        match fs::create_dir(&staging_dir) {
+            Ok(()) => return Ok(staging_dir),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "unable to allocate archive staging directory",
+    )
+    .into())
+}
+
+fn has_extension(path: &Path, expected: &str) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::Write,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn keeps_empty_sets() {
+        assert_eq!(
+            plan_archive_handling(Vec::<PathBuf>::new()),
+            ArchivePlan::KeepArchives { files: Vec::new() }
+        );
+    }
+
+    #[test]
+    fn detects_single_zip_case_insensitively() {
+        assert_eq!(
+            plan_archive_handling([PathBuf::from("RJ123456.ZIP")]),
+            ArchivePlan::SingleZip {
+                archive: PathBuf::from("RJ123456.ZIP")
+            }
+        );
+    }
+
+    #[test]
+    fn detects_legacy_split_rar_from_first_exe() {
+        assert_eq!(
+            plan_archive_handling([
+                PathBuf::from("RJ123456.exe"),
+                PathBuf::from("RJ123456.r00"),
+                PathBuf::from("RJ123456.r01"),
+            ]),
+            ArchivePlan::LegacySplitRar {
+                first_part: PathBuf::from("RJ123456.exe"),
+                parts: vec![
+                    PathBuf::from("RJ123456.exe"),
+                    PathBuf::from("RJ123456.r00"),
+                    PathBuf::from("RJ123456.r01"),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn detects_single_seven_zip_case_insensitively() {
+        assert_eq!(
+            plan_archive_handling([PathBuf::from("RJ123456.7Z")]),
+            ArchivePlan::SevenZip {
+                archive: PathBuf::from("RJ123456.7Z")
+            }
+        );
+    }
+
+    #[test]
+    fn detects_multi_volume_rar_layouts_in_volume_order() {
+        assert_eq!(
+            plan_archive_handling([
+                PathBuf::from("RJ123456.part10.rar"),
+                PathBuf::from("RJ123456.part2.rar"),
+                PathBuf::from("RJ123456.part1.rar"),
+            ]),
+            ArchivePlan::MultiVolumeRar {
+                first_volume: PathBuf::from("RJ123456.part1.rar"),
+                volumes: vec![
+                    PathBuf::from("RJ123456.part1.rar"),
+                    PathBuf::from("RJ123456.part2.rar"),
+                    PathBuf::from("RJ123456.part10.rar"),
+                ],
+            }
+        );
+        assert_eq!(
+            plan_archive_handling([
+                PathBuf::from("RJ123456.r00"),
+                PathBuf::from("RJ123456.RAR"),
+                PathBuf::from("RJ123456.r01"),
+            ]),
+            ArchivePlan::MultiVolumeRar {
+                first_volume: PathBuf::from("RJ123456.RAR"),
+                volumes: vec![
+                    PathBuf::from("RJ123456.RAR"),
+                    PathBuf::from("RJ123456.r00"),
+                    PathBuf::from("RJ123456.r01"),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn detects_split_zip_layouts_in_volume_order() {
+        assert_eq!(
+            plan_archive_handling([
+                PathBuf::from("RJ123456.zip"),
+                PathBuf::from("RJ123456.z02"),
+                PathBuf::from("RJ123456.z01"),
+            ]),
+            ArchivePlan::SplitZip {
+                volumes: vec![
+                    PathBuf::from("RJ123456.z01"),
+                    PathBuf::from("RJ123456.z02"),
+                    PathBuf::from("RJ123456.zip"),
+                ],
+            }
+        );
+        assert_eq!(
+            plan_archive_handling([
+                PathBuf::from("RJ123456.zip.002"),
+                PathBuf::from("RJ123456.zip.001"),
+            ]),
+            ArchivePlan::SplitZip {
+                volumes: vec![
+                    PathBuf::from("RJ123456.zip.001"),
+                    PathBuf::from("RJ123456.zip.002"),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_volumes_from_different_sets() {
+        let files = vec![
+            PathBuf::from("RJ123456.part1.rar"),
+            PathBuf::from("RJ654321.part2.rar"),
+        ];
+
+        assert_eq!(
+            plan_archive_handling(files.clone()),
+            ArchivePlan::KeepArchives { files }
+        );
+        assert!(!is_archive_volume(Path::new("readme.txt")));
+        assert!(is_archive_volume(Path::new("RJ123456.Z03")));
+    }
+
+    #[test]
+    fn keeps_unrecognized_files() {
+        assert_eq!(
+            plan_archive_handling([PathBuf::from("readme.txt")]),
+            ArchivePlan::KeepArchives {
+                files: vec![PathBuf::from("readme.txt")]
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_multiple_files_when_first_file_is_not_exe() {
+        assert_eq!(
+            plan_archive_handling([PathBuf::from("part1.bin"), PathBuf::from("part2.bin")]),
+            ArchivePlan::KeepArchives {
+                files: vec![PathBuf::from("part1.bin"), PathBuf::from("part2.bin")]
+            }
+        );
+    }
+
+    #[test]
+    fn sums_uncompressed_zip_entry_sizes() {
+        let dir = test_dir("zip-size");
+        let archive = dir.join("RJ123456.zip");
+        write_zip(
+            &archive,
+            &[
+                ("RJ123456/readme.txt", b"hello".as_slice()),
+                ("RJ123456/data.bin", &[0; 64]),
+            ],
+        );
+
+        assert_eq!(
+            uncompressed_size(&ArchivePlan::SingleZip {
+                archive: archive.clone()
+            })
+            .unwrap(),
+            Some(69)
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn extracts_single_zip_and_flattens_single_root() {
+        let dir = test_dir("single-zip");
+        let archive = dir.join("RJ123456.zip");
+        write_zip(&archive, &[("RJ123456/readme.txt", b"hello".as_slice())]);
+
+        let extraction =
+            extract_single_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap();
+
+        assert_eq!(std::fs::read(dir.join("readme.txt")).unwrap(), b"hello");
+        assert!(!archive.exists());
+        assert_eq!(extraction.extracted_paths, vec![dir.join("readme.txt")]);
+        assert_eq!(extraction.removed_sources, vec![archive]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn can_preserve_single_root_directory() {
+        let dir = test_dir("preserve-root");
+        let archive = dir.join("RJ123456.zip");
+        write_zip(&archive, &[("RJ123456/readme.txt", b"hello".as_slice())]);
+
+        extract_single_zip(
+            &archive,
+            &dir,
+            ArchiveExtractOptions {
+                flatten_single_root: false,
+                remove_sources: false,
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read(dir.join("RJ123456").join("readme.txt")).unwrap(),
+            b"hello"
+        );
+        assert!(archive.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn extracts_nested_archives_into_folders_named_after_them() {
+        let dir = test_dir("nested");
+        let output_dir = dir.join("out");
+        let archive = dir.join("RJ123456.zip");
+        let english = zip_bytes(&dir, &[("readme.txt", b"english".as_slice())]);
+        let bonus = seven_zip_bytes(&dir, &[("art.png", b"art".as_slice())]);
+        write_zip(
+            &archive,
+            &[
+                ("RJ123456/EN.zip", english.as_slice()),
+                ("RJ123456/extras/bonus.7z", bonus.as_slice()),
+                ("RJ123456/setup.exe", b"program"),
+            ],
+        );
 
-    if entries.len() == 1 && entries[0].file_type()?.is_dir() {
-        return Ok(entries[0].path());
+        let extraction = extract_archive_plan(
+            &plan_archive_handling([archive.clone()]),
+            &output_dir,
+            ArchiveExtractOptions {
+                nested: Some(NestedArchiveOptions::default()),
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read(output_dir.join("EN").join("readme.txt")).unwrap(),
+            b"english"
+        );
+        assert_eq!(
+            std::fs::read(output_dir.join("extras").join("bonus").join("art.png")).unwrap(),
+            b"art"
+        );
+        assert!(output_dir.join("setup.exe").exists());
+        assert!(!output_dir.join("EN.zip").exists());
+        assert_eq!(
+            extraction
+                .nested
+                .iter()
+                .map(|nested| nested.sources.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![output_dir.join("EN.zip")],
+                vec![output_dir.join("extras").join("bonus.7z")],
+            ]
+        );
+        let NestedArchiveOutcome::Extracted(english) = &extraction.nested[0].outcome else {
+            panic!("expected EN.zip to be extracted");
+        };
+        assert_eq!(english.output_dir, output_dir.join("EN"));
+        assert_eq!(english.removed_sources, vec![output_dir.join("EN.zip")]);
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
-    Ok(staging_dir.to_owned())
-}
+    #[test]
+    fn leaves_nested_archives_packed_past_the_depth_and_size_limits() {
+        let dir = test_dir("nested-limits");
+        let deep = zip_bytes(&dir, &[("deep.txt", b"deep".as_slice())]);
+        let middle = zip_bytes(&dir, &[("level2.zip", deep.as_slice())]);
+        let large = zip_bytes(&dir, &[("large.bin", [7; 4096].as_slice())]);
+        let write_outer = |name: &str| {
+            let archive = dir.join(name);
+            write_zip(
+                &archive,
+                &[
+                    ("level1.zip", middle.as_slice()),
+                    ("large.zip", large.as_slice()),
+                ],
+            );
+            plan_archive_handling([archive])
+        };
+
+        let depth_dir = dir.join("depth");
+        let extraction = extract_archive_plan(
+            &write_outer("depth.zip"),
+            &depth_dir,
+            ArchiveExtractOptions {
+                nested: Some(NestedArchiveOptions {
+                    max_depth: 1,
+                    keep_inner_archives: true,
+                    ..NestedArchiveOptions::default()
+                }),
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap();
 
-fn move_extracted_contents(content_root: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
-    let entries = fs::read_dir(content_root)?.collect::<io::Result<Vec<_>>>()?;
-    let mut extracted_paths = Vec::with_capacity(entries.len());
+        assert!(depth_dir.join("level1.zip").exists());
+        assert!(depth_dir.join("level1").join("level2.zip").exists());
+        assert!(!depth_dir.join("level1").join("level2").exists());
+        let NestedArchiveOutcome::Extracted(level1) = &extraction.nested[1].outcome else {
+            panic!("expected level1.zip to be extracted");
+        };
+        assert!(level1.removed_sources.is_empty());
+        assert_eq!(
+            level1.nested,
+            vec![NestedArchive {
+                sources: vec![depth_dir.join("level1").join("level2.zip")],
+                outcome: NestedArchiveOutcome::DepthLimit,
+            }]
+        );
 
-    for entry in entries {
-        let target_path = output_dir.join(entry.file_name());
+        let size_dir = dir.join("size");
+        let extraction = extract_archive_plan(
+            &write_outer("size.zip"),
+            &size_dir,
+            ArchiveExtractOptions {
+                nested: Some(NestedArchiveOptions {
+                    max_unpacked_bytes: 1024,
+                    ..NestedArchiveOptions::default()
+                }),
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap();
 
-        if target_path.try_exists()? {
-            return Err(ArchiveError::TargetAlreadyExists { path: target_path });
-        }
+        assert_eq!(
+            extraction.nested[0],
+            NestedArchive {
+                sources: vec![size_dir.join("large.zip")],
+                outcome: NestedArchiveOutcome::SizeLimit {
+                    required: 4096,
+                    remaining: 1024,
+                },
+            }
+        );
+        assert!(size_dir.join("large.zip").exists());
+        assert_eq!(
+            std::fs::read(size_dir.join("level1").join("level2").join("deep.txt")).unwrap(),
+            b"deep"
+        );
 
-        fs::rename(entry.path(), &target_path)?;
-        extracted_paths.push(target_path);
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
-    Ok(extracted_paths)
-}
+    #[test]
+    fn reports_inner_archives_that_fail_and_keeps_unpacking_the_rest() {
+        let dir = test_dir("nested-failures");
+        let locked_path = dir.join("locked.zip");
+        write_encrypted_zip(&locked_path, b"secret", &[("locked.txt", b"locked")]);
+        let locked = std::fs::read(&locked_path).unwrap();
+        std::fs::remove_file(locked_path).unwrap();
+        let good = zip_bytes(&dir, &[("readme.txt", b"good".as_slice())]);
+        let write_outer = |name: &str| {
+            let archive = dir.join(name);
+            write_zip(
+                &archive,
+                &[
+                    ("broken.zip", b"not a zip archive".as_slice()),
+                    ("good.zip", good.as_slice()),
+                    ("locked.zip", locked.as_slice()),
+                ],
+            );
+            plan_archive_handling([archive])
+        };
+
+        for (name, passwords, error) in [
+            ("missing.zip", Vec::new(), "needs a password"),
+            (
+                "wrong.zip",
+                vec![ArchivePassword::new("wrong")],
+                "none of the 1 passwords",
+            ),
+        ] {
+            let output_dir = dir.join(name).with_extension("");
+            let extraction = extract_archive_plan(
+                &write_outer(name),
+                &output_dir,
+                ArchiveExtractOptions {
+                    passwords,
+                    nested: Some(NestedArchiveOptions::default()),
+                    ..ArchiveExtractOptions::default()
+                },
+            )
+            .unwrap();
+
+            let outcomes = extraction
+                .nested
+                .iter()
+                .map(|nested| &nested.outcome)
+                .collect::<Vec<_>>();
+            assert!(matches!(outcomes[0], NestedArchiveOutcome::Failed { .. }));
+            assert!(matches!(outcomes[1], NestedArchiveOutcome::Extracted(_)));
+            let NestedArchiveOutcome::Failed { error: message } = outcomes[2] else {
+                panic!("expected locked.zip to fail");
+            };
+            assert!(message.contains(error), "{message}");
+            assert_eq!(
+                std::fs::read(output_dir.join("good").join("readme.txt")).unwrap(),
+                b"good"
+            );
+            assert!(output_dir.join("broken.zip").exists());
+            assert!(output_dir.join("locked.zip").exists());
+            assert!(!output_dir.join("broken").exists());
+            assert!(!output_dir.join("locked").exists());
+        }
 
-fn safe_archive_entry_path(base: &Path, entry: &str) -> Result<PathBuf> {
-    validate_archive_entry_str(entry)?;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 
-    Ok(base.join(relative_archive_entry_path(Path::new(entry), entry)?))
-}
+    #[test]
+    fn unpacks_nested_archives_next_to_taken_names() {
+        let dir = test_dir("nested-collisions");
+        let output_dir = dir.join("out");
+        let archive = dir.join("RJ123456.zip");
+        let zip = zip_bytes(&dir, &[("readme.txt", b"zip".as_slice())]);
+        let seven_zip = seven_zip_bytes(&dir, &[("readme.txt", b"7z".as_slice())]);
+        write_zip(
+            &archive,
+            &[
+                ("EN/readme.txt", b"outer".as_slice()),
+                ("EN.7z", seven_zip.as_slice()),
+                ("EN.zip", zip.as_slice()),
+            ],
+        );
 
-fn validate_archive_entry_path(path: &Path) -> Result<()> {
-    let entry = path.to_string_lossy();
-    validate_archive_entry_str(&entry)?;
-    relative_archive_entry_path(path, &entry)?;
+        let extraction = extract_archive_plan(
+            &plan_archive_handling([archive]),
+            &output_dir,
+            ArchiveExtractOptions {
+                nested: Some(NestedArchiveOptions::default()),
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap();
 
-    Ok(())
-}
+        let read =
+            |folder: &str| std::fs::read(output_dir.join(folder).join("readme.txt")).unwrap();
+        assert_eq!(read("EN"), b"outer");
+        assert_eq!(read("EN (2)"), b"7z");
+        assert_eq!(read("EN (3)"), b"zip");
+        assert!(extraction
+            .nested
+            .iter()
+            .all(|nested| matches!(nested.outcome, NestedArchiveOutcome::Extracted(_))));
 
-fn validate_archive_entry_str(entry: &str) -> Result<()> {
-    if entry.contains('\\') || entry.contains('\0') {
-        return Err(ArchiveError::UnsafeArchiveEntry {
-            entry: entry.to_owned(),
-        });
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
-    Ok(())
-}
-
-fn relative_archive_entry_path(path: &Path, original_entry: &str) -> Result<PathBuf> {
-    let mut relative = PathBuf::new();
+    #[test]
+    fn unpacks_a_lone_rar_only_when_nested() {
+        let archive = PathBuf::from("bonus.rar");
 
-    for component in path.components() {
-        match component {
-            Component::Normal(part) => relative.push(part),
-            Component::CurDir => {}
-            _ => {
-                return Err(ArchiveError::UnsafeArchiveEntry {
-                    entry: original_entry.to_owned(),
-                });
+        assert_eq!(
+            plan_archive_handling([archive.clone()]),
+            ArchivePlan::KeepArchives {
+                files: vec![archive.clone()]
             }
-        }
+        );
+        assert_eq!(
+            plan_nested_archive(vec![archive.clone()]),
+            ArchivePlan::MultiVolumeRar {
+                first_volume: archive.clone(),
+                volumes: vec![archive],
+            }
+        );
     }
 
-    if relative.as_os_str().is_empty() {
-        return Err(ArchiveError::UnsafeArchiveEntry {
-            entry: original_entry.to_owned(),
-        });
-    }
+    #[test]
+    fn rejects_zip_entries_that_escape_output_dir() {
+        let dir = test_dir("unsafe-entry");
+        let archive = dir.join("RJ123456.zip");
+        write_zip(&archive, &[("../evil.txt", b"evil".as_slice())]);
 
-    Ok(relative)
-}
+        let err = extract_single_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap_err();
 
-fn create_staging_dir(output_dir: &Path) -> Result<PathBuf> {
-    for index in 0..1000 {
-        let staging_dir = output_dir.join(format!(".dm-archive-{index}"));
+        assert!(matches!(err, ArchiveError::UnsafeArchiveEntry { .. }));
+        assert!(!dir.parent().unwrap().join("evil.txt").exists());
+        assert!(!dir.join(".dm-archive-0").exists());
 
-        match fs::create_dir(&staging_dir) {
-            Ok(()) => return Ok(staging_dir),
-            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
-            Err(err) => return Err(err.into()),
-        }
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
-    Err(io::Error::new(
-        io::ErrorKind::AlreadyExists,
-        "unable to allocate archive staging directory",
-    )
-    .into())
-}
+    #[test]
+    fn rejects_windows_style_escape_entries() {
+        let dir = test_dir("unsafe-backslash");
+        let archive = dir.join("RJ123456.zip");
+        write_zip(&archive, &[("..\\evil.txt", b"evil".as_slice())]);
 
-fn has_extension(path: &Path, expected: &str) -> bool {
-    path.extension()
-        .and_then(|extension| extension.to_str())
-        .is_some_and(|extension| extension.eq_ignore_ascii_case(expected))
-}
+        let err = extract_single_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap_err();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::{
-        io::Write,
-        time::{SystemTime, UNIX_EPOCH},
-    };
+        assert!(matches!(err, ArchiveError::UnsafeArchiveEntry { .. }));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 
     #[test]
-    fn keeps_empty_sets() {
+    fn extracts_seven_zip_and_flattens_single_root() {
+        let dir = test_dir("seven-zip");
+        let archive = dir.join("RJ123456.7z");
+        write_seven_zip(
+            &archive,
+            &[
+                ("RJ123456/readme.txt", b"hello".as_slice()),
+                ("RJ123456/audio/track.wav", &[3; 256]),
+                ("RJ123456/empty.txt", b""),
+            ],
+        );
+
         assert_eq!(
-            plan_archive_handling(Vec::<PathBuf>::new()),
-            ArchivePlan::KeepArchives { files: Vec::new() }
+            uncompressed_size(&ArchivePlan::SevenZip {
+                archive: archive.clone()
+            })
+            .unwrap(),
+            Some(261)
         );
-    }
 
-    #[test]
-    fn detects_single_zip_case_insensitively() {
+        let extraction = extract_archive_plan(
+            &plan_archive_handling([archive.clone()]),
+            &dir,
+            ArchiveExtractOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(std::fs::read(dir.join("readme.txt")).unwrap(), b"hello");
         assert_eq!(
-            plan_archive_handling([PathBuf::from("RJ123456.ZIP")]),
-            ArchivePlan::SingleZip {
-                archive: PathBuf::from("RJ123456.ZIP")
-            }
+            std::fs::read(dir.join("audio").join("track.wav")).unwrap(),
+            vec![3; 256]
         );
+        assert_eq!(std::fs::read(dir.join("empty.txt")).unwrap(), b"");
+        assert!(!archive.exists());
+        assert_eq!(extraction.removed_sources, vec![archive]);
+        assert!(!dir.join(".dm-archive-0").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
-    fn detects_legacy_split_rar_from_first_exe() {
+    fn keeps_seven_zip_sources_and_single_root_when_asked() {
+        let dir = test_dir("seven-zip-keep");
+        let archive = dir.join("RJ123456.7z");
+        write_seven_zip(&archive, &[("RJ123456/readme.txt", b"hello".as_slice())]);
+
+        extract_seven_zip(
+            &archive,
+            &dir,
+            ArchiveExtractOptions {
+                flatten_single_root: false,
+                remove_sources: false,
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap();
+
         assert_eq!(
-            plan_archive_handling([
-                PathBuf::from("RJ123456.exe"),
-                PathBuf::from("RJ123456.r00"),
-                PathBuf::from("RJ123456.r01"),
-            ]),
-            ArchivePlan::LegacySplitRar {
-                first_part: PathBuf::from("RJ123456.exe"),
-                parts: vec![
-                    PathBuf::from("RJ123456.exe"),
-                    PathBuf::from("RJ123456.r00"),
-                    PathBuf::from("RJ123456.r01"),
-                ],
-            }
+            std::fs::read(dir.join("RJ123456").join("readme.txt")).unwrap(),
+            b"hello"
         );
+        assert!(archive.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
-    fn keeps_unrecognized_files() {
-        assert_eq!(
-            plan_archive_handling([PathBuf::from("readme.txt")]),
-            ArchivePlan::KeepArchives {
-                files: vec![PathBuf::from("readme.txt")]
-            }
+    fn rejects_seven_zip_entries_that_escape_output_dir() {
+        let dir = test_dir("seven-zip-unsafe");
+        let archive = dir.join("RJ123456.7z");
+        write_seven_zip(
+            &archive,
+            &[
+                ("readme.txt", b"hello".as_slice()),
+                ("../evil.txt", b"evil"),
+            ],
         );
+
+        let err = extract_seven_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap_err();
+
+        assert!(matches!(
+            err,
+            ArchiveError::UnsafeArchiveEntry { entry } if entry == "../evil.txt"
+        ));
+        assert!(!dir.parent().unwrap().join("evil.txt").exists());
+        assert!(!dir.join("readme.txt").exists());
+        assert!(!dir.join(".dm-archive-0").exists());
+        assert!(archive.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
-    fn keeps_multiple_files_when_first_file_is_not_exe() {
+    fn extracts_encrypted_seven_zip_with_the_first_matching_password() {
+        let dir = test_dir("seven-zip-password");
+        let archive = dir.join("RJ123456.7z");
+        write_encrypted_seven_zip(
+            &archive,
+            "ABCD-EFGH-1234",
+            &[("RJ123456/readme.txt", b"hello from 7z".as_slice())],
+        );
+        let options = |passwords: &[&str]| ArchiveExtractOptions {
+            remove_sources: false,
+            passwords: passwords
+                .iter()
+                .map(|password| ArchivePassword::new(*password))
+                .collect(),
+            ..ArchiveExtractOptions::default()
+        };
+
+        assert!(matches!(
+            extract_seven_zip(&archive, &dir, options(&[])).unwrap_err(),
+            ArchiveError::PasswordRequired { archive: ref path } if path == &archive
+        ));
+        assert!(matches!(
+            extract_seven_zip(&archive, &dir, options(&["not-it"])).unwrap_err(),
+            ArchiveError::WrongPassword { tried: 1, .. }
+        ));
+        assert!(!dir.join("readme.txt").exists());
+        assert!(!dir.join(".dm-archive-0").exists());
+
+        extract_seven_zip(&archive, &dir, options(&["not-it", "ABCD-EFGH-1234"])).unwrap();
+
         assert_eq!(
-            plan_archive_handling([PathBuf::from("part1.bin"), PathBuf::from("part2.bin")]),
-            ArchivePlan::KeepArchives {
-                files: vec![PathBuf::from("part1.bin"), PathBuf::from("part2.bin")]
-            }
+            std::fs::read(dir.join("readme.txt")).unwrap(),
+            b"hello from 7z"
         );
+        assert!(archive.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
-    fn extracts_single_zip_and_flattens_single_root() {
-        let dir = test_dir("single-zip");
+    fn extracts_zip_split_into_fixed_size_volumes() {
+        let dir = test_dir("split-zip");
         let archive = dir.join("RJ123456.zip");
-        write_zip(&archive, &[("RJ123456/readme.txt", b"hello".as_slice())]);
+        write_zip(
+            &archive,
+            &[
+                ("RJ123456/readme.txt", b"hello".as_slice()),
+                ("RJ123456/video.bin", &[5; 300]),
+            ],
+        );
+        let bytes = std::fs::read(&archive).unwrap();
+        std::fs::remove_file(&archive).unwrap();
+        let volumes = bytes
+            .chunks(128)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let volume = dir.join(format!("RJ123456.zip.{:03}", index + 1));
+                std::fs::write(&volume, chunk).unwrap();
+                volume
+            })
+            .collect::<Vec<_>>();
+        let plan = plan_archive_handling(volumes.iter().rev().cloned());
+
+        assert_eq!(uncompressed_size(&plan).unwrap(), Some(305));
 
         let extraction =
-            extract_single_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap();
+            extract_archive_plan(&plan, &dir, ArchiveExtractOptions::default()).unwrap();
 
         assert_eq!(std::fs::read(dir.join("readme.txt")).unwrap(), b"hello");
-        assert!(!archive.exists());
-        assert_eq!(extraction.extracted_paths, vec![dir.join("readme.txt")]);
-        assert_eq!(extraction.removed_sources, vec![archive]);
+        assert_eq!(std::fs::read(dir.join("video.bin")).unwrap(), vec![5; 300]);
+        assert_eq!(extraction.removed_sources, volumes);
+        assert!(volumes.iter().all(|volume| !volume.exists()));
 
         std::fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
-    fn can_preserve_single_root_directory() {
-        let dir = test_dir("preserve-root");
-        let archive = dir.join("RJ123456.zip");
-        write_zip(&archive, &[("RJ123456/readme.txt", b"hello".as_slice())]);
+    fn extracts_spanned_zip_volumes() {
+        let dir = test_dir("spanned-zip");
+        let volumes = write_spanned_zip(
+            &dir,
+            "RJ123456",
+            128,
+            &[
+                ("RJ123456/readme.txt", b"hello".as_slice()),
+                ("RJ123456/video.bin", &[6; 300]),
+            ],
+        );
+        let plan = plan_archive_handling(volumes.clone());
+
+        assert!(matches!(plan, ArchivePlan::SplitZip { .. }));
 
-        extract_single_zip(
-            &archive,
+        let extraction = extract_archive_plan(
+            &plan,
             &dir,
             ArchiveExtractOptions {
-                flatten_single_root: false,
+                flatten_single_root: true,
                 remove_sources: false,
+                ..ArchiveExtractOptions::default()
             },
         )
         .unwrap();
 
+        assert_eq!(std::fs::read(dir.join("readme.txt")).unwrap(), b"hello");
+        assert_eq!(std::fs::read(dir.join("video.bin")).unwrap(), vec![6; 300]);
         assert_eq!(
-            std::fs::read(dir.join("RJ123456").join("readme.txt")).unwrap(),
-            b"hello"
+            extraction.extracted_paths,
+            vec![dir.join("readme.txt"), dir.join("video.bin")]
         );
-        assert!(archive.exists());
+        assert!(volumes.iter().all(|volume| volume.exists()));
 
         std::fs::remove_dir_all(dir).unwrap();
     }
 
     #[test]
-    fn rejects_zip_entries_that_escape_output_dir() {
-        let dir = test_dir("unsafe-entry");
-        let archive = dir.join("RJ123456.zip");
-        write_zip(&archive, &[("../evil.txt", b"evil".as_slice())]);
-
-        let err = extract_single_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap_err();
+    fn rejects_volume_sets_with_gaps_or_mismatched_sizes() {
+        let dir = test_dir("volume-checks");
+        let part = |name: &str, size: usize| {
+            let path = dir.join(name);
+            std::fs::write(&path, vec![0; size]).unwrap();
+            path
+        };
+        let first = part("RJ123456.part1.rar", 64);
+        let third = part("RJ123456.part3.rar", 10);
+
+        let err = extract_archive_plan(
+            &plan_archive_handling([first.clone(), third.clone()]),
+            &dir,
+            ArchiveExtractOptions::default(),
+        )
+        .unwrap_err();
 
-        assert!(matches!(err, ArchiveError::UnsafeArchiveEntry { .. }));
-        assert!(!dir.parent().unwrap().join("evil.txt").exists());
-        assert!(!dir.join(".dm-archive-0").exists());
+        assert!(matches!(
+            err,
+            ArchiveError::MissingArchiveVolume { volume } if volume == dir.join("RJ123456.part2.rar")
+        ));
 
-        std::fs::remove_dir_all(dir).unwrap();
-    }
+        let second = part("RJ123456.part2.rar", 63);
+        let err = extract_archive_plan(
+            &plan_archive_handling([first.clone(), second.clone(), third.clone()]),
+            &dir,
+            ArchiveExtractOptions::default(),
+        )
+        .unwrap_err();
 
-    #[test]
-    fn rejects_windows_style_escape_entries() {
-        let dir = test_dir("unsafe-backslash");
-        let archive = dir.join("RJ123456.zip");
-        write_zip(&archive, &[("..\\evil.txt", b"evil".as_slice())]);
+        assert!(matches!(
+            err,
+            ArchiveError::ArchiveVolumeSizeMismatch { volume, expected: 64, actual: 63 }
+                if volume == second
+        ));
 
-        let err = extract_single_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap_err();
+        let spanned = [part("RJ654321.z01", 64), part("RJ654321.z02", 64)];
+        let err = uncompressed_size(&plan_archive_handling(spanned)).unwrap_err();
 
-        assert!(matches!(err, ArchiveError::UnsafeArchiveEntry { .. }));
+        assert!(matches!(
+            err,
+            ArchiveError::MissingArchiveVolume { volume } if volume == dir.join("RJ654321.zip")
+        ));
+        assert!(first.exists() && second.exists() && third.exists());
+        assert!(!dir.join(".dm-archive-0").exists());
 
         std::fs::remove_dir_all(dir).unwrap();
     }
@@ -604,6 +2764,7 @@ mod tests {
             ArchiveExtractOptions {
                 flatten_single_root: true,
                 remove_sources: false,
+                ..ArchiveExtractOptions::default()
             },
         )
         .unwrap_err();
@@ -614,6 +2775,511 @@ mod tests {
         std::fs::remove_dir_all(dir).unwrap();
     }
 
+    #[test]
+    fn extracts_zip_stream_and_checks_the_central_directory() {
+        let dir = test_dir("zip-stream");
+        let archive = dir.join("RJ123456.zip");
+        write_zip(
+            &archive,
+            &[
+                ("RJ123456/readme.txt", b"hello".as_slice()),
+                ("RJ123456/video.bin", &[7; 128]),
+            ],
+        );
+
+        let mut extractor = ZipStreamExtractor::new(&dir, ArchiveNameEncoding::Auto).unwrap();
+        extractor
+            .extract(std::fs::File::open(&archive).unwrap())
+            .unwrap();
+        let extraction = extractor
+            .finish(&archive, ArchiveExtractOptions::default())
+            .unwrap();
+
+        assert_eq!(std::fs::read(dir.join("readme.txt")).unwrap(), b"hello");
+        assert_eq!(std::fs::read(dir.join("video.bin")).unwrap(), vec![7; 128]);
+        assert_eq!(extraction.removed_sources, vec![archive]);
+        assert!(!dir.join(".dm-archive-0").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_zip_streams_that_need_the_central_directory() {
+        let dir = test_dir("zip-stream-descriptor");
+        let archive = dir.join("RJ123456.zip");
+        let other = dir.join("other.zip");
+        write_zip(&archive, &[("readme.txt", b"hello".as_slice())]);
+        write_zip(
+            &other,
+            &[("readme.txt", b"hello".as_slice()), ("extra.txt", b"")],
+        );
+
+        let mut bytes = std::fs::read(&archive).unwrap();
+        // General purpose flag bit 3: sizes follow the entry data instead of the header.
+        bytes[6] |= 1 << 3;
+        let mut extractor = ZipStreamExtractor::new(&dir, ArchiveNameEncoding::Auto).unwrap();
+        let err = extractor.extract(bytes.as_slice()).unwrap_err();
+
+        assert!(matches!(err, ArchiveError::Zip(_)));
+        drop(extractor);
+        assert!(!dir.join(".dm-archive-0").exists());
+
+        let mut extractor = ZipStreamExtractor::new(&dir, ArchiveNameEncoding::Auto).unwrap();
+        extractor
+            .extract(std::fs::File::open(&archive).unwrap())
+            .unwrap();
+        let err = extractor
+            .finish(&other, ArchiveExtractOptions::default())
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ArchiveError::StreamedEntryMismatch { entry } if entry == "extra.txt"
+        ));
+        assert!(!dir.join("readme.txt").exists());
+        assert!(!dir.join(".dm-archive-0").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn detects_cp932_entry_names_without_the_utf8_flag() {
+        let dir = test_dir("zip-cp932");
+        let archive = dir.join("RJ123456.zip");
+        write_zip_with_raw_names(
+            &archive,
+            &[
+                (
+                    &shift_jis("RJ123456/音声/01_はじめに.wav"),
+                    b"intro".as_slice(),
+                ),
+                (&shift_jis("RJ123456/音声/02_本編（ﾛﾝｸﾞ）.wav"), b"main"),
+                (&shift_jis("RJ123456/おまけ/readme.txt"), b"bonus"),
+            ],
+        );
+
+        let extraction =
+            extract_single_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap();
+
+        assert_eq!(extraction.name_encoding, Some(ArchiveNameEncoding::Cp932));
+        assert!(extraction.fallback_names.is_empty());
+        assert_eq!(
+            std::fs::read(dir.join("音声").join("01_はじめに.wav")).unwrap(),
+            b"intro"
+        );
+        assert_eq!(
+            std::fs::read(dir.join("音声").join("02_本編（ﾛﾝｸﾞ）.wav")).unwrap(),
+            b"main"
+        );
+        assert_eq!(
+            std::fs::read(dir.join("おまけ").join("readme.txt")).unwrap(),
+            b"bonus"
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_utf8_and_cp437_entry_names() {
+        let dir = test_dir("zip-cp437");
+        let archive = dir.join("RJ123456.zip");
+        // "Café/Übersicht.txt" as CP437, which is not valid Shift_JIS.
+        write_zip_with_raw_names(
+            &archive,
+            &[(b"Caf\x82/\x9abersicht.txt".as_slice(), b"hello".as_slice())],
+        );
+
+        let extraction = extract_single_zip(
+            &archive,
+            dir.join("cp437"),
+            ArchiveExtractOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(extraction.name_encoding, Some(ArchiveNameEncoding::Cp437));
+        assert!(dir.join("cp437").join("Übersicht.txt").exists());
+
+        let archive = dir.join("RJ654321.zip");
+        write_zip(&archive, &[("トラック/01.wav", b"intro".as_slice())]);
+
+        let extraction =
+            extract_single_zip(&archive, dir.join("utf8"), ArchiveExtractOptions::default())
+                .unwrap();
+
+        assert_eq!(extraction.name_encoding, Some(ArchiveNameEncoding::Utf8));
+        assert!(dir.join("utf8").join("01.wav").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn records_raw_names_that_the_forced_encoding_cannot_decode() {
+        let dir = test_dir("zip-forced-encoding");
+        let archive = dir.join("RJ123456.zip");
+        let raw_name = shift_jis("音声.wav");
+        write_zip_with_raw_names(
+            &archive,
+            &[(&raw_name, b"intro".as_slice()), (b"readme.txt", b"hello")],
+        );
+
+        let extraction = extract_single_zip(
+            &archive,
+            &dir,
+            ArchiveExtractOptions {
+                name_encoding: ArchiveNameEncoding::Utf8,
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(extraction.name_encoding, Some(ArchiveNameEncoding::Utf8));
+        assert_eq!(extraction.fallback_names.len(), 1);
+        assert_eq!(extraction.fallback_names[0].raw_name, raw_name);
+        assert_eq!(
+            std::fs::read(dir.join(&extraction.fallback_names[0].extracted_name)).unwrap(),
+            b"intro"
+        );
+        assert!(!dir.join("音声.wav").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn decodes_cp932_entry_names_while_streaming() {
+        let dir = test_dir("zip-stream-cp932");
+        let archive = dir.join("RJ123456.zip");
+        write_zip_with_raw_names(
+            &archive,
+            &[
+                (&shift_jis("RJ123456/台本.txt"), b"script".as_slice()),
+                (b"RJ123456/readme.txt", b"hello"),
+            ],
+        );
+
+        let mut extractor = ZipStreamExtractor::new(&dir, ArchiveNameEncoding::Auto).unwrap();
+        extractor
+            .extract(std::fs::File::open(&archive).unwrap())
+            .unwrap();
+        let extraction = extractor
+            .finish(&archive, ArchiveExtractOptions::default())
+            .unwrap();
+
+        assert_eq!(extraction.name_encoding, Some(ArchiveNameEncoding::Cp932));
+        assert_eq!(std::fs::read(dir.join("台本.txt")).unwrap(), b"script");
+        assert_eq!(std::fs::read(dir.join("readme.txt")).unwrap(), b"hello");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn extracts_encrypted_zip_with_the_first_matching_password() {
+        let dir = test_dir("zip-password");
+        let archive = dir.join("RJ123456.zip");
+        write_encrypted_zip(
+            &archive,
+            b"ABCD-EFGH-1234",
+            &[
+                ("RJ123456/readme.txt", b"hello".as_slice()),
+                ("RJ123456/audio/01.wav", &[7; 64]),
+            ],
+        );
+        let options = ArchiveExtractOptions {
+            passwords: vec![
+                ArchivePassword::new("not-it"),
+                ArchivePassword::new("ABCD-EFGH-1234"),
+            ],
+            ..ArchiveExtractOptions::default()
+        };
+
+        assert!(!format!("{options:?}").contains("ABCD"));
+
+        let extraction = extract_single_zip(&archive, &dir, options).unwrap();
+
+        assert_eq!(std::fs::read(dir.join("readme.txt")).unwrap(), b"hello");
+        assert_eq!(
+            std::fs::read(dir.join("audio").join("01.wav")).unwrap(),
+            vec![7; 64]
+        );
+        assert_eq!(extraction.removed_sources, vec![archive]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reports_missing_and_wrong_zip_passwords() {
+        let dir = test_dir("zip-password-errors");
+        let archive = dir.join("RJ123456.zip");
+        write_encrypted_zip(
+            &archive,
+            b"ABCD-EFGH-1234",
+            &[("readme.txt", b"hello".as_slice())],
+        );
+
+        let err = extract_single_zip(&archive, &dir, ArchiveExtractOptions::default()).unwrap_err();
+
+        assert!(matches!(
+            err,
+            ArchiveError::PasswordRequired { archive: ref path } if path == &archive
+        ));
+
+        let err = extract_single_zip(
+            &archive,
+            &dir,
+            ArchiveExtractOptions {
+                passwords: vec![ArchivePassword::new("not-it")],
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ArchiveError::WrongPassword { tried: 1, .. }));
+        assert!(archive.exists());
+        assert!(!dir.join("readme.txt").exists());
+        assert!(!dir.join(".dm-archive-0").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn confirms_zip_passwords_beyond_the_check_byte() {
+        let dir = test_dir("zip-password-check-byte");
+        let archive = dir.join("RJ123456.zip");
+        write_encrypted_zip(
+            &archive,
+            b"ABCD-EFGH-1234",
+            &[("readme.txt", b"hello".as_slice())],
+        );
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(&archive).unwrap()).unwrap();
+        // About one wrong password in 256 passes ZipCrypto's header check.
+        let lucky_guess = (0..10_000)
+            .map(|attempt| format!("guess-{attempt}"))
+            .find(|guess| zip.by_index_decrypt(0, guess.as_bytes()).is_ok())
+            .unwrap();
+        drop(zip);
+        let options = |passwords: &[&str]| ArchiveExtractOptions {
+            passwords: passwords
+                .iter()
+                .map(|password| ArchivePassword::new(*password))
+                .collect(),
+            ..ArchiveExtractOptions::default()
+        };
+
+        assert!(matches!(
+            extract_single_zip(&archive, &dir, options(&[&lucky_guess])).unwrap_err(),
+            ArchiveError::WrongPassword { tried: 1, .. }
+        ));
+
+        extract_single_zip(&archive, &dir, options(&[&lucky_guess, "ABCD-EFGH-1234"])).unwrap();
+
+        assert_eq!(std::fs::read(dir.join("readme.txt")).unwrap(), b"hello");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reports_aes_encrypted_zip_as_unsupported() {
+        let dir = test_dir("zip-aes");
+        let archive = dir.join("RJ123456.zip");
+        write_aes_marked_zip(&archive, &[("readme.txt", b"hello".as_slice())]);
+
+        let err = extract_single_zip(
+            &archive,
+            &dir,
+            ArchiveExtractOptions {
+                passwords: vec![ArchivePassword::new("secret")],
+                ..ArchiveExtractOptions::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ArchiveError::UnsupportedEncryption { archive: ref path } if path == &archive
+        ));
+        assert!(archive.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn treats_only_unrar_password_codes_as_password_errors() {
+        let error = |code| unrar_ng::error::UnrarError {
+            code,
+            when: unrar_ng::error::When::Process,
+        };
+
+        assert!(is_rar_password_error(&error(
+            unrar_ng::error::Code::BadPassword
+        )));
+        assert!(is_rar_password_error(&error(
+            unrar_ng::error::Code::MissingPassword
+        )));
+        assert!(!is_rar_password_error(&error(
+            unrar_ng::error::Code::BadData
+        )));
+    }
+
+    /// Writes stored entries encrypted with traditional PKWARE encryption, which most Windows
+    /// archivers still use for password-protected ZIPs.
+    fn write_encrypted_zip(path: &Path, password: &[u8], entries: &[(&str, &[u8])]) {
+        write_encrypted_zip_with_extra(path, password, entries, &[]);
+    }
+
+    /// Marks every entry as WinZip AES-256 the way AES archivers record it; the data itself stays
+    /// ZipCrypto, which is enough to test that AES is recognized before anything is decrypted.
+    fn write_aes_marked_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut aes_extra = Vec::new();
+        aes_extra.extend_from_slice(&0x9901u16.to_le_bytes());
+        aes_extra.extend_from_slice(&7u16.to_le_bytes());
+        aes_extra.extend_from_slice(&2u16.to_le_bytes());
+        aes_extra.extend_from_slice(b"AE");
+        aes_extra.push(3);
+        aes_extra.extend_from_slice(&0u16.to_le_bytes());
+
+        write_encrypted_zip_with_extra(path, b"secret", entries, &aes_extra);
+    }
+
+    fn write_encrypted_zip_with_extra(
+        path: &Path,
+        password: &[u8],
+        entries: &[(&str, &[u8])],
+        extra: &[u8],
+    ) {
+        let mut bytes = Vec::new();
+        let mut central_directory = Vec::new();
+
+        for (name, content) in entries {
+            let crc = crc32(content);
+            let mut keys = ZipCryptoKeys::new(password);
+            let mut data = (0..11).map(|byte| keys.encrypt(byte)).collect::<Vec<_>>();
+            data.push(keys.encrypt((crc >> 24) as u8));
+            data.extend(content.iter().map(|&byte| keys.encrypt(byte)));
+
+            let mut header = Vec::new();
+            header.extend_from_slice(&20u16.to_le_bytes());
+            header.extend_from_slice(&1u16.to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes());
+            header.extend_from_slice(&0x21u16.to_le_bytes());
+            header.extend_from_slice(&crc.to_le_bytes());
+            header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            header.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            header.extend_from_slice(&(extra.len() as u16).to_le_bytes());
+
+            central_directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            central_directory.extend_from_slice(&20u16.to_le_bytes());
+            central_directory.extend_from_slice(&header);
+            central_directory.extend_from_slice(&[0; 6]);
+            central_directory.extend_from_slice(&0u32.to_le_bytes());
+            central_directory.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            central_directory.extend_from_slice(name.as_bytes());
+            central_directory.extend_from_slice(extra);
+
+            bytes.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+            bytes.extend_from_slice(&header);
+            bytes.extend_from_slice(name.as_bytes());
+            bytes.extend_from_slice(extra);
+            bytes.extend_from_slice(&data);
+        }
+
+        let central_directory_offset = bytes.len() as u32;
+        bytes.extend_from_slice(&central_directory);
+        bytes.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&(central_directory.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&central_directory_offset.to_le_bytes());
+        bytes.extend_from_slice(&0u16.to_le_bytes());
+
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    struct ZipCryptoKeys([u32; 3]);
+
+    impl ZipCryptoKeys {
+        fn new(password: &[u8]) -> Self {
+            let mut keys = Self([0x1234_5678, 0x2345_6789, 0x3456_7890]);
+            for &byte in password {
+                keys.update(byte);
+            }
+            keys
+        }
+
+        fn update(&mut self, byte: u8) {
+            self.0[0] = crc32_update(self.0[0], byte);
+            self.0[1] = self.0[1]
+                .wrapping_add(self.0[0] & 0xff)
+                .wrapping_mul(134_775_813)
+                .wrapping_add(1);
+            self.0[2] = crc32_update(self.0[2], (self.0[1] >> 24) as u8);
+        }
+
+        fn encrypt(&mut self, byte: u8) -> u8 {
+            let temp = (self.0[2] | 2) & 0xffff;
+            let encrypted = byte ^ ((temp * (temp ^ 1)) >> 8) as u8;
+            self.update(byte);
+            encrypted
+        }
+    }
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        !bytes
+            .iter()
+            .fold(u32::MAX, |crc, &byte| crc32_update(crc, byte))
+    }
+
+    fn crc32_update(crc: u32, byte: u8) -> u32 {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    }
+
+    fn shift_jis(name: &str) -> Vec<u8> {
+        let (bytes, _, unmappable) = encoding_rs::SHIFT_JIS.encode(name);
+        assert!(!unmappable);
+        bytes.into_owned()
+    }
+
+    /// Writes entries whose names are stored as the given bytes without the UTF-8 flag, the way
+    /// older Windows archivers do.
+    fn write_zip_with_raw_names(path: &Path, entries: &[(&[u8], &[u8])]) {
+        let placeholders = entries
+            .iter()
+            .enumerate()
+            .map(|(index, (raw_name, _))| {
+                char::from(b'A' + index as u8)
+                    .to_string()
+                    .repeat(raw_name.len())
+            })
+            .collect::<Vec<_>>();
+        let named_entries = placeholders
+            .iter()
+            .zip(entries)
+            .map(|(placeholder, (_, content))| (placeholder.as_str(), *content))
+            .collect::<Vec<_>>();
+        write_zip(path, &named_entries);
+
+        let mut bytes = std::fs::read(path).unwrap();
+        for (placeholder, (raw_name, _)) in placeholders.iter().zip(entries) {
+            let mut start = 0;
+            while let Some(offset) = bytes[start..]
+                .windows(raw_name.len())
+                .position(|window| window == placeholder.as_bytes())
+            {
+                let index = start + offset;
+                bytes[index..index + raw_name.len()].copy_from_slice(raw_name);
+                start = index + raw_name.len();
+            }
+        }
+        std::fs::write(path, bytes).unwrap();
+    }
+
     fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
         let file = std::fs::File::create(path).unwrap();
         let mut zip = zip::ZipWriter::new(file);
@@ -628,6 +3294,108 @@ mod tests {
         zip.finish().unwrap();
     }
 
+    fn zip_bytes(dir: &Path, entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let path = dir.join("inner.zip");
+        write_zip(&path, entries);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        bytes
+    }
+
+    fn seven_zip_bytes(dir: &Path, entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let path = dir.join("inner.7z");
+        write_seven_zip(&path, entries);
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        bytes
+    }
+
+    fn write_seven_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        write_encrypted_seven_zip(path, "", entries);
+    }
+
+    /// Leaves the archive unencrypted when `password` is empty.
+    fn write_encrypted_seven_zip(path: &Path, password: &str, entries: &[(&str, &[u8])]) {
+        let mut writer = sevenz_rust::SevenZWriter::create(path).unwrap();
+
+        if !password.is_empty() {
+            writer.set_content_methods(vec![
+                sevenz_rust::AesEncoderOptions::new(password.into()).into(),
+                sevenz_rust::SevenZMethod::LZMA2.into(),
+            ]);
+        }
+
+        for (name, content) in entries {
+            let mut entry = sevenz_rust::SevenZArchiveEntry::new();
+            entry.name = (*name).to_owned();
+            writer
+                .push_archive_entry(entry, (!content.is_empty()).then_some(*content))
+                .unwrap();
+        }
+
+        writer.finish().unwrap();
+    }
+
+    /// Writes a ZIP the way spanning tools do: a split signature first, volumes of `volume_size`
+    /// bytes, and a central directory in the last volume that counts offsets per volume.
+    fn write_spanned_zip(
+        dir: &Path,
+        base: &str,
+        volume_size: usize,
+        entries: &[(&str, &[u8])],
+    ) -> Vec<PathBuf> {
+        let single = dir.join(format!("{base}.single"));
+        write_zip(&single, entries);
+        let bytes = std::fs::read(&single).unwrap();
+        std::fs::remove_file(&single).unwrap();
+
+        let eocd_start = bytes.len() - ZIP_END_OF_CENTRAL_DIRECTORY_LEN;
+        let directory_start = read_u32_le(&bytes, eocd_start + 16) as usize;
+        let mut data = b"PK\x07\x08".to_vec();
+        data.extend_from_slice(&bytes[..directory_start]);
+        let mut chunks = data
+            .chunks(volume_size)
+            .map(<[u8]>::to_vec)
+            .collect::<Vec<_>>();
+        let last_disk = chunks.len() - 1;
+        let mut directory = bytes[directory_start..eocd_start].to_vec();
+        let mut index = 0;
+
+        while index < directory.len() {
+            let offset = read_u32_le(&directory, index + 42) as usize + 4;
+            let disk = (offset / volume_size) as u16;
+            let relative = (offset % volume_size) as u32;
+            directory[index + 34..index + 36].copy_from_slice(&disk.to_le_bytes());
+            directory[index + 42..index + 46].copy_from_slice(&relative.to_le_bytes());
+            index += ZIP_CENTRAL_HEADER_LEN
+                + read_u16_le(&directory, index + 28) as usize
+                + read_u16_le(&directory, index + 30) as usize
+                + read_u16_le(&directory, index + 32) as usize;
+        }
+
+        let mut eocd = bytes[eocd_start..].to_vec();
+        eocd[4..6].copy_from_slice(&(last_disk as u16).to_le_bytes());
+        eocd[6..8].copy_from_slice(&(last_disk as u16).to_le_bytes());
+        eocd[16..20].copy_from_slice(&(chunks[last_disk].len() as u32).to_le_bytes());
+        chunks[last_disk].extend_from_slice(&directory);
+        chunks[last_disk].extend_from_slice(&eocd);
+
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(disk, chunk)| {
+                let name = if disk == last_disk {
+                    format!("{base}.zip")
+                } else {
+                    format!("{base}.z{:02}", disk + 1)
+                };
+                let path = dir.join(name);
+                std::fs::write(&path, chunk).unwrap();
+                path
+            })
+            .collect()
+    }
+
     fn test_dir(name: &str) -> PathBuf {
         let unique = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -35,6 +35,22 @@ impl CredentialRef {
         Self::new(format!("account:{account_id}:password"))
     }
 
+    pub fn account_session(account_id: &str) -> Result<Self> {
+        validate_identifier(account_id).map_err(CredentialsError::InvalidCredentialRef)?;
+        Self::new(format!("account:{account_id}:session"))
+    }
+
+    pub fn account_proxy_password(account_id: &str) -> Result<Self> {
+        validate_identifier(account_id).map_err(CredentialsError::InvalidCredentialRef)?;
+        Self::new(format!("account:{account_id}:proxy-password"))
+    }
+
+    /// Password the user entered for a work's encrypted archives.
+    pub fn work_archive_password(work_id: &str) -> Result<Self> {
+        validate_identifier(work_id).map_err(CredentialsError::InvalidCredentialRef)?;
+        Self::new(format!("work:{work_id}:archive-password"))
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
@@ -50,6 +66,9 @@ pub trait CredentialStore: Send + Sync {
     fn save_password(&self, credential_ref: &CredentialRef, password: &str) -> Result<()>;
     fn load_password(&self, credential_ref: &CredentialRef) -> Result<Option<String>>;
     fn delete_password(&self, credential_ref: &CredentialRef) -> Result<()>;
+    fn save_session(&self, session_ref: &CredentialRef, session: &str) -> Result<()>;
+    fn load_session(&self, session_ref: &CredentialRef) -> Result<Option<String>>;
+    fn delete_session(&self, session_ref: &CredentialRef) -> Result<()>;
 }
 
 #[derive(Debug, Clone)]
@@ -130,17 +149,55 @@ impl CredentialStore for LocalCredentialStore {
         file.passwords.remove(credential_ref.as_str());
         self.save_file(&file)
     }
+
+    fn save_session(&self, session_ref: &CredentialRef, session: &str) -> Result<()> {
+        let _guard = self
+            .file_lock
+            .lock()
+            .map_err(|_| CredentialsError::StorePoisoned)?;
+        let mut file = self.load_file()?;
+
+        file.sessions
+            .insert(session_ref.as_str().to_owned(), session.to_owned());
+        self.save_file(&file)
+    }
+
+    fn load_session(&self, session_ref: &CredentialRef) -> Result<Option<String>> {
+        let _guard = self
+            .file_lock
+            .lock()
+            .map_err(|_| CredentialsError::StorePoisoned)?;
+        Ok(self
+            .load_file()?
+            .sessions
+            .get(session_ref.as_str())
+            .cloned())
+    }
+
+    fn delete_session(&self, session_ref: &CredentialRef) -> Result<()> {
+        let _guard = self
+            .file_lock
+            .lock()
+            .map_err(|_| CredentialsError::StorePoisoned)?;
+        let mut file = self.load_file()?;
+
+        file.sessions.remove(session_ref.as_str());
+        self.save_file(&file)
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 struct PasswordFile {
     #[serde(default)]
     passwords: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    sessions: HashMap<String, String>,
 }
 
 #[derive(Clone, Default)]
 pub struct InMemoryCredentialStore {
     passwords: Arc<Mutex<HashMap<CredentialRef, String>>>,
+    sessions: Arc<Mutex<HashMap<CredentialRef, String>>>,
 }
 
 impl fmt::Debug for InMemoryCredentialStore {
@@ -148,6 +205,7 @@ impl fmt::Debug for InMemoryCredentialStore {
         formatter
             .debug_struct("InMemoryCredentialStore")
             .field("passwords", &"<redacted>")
+            .field("sessions", &"<redacted>")
             .finish()
     }
 }
@@ -184,6 +242,32 @@ impl CredentialStore for InMemoryCredentialStore {
         passwords.remove(credential_ref);
         Ok(())
     }
+
+    fn save_session(&self, session_ref: &CredentialRef, session: &str) -> Result<()> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| CredentialsError::StorePoisoned)?;
+        sessions.insert(session_ref.clone(), session.to_owned());
+        Ok(())
+    }
+
+    fn load_session(&self, session_ref: &CredentialRef) -> Result<Option<String>> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| CredentialsError::StorePoisoned)?;
+        Ok(sessions.get(session_ref).cloned())
+    }
+
+    fn delete_session(&self, session_ref: &CredentialRef) -> Result<()> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| CredentialsError::StorePoisoned)?;
+        sessions.remove(session_ref);
+        Ok(())
+    }
 }
 
 fn write_password_file(path: &Path, file: &PasswordFile) -> Result<()> {
@@ -274,6 +358,18 @@ mod tests {
         let credential_ref = CredentialRef::account_password("local-account-id")?;
 
         assert_eq!(credential_ref.as_str(), "account:local-account-id:password");
+        assert_eq!(
+            CredentialRef::account_session("local-account-id")?.as_str(),
+            "account:local-account-id:session"
+        );
+        assert_eq!(
+            CredentialRef::account_proxy_password("local-account-id")?.as_str(),
+            "account:local-account-id:proxy-password"
+        );
+        assert_eq!(
+            CredentialRef::work_archive_password("RJ123456")?.as_str(),
+            "work:RJ123456:archive-password"
+        );
 
         Ok(())
     }
@@ -351,6 +447,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn local_store_keeps_sessions_apart_from_passwords() -> Result<()> {
+        let directory = tempfile::tempdir()?;
+        let path = directory.path().join("vault.json");
+        let password_ref = CredentialRef::account_password("account-a")?;
+        let session_ref = CredentialRef::account_session("account-a")?;
+
+        let store = LocalCredentialStore::open(&path)?;
+        store.save_password(&password_ref, "secret")?;
+        store.save_session(&session_ref, r#"{"cookies":[]}"#)?;
+
+        let reopened = LocalCredentialStore::open(&path)?;
+        assert_eq!(
+            reopened.load_session(&session_ref)?,
+            Some(r#"{"cookies":[]}"#.to_owned())
+        );
+        assert_eq!(reopened.load_password(&session_ref)?, None);
+
+        reopened.delete_session(&session_ref)?;
+        reopened.delete_session(&session_ref)?;
+        assert_eq!(store.load_session(&session_ref)?, None);
+        assert_eq!(
+            store.load_password(&password_ref)?,
+            Some("secret".to_owned())
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn local_store_delete_is_idempotent() -> Result<()> {
         let directory = tempfile::tempdir()?;
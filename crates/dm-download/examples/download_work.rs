@@ -1,7 +1,8 @@
 use dm_api::{Credentials, DlsiteClient, DlsiteClientConfig, WorkId};
 use dm_download::{
-    download_work_files, probe_download_file_metadata, CancellationToken, DownloadJobRequest,
-    DownloadPhase, UnpackPolicy,
+    download_work_files, probe_download_file_metadata, ArchiveNameEncoding, CancellationToken,
+    DownloadJobRequest, DownloadPhase, RetryPolicy, StallPolicy, UnpackPolicy,
+    DEFAULT_CONNECTIONS_PER_FILE, DEFAULT_PARALLEL_FILES,
 };
 use std::{
     env,
@@ -145,6 +146,13 @@ async fn download_one_work(
         work_id: work_id.clone(),
         target_root: target_root.to_path_buf(),
         unpack_policy: env.unpack_policy,
+        archive_name_encoding: ArchiveNameEncoding::Auto,
+        archive_passwords: Vec::new(),
+        nested_archives: None,
+        connections_per_file: DEFAULT_CONNECTIONS_PER_FILE,
+        parallel_files: DEFAULT_PARALLEL_FILES,
+        retry_policy: RetryPolicy::default(),
+        stall_policy: StallPolicy::default(),
     };
     let cancellation = CancellationToken::new();
     let mut last_reported = vec![None; plan.files.len()];
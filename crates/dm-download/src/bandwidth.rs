@@ -0,0 +1,256 @@
+use chrono::{Local, Timelike};
+use std::{
+    fmt,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::{sync::Mutex, time::Instant};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+/// Longest single wait, so a changed policy takes effect while a download is throttled.
+const MAX_WAIT_SLICE: Duration = Duration::from_millis(250);
+
+/// Time-of-day window with its own limit. A window whose end is before its start wraps past
+/// midnight; equal start and end cover the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandwidthSchedule {
+    pub start_minute: u32,
+    pub end_minute: u32,
+    /// `None` lifts the limit inside the window.
+    pub bytes_per_second: Option<u64>,
+}
+
+impl BandwidthSchedule {
+    fn contains(&self, minute_of_day: u32) -> bool {
+        let start = self.start_minute % MINUTES_PER_DAY;
+        let end = self.end_minute % MINUTES_PER_DAY;
+
+        match start.cmp(&end) {
+            std::cmp::Ordering::Less => (start..end).contains(&minute_of_day),
+            std::cmp::Ordering::Greater => minute_of_day >= start || minute_of_day < end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BandwidthPolicy {
+    /// Limit outside every schedule; `None` means unlimited.
+    pub bytes_per_second: Option<u64>,
+    /// Checked in order; the first window containing the current time wins.
+    pub schedules: Vec<BandwidthSchedule>,
+}
+
+impl BandwidthPolicy {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn limit_at(&self, minute_of_day: u32) -> Option<u64> {
+        self.schedules
+            .iter()
+            .find(|schedule| schedule.contains(minute_of_day))
+            .map_or(self.bytes_per_second, |schedule| schedule.bytes_per_second)
+            .filter(|limit| *limit > 0)
+    }
+}
+
+type TimeOfDay = Arc<dyn Fn() -> u32 + Send + Sync>;
+
+/// Token bucket shared by every download in the process.
+///
+/// Clones share the same bucket. The bucket holds at most one second of traffic, so a lifted
+/// limit cannot be followed by an unbounded burst.
+#[derive(Clone)]
+pub struct BandwidthLimiter {
+    state: Arc<Mutex<LimiterState>>,
+    time_of_day: TimeOfDay,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    policy: BandwidthPolicy,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl BandwidthLimiter {
+    pub fn new(policy: BandwidthPolicy) -> Self {
+        Self::with_time_of_day(policy, local_minute_of_day)
+    }
+
+    /// Uses `time_of_day` (minutes since midnight) to pick the active schedule.
+    pub fn with_time_of_day(
+        policy: BandwidthPolicy,
+        time_of_day: impl Fn() -> u32 + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(LimiterState {
+                policy,
+                tokens: 0.0,
+                refilled_at: Instant::now(),
+            })),
+            time_of_day: Arc::new(time_of_day),
+        }
+    }
+
+    /// The limiter every `download_file` call draws from.
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<BandwidthLimiter> = OnceLock::new();
+
+        GLOBAL.get_or_init(|| Self::new(BandwidthPolicy::unlimited()))
+    }
+
+    pub async fn policy(&self) -> BandwidthPolicy {
+        self.state.lock().await.policy.clone()
+    }
+
+    /// Replaces the policy; downloads waiting on the old limit pick up the new one.
+    pub async fn set_policy(&self, policy: BandwidthPolicy) {
+        let mut state = self.state.lock().await;
+
+        state.policy = policy;
+        state.tokens = state.tokens.min(0.0);
+        state.refilled_at = Instant::now();
+    }
+
+    pub async fn current_limit(&self) -> Option<u64> {
+        let minute_of_day = (self.time_of_day)();
+
+        self.state.lock().await.policy.limit_at(minute_of_day)
+    }
+
+    /// Waits until `bytes` may be transferred under the active limit.
+    pub async fn acquire(&self, bytes: u64) {
+        loop {
+            let wait = {
+                let minute_of_day = (self.time_of_day)();
+                let mut state = self.state.lock().await;
+                let now = Instant::now();
+                let Some(rate) = state.policy.limit_at(minute_of_day) else {
+                    state.tokens = 0.0;
+                    state.refilled_at = now;
+                    return;
+                };
+                let rate = rate as f64;
+                let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * rate).min(rate);
+                state.refilled_at = now;
+
+                // Chunks larger than the bucket go through once it is full and leave a debt.
+                let needed = (bytes as f64).min(rate);
+
+                if state.tokens >= needed {
+                    state.tokens -= bytes as f64;
+                    return;
+                }
+
+                Duration::from_secs_f64((needed - state.tokens) / rate).min(MAX_WAIT_SLICE)
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl fmt::Debug for BandwidthLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BandwidthLimiter").finish_non_exhaustive()
+    }
+}
+
+fn local_minute_of_day() -> u32 {
+    let now = Local::now();
+
+    now.hour() * 60 + now.minute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn schedules_pick_the_first_matching_window() {
+        let policy = night_unlimited_policy();
+
+        assert_eq!(policy.limit_at(0), Some(2 * MIB));
+        assert_eq!(policy.limit_at(60), None);
+        assert_eq!(policy.limit_at(419), None);
+        assert_eq!(policy.limit_at(420), Some(2 * MIB));
+
+        let wrapping = BandwidthSchedule {
+            start_minute: 22 * 60,
+            end_minute: 2 * 60,
+            bytes_per_second: Some(MIB),
+        };
+
+        assert!(wrapping.contains(23 * 60));
+        assert!(wrapping.contains(60));
+        assert!(!wrapping.contains(12 * 60));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paces_transfers_to_the_configured_rate() {
+        let limiter = BandwidthLimiter::with_time_of_day(
+            BandwidthPolicy {
+                bytes_per_second: Some(MIB),
+                schedules: Vec::new(),
+            },
+            || 12 * 60,
+        );
+        let start = Instant::now();
+
+        for _ in 0..8 {
+            limiter.acquire(MIB / 2).await;
+        }
+
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(3900), "{elapsed:?}");
+        assert!(elapsed <= Duration::from_millis(4300), "{elapsed:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn follows_schedules_and_policy_changes_while_running() {
+        let minute = Arc::new(AtomicU32::new(12 * 60));
+        let clock = minute.clone();
+        let limiter = BandwidthLimiter::with_time_of_day(night_unlimited_policy(), move || {
+            clock.load(Ordering::SeqCst)
+        });
+
+        assert_eq!(limiter.current_limit().await, Some(2 * MIB));
+
+        minute.store(3 * 60, Ordering::SeqCst);
+        let start = Instant::now();
+        limiter.acquire(64 * MIB).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        minute.store(12 * 60, Ordering::SeqCst);
+        let throttled = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                limiter.acquire(2 * MIB).await;
+                limiter.acquire(2 * MIB).await;
+                Instant::now()
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        limiter.set_policy(BandwidthPolicy::unlimited()).await;
+        let finished_at = throttled.await.unwrap();
+
+        assert!(finished_at - start < Duration::from_millis(1500));
+    }
+
+    fn night_unlimited_policy() -> BandwidthPolicy {
+        BandwidthPolicy {
+            bytes_per_second: Some(2 * MIB),
+            schedules: vec![BandwidthSchedule {
+                start_minute: 60,
+                end_minute: 7 * 60,
+                bytes_per_second: None,
+            }],
+        }
+    }
+}
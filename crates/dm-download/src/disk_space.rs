@@ -0,0 +1,58 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Free bytes available to this process on the volume holding `path`.
+///
+/// `path` doesn't have to exist yet; the nearest existing ancestor is queried instead.
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    fs4::available_space(existing_ancestor(path)?)
+}
+
+/// Whether two paths live on the same volume, so moving between them is a rename.
+pub fn same_volume(left: &Path, right: &Path) -> io::Result<bool> {
+    let left = existing_ancestor(left)?;
+    let right = existing_ancestor(right)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        Ok(std::fs::metadata(left)?.dev() == std::fs::metadata(right)?.dev())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let left = std::fs::canonicalize(left)?;
+        let right = std::fs::canonicalize(right)?;
+
+        Ok(left.components().next() == right.components().next())
+    }
+}
+
+fn existing_ancestor(path: &Path) -> io::Result<PathBuf> {
+    for ancestor in path.ancestors() {
+        if ancestor.try_exists()? {
+            return Ok(ancestor.to_path_buf());
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("no existing ancestor for {}", path.display()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_the_nearest_existing_ancestor() {
+        let missing = std::env::temp_dir().join("dm-download-disk-space/not/created/yet");
+
+        assert!(available_space(&missing).unwrap() > 0);
+        assert!(same_volume(&missing, &std::env::temp_dir()).unwrap());
+    }
+}
@@ -1,40 +1,102 @@
+mod bandwidth;
+mod disk_space;
+mod manifest;
+mod resume;
+mod retry;
+mod stream_unpack;
+
+pub use bandwidth::{BandwidthLimiter, BandwidthPolicy, BandwidthSchedule};
+pub use disk_space::{available_space, same_volume};
 use dm_api::{
     DlsiteClient, DownloadByteRange, DownloadFile, DownloadFileKind, DownloadPlan, DownloadStream,
     DownloadStreamRequest, WorkId,
 };
 use dm_archive::{ArchiveExtractOptions, ArchiveExtraction, ArchivePlan};
+pub use dm_archive::{ArchiveNameEncoding, ArchivePassword, NestedArchiveOptions};
+pub use manifest::{
+    build_file_manifest, verify_file_manifest, FileManifestEntry, ManifestVerification,
+};
+pub use resume::RemoteFileValidators;
+use resume::{load_resume_validators, resume_sidecar_path, save_resume_validators};
+pub use retry::{
+    BackoffCurve, RetryPolicy, RetryableErrors, DEFAULT_INITIAL_RETRY_DELAY,
+    DEFAULT_MAX_RETRY_DELAY, DEFAULT_RETRY_JITTER_PERCENT,
+};
+use retry::{RetryState, SharedRetryState};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt,
     future::Future,
     path::{Component, Path, PathBuf},
     pin::Pin,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, Mutex,
     },
 };
+use stream_unpack::StreamingUnpack;
 use tokio::{
     fs::{self, OpenOptions},
-    io::AsyncWriteExt,
-    time::{sleep, Duration},
+    io::{AsyncSeekExt, AsyncWriteExt},
+    sync::mpsc,
+    task::JoinSet,
+    time::{sleep, timeout, Duration, Instant},
 };
 use url::Url;
 
 pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_CONNECTIONS_PER_FILE: usize = 1;
+pub const MAX_CONNECTIONS_PER_FILE: usize = 16;
+pub const DEFAULT_PARALLEL_FILES: usize = 1;
+pub const MAX_PARALLEL_FILES: usize = 8;
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(60);
+pub const DEFAULT_THROUGHPUT_WINDOW: Duration = Duration::from_secs(30);
 const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const MIN_SEGMENT_SIZE: u64 = 1024 * 1024;
+const SEGMENT_STATE_PERSIST_INTERVAL: u64 = 4 * 1024 * 1024;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DownloadJobRequest {
     pub work_id: WorkId,
     pub target_root: PathBuf,
     pub unpack_policy: UnpackPolicy,
+    pub archive_name_encoding: ArchiveNameEncoding,
+    /// Tried in order on encrypted archives.
+    pub archive_passwords: Vec<ArchivePassword>,
+    /// Unset leaves archives found inside the work's archives packed.
+    pub nested_archives: Option<NestedArchiveOptions>,
+    /// Connections used per file; `1` keeps the single-stream download.
+    pub connections_per_file: usize,
+    /// Files of the plan fetched at once; `1` downloads them one after another.
+    pub parallel_files: usize,
+    pub retry_policy: RetryPolicy,
+    pub stall_policy: StallPolicy,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UnpackPolicy {
     KeepArchives,
     UnpackWhenRecognized,
+    /// Unpacks like `UnpackWhenRecognized` but leaves the archives next to the extracted files.
+    UnpackAndKeepArchives,
+}
+
+impl DownloadJobRequest {
+    fn extract_options(&self) -> ArchiveExtractOptions {
+        ArchiveExtractOptions {
+            remove_sources: self.unpack_policy != UnpackPolicy::UnpackAndKeepArchives,
+            name_encoding: self.archive_name_encoding,
+            passwords: self.archive_passwords.clone(),
+            nested: self.nested_archives,
+            ..ArchiveExtractOptions::default()
+        }
+    }
+}
+
+impl UnpackPolicy {
+    pub fn unpacks(self) -> bool {
+        self != Self::KeepArchives
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -44,6 +106,8 @@ pub struct DownloadProgress {
     pub file_kind: Option<DownloadFileKind>,
     pub bytes_received: u64,
     pub bytes_total: Option<u64>,
+    /// Set on the update sent right before a connection is reopened.
+    pub retry: Option<DownloadRetry>,
 }
 
 impl DownloadProgress {
@@ -59,6 +123,36 @@ impl DownloadProgress {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadRetry {
+    pub attempt: u32,
+    pub max_retries: u32,
+    /// Wait before the connection is reopened.
+    pub delay: Duration,
+    /// Why the previous connection was given up.
+    pub reason: String,
+}
+
+/// When a silent or crawling connection is dropped and reopened from the current offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StallPolicy {
+    /// Longest wait for the next chunk of a response.
+    pub idle_timeout: Duration,
+    /// Lowest acceptable average throughput over each window; unset disables the check.
+    pub min_bytes_per_second: Option<u64>,
+    pub throughput_window: Duration,
+}
+
+impl Default for StallPolicy {
+    fn default() -> Self {
+        Self {
+            idle_timeout: DEFAULT_STALL_TIMEOUT,
+            min_bytes_per_second: None,
+            throughput_window: DEFAULT_THROUGHPUT_WINDOW,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DownloadFileRequest {
     pub file_index: usize,
@@ -66,7 +160,11 @@ pub struct DownloadFileRequest {
     pub target_dir: PathBuf,
     pub file_name: String,
     pub expected_size: Option<u64>,
-    pub max_retries: u32,
+    pub retry_policy: RetryPolicy,
+    pub connections: usize,
+    /// Leaves the staging folder in place for sibling downloads that share it.
+    pub shared_staging_dir: bool,
+    pub stall_policy: StallPolicy,
 }
 
 impl DownloadFileRequest {
@@ -82,7 +180,10 @@ impl DownloadFileRequest {
             target_dir: target_dir.into(),
             file_name: file_name.into(),
             expected_size: None,
-            max_retries: DEFAULT_MAX_RETRIES,
+            retry_policy: RetryPolicy::default(),
+            connections: DEFAULT_CONNECTIONS_PER_FILE,
+            shared_staging_dir: false,
+            stall_policy: StallPolicy::default(),
         }
     }
 }
@@ -93,6 +194,8 @@ pub struct DownloadedFile {
     pub path: PathBuf,
     pub bytes_written: u64,
     pub resumed_from: u64,
+    /// Why a stale partial file was thrown away before this download restarted from zero.
+    pub discarded_partial: Option<InvalidDownloadResponseReason>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -142,6 +245,16 @@ pub trait RangedDownloadSource {
 
 pub trait DownloadByteStream {
     fn next_chunk<'a>(&'a mut self) -> DownloadChunkFuture<'a>;
+
+    fn validators(&self) -> RemoteFileValidators {
+        RemoteFileValidators::default()
+    }
+
+    /// Offset in the remote file of the first byte of this response; `None` when the server
+    /// didn't say.
+    fn body_start(&self) -> Option<u64> {
+        None
+    }
 }
 
 #[derive(Clone)]
@@ -169,8 +282,20 @@ impl RangedDownloadSource for DlsiteDownloadSource {
                     Some(DownloadByteRange::from_start(start)),
                 )
                 .await?;
+            let headers = stream.headers();
+            let validators = RemoteFileValidators::from_headers(&headers);
+            // A plain 200 means the server ignored the range and sent the whole file.
+            let body_start = if stream.status().as_u16() == 206 {
+                header_value(&headers, "content-range").and_then(start_from_content_range)
+            } else {
+                Some(0)
+            };
             let stream: Box<dyn DownloadByteStream + Send + 'a> =
-                Box::new(DlsiteDownloadByteStream { stream });
+                Box::new(DlsiteDownloadByteStream {
+                    stream,
+                    validators,
+                    body_start,
+                });
 
             Ok(stream)
         })
@@ -179,6 +304,8 @@ impl RangedDownloadSource for DlsiteDownloadSource {
 
 struct DlsiteDownloadByteStream {
     stream: DownloadStream,
+    validators: RemoteFileValidators,
+    body_start: Option<u64>,
 }
 
 impl DownloadByteStream for DlsiteDownloadByteStream {
@@ -191,6 +318,14 @@ impl DownloadByteStream for DlsiteDownloadByteStream {
                 .map_err(|err| DownloadError::Stream(err.to_string()))
         })
     }
+
+    fn validators(&self) -> RemoteFileValidators {
+        self.validators.clone()
+    }
+
+    fn body_start(&self) -> Option<u64> {
+        self.body_start
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -250,6 +385,18 @@ pub enum DownloadError {
     IncompleteDownload { expected: u64, actual: u64 },
     #[error("download exceeded expected size; expected {expected} bytes, got {actual}")]
     SizeExceeded { expected: u64, actual: u64 },
+    #[error("download segments do not cover the file contiguously at byte {offset}")]
+    SegmentGap { offset: u64 },
+    #[error("path is not valid UTF-8: {path}")]
+    NonUtf8Path { path: PathBuf },
+    #[error("download stalled: {reason}")]
+    Stalled { reason: StallReason },
+    #[error("not enough free space on {path}; need {required} bytes, {available} available")]
+    InsufficientDiskSpace {
+        path: PathBuf,
+        required: u64,
+        available: u64,
+    },
     #[error("download stream error: {0}")]
     Stream(String),
     #[error("dlsite api error")]
@@ -262,8 +409,19 @@ pub enum DownloadError {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InvalidDownloadResponseReason {
-    HtmlContentType { content_type: String },
+    HtmlContentType {
+        content_type: String,
+    },
     HtmlDocumentBody,
+    RemoteFileChanged {
+        validator: &'static str,
+        previous: String,
+        current: String,
+    },
+    RangeIgnored {
+        requested: u64,
+        served: Option<u64>,
+    },
 }
 
 impl fmt::Display for InvalidDownloadResponseReason {
@@ -273,6 +431,51 @@ impl fmt::Display for InvalidDownloadResponseReason {
                 write!(f, "response content type is HTML ({content_type})")
             }
             Self::HtmlDocumentBody => write!(f, "response body looks like an HTML document"),
+            Self::RemoteFileChanged {
+                validator,
+                previous,
+                current,
+            } => write!(
+                f,
+                "remote file changed since the partial download ({validator} was {previous}, now {current})"
+            ),
+            Self::RangeIgnored {
+                requested,
+                served: Some(served),
+            } => write!(
+                f,
+                "server answered a request for byte {requested} with data from byte {served}"
+            ),
+            Self::RangeIgnored {
+                requested,
+                served: None,
+            } => write!(
+                f,
+                "server didn't confirm a request for data from byte {requested}"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StallReason {
+    Idle { timeout: Duration },
+    TooSlow { bytes_per_second: u64, minimum: u64 },
+}
+
+impl fmt::Display for StallReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Idle { timeout } => {
+                write!(f, "no data received for {}s", timeout.as_secs_f64())
+            }
+            Self::TooSlow {
+                bytes_per_second,
+                minimum,
+            } => write!(
+                f,
+                "throughput of {bytes_per_second} B/s is below the minimum of {minimum} B/s"
+            ),
         }
     }
 }
@@ -296,7 +499,6 @@ where
 
     let target_dir = job.target_root.join(job.work_id.as_ref());
     let mut file_metadata = Vec::with_capacity(plan.files.len());
-    let mut downloaded_files = Vec::with_capacity(plan.files.len());
 
     for (file_index, file) in plan.files.iter().enumerate() {
         if cancellation.is_cancelled() {
@@ -309,6 +511,7 @@ where
             file_kind: Some(file.kind.clone()),
             bytes_received: 0,
             bytes_total: None,
+            retry: None,
         });
 
         let metadata = cancellable(
@@ -324,6 +527,124 @@ where
             .iter()
             .map(|(metadata, _stream_request)| metadata),
     );
+    let streaming_unpack = streamable_zip(job, &file_metadata, &target_dir)?
+        .map(|file_name| StreamingUnpack::start(&target_dir, file_name, job.archive_name_encoding));
+    let mut observed_progress = |progress: DownloadProgress| {
+        if let Some(streaming_unpack) = &streaming_unpack {
+            streaming_unpack.observe(&progress);
+        }
+
+        on_progress(progress);
+    };
+    let parallel_files = job.parallel_files.clamp(1, MAX_PARALLEL_FILES);
+    let downloaded_files = if parallel_files > 1 && file_metadata.len() > 1 {
+        download_files_in_parallel(
+            &client,
+            job,
+            file_metadata,
+            &target_dir,
+            aggregate_bytes_total,
+            cancellation,
+            &mut observed_progress,
+        )
+        .await?
+    } else {
+        download_files_in_order(
+            &client,
+            job,
+            file_metadata,
+            &target_dir,
+            aggregate_bytes_total,
+            cancellation,
+            &mut observed_progress,
+        )
+        .await?
+    };
+    let completed_bytes = downloaded_files.iter().map(|file| file.bytes_written).sum();
+
+    let archive_plan = plan_downloaded_archive(&downloaded_files);
+
+    if job.unpack_policy.unpacks()
+        && matches!(
+            archive_plan,
+            ArchivePlan::SingleZip { .. }
+                | ArchivePlan::SevenZip { .. }
+                | ArchivePlan::LegacySplitRar { .. }
+                | ArchivePlan::MultiVolumeRar { .. }
+                | ArchivePlan::SplitZip { .. }
+        )
+    {
+        on_progress(DownloadProgress {
+            phase: DownloadPhase::Unpacking,
+            file_index: None,
+            file_kind: None,
+            bytes_received: completed_bytes,
+            bytes_total: aggregate_bytes_total,
+            retry: None,
+        });
+    }
+
+    let streamed = match streaming_unpack {
+        Some(streaming_unpack) => {
+            let intact = downloaded_files
+                .iter()
+                .all(|file| file.discarded_partial.is_none());
+            streaming_unpack.finish(intact).await
+        }
+        None => None,
+    };
+
+    if cancellation.is_cancelled() {
+        return Err(DownloadError::Cancelled);
+    }
+
+    let streamed_extraction = match (streamed, &archive_plan) {
+        (Some(extractor), ArchivePlan::SingleZip { archive }) => {
+            finish_streamed_extraction(extractor, archive, &target_dir, job.extract_options())?
+        }
+        _ => None,
+    };
+    let archive_extraction = match streamed_extraction {
+        Some(extraction) => Some(extraction),
+        None => {
+            if job.unpack_policy.unpacks() {
+                ensure_unpack_space(&archive_plan, &target_dir, completed_bytes)?;
+            }
+
+            unpack_downloaded_archive_plan(
+                archive_plan,
+                &target_dir,
+                job.unpack_policy,
+                job.extract_options(),
+            )?
+        }
+    };
+
+    if cancellation.is_cancelled() {
+        return Err(DownloadError::Cancelled);
+    }
+
+    Ok(DownloadedWork {
+        work_id: job.work_id.clone(),
+        target_dir,
+        files: downloaded_files,
+        archive_extraction,
+    })
+}
+
+async fn download_files_in_order<F>(
+    client: &DlsiteClient,
+    job: &DownloadJobRequest,
+    file_metadata: Vec<(DownloadFileMetadata, DownloadStreamRequest)>,
+    target_dir: &Path,
+    aggregate_bytes_total: Option<u64>,
+    cancellation: &CancellationToken,
+    on_progress: &mut F,
+) -> Result<Vec<DownloadedFile>, DownloadError>
+where
+    F: FnMut(DownloadProgress),
+{
+    let mut downloaded_files = Vec::with_capacity(file_metadata.len());
     let mut completed_bytes = 0;
 
     for (metadata, stream_request) in file_metadata {
@@ -331,8 +652,11 @@ where
             return Err(DownloadError::Cancelled);
         }
 
-        let request = metadata.to_file_request(target_dir.clone());
-        let mut source = DlsiteDownloadSource::new(client.clone(), stream_request);
+        let mut request = metadata.to_file_request(target_dir);
+        request.connections = job.connections_per_file;
+        request.retry_policy = job.retry_policy;
+        request.stall_policy = job.stall_policy;
+        let source = DlsiteDownloadSource::new(client.clone(), stream_request);
         let file_offset = completed_bytes;
         let mut aggregate_progress = |progress| {
             on_progress(aggregate_file_progress(
@@ -342,7 +666,8 @@ where
             ));
         };
         let downloaded =
-            download_file(&mut source, &request, cancellation, &mut aggregate_progress).await?;
+            download_file_segmented(&source, &request, cancellation, &mut aggregate_progress)
+                .await?;
 
         completed_bytes = completed_bytes.saturating_add(downloaded.bytes_written);
         on_progress(DownloadProgress {
@@ -351,48 +676,147 @@ where
             file_kind: Some(metadata.file_kind.clone()),
             bytes_received: completed_bytes,
             bytes_total: aggregate_bytes_total,
+            retry: None,
         });
 
         downloaded_files.push(downloaded);
     }
 
-    let archive_plan = plan_downloaded_archive(&downloaded_files);
-    if job.unpack_policy == UnpackPolicy::UnpackWhenRecognized
-        && matches!(
-            archive_plan,
-            ArchivePlan::SingleZip { .. } | ArchivePlan::LegacySplitRar { .. }
-        )
+    Ok(downloaded_files)
+}
+
+/// Fetches up to `job.parallel_files` files at once and reports their combined progress.
+async fn download_files_in_parallel<F>(
+    client: &DlsiteClient,
+    job: &DownloadJobRequest,
+    file_metadata: Vec<(DownloadFileMetadata, DownloadStreamRequest)>,
+    target_dir: &Path,
+    aggregate_bytes_total: Option<u64>,
+    cancellation: &CancellationToken,
+    on_progress: &mut F,
+) -> Result<Vec<DownloadedFile>, DownloadError>
+where
+    F: FnMut(DownloadProgress),
+{
+    let mut received = vec![0u64; file_metadata.len()];
+    let mut downloaded_files = Vec::with_capacity(file_metadata.len());
+    let mut pending = file_metadata.into_iter();
+    let (progress_sender, mut progress_receiver) = mpsc::unbounded_channel();
+    let files_cancellation = CancellationToken::new();
+    let mut tasks = JoinSet::new();
+
+    for entry in pending
+        .by_ref()
+        .take(job.parallel_files.clamp(1, MAX_PARALLEL_FILES))
     {
-        on_progress(DownloadProgress {
-            phase: DownloadPhase::Unpacking,
-            file_index: None,
-            file_kind: None,
-            bytes_received: completed_bytes,
-            bytes_total: aggregate_bytes_total,
-        });
+        spawn_file_download(
+            &mut tasks,
+            client,
+            job,
+            target_dir,
+            entry,
+            &files_cancellation,
+            &progress_sender,
+        );
     }
 
-    if cancellation.is_cancelled() {
-        return Err(DownloadError::Cancelled);
+    let mut failure = None;
+
+    loop {
+        // Progress goes first: a finished task has already queued all of its updates.
+        tokio::select! {
+            biased;
+
+            Some(progress) = progress_receiver.recv() => {
+                if progress.phase != DownloadPhase::Downloading {
+                    continue;
+                }
+
+                if let Some(file_index) = progress.file_index {
+                    received[file_index] = progress.bytes_received;
+                }
+
+                on_progress(DownloadProgress {
+                    bytes_received: received.iter().sum(),
+                    bytes_total: aggregate_bytes_total,
+                    ..progress
+                });
+            }
+            joined = tasks.join_next() => match joined {
+                Some(Ok(Ok((file_index, downloaded)))) => {
+                    received[file_index] = downloaded.bytes_written;
+                    downloaded_files.push((file_index, downloaded));
+
+                    if let Some(entry) = pending.next() {
+                        spawn_file_download(
+                            &mut tasks,
+                            client,
+                            job,
+                            target_dir,
+                            entry,
+                            &files_cancellation,
+                            &progress_sender,
+                        );
+                    }
+                }
+                Some(Ok(Err(err))) => {
+                    failure = Some(err);
+                    break;
+                }
+                Some(Err(err)) => {
+                    failure = Some(std::io::Error::other(err).into());
+                    break;
+                }
+                None => break,
+            },
+            _ = cancellation.cancelled() => {
+                failure = Some(DownloadError::Cancelled);
+                break;
+            }
+        }
     }
 
-    let archive_extraction = unpack_downloaded_archive_plan(
-        archive_plan,
-        &target_dir,
-        job.unpack_policy,
-        ArchiveExtractOptions::default(),
-    )?;
+    files_cancellation.cancel();
+    while tasks.join_next().await.is_some() {}
+    // The parts share one staging folder, so it is only removed once none of them can use it.
+    fs::remove_dir(staging_dir_for(target_dir)).await.ok();
 
-    if cancellation.is_cancelled() {
-        return Err(DownloadError::Cancelled);
+    if let Some(err) = failure {
+        return Err(err);
     }
 
-    Ok(DownloadedWork {
-        work_id: job.work_id.clone(),
-        target_dir,
-        files: downloaded_files,
-        archive_extraction,
-    })
+    downloaded_files.sort_by_key(|(file_index, _downloaded)| *file_index);
+    Ok(downloaded_files
+        .into_iter()
+        .map(|(_file_index, downloaded)| downloaded)
+        .collect())
+}
+
+fn spawn_file_download(
+    tasks: &mut JoinSet<Result<(usize, DownloadedFile), DownloadError>>,
+    client: &DlsiteClient,
+    job: &DownloadJobRequest,
+    target_dir: &Path,
+    (metadata, stream_request): (DownloadFileMetadata, DownloadStreamRequest),
+    cancellation: &CancellationToken,
+    progress_sender: &mpsc::UnboundedSender<DownloadProgress>,
+) {
+    let mut request = metadata.to_file_request(target_dir);
+    request.shared_staging_dir = true;
+    request.connections = job.connections_per_file;
+    request.retry_policy = job.retry_policy;
+    request.stall_policy = job.stall_policy;
+    let source = DlsiteDownloadSource::new(client.clone(), stream_request);
+    let cancellation = cancellation.clone();
+    let progress_sender = progress_sender.clone();
+
+    tasks.spawn(async move {
+        download_file_segmented(&source, &request, &cancellation, |progress| {
+            progress_sender.send(progress).ok();
+        })
+        .await
+        .map(|downloaded| (request.file_index, downloaded))
+    });
 }
 
 pub fn unpack_downloaded_files(
@@ -405,6 +829,73 @@ pub fn unpack_downloaded_files(
     unpack_downloaded_archive_plan(archive_plan, target_dir, unpack_policy, options)
 }
 
+/// Name of the work's only file when it is a ZIP archive written front to back, so it can be
+/// unpacked while it downloads. Segmented downloads fill the file out of order and unpack after.
+fn streamable_zip<'a>(
+    job: &DownloadJobRequest,
+    file_metadata: &'a [(DownloadFileMetadata, DownloadStreamRequest)],
+    target_dir: &Path,
+) -> Result<Option<&'a str>, DownloadError> {
+    let [(metadata, _stream_request)] = file_metadata else {
+        return Ok(None);
+    };
+
+    if !job.unpack_policy.unpacks()
+        || validate_file_name(&metadata.file_name).is_err()
+        || !matches!(
+            dm_archive::plan_archive_handling([PathBuf::from(&metadata.file_name)]),
+            ArchivePlan::SingleZip { .. }
+        )
+    {
+        return Ok(None);
+    }
+
+    let mut request = metadata.to_file_request(target_dir);
+    request.connections = job.connections_per_file;
+
+    Ok(downloads_as_single_stream(&request)?.then_some(metadata.file_name.as_str()))
+}
+
+/// Moves the entries unpacked during the download into place.
+///
+/// Returns `None` when that fails for any reason, after removing whatever the attempt added to
+/// `target_dir`, so the archive still on disk is unpacked from scratch instead.
+fn finish_streamed_extraction(
+    extractor: dm_archive::ZipStreamExtractor,
+    archive: &Path,
+    target_dir: &Path,
+    options: ArchiveExtractOptions,
+) -> Result<Option<ArchiveExtraction>, DownloadError> {
+    let existing = directory_entry_names(target_dir)?;
+
+    match extractor.finish(archive, options) {
+        Ok(extraction) => Ok(Some(extraction)),
+        Err(_) => {
+            for entry in std::fs::read_dir(target_dir)? {
+                let entry = entry?;
+
+                if existing.contains(&entry.file_name()) {
+                    continue;
+                }
+
+                if entry.file_type()?.is_dir() {
+                    std::fs::remove_dir_all(entry.path())?;
+                } else {
+                    std::fs::remove_file(entry.path())?;
+                }
+            }
+
+            Ok(None)
+        }
+    }
+}
+
+fn directory_entry_names(dir: &Path) -> std::io::Result<BTreeSet<std::ffi::OsString>> {
+    std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.file_name()))
+        .collect()
+}
+
 fn plan_downloaded_archive(files: &[DownloadedFile]) -> ArchivePlan {
     dm_archive::plan_archive_handling(
         files
@@ -420,12 +911,16 @@ fn unpack_downloaded_archive_plan(
     unpack_policy: UnpackPolicy,
     options: ArchiveExtractOptions,
 ) -> Result<Option<ArchiveExtraction>, DownloadError> {
-    if unpack_policy == UnpackPolicy::KeepArchives {
+    if !unpack_policy.unpacks() {
         return Ok(None);
     }
 
     match archive_plan {
-        ArchivePlan::SingleZip { .. } | ArchivePlan::LegacySplitRar { .. } => {
+        ArchivePlan::SingleZip { .. }
+        | ArchivePlan::SevenZip { .. }
+        | ArchivePlan::LegacySplitRar { .. }
+        | ArchivePlan::MultiVolumeRar { .. }
+        | ArchivePlan::SplitZip { .. } => {
             dm_archive::extract_archive_plan(&archive_plan, target_dir, options)
                 .map(Some)
                 .map_err(Into::into)
@@ -434,6 +929,31 @@ fn unpack_downloaded_archive_plan(
     }
 }
 
+/// Checks the archive's uncompressed total against the free space next to it, falling back to the
+/// downloaded size when the archive can't be listed up front.
+fn ensure_unpack_space(
+    archive_plan: &ArchivePlan,
+    target_dir: &Path,
+    archive_bytes: u64,
+) -> Result<(), DownloadError> {
+    if !archive_plan.is_unpackable() {
+        return Ok(());
+    }
+
+    let required = dm_archive::uncompressed_size(archive_plan)?.unwrap_or(archive_bytes);
+    let available = available_space(target_dir)?;
+
+    if available < required {
+        return Err(DownloadError::InsufficientDiskSpace {
+            path: target_dir.to_path_buf(),
+            required,
+            available,
+        });
+    }
+
+    Ok(())
+}
+
 fn total_expected_size<'a>(
     metadata: impl IntoIterator<Item = &'a DownloadFileMetadata>,
 ) -> Option<u64> {
@@ -495,23 +1015,10 @@ where
     let target_path = request.target_dir.join(&request.file_name);
     let staging_dir = staging_dir_for(&request.target_dir);
     let staging_path = staging_dir.join(&request.file_name);
+    let resume_path = resume_sidecar_path(&staging_path);
 
-    if target_path.try_exists()? {
-        let existing_size = fs::metadata(&target_path).await?.len();
-
-        if request
-            .expected_size
-            .is_some_and(|expected_size| existing_size == expected_size)
-        {
-            return Ok(DownloadedFile {
-                file_name: request.file_name.clone(),
-                path: target_path,
-                bytes_written: existing_size,
-                resumed_from: existing_size,
-            });
-        }
-
-        return Err(DownloadError::TargetAlreadyExists { path: target_path });
+    if let Some(existing) = existing_target_file(request, &target_path).await? {
+        return Ok(existing);
     }
 
     fs::create_dir_all(&staging_dir).await?;
@@ -525,13 +1032,19 @@ where
         }
     }
 
+    let mut saved_validators = if resumed_from > 0 {
+        load_resume_validators(&resume_path).await?
+    } else {
+        None
+    };
+    let mut discarded_partial = None;
     let mut bytes_written = resumed_from;
     let mut file = OpenOptions::new()
         .create(true)
         .append(true)
         .open(&staging_path)
         .await?;
-    let mut retries = 0;
+    let mut retries = RetryState::new(request.retry_policy);
 
     'download: loop {
         if cancellation.is_cancelled() {
@@ -548,12 +1061,69 @@ where
 
         let mut stream = match cancellable(cancellation, source.open_range(bytes_written)).await {
             Ok(stream) => stream,
-            Err(_err) if retries < request.max_retries => {
-                retries += 1;
+            Err(err) => {
+                retries
+                    .retry_after(err, cancellation, |retry| {
+                        on_progress(retry_progress(request, bytes_written, retry))
+                    })
+                    .await?;
                 continue;
             }
-            Err(err) => return Err(err),
         };
+        let validators = stream.validators();
+
+        if let Some(reason) = saved_validators
+            .as_ref()
+            .and_then(|saved| saved.mismatch(&validators))
+        {
+            // The partial belongs to a file that was replaced upstream; appending to it would
+            // mix both versions.
+            drop(stream);
+
+            if discarded_partial.is_some() {
+                let err = DownloadError::InvalidDownloadResponse {
+                    reason: reason.clone(),
+                };
+                retries
+                    .retry_after(err, cancellation, |retry| {
+                        on_progress(retry_progress(request, bytes_written, retry))
+                    })
+                    .await?;
+            }
+
+            file.set_len(0).await?;
+            fs::remove_file(&resume_path).await.ok();
+            saved_validators = None;
+            discarded_partial = Some(reason);
+            bytes_written = 0;
+            resumed_from = 0;
+            continue;
+        }
+
+        match stream.body_start() {
+            // The server ignored the range and resent the whole file, so start over with it.
+            Some(0) if bytes_written > 0 => {
+                file.set_len(0).await?;
+                bytes_written = 0;
+                resumed_from = 0;
+            }
+            Some(start) if start != bytes_written => {
+                return Err(DownloadError::InvalidDownloadResponse {
+                    reason: InvalidDownloadResponseReason::RangeIgnored {
+                        requested: bytes_written,
+                        served: Some(start),
+                    },
+                });
+            }
+            _ => {}
+        }
+
+        if saved_validators.is_none() && !validators.is_empty() {
+            save_resume_validators(&resume_path, &validators).await?;
+            saved_validators = Some(validators);
+        }
+
+        let mut throughput = ThroughputMonitor::new(request.stall_policy);
 
         loop {
             if cancellation.is_cancelled() {
@@ -561,15 +1131,23 @@ where
                 return Err(DownloadError::Cancelled);
             }
 
-            let chunk = match cancellable(cancellation, stream.next_chunk()).await {
-                Ok(Some(chunk)) => chunk,
-                Ok(None) => break,
-                Err(_err) if retries < request.max_retries => {
-                    retries += 1;
-                    continue 'download;
-                }
-                Err(err) => return Err(err),
-            };
+            let chunk =
+                match cancellable(cancellation, throughput.next_chunk(stream.as_mut())).await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break,
+                    Err(DownloadError::Cancelled) => {
+                        file.flush().await.ok();
+                        return Err(DownloadError::Cancelled);
+                    }
+                    Err(err) => {
+                        retries
+                            .retry_after(err, cancellation, |retry| {
+                                on_progress(retry_progress(request, bytes_written, retry))
+                            })
+                            .await?;
+                        continue 'download;
+                    }
+                };
 
             if chunk.is_empty() {
                 continue;
@@ -579,12 +1157,15 @@ where
                 file.flush().await.ok();
                 drop(file);
                 fs::remove_file(&staging_path).await.ok();
-                fs::remove_dir(&staging_dir).await.ok();
+                fs::remove_file(&resume_path).await.ok();
+                remove_staging_dir(request, &staging_dir).await;
                 return Err(DownloadError::InvalidDownloadResponse {
                     reason: InvalidDownloadResponseReason::HtmlDocumentBody,
                 });
             }
 
+            limit_bandwidth(cancellation, chunk.len()).await?;
+
             let next_bytes_written = bytes_written + chunk.len() as u64;
 
             if let Some(expected) = request.expected_size {
@@ -605,20 +1186,23 @@ where
                 file_kind: Some(request.file_kind.clone()),
                 bytes_received: bytes_written,
                 bytes_total: request.expected_size,
+                retry: None,
             });
         }
 
         if let Some(expected) = request.expected_size {
             if bytes_written < expected {
-                if retries < request.max_retries {
-                    retries += 1;
-                    continue;
-                }
-
-                return Err(DownloadError::IncompleteDownload {
+                let err = DownloadError::IncompleteDownload {
                     expected,
                     actual: bytes_written,
-                });
+                };
+
+                retries
+                    .retry_after(err, cancellation, |retry| {
+                        on_progress(retry_progress(request, bytes_written, retry))
+                    })
+                    .await?;
+                continue;
             }
         }
 
@@ -632,19 +1216,648 @@ where
         return Err(DownloadError::Cancelled);
     }
 
-    if target_path.try_exists()? {
-        return Err(DownloadError::TargetAlreadyExists { path: target_path });
+    if target_path.try_exists()? {
+        return Err(DownloadError::TargetAlreadyExists { path: target_path });
+    }
+
+    fs::rename(&staging_path, &target_path).await?;
+    fs::remove_file(&resume_path).await.ok();
+    remove_staging_dir(request, &staging_dir).await;
+
+    Ok(DownloadedFile {
+        file_name: request.file_name.clone(),
+        path: target_path,
+        bytes_written,
+        resumed_from,
+        discarded_partial,
+    })
+}
+
+/// Downloads a file of known size over several ranged connections.
+///
+/// Each segment is written at its offset in a preallocated part file and its progress is kept in a
+/// sidecar, so an interrupted download resumes every segment separately. Files of unknown size,
+/// files too small to split and files with a single-stream partial fall back to [`download_file`].
+/// When the remote file turns out to have changed, the partial is discarded and the download
+/// starts over once.
+pub async fn download_file_segmented<S, F>(
+    source: &S,
+    request: &DownloadFileRequest,
+    cancellation: &CancellationToken,
+    mut on_progress: F,
+) -> Result<DownloadedFile, DownloadError>
+where
+    S: RangedDownloadSource + Clone + Send + 'static,
+    F: FnMut(DownloadProgress),
+{
+    validate_file_name(&request.file_name)?;
+
+    let target_path = request.target_dir.join(&request.file_name);
+    let expected_size = match request.expected_size {
+        Some(expected_size) if !downloads_as_single_stream(request)? => expected_size,
+        _ => return download_file(&mut source.clone(), request, cancellation, on_progress).await,
+    };
+
+    if let Some(existing) = existing_target_file(request, &target_path).await? {
+        return Ok(existing);
+    }
+
+    match download_segments(
+        source,
+        request,
+        expected_size,
+        cancellation,
+        &mut on_progress,
+    )
+    .await
+    {
+        Err(DownloadError::InvalidDownloadResponse {
+            reason: reason @ InvalidDownloadResponseReason::RemoteFileChanged { .. },
+        }) => {
+            let mut downloaded = download_segments(
+                source,
+                request,
+                expected_size,
+                cancellation,
+                &mut on_progress,
+            )
+            .await?;
+            downloaded.discarded_partial = Some(reason);
+            Ok(downloaded)
+        }
+        Err(DownloadError::InvalidDownloadResponse {
+            reason: InvalidDownloadResponseReason::RangeIgnored { .. },
+        }) => download_file(&mut source.clone(), request, cancellation, on_progress).await,
+        result => result,
+    }
+}
+
+/// Whether [`download_file_segmented`] hands the file to the single-stream [`download_file`].
+fn downloads_as_single_stream(request: &DownloadFileRequest) -> std::io::Result<bool> {
+    let Some(expected_size) = request.expected_size else {
+        return Ok(true);
+    };
+    let staging_dir = staging_dir_for(&request.target_dir);
+    let state_path = staging_dir.join(format!("{}.segments.state", request.file_name));
+
+    Ok(staging_dir.join(&request.file_name).try_exists()?
+        || !(state_path.try_exists()?
+            || segment_ranges(expected_size, request.connections).len() > 1))
+}
+
+async fn download_segments<S, F>(
+    source: &S,
+    request: &DownloadFileRequest,
+    expected_size: u64,
+    cancellation: &CancellationToken,
+    on_progress: &mut F,
+) -> Result<DownloadedFile, DownloadError>
+where
+    S: RangedDownloadSource + Clone + Send + 'static,
+    F: FnMut(DownloadProgress),
+{
+    let target_path = request.target_dir.join(&request.file_name);
+    let staging_dir = staging_dir_for(&request.target_dir);
+    let part_path = staging_dir.join(format!("{}.segments", request.file_name));
+    let state_path = staging_dir.join(format!("{}.segments.state", request.file_name));
+    let resume_path = resume_sidecar_path(&part_path);
+
+    fs::create_dir_all(&staging_dir).await?;
+
+    let part_size = file_size_if_exists(&part_path).await?;
+    let saved_segments = load_segment_state(&state_path, expected_size)
+        .await?
+        .filter(|_| part_size == expected_size);
+    let saved_validators = match saved_segments {
+        Some(_) => load_resume_validators(&resume_path).await?,
+        None => None,
+    };
+    let mut segments = saved_segments.unwrap_or_else(|| {
+        segment_ranges(expected_size, request.connections)
+            .into_iter()
+            .map(|(start, end)| DownloadSegment {
+                start,
+                end,
+                written: 0,
+            })
+            .collect()
+    });
+    let part = SegmentPart {
+        path: part_path.clone(),
+        validators: Arc::new(Mutex::new(saved_validators)),
+        retries: SharedRetryState::new(request.retry_policy),
+        stall_policy: request.stall_policy,
+    };
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(&part_path)
+        .await?;
+    file.set_len(expected_size).await?;
+    drop(file);
+    save_segment_progress(&state_path, &resume_path, &segments, &part).await?;
+
+    let resumed_from = segments_written(&segments);
+    let (progress_sender, mut progress_receiver) = mpsc::unbounded_channel();
+    let segment_cancellation = CancellationToken::new();
+    let mut tasks = JoinSet::new();
+
+    for (index, segment) in segments.iter().copied().enumerate() {
+        if segment.written == segment.len() {
+            continue;
+        }
+
+        tasks.spawn(download_segment(
+            source.clone(),
+            part.clone(),
+            index,
+            segment,
+            segment_cancellation.clone(),
+            progress_sender.clone(),
+        ));
+    }
+    drop(progress_sender);
+
+    let mut persisted_bytes = resumed_from;
+    let mut failure = None;
+
+    loop {
+        tokio::select! {
+            Some(event) = progress_receiver.recv() => {
+                let (index, written) = match event {
+                    SegmentEvent::Written { index, written } => (index, written),
+                    SegmentEvent::Retry(retry) => {
+                        on_progress(DownloadProgress {
+                            phase: DownloadPhase::Downloading,
+                            file_index: Some(request.file_index),
+                            file_kind: Some(request.file_kind.clone()),
+                            bytes_received: segments_written(&segments),
+                            bytes_total: Some(expected_size),
+                            retry: Some(retry),
+                        });
+                        continue;
+                    }
+                };
+                segments[index].written = written;
+                let bytes_received = segments_written(&segments);
+
+                if bytes_received.saturating_sub(persisted_bytes) >= SEGMENT_STATE_PERSIST_INTERVAL {
+                    save_segment_progress(&state_path, &resume_path, &segments, &part).await?;
+                    persisted_bytes = bytes_received;
+                }
+
+                on_progress(DownloadProgress {
+                    phase: DownloadPhase::Downloading,
+                    file_index: Some(request.file_index),
+                    file_kind: Some(request.file_kind.clone()),
+                    bytes_received,
+                    bytes_total: Some(expected_size),
+                    retry: None,
+                });
+            }
+            joined = tasks.join_next() => match joined {
+                Some(Ok(Ok(()))) => {}
+                Some(Ok(Err(err))) => {
+                    failure = Some(err);
+                    break;
+                }
+                Some(Err(err)) => {
+                    failure = Some(std::io::Error::other(err).into());
+                    break;
+                }
+                None => break,
+            },
+            _ = cancellation.cancelled() => {
+                failure = Some(DownloadError::Cancelled);
+                break;
+            }
+        }
+    }
+
+    segment_cancellation.cancel();
+    while tasks.join_next().await.is_some() {}
+    while let Ok(event) = progress_receiver.try_recv() {
+        if let SegmentEvent::Written { index, written } = event {
+            segments[index].written = written;
+        }
+    }
+
+    if let Some(err) = failure {
+        if matches!(err, DownloadError::InvalidDownloadResponse { .. }) {
+            fs::remove_file(&part_path).await.ok();
+            fs::remove_file(&state_path).await.ok();
+            fs::remove_file(&resume_path).await.ok();
+            remove_staging_dir(request, &staging_dir).await;
+        } else {
+            save_segment_progress(&state_path, &resume_path, &segments, &part).await?;
+        }
+
+        return Err(err);
+    }
+
+    if let Err(err) = verify_contiguous_segments(&segments, expected_size) {
+        save_segment_progress(&state_path, &resume_path, &segments, &part).await?;
+        return Err(err);
+    }
+
+    if target_path.try_exists()? {
+        return Err(DownloadError::TargetAlreadyExists { path: target_path });
+    }
+
+    fs::rename(&part_path, &target_path).await?;
+    fs::remove_file(&state_path).await.ok();
+    fs::remove_file(&resume_path).await.ok();
+    remove_staging_dir(request, &staging_dir).await;
+
+    Ok(DownloadedFile {
+        file_name: request.file_name.clone(),
+        path: target_path,
+        bytes_written: expected_size,
+        resumed_from,
+        discarded_partial: None,
+    })
+}
+
+/// The preallocated part file shared by the segment tasks of one download.
+#[derive(Clone)]
+struct SegmentPart {
+    path: PathBuf,
+    validators: Arc<Mutex<Option<RemoteFileValidators>>>,
+    retries: SharedRetryState,
+    stall_policy: StallPolicy,
+}
+
+enum SegmentEvent {
+    Written { index: usize, written: u64 },
+    Retry(DownloadRetry),
+}
+
+impl SegmentPart {
+    /// Records the validators of the first response and rejects responses for another version.
+    fn check_validators(&self, current: RemoteFileValidators) -> Result<(), DownloadError> {
+        let mut validators = self.validators.lock().expect("segment validators lock");
+
+        if let Some(reason) = validators
+            .as_ref()
+            .and_then(|validators| validators.mismatch(&current))
+        {
+            return Err(DownloadError::InvalidDownloadResponse { reason });
+        }
+
+        if validators.is_none() && !current.is_empty() {
+            *validators = Some(current);
+        }
+
+        Ok(())
+    }
+
+    fn validators(&self) -> Option<RemoteFileValidators> {
+        self.validators
+            .lock()
+            .expect("segment validators lock")
+            .clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DownloadSegment {
+    start: u64,
+    end: u64,
+    written: u64,
+}
+
+impl DownloadSegment {
+    fn len(&self) -> u64 {
+        self.end - self.start
+    }
+}
+
+fn segment_ranges(size: u64, connections: usize) -> Vec<(u64, u64)> {
+    let count = (connections.clamp(1, MAX_CONNECTIONS_PER_FILE) as u64)
+        .min(size / MIN_SEGMENT_SIZE)
+        .max(1);
+    let segment_size = size / count;
+
+    (0..count)
+        .map(|index| {
+            let start = index * segment_size;
+            let end = if index + 1 == count {
+                size
+            } else {
+                start + segment_size
+            };
+
+            (start, end)
+        })
+        .collect()
+}
+
+fn segments_written(segments: &[DownloadSegment]) -> u64 {
+    segments.iter().map(|segment| segment.written).sum()
+}
+
+fn first_segment_gap(segments: &[DownloadSegment], size: u64) -> Option<u64> {
+    let mut offset = 0;
+
+    for segment in segments {
+        if segment.start != offset {
+            return Some(offset);
+        }
+
+        offset = segment.end;
+    }
+
+    (offset != size).then_some(offset)
+}
+
+fn verify_contiguous_segments(
+    segments: &[DownloadSegment],
+    expected_size: u64,
+) -> Result<(), DownloadError> {
+    if let Some(offset) = first_segment_gap(segments, expected_size) {
+        return Err(DownloadError::SegmentGap { offset });
+    }
+
+    if segments
+        .iter()
+        .any(|segment| segment.written != segment.len())
+    {
+        return Err(DownloadError::IncompleteDownload {
+            expected: expected_size,
+            actual: segments_written(segments),
+        });
+    }
+
+    Ok(())
+}
+
+async fn load_segment_state(
+    path: &Path,
+    expected_size: u64,
+) -> Result<Option<Vec<DownloadSegment>>, DownloadError> {
+    let contents = match fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let segments = contents
+        .lines()
+        .map(|line| {
+            let mut values = line.split_whitespace().map(str::parse::<u64>);
+            let segment = DownloadSegment {
+                start: values.next()?.ok()?,
+                end: values.next()?.ok()?,
+                written: values.next()?.ok()?,
+            };
+
+            (segment.start <= segment.end && segment.written <= segment.len()).then_some(segment)
+        })
+        .collect::<Option<Vec<_>>>();
+
+    Ok(segments.filter(|segments| {
+        !segments.is_empty() && first_segment_gap(segments, expected_size).is_none()
+    }))
+}
+
+async fn save_segment_state(
+    path: &Path,
+    segments: &[DownloadSegment],
+) -> Result<(), DownloadError> {
+    let contents = segments
+        .iter()
+        .map(|segment| format!("{} {} {}\n", segment.start, segment.end, segment.written))
+        .collect::<String>();
+
+    fs::write(path, contents).await?;
+    Ok(())
+}
+
+async fn save_segment_progress(
+    state_path: &Path,
+    resume_path: &Path,
+    segments: &[DownloadSegment],
+    part: &SegmentPart,
+) -> Result<(), DownloadError> {
+    save_segment_state(state_path, segments).await?;
+
+    match part.validators() {
+        Some(validators) => save_resume_validators(resume_path, &validators).await,
+        None => {
+            fs::remove_file(resume_path).await.ok();
+            Ok(())
+        }
+    }
+}
+
+async fn download_segment<S>(
+    mut source: S,
+    part: SegmentPart,
+    index: usize,
+    segment: DownloadSegment,
+    cancellation: CancellationToken,
+    progress: mpsc::UnboundedSender<SegmentEvent>,
+) -> Result<(), DownloadError>
+where
+    S: RangedDownloadSource + Send,
+{
+    let mut file = OpenOptions::new().write(true).open(&part.path).await?;
+    let mut written = segment.written;
+    let retries = &part.retries;
+    let report = |retry: DownloadRetry| {
+        progress.send(SegmentEvent::Retry(retry)).ok();
+    };
+
+    'download: while written < segment.len() {
+        let offset = segment.start + written;
+        let mut stream = match cancellable(&cancellation, source.open_range(offset)).await {
+            Ok(stream) => stream,
+            Err(err) => {
+                retries.retry_after(err, &cancellation, report).await?;
+                continue;
+            }
+        };
+        part.check_validators(stream.validators())?;
+
+        // Writing a body that starts elsewhere at this offset would silently corrupt the file.
+        if offset > 0 && stream.body_start() != Some(offset) {
+            return Err(DownloadError::InvalidDownloadResponse {
+                reason: InvalidDownloadResponseReason::RangeIgnored {
+                    requested: offset,
+                    served: stream.body_start(),
+                },
+            });
+        }
+
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        let mut throughput = ThroughputMonitor::new(part.stall_policy);
+
+        while written < segment.len() {
+            let chunk =
+                match cancellable(&cancellation, throughput.next_chunk(stream.as_mut())).await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break,
+                    Err(err) => {
+                        retries.retry_after(err, &cancellation, report).await?;
+                        continue 'download;
+                    }
+                };
+
+            if chunk.is_empty() {
+                continue;
+            }
+
+            if segment.start + written == 0 && looks_like_html_document(&chunk) {
+                return Err(DownloadError::InvalidDownloadResponse {
+                    reason: InvalidDownloadResponseReason::HtmlDocumentBody,
+                });
+            }
+
+            // The range is open-ended, so the stream keeps going past this segment.
+            let take = chunk.len().min((segment.len() - written) as usize);
+            limit_bandwidth(&cancellation, take).await?;
+            file.write_all(&chunk[..take]).await?;
+            file.flush().await?;
+            written += take as u64;
+            progress.send(SegmentEvent::Written { index, written }).ok();
+        }
+
+        if written < segment.len() {
+            let err = DownloadError::IncompleteDownload {
+                expected: segment.len(),
+                actual: written,
+            };
+
+            retries.retry_after(err, &cancellation, report).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn retry_progress(
+    request: &DownloadFileRequest,
+    bytes_received: u64,
+    retry: DownloadRetry,
+) -> DownloadProgress {
+    DownloadProgress {
+        phase: DownloadPhase::Downloading,
+        file_index: Some(request.file_index),
+        file_kind: Some(request.file_kind.clone()),
+        bytes_received,
+        bytes_total: request.expected_size,
+        retry: Some(retry),
+    }
+}
+
+/// Reads chunks of one response and gives up on it once it goes quiet or too slow.
+///
+/// Only time spent waiting on the response counts toward throughput, so the bandwidth limiter
+/// never makes a connection look stalled.
+struct ThroughputMonitor {
+    policy: StallPolicy,
+    window_bytes: u64,
+    window_waited: Duration,
+    too_slow: Option<StallReason>,
+}
+
+impl ThroughputMonitor {
+    fn new(policy: StallPolicy) -> Self {
+        Self {
+            policy,
+            window_bytes: 0,
+            window_waited: Duration::ZERO,
+            too_slow: None,
+        }
+    }
+
+    async fn next_chunk(
+        &mut self,
+        stream: &mut (dyn DownloadByteStream + Send + '_),
+    ) -> Result<Option<Vec<u8>>, DownloadError> {
+        // The chunk that completed a slow window is still handed out so it doesn't get refetched.
+        if let Some(reason) = self.too_slow.take() {
+            return Err(DownloadError::Stalled { reason });
+        }
+
+        let started = Instant::now();
+        let chunk = timeout(self.policy.idle_timeout, stream.next_chunk())
+            .await
+            .map_err(|_elapsed| DownloadError::Stalled {
+                reason: StallReason::Idle {
+                    timeout: self.policy.idle_timeout,
+                },
+            })??;
+
+        if let Some(chunk) = &chunk {
+            self.record(chunk.len() as u64, started.elapsed());
+        }
+
+        Ok(chunk)
+    }
+
+    fn record(&mut self, bytes: u64, waited: Duration) {
+        let Some(minimum) = self.policy.min_bytes_per_second else {
+            return;
+        };
+
+        self.window_bytes = self.window_bytes.saturating_add(bytes);
+        self.window_waited += waited;
+
+        if self.window_waited < self.policy.throughput_window {
+            return;
+        }
+
+        let bytes_per_second = (self.window_bytes as f64 / self.window_waited.as_secs_f64()) as u64;
+        self.window_bytes = 0;
+        self.window_waited = Duration::ZERO;
+
+        if bytes_per_second < minimum {
+            self.too_slow = Some(StallReason::TooSlow {
+                bytes_per_second,
+                minimum,
+            });
+        }
+    }
+}
+
+async fn existing_target_file(
+    request: &DownloadFileRequest,
+    target_path: &Path,
+) -> Result<Option<DownloadedFile>, DownloadError> {
+    if !target_path.try_exists()? {
+        return Ok(None);
+    }
+
+    let existing_size = fs::metadata(target_path).await?.len();
+
+    if request
+        .expected_size
+        .is_some_and(|expected_size| existing_size == expected_size)
+    {
+        return Ok(Some(DownloadedFile {
+            file_name: request.file_name.clone(),
+            path: target_path.to_path_buf(),
+            bytes_written: existing_size,
+            resumed_from: existing_size,
+            discarded_partial: None,
+        }));
     }
 
-    fs::rename(&staging_path, &target_path).await?;
-    fs::remove_dir(&staging_dir).await.ok();
+    Err(DownloadError::TargetAlreadyExists {
+        path: target_path.to_path_buf(),
+    })
+}
 
-    Ok(DownloadedFile {
-        file_name: request.file_name.clone(),
-        path: target_path,
-        bytes_written,
-        resumed_from,
+async fn limit_bandwidth(
+    cancellation: &CancellationToken,
+    bytes: usize,
+) -> Result<(), DownloadError> {
+    cancellable(cancellation, async {
+        BandwidthLimiter::global().acquire(bytes as u64).await;
+        Ok(())
     })
+    .await
 }
 
 async fn cancellable<T, F>(cancellation: &CancellationToken, future: F) -> Result<T, DownloadError>
@@ -665,6 +1878,13 @@ pub fn staging_dir_for(target_dir: &Path) -> PathBuf {
     target_dir.join(".dm-download")
 }
 
+/// Removes the staging folder once it is empty, unless sibling downloads still share it.
+async fn remove_staging_dir(request: &DownloadFileRequest, staging_dir: &Path) {
+    if !request.shared_staging_dir {
+        fs::remove_dir(staging_dir).await.ok();
+    }
+}
+
 fn validate_file_name(file_name: &str) -> Result<(), DownloadError> {
     let path = Path::new(file_name);
 
@@ -711,6 +1931,15 @@ pub fn file_name_from_download_url(url: &Url) -> Option<String> {
     None
 }
 
+pub fn start_from_content_range(content_range: &str) -> Option<u64> {
+    let (start, _) = content_range
+        .trim()
+        .strip_prefix("bytes ")?
+        .split_once('-')?;
+
+    start.trim().parse().ok()
+}
+
 pub fn total_size_from_content_range(content_range: &str) -> Option<u64> {
     let (_, size) = content_range.rsplit_once('/')?;
 
@@ -801,6 +2030,7 @@ mod tests {
             file_kind: Some(DownloadFileKind::Direct),
             bytes_received: 25,
             bytes_total: Some(100),
+            retry: None,
         };
 
         assert_eq!(progress.percentage(), Some(25));
@@ -814,6 +2044,7 @@ mod tests {
             file_kind: Some(DownloadFileKind::Direct),
             bytes_received: 150,
             bytes_total: Some(100),
+            retry: None,
         };
 
         assert_eq!(progress.percentage(), Some(100));
@@ -827,6 +2058,7 @@ mod tests {
             file_kind: Some(DownloadFileKind::Direct),
             bytes_received: 25,
             bytes_total: None,
+            retry: None,
         };
 
         assert_eq!(progress.percentage(), None);
@@ -851,6 +2083,7 @@ mod tests {
                 file_kind: Some(DownloadFileKind::SplitPart { number: 2 }),
                 bytes_received: 75,
                 bytes_total: Some(250),
+                retry: None,
             },
             100,
             total_expected_size(metadata.iter()),
@@ -879,6 +2112,7 @@ mod tests {
             file_kind: None,
             bytes_received: 100,
             bytes_total: Some(100),
+            retry: None,
         };
 
         assert_eq!(
@@ -931,6 +2165,13 @@ mod tests {
         assert_eq!(total_size_from_content_range("invalid"), None);
     }
 
+    #[test]
+    fn extracts_start_from_content_range() {
+        assert_eq!(start_from_content_range("bytes 1024-2047/4096"), Some(1024));
+        assert_eq!(start_from_content_range("bytes */4096"), None);
+        assert_eq!(start_from_content_range("invalid"), None);
+    }
+
     #[test]
     fn converts_metadata_to_file_request() {
         let metadata = DownloadFileMetadata {
@@ -1029,6 +2270,26 @@ mod tests {
         std::fs::remove_dir_all(dir).unwrap();
     }
 
+    #[tokio::test]
+    async fn leaves_shared_staging_dir_for_sibling_parts() {
+        let dir = test_dir("shared-staging");
+        let mut source = ScriptedSource::new(vec![Ok(vec![b"abc".to_vec()])]);
+        let mut request = request(&dir, "RJ123456.part1.exe", Some(3));
+        request.shared_staging_dir = true;
+
+        download_file(&mut source, &request, &CancellationToken::new(), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read(dir.join("RJ123456.part1.exe")).unwrap(),
+            b"abc"
+        );
+        assert!(staging_dir_for(&dir).is_dir());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[tokio::test]
     async fn resumes_from_existing_staging_file() {
         let dir = test_dir("resume");
@@ -1051,6 +2312,140 @@ mod tests {
         std::fs::remove_dir_all(dir).unwrap();
     }
 
+    #[tokio::test]
+    async fn records_validators_and_resumes_while_they_match() {
+        let dir = test_dir("resume-validators");
+        let mut source =
+            ScriptedSource::new(vec![Ok(vec![b"abc".to_vec()]), Ok(vec![b"def".to_vec()])])
+                .with_etag("\"v1\"")
+                .with_fail_after_chunks(0, 1);
+        let request = request(&dir, "RJ123456.zip", Some(6));
+        let resume_path = staging_dir_for(&dir).join("RJ123456.zip.resume");
+        let mut recorded = None;
+
+        let downloaded = download_file(&mut source, &request, &CancellationToken::new(), |_| {
+            recorded = recorded
+                .take()
+                .or_else(|| std::fs::read_to_string(&resume_path).ok())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(recorded.as_deref(), Some("etag \"v1\"\n"));
+        assert_eq!(downloaded.discarded_partial, None);
+        assert_eq!(source.starts(), vec![0, 3]);
+        assert_eq!(std::fs::read(dir.join("RJ123456.zip")).unwrap(), b"abcdef");
+        assert!(!resume_path.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn discards_partial_when_remote_file_changed() {
+        let dir = test_dir("resume-changed");
+        let staging_dir = staging_dir_for(&dir);
+        std::fs::create_dir_all(&staging_dir).unwrap();
+        std::fs::write(staging_dir.join("RJ123456.zip"), b"xyz").unwrap();
+        std::fs::write(staging_dir.join("RJ123456.zip.resume"), "etag \"v1\"\n").unwrap();
+        let mut source = ScriptedSource::new(vec![
+            Ok(vec![b"ignored".to_vec()]),
+            Ok(vec![b"abc".to_vec(), b"def".to_vec()]),
+        ])
+        .with_etag("\"v2\"");
+        let request = request(&dir, "RJ123456.zip", Some(6));
+
+        let downloaded = download_file(&mut source, &request, &CancellationToken::new(), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(downloaded.resumed_from, 0);
+        assert_eq!(
+            downloaded.discarded_partial,
+            Some(InvalidDownloadResponseReason::RemoteFileChanged {
+                validator: "ETag",
+                previous: "\"v1\"".to_owned(),
+                current: "\"v2\"".to_owned(),
+            })
+        );
+        assert_eq!(source.starts(), vec![3, 0]);
+        assert_eq!(std::fs::read(dir.join("RJ123456.zip")).unwrap(), b"abcdef");
+        assert!(!staging_dir.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reconnects_stalled_stream_from_current_offset() {
+        let dir = test_dir("stall-idle");
+        let mut source =
+            ScriptedSource::new(vec![Ok(vec![b"abc".to_vec()]), Ok(vec![b"def".to_vec()])])
+                .with_stall_after_chunks(0, 1);
+        let mut request = request(&dir, "RJ123456.zip", Some(6));
+        request.retry_policy.max_retries = 1;
+        request.retry_policy.jitter_percent = 0;
+        request.stall_policy.idle_timeout = Duration::from_secs(5);
+        let mut retries = Vec::new();
+
+        let downloaded = download_file(
+            &mut source,
+            &request,
+            &CancellationToken::new(),
+            |progress| {
+                retries.extend(progress.retry.map(|retry| (progress.bytes_received, retry)));
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(downloaded.bytes_written, 6);
+        assert_eq!(source.starts(), vec![0, 3]);
+        assert_eq!(
+            retries,
+            vec![(
+                3,
+                DownloadRetry {
+                    attempt: 1,
+                    max_retries: 1,
+                    delay: DEFAULT_INITIAL_RETRY_DELAY,
+                    reason: "download stalled: no data received for 5s".to_owned(),
+                }
+            )]
+        );
+        assert_eq!(std::fs::read(dir.join("RJ123456.zip")).unwrap(), b"abcdef");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_on_streams_below_minimum_throughput() {
+        let dir = test_dir("stall-slow");
+        let chunks = vec![vec![0; 10]; 4];
+        let mut source = ScriptedSource::new(vec![Ok(chunks.clone()), Ok(chunks)])
+            .with_chunk_delay(Duration::from_secs(2));
+        let mut request = request(&dir, "RJ123456.zip", Some(80));
+        request.retry_policy.max_retries = 1;
+        request.stall_policy = StallPolicy {
+            min_bytes_per_second: Some(100),
+            throughput_window: Duration::from_secs(4),
+            ..StallPolicy::default()
+        };
+
+        let result = download_file(&mut source, &request, &CancellationToken::new(), |_| {}).await;
+
+        assert!(matches!(
+            result,
+            Err(DownloadError::Stalled {
+                reason: StallReason::TooSlow {
+                    bytes_per_second: 5,
+                    minimum: 100,
+                },
+            })
+        ));
+        assert_eq!(source.starts(), vec![0, 20]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[tokio::test]
     async fn reuses_existing_finalized_file_when_size_matches() {
         let dir = test_dir("existing-final");
@@ -1076,7 +2471,7 @@ mod tests {
             ScriptedSource::new(vec![Ok(vec![b"abc".to_vec()]), Ok(vec![b"def".to_vec()])])
                 .with_fail_after_chunks(0, 1);
         let mut request = request(&dir, "RJ123456.zip", Some(6));
-        request.max_retries = 1;
+        request.retry_policy.max_retries = 1;
 
         let downloaded = download_file(&mut source, &request, &CancellationToken::new(), |_| {})
             .await
@@ -1176,6 +2571,221 @@ mod tests {
         std::fs::remove_dir_all(dir).unwrap();
     }
 
+    #[tokio::test]
+    async fn resumes_each_segment_from_its_own_offset() {
+        let dir = test_dir("segmented-resume");
+        let data = (0..3 * MIN_SEGMENT_SIZE)
+            .map(|index| (index % 251) as u8)
+            .collect::<Vec<_>>();
+        let staging_dir = staging_dir_for(&dir);
+        let mut part = vec![0; data.len()];
+        let resumed_to = MIN_SEGMENT_SIZE + MIN_SEGMENT_SIZE / 2;
+        part[..resumed_to as usize].copy_from_slice(&data[..resumed_to as usize]);
+        std::fs::create_dir_all(&staging_dir).unwrap();
+        std::fs::write(staging_dir.join("RJ123456.zip.segments"), part).unwrap();
+        std::fs::write(
+            staging_dir.join("RJ123456.zip.segments.state"),
+            format!(
+                "0 {one} {one}\n{one} {two} {half}\n{two} {three} 0\n",
+                one = MIN_SEGMENT_SIZE,
+                two = 2 * MIN_SEGMENT_SIZE,
+                three = 3 * MIN_SEGMENT_SIZE,
+                half = MIN_SEGMENT_SIZE / 2,
+            ),
+        )
+        .unwrap();
+        let source = SliceSource::new(data.clone());
+        let mut request = request(&dir, "RJ123456.zip", Some(data.len() as u64));
+        request.connections = 3;
+
+        let downloaded =
+            download_file_segmented(&source, &request, &CancellationToken::new(), |_| {})
+                .await
+                .unwrap();
+        let mut starts = source.starts();
+        starts.sort();
+
+        assert_eq!(downloaded.resumed_from, resumed_to);
+        assert_eq!(downloaded.bytes_written, data.len() as u64);
+        assert_eq!(starts, vec![resumed_to, 2 * MIN_SEGMENT_SIZE]);
+        assert_eq!(std::fs::read(dir.join("RJ123456.zip")).unwrap(), data);
+        assert!(!staging_dir.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn segments_share_one_retry_budget_per_file() {
+        let dir = test_dir("segmented-retry-budget");
+        let data = vec![7; 3 * MIN_SEGMENT_SIZE as usize];
+        let source = SliceSource::new(data.clone()).failing_from(MIN_SEGMENT_SIZE);
+        let mut request = request(&dir, "RJ123456.zip", Some(data.len() as u64));
+        request.connections = 3;
+        request.retry_policy.max_retries = 3;
+        request.retry_policy.initial_delay = Duration::from_millis(1);
+        request.retry_policy.jitter_percent = 0;
+        let mut retries = Vec::new();
+
+        let err =
+            download_file_segmented(&source, &request, &CancellationToken::new(), |progress| {
+                retries.extend(progress.retry.map(|retry| retry.attempt));
+            })
+            .await
+            .unwrap_err();
+
+        // Separate budgets would allow three retries to each failing segment; a segment still
+        // waiting out its retry when the download gives up may never reopen.
+        assert!(matches!(err, DownloadError::Stream(_)));
+        assert!(retries.iter().all(|attempt| *attempt <= 3), "{retries:?}");
+        assert!(
+            source
+                .starts()
+                .iter()
+                .filter(|start| **start >= MIN_SEGMENT_SIZE)
+                .count()
+                <= 2 + 3
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn restarts_segments_when_remote_file_changed() {
+        let dir = test_dir("segmented-changed");
+        let data = (0..2 * MIN_SEGMENT_SIZE)
+            .map(|index| (index % 251) as u8)
+            .collect::<Vec<_>>();
+        let staging_dir = staging_dir_for(&dir);
+        std::fs::create_dir_all(&staging_dir).unwrap();
+        std::fs::write(
+            staging_dir.join("RJ123456.zip.segments"),
+            vec![0; data.len()],
+        )
+        .unwrap();
+        std::fs::write(
+            staging_dir.join("RJ123456.zip.segments.state"),
+            format!(
+                "0 {one} 16\n{one} {two} 0\n",
+                one = MIN_SEGMENT_SIZE,
+                two = 2 * MIN_SEGMENT_SIZE,
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            staging_dir.join("RJ123456.zip.segments.resume"),
+            "etag \"v1\"\n",
+        )
+        .unwrap();
+        let source = SliceSource::new(data.clone()).with_etag("\"v2\"");
+        let mut request = request(&dir, "RJ123456.zip", Some(data.len() as u64));
+        request.connections = 2;
+
+        let downloaded =
+            download_file_segmented(&source, &request, &CancellationToken::new(), |_| {})
+                .await
+                .unwrap();
+
+        assert_eq!(downloaded.resumed_from, 0);
+        assert!(matches!(
+            downloaded.discarded_partial,
+            Some(InvalidDownloadResponseReason::RemoteFileChanged { .. })
+        ));
+        assert!(source.starts().contains(&0));
+        assert_eq!(std::fs::read(dir.join("RJ123456.zip")).unwrap(), data);
+        assert!(!staging_dir.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn segmented_download_falls_back_to_single_stream_when_ranges_are_ignored() {
+        let dir = test_dir("segmented-no-ranges");
+        let data = (0..2 * MIN_SEGMENT_SIZE)
+            .map(|index| (index % 251) as u8)
+            .collect::<Vec<_>>();
+        let source = SliceSource::new(data.clone()).ignoring_ranges();
+        let mut request = request(&dir, "RJ123456.zip", Some(data.len() as u64));
+        request.connections = 2;
+
+        let downloaded =
+            download_file_segmented(&source, &request, &CancellationToken::new(), |_| {})
+                .await
+                .unwrap();
+
+        assert_eq!(downloaded.bytes_written, data.len() as u64);
+        assert_eq!(source.starts().last(), Some(&0));
+        assert_eq!(std::fs::read(dir.join("RJ123456.zip")).unwrap(), data);
+        assert!(!staging_dir_for(&dir).exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn restarts_single_stream_resume_when_range_is_ignored() {
+        let dir = test_dir("resume-no-ranges");
+        let staging_dir = staging_dir_for(&dir);
+        std::fs::create_dir_all(&staging_dir).unwrap();
+        std::fs::write(staging_dir.join("RJ123456.zip"), b"abc").unwrap();
+        let mut source = SliceSource::new(b"abcdef".to_vec()).ignoring_ranges();
+        let request = request(&dir, "RJ123456.zip", Some(6));
+
+        let downloaded = download_file(&mut source, &request, &CancellationToken::new(), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(downloaded.resumed_from, 0);
+        assert_eq!(source.starts(), vec![3]);
+        assert_eq!(std::fs::read(dir.join("RJ123456.zip")).unwrap(), b"abcdef");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn segmented_download_falls_back_to_single_stream_for_small_files() {
+        let dir = test_dir("segmented-small");
+        let source = SliceSource::new(b"abcdef".to_vec());
+        let mut request = request(&dir, "RJ123456.zip", Some(6));
+        request.connections = 4;
+
+        download_file_segmented(&source, &request, &CancellationToken::new(), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(dir.join("RJ123456.zip")).unwrap(), b"abcdef");
+        assert_eq!(source.starts(), vec![0]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn detects_gaps_between_segments() {
+        let segments = [
+            DownloadSegment {
+                start: 0,
+                end: 4,
+                written: 4,
+            },
+            DownloadSegment {
+                start: 5,
+                end: 8,
+                written: 3,
+            },
+        ];
+
+        assert!(matches!(
+            verify_contiguous_segments(&segments, 8),
+            Err(DownloadError::SegmentGap { offset: 4 })
+        ));
+        assert_eq!(
+            segment_ranges(4 * MIN_SEGMENT_SIZE + 3, 3),
+            vec![
+                (0, 4 * MIN_SEGMENT_SIZE / 3 + 1),
+                (4 * MIN_SEGMENT_SIZE / 3 + 1, 8 * MIN_SEGMENT_SIZE / 3 + 2),
+                (8 * MIN_SEGMENT_SIZE / 3 + 2, 4 * MIN_SEGMENT_SIZE + 3),
+            ]
+        );
+    }
+
     #[test]
     fn unpacks_single_zip_when_policy_requests_it() {
         let dir = test_dir("unpack-zip");
@@ -1186,6 +2796,7 @@ mod tests {
             path: archive.clone(),
             bytes_written: std::fs::metadata(&archive).unwrap().len(),
             resumed_from: 0,
+            discarded_partial: None,
         };
 
         let extraction = unpack_downloaded_files(
@@ -1217,12 +2828,14 @@ mod tests {
                 path: first_part.clone(),
                 bytes_written: 5,
                 resumed_from: 0,
+                discarded_partial: None,
             },
             DownloadedFile {
                 file_name: "RJ123456.part2.rar".to_owned(),
                 path: second_part.clone(),
                 bytes_written: 5,
                 resumed_from: 0,
+                discarded_partial: None,
             },
         ];
 
@@ -1241,6 +2854,35 @@ mod tests {
         std::fs::remove_dir_all(dir).unwrap();
     }
 
+    #[test]
+    fn removes_partial_streamed_output_when_finishing_fails() {
+        let dir = test_dir("streamed-finish-fails");
+        let archive = dir.join("RJ123456.zip");
+        write_zip(
+            &archive,
+            &[("a.txt", b"new".as_slice()), ("b.txt", b"new".as_slice())],
+        );
+        std::fs::write(dir.join("b.txt"), b"old").unwrap();
+        let mut extractor =
+            dm_archive::ZipStreamExtractor::new(&dir, ArchiveNameEncoding::Auto).unwrap();
+        extractor
+            .extract(std::fs::File::open(&archive).unwrap())
+            .unwrap();
+
+        let extraction =
+            finish_streamed_extraction(extractor, &archive, &dir, ArchiveExtractOptions::default())
+                .unwrap();
+        let mut names = directory_entry_names(&dir).unwrap().into_iter();
+
+        assert!(extraction.is_none());
+        assert_eq!(names.next().as_deref(), Some("RJ123456.zip".as_ref()));
+        assert_eq!(names.next().as_deref(), Some("b.txt".as_ref()));
+        assert_eq!(names.next(), None);
+        assert_eq!(std::fs::read(dir.join("b.txt")).unwrap(), b"old");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     fn request(dir: &Path, file_name: &str, expected_size: Option<u64>) -> DownloadFileRequest {
         let mut request =
             DownloadFileRequest::new(0, DownloadFileKind::Direct, dir, file_name.to_owned());
@@ -1280,7 +2922,10 @@ mod tests {
         openings: VecDeque<Result<Vec<Vec<u8>>, DownloadError>>,
         starts: Arc<Mutex<Vec<u64>>>,
         fail_after: Option<(usize, usize)>,
+        stall_after: Option<(usize, usize)>,
+        chunk_delay: Duration,
         open_count: usize,
+        validators: RemoteFileValidators,
     }
 
     impl ScriptedSource {
@@ -1289,15 +2934,33 @@ mod tests {
                 openings: VecDeque::from(openings),
                 starts: Arc::new(Mutex::new(Vec::new())),
                 fail_after: None,
+                stall_after: None,
+                chunk_delay: Duration::ZERO,
                 open_count: 0,
+                validators: RemoteFileValidators::default(),
             }
         }
 
+        fn with_etag(mut self, etag: &str) -> Self {
+            self.validators.etag = Some(etag.to_owned());
+            self
+        }
+
         fn with_fail_after_chunks(mut self, open_index: usize, chunks: usize) -> Self {
             self.fail_after = Some((open_index, chunks));
             self
         }
 
+        fn with_stall_after_chunks(mut self, open_index: usize, chunks: usize) -> Self {
+            self.stall_after = Some((open_index, chunks));
+            self
+        }
+
+        fn with_chunk_delay(mut self, delay: Duration) -> Self {
+            self.chunk_delay = delay;
+            self
+        }
+
         fn starts(&self) -> Vec<u64> {
             self.starts.lock().unwrap().clone()
         }
@@ -1316,11 +2979,18 @@ mod tests {
                 let fail_after = self
                     .fail_after
                     .and_then(|(index, chunks)| (index == open_index).then_some(chunks));
+                let stall_after = self
+                    .stall_after
+                    .and_then(|(index, chunks)| (index == open_index).then_some(chunks));
 
                 let stream: Box<dyn DownloadByteStream + Send + 'a> = Box::new(ScriptedStream {
                     chunks: VecDeque::from(chunks),
                     fail_after,
+                    stall_after,
+                    chunk_delay: self.chunk_delay,
                     emitted_chunks: 0,
+                    validators: self.validators.clone(),
+                    body_start: Some(start),
                 });
 
                 Ok(stream)
@@ -1331,7 +3001,11 @@ mod tests {
     struct ScriptedStream {
         chunks: VecDeque<Vec<u8>>,
         fail_after: Option<usize>,
+        stall_after: Option<usize>,
+        chunk_delay: Duration,
         emitted_chunks: usize,
+        validators: RemoteFileValidators,
+        body_start: Option<u64>,
     }
 
     impl DownloadByteStream for ScriptedStream {
@@ -1342,6 +3016,11 @@ mod tests {
                     return Err(DownloadError::Stream("scripted failure".to_owned()));
                 }
 
+                if self.stall_after == Some(self.emitted_chunks) {
+                    std::future::pending::<()>().await;
+                }
+
+                sleep(self.chunk_delay).await;
                 let chunk = self.chunks.pop_front();
 
                 if chunk.is_some() {
@@ -1351,6 +3030,85 @@ mod tests {
                 Ok(chunk)
             })
         }
+
+        fn validators(&self) -> RemoteFileValidators {
+            self.validators.clone()
+        }
+
+        fn body_start(&self) -> Option<u64> {
+            self.body_start
+        }
+    }
+
+    #[derive(Clone)]
+    struct SliceSource {
+        data: Arc<Vec<u8>>,
+        starts: Arc<Mutex<Vec<u64>>>,
+        validators: RemoteFileValidators,
+        ignores_ranges: bool,
+        fails_from: Option<u64>,
+    }
+
+    impl SliceSource {
+        fn new(data: Vec<u8>) -> Self {
+            Self {
+                data: Arc::new(data),
+                starts: Arc::new(Mutex::new(Vec::new())),
+                validators: RemoteFileValidators::default(),
+                ignores_ranges: false,
+                fails_from: None,
+            }
+        }
+
+        /// Fails every request for a range starting at or after `offset`.
+        fn failing_from(mut self, offset: u64) -> Self {
+            self.fails_from = Some(offset);
+            self
+        }
+
+        fn with_etag(mut self, etag: &str) -> Self {
+            self.validators.etag = Some(etag.to_owned());
+            self
+        }
+
+        /// Answers every request with the whole file, like a server without range support.
+        fn ignoring_ranges(mut self) -> Self {
+            self.ignores_ranges = true;
+            self
+        }
+
+        fn starts(&self) -> Vec<u64> {
+            self.starts.lock().unwrap().clone()
+        }
+    }
+
+    impl RangedDownloadSource for SliceSource {
+        fn open_range<'a>(&'a mut self, start: u64) -> DownloadOpenFuture<'a> {
+            Box::pin(async move {
+                self.starts.lock().unwrap().push(start);
+
+                if self.fails_from.is_some_and(|offset| start >= offset) {
+                    return Err(DownloadError::Stream("connection reset".to_owned()));
+                }
+
+                let body_start = if self.ignores_ranges { 0 } else { start };
+                let chunks = self.data[body_start as usize..]
+                    .chunks(64 * 1024)
+                    .map(<[u8]>::to_vec)
+                    .collect();
+                let stream: Box<dyn DownloadByteStream + Send + 'a> = Box::new(ScriptedStream {
+                    chunks,
+                    fail_after: None,
+                    stall_after: None,
+                    chunk_delay: Duration::ZERO,
+                    emitted_chunks: 0,
+                    validators: self.validators.clone(),
+                    body_start: Some(body_start),
+                });
+
+                Ok(stream)
+            })
+        }
     }
 
     struct PendingStreamSource;
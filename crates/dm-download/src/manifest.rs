@@ -0,0 +1,238 @@
+use crate::{CancellationToken, DownloadError};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+const HASH_BUFFER_SIZE: usize = 256 * 1024;
+
+/// One file of a finished work, keyed by its `/`-separated path below the work folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileManifestEntry {
+    pub relative_path: String,
+    pub size: u64,
+    /// Lowercase hex SHA-256 of the file contents.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestVerification {
+    pub checked_count: usize,
+    pub missing: Vec<String>,
+    pub changed: Vec<String>,
+    pub extra: Vec<String>,
+}
+
+impl ManifestVerification {
+    pub fn is_intact(&self) -> bool {
+        self.missing.is_empty() && self.changed.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// Hashes every regular file below `root`, skipping symlinks and the names in `excluded`.
+///
+/// Blocking; run it on a blocking thread.
+pub fn build_file_manifest(
+    root: &Path,
+    excluded: &[&str],
+    cancellation: &CancellationToken,
+) -> Result<Vec<FileManifestEntry>, DownloadError> {
+    list_manifest_files(root, excluded)?
+        .into_iter()
+        .map(|(relative_path, size)| {
+            if cancellation.is_cancelled() {
+                return Err(DownloadError::Cancelled);
+            }
+
+            let sha256 = hash_file(&root.join(&relative_path), cancellation)?;
+
+            Ok(FileManifestEntry {
+                relative_path,
+                size,
+                sha256,
+            })
+        })
+        .collect()
+}
+
+/// Re-hashes the files of `root` and compares them with a recorded manifest.
+///
+/// Files whose size already differs are reported as changed without being hashed.
+pub fn verify_file_manifest(
+    root: &Path,
+    manifest: &[FileManifestEntry],
+    excluded: &[&str],
+    cancellation: &CancellationToken,
+) -> Result<ManifestVerification, DownloadError> {
+    let mut current = list_manifest_files(root, excluded)?;
+    let mut verification = ManifestVerification::default();
+
+    for entry in manifest {
+        if cancellation.is_cancelled() {
+            return Err(DownloadError::Cancelled);
+        }
+
+        let Some(size) = current.remove(&entry.relative_path) else {
+            verification.missing.push(entry.relative_path.clone());
+            continue;
+        };
+
+        verification.checked_count += 1;
+
+        if size != entry.size
+            || hash_file(&root.join(&entry.relative_path), cancellation)? != entry.sha256
+        {
+            verification.changed.push(entry.relative_path.clone());
+        }
+    }
+
+    verification.extra = current.into_keys().collect();
+
+    Ok(verification)
+}
+
+fn list_manifest_files(
+    root: &Path,
+    excluded: &[&str],
+) -> Result<BTreeMap<String, u64>, DownloadError> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![(root.to_path_buf(), String::new())];
+
+    while let Some((dir, prefix)) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str() else {
+                return Err(DownloadError::NonUtf8Path { path: entry.path() });
+            };
+            let relative_path = format!("{prefix}{name}");
+
+            if excluded.contains(&relative_path.as_str()) {
+                continue;
+            }
+
+            let file_type = entry.file_type()?;
+
+            if file_type.is_dir() {
+                pending.push((entry.path(), format!("{relative_path}/")));
+            } else if file_type.is_file() {
+                files.insert(relative_path, entry.metadata()?.len());
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn hash_file(path: &Path, cancellation: &CancellationToken) -> Result<String, DownloadError> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; HASH_BUFFER_SIZE];
+
+    loop {
+        if cancellation.is_cancelled() {
+            return Err(DownloadError::Cancelled);
+        }
+
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => hasher.update(&buffer[..read]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn records_nested_files_with_sha256() {
+        let dir = test_dir("manifest-build");
+        std::fs::create_dir_all(dir.join("audio/disc 1")).unwrap();
+        std::fs::write(dir.join("readme.txt"), b"abc").unwrap();
+        std::fs::write(dir.join("audio/disc 1/track.wav"), b"").unwrap();
+        std::fs::write(dir.join("manifest.json"), b"{}").unwrap();
+
+        let manifest =
+            build_file_manifest(&dir, &["manifest.json"], &CancellationToken::new()).unwrap();
+
+        assert_eq!(
+            manifest,
+            vec![
+                FileManifestEntry {
+                    relative_path: "audio/disc 1/track.wav".to_owned(),
+                    size: 0,
+                    sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                        .to_owned(),
+                },
+                FileManifestEntry {
+                    relative_path: "readme.txt".to_owned(),
+                    size: 3,
+                    sha256: "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+                        .to_owned(),
+                },
+            ]
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reports_missing_changed_and_extra_files() {
+        let dir = test_dir("manifest-verify");
+        std::fs::create_dir_all(dir.join("data")).unwrap();
+        std::fs::write(dir.join("data/kept.bin"), b"kept").unwrap();
+        std::fs::write(dir.join("data/edited.bin"), b"before").unwrap();
+        std::fs::write(dir.join("data/resized.bin"), b"short").unwrap();
+        std::fs::write(dir.join("removed.bin"), b"gone").unwrap();
+        let cancellation = CancellationToken::new();
+        let manifest = build_file_manifest(&dir, &[], &cancellation).unwrap();
+
+        std::fs::write(dir.join("data/edited.bin"), b"after!").unwrap();
+        std::fs::write(dir.join("data/resized.bin"), b"much longer").unwrap();
+        std::fs::remove_file(dir.join("removed.bin")).unwrap();
+        std::fs::write(dir.join("added.bin"), b"new").unwrap();
+
+        let verification = verify_file_manifest(&dir, &manifest, &[], &cancellation).unwrap();
+
+        assert!(!verification.is_intact());
+        assert_eq!(verification.checked_count, 3);
+        assert_eq!(verification.missing, vec!["removed.bin"]);
+        assert_eq!(
+            verification.changed,
+            vec!["data/edited.bin", "data/resized.bin"]
+        );
+        assert_eq!(verification.extra, vec!["added.bin"]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "dm-download-{name}-{}-{unique}",
+            std::process::id()
+        ));
+
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    header_value, total_size_from_content_range, DownloadError, InvalidDownloadResponseReason,
+};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+
+/// Identity of the remote object a staging file was downloaded from.
+///
+/// Kept in a sidecar next to the staging file so a resumed download can tell whether the remote
+/// file was replaced in between.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemoteFileValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub total_size: Option<u64>,
+}
+
+impl RemoteFileValidators {
+    pub fn from_headers(headers: &BTreeMap<String, String>) -> Self {
+        Self {
+            etag: header_value(headers, "etag").map(str::to_owned),
+            last_modified: header_value(headers, "last-modified").map(str::to_owned),
+            total_size: header_value(headers, "content-range")
+                .and_then(total_size_from_content_range),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none() && self.total_size.is_none()
+    }
+
+    /// Returns the first validator known on both sides whose value differs.
+    pub fn mismatch(&self, current: &Self) -> Option<InvalidDownloadResponseReason> {
+        fn changed<T: ToString + PartialEq>(
+            validator: &'static str,
+            previous: &Option<T>,
+            current: &Option<T>,
+        ) -> Option<InvalidDownloadResponseReason> {
+            match (previous, current) {
+                (Some(previous), Some(current)) if previous != current => {
+                    Some(InvalidDownloadResponseReason::RemoteFileChanged {
+                        validator,
+                        previous: previous.to_string(),
+                        current: current.to_string(),
+                    })
+                }
+                _ => None,
+            }
+        }
+
+        changed("ETag", &self.etag, &current.etag)
+            .or_else(|| changed("Last-Modified", &self.last_modified, &current.last_modified))
+            .or_else(|| changed("size", &self.total_size, &current.total_size))
+    }
+}
+
+pub(crate) fn resume_sidecar_path(staging_path: &Path) -> PathBuf {
+    let mut path = staging_path.as_os_str().to_owned();
+    path.push(".resume");
+    PathBuf::from(path)
+}
+
+pub(crate) async fn load_resume_validators(
+    path: &Path,
+) -> Result<Option<RemoteFileValidators>, DownloadError> {
+    let contents = match fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut validators = RemoteFileValidators::default();
+
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once(' ') else {
+            continue;
+        };
+
+        match key {
+            "etag" => validators.etag = Some(value.to_owned()),
+            "last-modified" => validators.last_modified = Some(value.to_owned()),
+            "size" => validators.total_size = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Ok((!validators.is_empty()).then_some(validators))
+}
+
+pub(crate) async fn save_resume_validators(
+    path: &Path,
+    validators: &RemoteFileValidators,
+) -> Result<(), DownloadError> {
+    let mut contents = String::new();
+
+    if let Some(etag) = &validators.etag {
+        contents.push_str(&format!("etag {etag}\n"));
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        contents.push_str(&format!("last-modified {last_modified}\n"));
+    }
+    if let Some(total_size) = validators.total_size {
+        contents.push_str(&format!("size {total_size}\n"));
+    }
+
+    fs::write(path, contents).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_validators_from_range_response_headers() {
+        let headers = BTreeMap::from([
+            ("ETag".to_owned(), "\"abc\"".to_owned()),
+            (
+                "Last-Modified".to_owned(),
+                "Wed, 01 Jan 2025 00:00:00 GMT".to_owned(),
+            ),
+            ("Content-Range".to_owned(), "bytes 10-99/100".to_owned()),
+        ]);
+
+        let validators = RemoteFileValidators::from_headers(&headers);
+
+        assert_eq!(validators.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(validators.total_size, Some(100));
+        assert_eq!(validators.mismatch(&validators), None);
+        assert_eq!(
+            validators.mismatch(&RemoteFileValidators {
+                etag: None,
+                total_size: Some(120),
+                ..validators.clone()
+            }),
+            Some(InvalidDownloadResponseReason::RemoteFileChanged {
+                validator: "size",
+                previous: "100".to_owned(),
+                current: "120".to_owned(),
+            })
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{cancellable, CancellationToken, DownloadError, DownloadRetry, DEFAULT_MAX_RETRIES};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::{Arc, Mutex},
+};
+use tokio::time::{sleep, Duration, Instant};
+
+pub const DEFAULT_INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+pub const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+pub const DEFAULT_RETRY_JITTER_PERCENT: u32 = 20;
+
+/// How a failed connection of one file is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff: BackoffCurve,
+    /// Delay before the first retry; later delays grow from it along `backoff`.
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Up to this share of each delay is added at random so parallel connections spread out.
+    pub jitter_percent: u32,
+    pub retryable: RetryableErrors,
+    /// No retry starts once this much time has passed since the file download began.
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff: BackoffCurve::Exponential,
+            initial_delay: DEFAULT_INITIAL_RETRY_DELAY,
+            max_delay: DEFAULT_MAX_RETRY_DELAY,
+            jitter_percent: DEFAULT_RETRY_JITTER_PERCENT,
+            retryable: RetryableErrors::default(),
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the given 1-based retry, without jitter.
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let delay = match self.backoff {
+            BackoffCurve::Constant => self.initial_delay,
+            BackoffCurve::Linear => self.initial_delay.saturating_mul(attempt),
+            BackoffCurve::Exponential => self
+                .initial_delay
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1))),
+        };
+
+        delay.min(self.max_delay)
+    }
+
+    fn jittered_delay(&self, attempt: u32) -> Duration {
+        let delay = self.backoff_delay(attempt);
+        let max_jitter = delay.saturating_mul(self.jitter_percent.min(100)) / 100;
+
+        if max_jitter.is_zero() {
+            return delay;
+        }
+
+        let random = RandomState::new().build_hasher().finish();
+        delay + max_jitter.mul_f64(random as f64 / u64::MAX as f64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackoffCurve {
+    Constant,
+    Linear,
+    #[default]
+    Exponential,
+}
+
+/// Which kinds of [`DownloadError`] are worth another attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryableErrors {
+    /// Failed requests and broken response streams.
+    pub network: bool,
+    pub stalled: bool,
+    /// Responses that ended before the expected size.
+    pub incomplete: bool,
+    /// Responses that looked wrong, including a remote file replaced mid-download.
+    pub invalid_response: bool,
+}
+
+impl Default for RetryableErrors {
+    fn default() -> Self {
+        Self {
+            network: true,
+            stalled: true,
+            incomplete: true,
+            invalid_response: true,
+        }
+    }
+}
+
+impl RetryableErrors {
+    pub fn contains(&self, error: &DownloadError) -> bool {
+        match error {
+            DownloadError::Api(err) => self.network && err.is_retriable(),
+            DownloadError::Stream(_) => self.network,
+            DownloadError::Stalled { .. } => self.stalled,
+            DownloadError::IncompleteDownload { .. } => self.incomplete,
+            DownloadError::InvalidDownloadResponse { .. } => self.invalid_response,
+            _ => false,
+        }
+    }
+}
+
+/// Retries spent by one connection sequence against its [`RetryPolicy`].
+pub(crate) struct RetryState {
+    policy: RetryPolicy,
+    attempts: u32,
+    started: Instant,
+}
+
+impl RetryState {
+    pub(crate) fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            attempts: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Counts another attempt for `error`, or returns `None` when the policy gives up on it.
+    fn next(&mut self, error: &DownloadError) -> Option<DownloadRetry> {
+        if self.attempts >= self.policy.max_retries || !self.policy.retryable.contains(error) {
+            return None;
+        }
+
+        let delay = self.policy.jittered_delay(self.attempts + 1);
+
+        if self
+            .policy
+            .deadline
+            .is_some_and(|deadline| self.started.elapsed() + delay > deadline)
+        {
+            return None;
+        }
+
+        self.attempts += 1;
+        Some(DownloadRetry {
+            attempt: self.attempts,
+            max_retries: self.policy.max_retries,
+            delay,
+            reason: error.to_string(),
+        })
+    }
+
+    /// Reports the retry and waits out its delay, or hands `error` back when the policy gives up.
+    pub(crate) async fn retry_after(
+        &mut self,
+        error: DownloadError,
+        cancellation: &CancellationToken,
+        report: impl FnOnce(DownloadRetry),
+    ) -> Result<(), DownloadError> {
+        let Some(retry) = self.next(&error) else {
+            return Err(error);
+        };
+
+        wait_for_retry(retry, cancellation, report).await
+    }
+}
+
+/// A [`RetryState`] the segment connections of one file draw on together, so the file gets one
+/// retry budget and one deadline however many connections it uses.
+#[derive(Clone)]
+pub(crate) struct SharedRetryState(Arc<Mutex<RetryState>>);
+
+impl SharedRetryState {
+    pub(crate) fn new(policy: RetryPolicy) -> Self {
+        Self(Arc::new(Mutex::new(RetryState::new(policy))))
+    }
+
+    /// Like [`RetryState::retry_after`], counting the attempt against the whole file.
+    pub(crate) async fn retry_after(
+        &self,
+        error: DownloadError,
+        cancellation: &CancellationToken,
+        report: impl FnOnce(DownloadRetry),
+    ) -> Result<(), DownloadError> {
+        let retry = self.0.lock().expect("retry state lock").next(&error);
+        let Some(retry) = retry else {
+            return Err(error);
+        };
+
+        wait_for_retry(retry, cancellation, report).await
+    }
+}
+
+async fn wait_for_retry(
+    retry: DownloadRetry,
+    cancellation: &CancellationToken,
+    report: impl FnOnce(DownloadRetry),
+) -> Result<(), DownloadError> {
+    let delay = retry.delay;
+
+    report(retry);
+    cancellable(cancellation, async {
+        sleep(delay).await;
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_delays_along_the_backoff_curve_up_to_the_cap() {
+        let policy = RetryPolicy {
+            initial_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(10),
+            ..RetryPolicy::default()
+        };
+        let delays = |backoff| {
+            (1..=4)
+                .map(|attempt| RetryPolicy { backoff, ..policy }.backoff_delay(attempt))
+                .map(|delay| delay.as_secs())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(delays(BackoffCurve::Constant), vec![2, 2, 2, 2]);
+        assert_eq!(delays(BackoffCurve::Linear), vec![2, 4, 6, 8]);
+        assert_eq!(delays(BackoffCurve::Exponential), vec![2, 4, 8, 10]);
+
+        for _ in 0..20 {
+            let delay = policy.jittered_delay(1);
+            assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_millis(2400));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_on_errors_outside_the_policy_or_past_the_deadline() {
+        let mut retries = RetryState::new(RetryPolicy {
+            max_retries: 5,
+            initial_delay: Duration::from_secs(10),
+            jitter_percent: 0,
+            retryable: RetryableErrors {
+                stalled: false,
+                ..RetryableErrors::default()
+            },
+            deadline: Some(Duration::from_secs(25)),
+            ..RetryPolicy::default()
+        });
+        let stream_error = || DownloadError::Stream("reset".to_owned());
+
+        assert!(retries
+            .next(&DownloadError::Stalled {
+                reason: crate::StallReason::Idle {
+                    timeout: Duration::from_secs(1),
+                },
+            })
+            .is_none());
+        assert!(retries
+            .next(&DownloadError::SegmentGap { offset: 0 })
+            .is_none());
+        assert_eq!(
+            retries.next(&stream_error()).map(|retry| retry.delay),
+            Some(Duration::from_secs(10))
+        );
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+
+        assert!(retries.next(&stream_error()).is_none());
+    }
+
+    #[test]
+    fn retries_only_api_errors_that_may_succeed_later() {
+        let unavailable = |status| {
+            DownloadError::Api(dm_api::DmApiError::DownloadUnavailable {
+                work_id: dm_api::WorkId::from("RJ000001"),
+                reason: dm_api::DownloadUnavailableReason::UnexpectedStatus {
+                    status,
+                    body_snippet: None,
+                },
+            })
+        };
+        let retryable = RetryableErrors::default();
+
+        assert!(retryable.contains(&unavailable(503)));
+        assert!(!retryable.contains(&unavailable(404)));
+        assert!(!retryable.contains(&DownloadError::Api(dm_api::DmApiError::NotAuthorized)));
+        assert!(!RetryableErrors {
+            network: false,
+            ..retryable
+        }
+        .contains(&unavailable(503)));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{staging_dir_for, DownloadPhase, DownloadProgress};
+use dm_archive::{ArchiveNameEncoding, ZipStreamExtractor};
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Unpacks a single ZIP download from its staging file while the download is still appending
+/// to it.
+pub(crate) struct StreamingUnpack {
+    state: Arc<StreamState>,
+    task: Option<JoinHandle<Option<ZipStreamExtractor>>>,
+}
+
+#[derive(Default)]
+struct StreamState {
+    bytes_received: AtomicU64,
+    finished: AtomicBool,
+    abandoned: AtomicBool,
+}
+
+impl StreamingUnpack {
+    pub(crate) fn start(
+        target_dir: &Path,
+        file_name: &str,
+        name_encoding: ArchiveNameEncoding,
+    ) -> Self {
+        let state = Arc::new(StreamState::default());
+        let reader = GrowingFileReader {
+            path: staging_dir_for(target_dir).join(file_name),
+            file: None,
+            state: state.clone(),
+        };
+        let output_dir = target_dir.to_owned();
+        let task = tokio::task::spawn_blocking(move || {
+            // Left over when an earlier attempt at this work was interrupted mid-stream.
+            dm_archive::remove_stale_staging_dirs(&output_dir).ok()?;
+            let mut extractor = ZipStreamExtractor::new(&output_dir, name_encoding).ok()?;
+            extractor.extract(reader).ok()?;
+            Some(extractor)
+        });
+
+        Self {
+            state,
+            task: Some(task),
+        }
+    }
+
+    /// Gives up as soon as the staging file shrinks, which means its bytes were thrown away.
+    pub(crate) fn observe(&self, progress: &DownloadProgress) {
+        if progress.phase != DownloadPhase::Downloading {
+            return;
+        }
+
+        let previous = self
+            .state
+            .bytes_received
+            .swap(progress.bytes_received, Ordering::SeqCst);
+
+        if progress.bytes_received < previous {
+            self.state.abandoned.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Waits for the entries still being extracted from a complete download.
+    ///
+    /// Returns `None` when the archive couldn't be read front to back, or when `complete` is
+    /// false because the downloaded bytes can't be trusted; the archive is then unpacked from disk.
+    pub(crate) async fn finish(mut self, complete: bool) -> Option<ZipStreamExtractor> {
+        let flag = if complete {
+            &self.state.finished
+        } else {
+            &self.state.abandoned
+        };
+        flag.store(true, Ordering::SeqCst);
+
+        let extractor = self.task.take()?.await.ok().flatten();
+
+        if complete {
+            return extractor;
+        }
+
+        // Dropping the extractor deletes everything it has written so far.
+        tokio::task::spawn_blocking(move || drop(extractor))
+            .await
+            .ok();
+        None
+    }
+}
+
+impl Drop for StreamingUnpack {
+    fn drop(&mut self) {
+        self.state.abandoned.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Reads a staging file front to back, waiting for bytes the download hasn't written yet.
+struct GrowingFileReader {
+    path: PathBuf,
+    file: Option<fs::File>,
+    state: Arc<StreamState>,
+}
+
+impl Read for GrowingFileReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.state.abandoned.load(Ordering::SeqCst) {
+                return Err(io::Error::other("streaming unpack was abandoned"));
+            }
+
+            // Loaded before reading: once the download has finished, an empty read is the end.
+            let finished = self.state.finished.load(Ordering::SeqCst);
+
+            // The staging file is only opened after the download has written to it, so a stale
+            // partial that gets replaced up front is never read.
+            if self.file.is_none()
+                && (finished || self.state.bytes_received.load(Ordering::SeqCst) > 0)
+            {
+                self.file = Some(fs::File::open(&self.path)?);
+            }
+
+            if let Some(file) = self.file.as_mut() {
+                let read = file.read(buf)?;
+
+                if read > 0 || finished || buf.is_empty() {
+                    return Ok(read);
+                }
+            }
+
+            std::thread::sleep(STREAM_POLL_INTERVAL);
+        }
+    }
+}
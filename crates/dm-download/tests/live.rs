@@ -1,7 +1,8 @@
 use dm_api::{Credentials, DlsiteClient, DlsiteClientConfig, WorkId};
 use dm_download::{
-    download_work_files, probe_download_file_metadata, CancellationToken, DownloadJobRequest,
-    UnpackPolicy,
+    download_work_files, probe_download_file_metadata, ArchiveNameEncoding, CancellationToken,
+    DownloadJobRequest, RetryPolicy, StallPolicy, UnpackPolicy, DEFAULT_CONNECTIONS_PER_FILE,
+    DEFAULT_PARALLEL_FILES,
 };
 use std::{
     env,
@@ -77,6 +78,13 @@ async fn live_downloads_pinned_small_work_to_temp_dir() -> TestResult {
         work_id: env.work_id,
         target_root: target_root.clone(),
         unpack_policy: UnpackPolicy::KeepArchives,
+        archive_name_encoding: ArchiveNameEncoding::Auto,
+        archive_passwords: Vec::new(),
+        nested_archives: None,
+        connections_per_file: DEFAULT_CONNECTIONS_PER_FILE,
+        parallel_files: DEFAULT_PARALLEL_FILES,
+        retry_policy: RetryPolicy::default(),
+        stall_policy: StallPolicy::default(),
     };
     let downloaded =
         download_work_files(client, &job, &plan, &CancellationToken::new(), |_| {}).await?;
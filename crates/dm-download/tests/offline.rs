@@ -0,0 +1,592 @@
+use dm_api::{
+    DlsiteClient, DlsiteClientConfig, DlsiteEndpoints, DownloadFile, DownloadFileKind,
+    DownloadPlan, DownloadStreamRequest, RequestGovernor, RequestGovernorConfig, WorkId,
+};
+use dm_download::{
+    download_work_files, ArchiveNameEncoding, CancellationToken, DownloadError, DownloadJobRequest,
+    DownloadPhase, InvalidDownloadResponseReason, RetryPolicy, StallPolicy, UnpackPolicy,
+    DEFAULT_CONNECTIONS_PER_FILE, DEFAULT_PARALLEL_FILES,
+};
+use dm_fake_dlsite::{
+    FakeDlsite, FakeDlsiteConfig, FakeDownload, FakeEndpoint, FakeFile, FakeWork, Fault, FaultRule,
+};
+use std::{
+    error::Error,
+    io::{Cursor, Write},
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+#[tokio::test]
+async fn downloads_and_unpacks_direct_zip_from_fake_server() -> TestResult {
+    let fake = FakeDlsite::start(config(direct_work("RJ000001"))).await?;
+    let target_root = test_dir("offline-direct");
+    let mut phases = Vec::new();
+
+    let downloaded = download_work_files(
+        client(&fake)?,
+        &job("RJ000001", &target_root, UnpackPolicy::UnpackWhenRecognized),
+        &direct_plan(&fake, "RJ000001"),
+        &CancellationToken::new(),
+        |progress| phases.push(progress.phase),
+    )
+    .await?;
+
+    assert!(downloaded.archive_extraction.is_some());
+    assert_eq!(
+        std::fs::read(target_root.join("RJ000001/readme.txt"))?,
+        b"hello from the fake server"
+    );
+    assert!(!target_root.join("RJ000001/RJ000001.zip").exists());
+    assert!(phases.contains(&DownloadPhase::Unpacking));
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn keeps_the_archive_next_to_the_unpacked_files() -> TestResult {
+    let fake = FakeDlsite::start(config(direct_work("RJ000001"))).await?;
+    let target_root = test_dir("offline-unpack-and-keep");
+
+    let downloaded = download_work_files(
+        client(&fake)?,
+        &job(
+            "RJ000001",
+            &target_root,
+            UnpackPolicy::UnpackAndKeepArchives,
+        ),
+        &direct_plan(&fake, "RJ000001"),
+        &CancellationToken::new(),
+        |_| {},
+    )
+    .await?;
+
+    assert_eq!(
+        downloaded
+            .archive_extraction
+            .map(|extraction| extraction.removed_sources),
+        Some(Vec::new())
+    );
+    assert!(target_root.join("RJ000001/readme.txt").is_file());
+    assert!(target_root.join("RJ000001/RJ000001.zip").is_file());
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unpacks_single_zip_while_it_downloads() -> TestResult {
+    let archive = zip_bytes(&[("first.bin", &[1; 4096]), ("last.bin", &[2; 4096])]);
+    let mut work = FakeWork::new("RJ000001", "Streamed Work", "Maker");
+    work.download = Some(FakeDownload::Direct {
+        file: FakeFile::new("RJ000001.zip", archive),
+    });
+    let fake = FakeDlsite::start(config(work)).await?;
+    let target_root = test_dir("offline-streamed-unpack");
+    let streamed_entry = target_root.join("RJ000001/.dm-archive-0/first.bin");
+    let mut unpacked_early = false;
+    fake.push_fault(FaultRule::new(
+        FakeEndpoint::File,
+        Fault::SlowChunks {
+            chunk_size: 1024,
+            delay: Duration::from_millis(20),
+        },
+    ));
+
+    let downloaded = download_work_files(
+        client(&fake)?,
+        &job("RJ000001", &target_root, UnpackPolicy::UnpackWhenRecognized),
+        &direct_plan(&fake, "RJ000001"),
+        &CancellationToken::new(),
+        |progress| {
+            if progress.phase == DownloadPhase::Downloading
+                && progress.bytes_total > Some(progress.bytes_received)
+                && streamed_entry.exists()
+            {
+                unpacked_early = true;
+            }
+        },
+    )
+    .await?;
+
+    assert!(unpacked_early);
+    assert!(downloaded.archive_extraction.is_some());
+    assert_eq!(
+        std::fs::read(target_root.join("RJ000001/last.bin"))?,
+        vec![2; 4096]
+    );
+    assert!(!target_root.join("RJ000001/RJ000001.zip").exists());
+    assert!(!target_root.join("RJ000001/.dm-archive-0").exists());
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unpacks_zip_after_download_when_entries_need_the_central_directory() -> TestResult {
+    let mut archive = zip_bytes(&[("readme.txt", b"sizes after the data")]);
+    // General purpose flag bit 3 of the first local header: sizes follow the entry data.
+    archive[6] |= 1 << 3;
+    let mut work = FakeWork::new("RJ000001", "Descriptor Work", "Maker");
+    work.download = Some(FakeDownload::Direct {
+        file: FakeFile::new("RJ000001.zip", archive),
+    });
+    let fake = FakeDlsite::start(config(work)).await?;
+    let target_root = test_dir("offline-descriptor-unpack");
+
+    let downloaded = download_work_files(
+        client(&fake)?,
+        &job("RJ000001", &target_root, UnpackPolicy::UnpackWhenRecognized),
+        &direct_plan(&fake, "RJ000001"),
+        &CancellationToken::new(),
+        |_| {},
+    )
+    .await?;
+
+    assert!(downloaded.archive_extraction.is_some());
+    assert_eq!(
+        std::fs::read(target_root.join("RJ000001/readme.txt"))?,
+        b"sizes after the data"
+    );
+    assert!(!target_root.join("RJ000001/.dm-archive-0").exists());
+    assert!(!target_root.join("RJ000001/.dm-archive-1").exists());
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn downloads_large_file_over_several_ranged_connections() -> TestResult {
+    let payload = (0..3 * 1024 * 1024)
+        .map(|index: u32| (index % 251) as u8)
+        .collect::<Vec<_>>();
+    let archive = zip_bytes(&[("movie.bin", &payload)]);
+    let segment_size = archive.len() / 3;
+    let mut work = FakeWork::new("RJ000001", "Large Work", "Maker");
+    work.download = Some(FakeDownload::Direct {
+        file: FakeFile::new("RJ000001.zip", archive),
+    });
+    let fake = FakeDlsite::start(config(work)).await?;
+    let target_root = test_dir("offline-segmented");
+
+    download_work_files(
+        client(&fake)?,
+        &DownloadJobRequest {
+            connections_per_file: 3,
+            ..job("RJ000001", &target_root, UnpackPolicy::UnpackWhenRecognized)
+        },
+        &direct_plan(&fake, "RJ000001"),
+        &CancellationToken::new(),
+        |_| {},
+    )
+    .await?;
+    let mut ranges = fake
+        .requests_to(FakeEndpoint::File)
+        .into_iter()
+        .filter_map(|request| request.range)
+        .collect::<Vec<_>>();
+    ranges.sort();
+
+    assert_eq!(
+        std::fs::read(target_root.join("RJ000001/movie.bin"))?,
+        payload
+    );
+    assert_eq!(
+        ranges,
+        vec![
+            "bytes=0-".to_owned(),
+            "bytes=0-0".to_owned(),
+            format!("bytes={segment_size}-"),
+            format!("bytes={}-", 2 * segment_size),
+        ]
+    );
+    assert!(!target_root.join("RJ000001/.dm-download").exists());
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resumes_from_received_offset_after_truncated_body() -> TestResult {
+    let fake = FakeDlsite::start(config(direct_work("RJ000001"))).await?;
+    let target_root = test_dir("offline-truncated");
+    // The one-byte metadata probe consumes the first use without being cut short.
+    fake.push_fault(FaultRule {
+        times: Some(2),
+        ..FaultRule::new(FakeEndpoint::File, Fault::TruncatedBody { after_bytes: 16 })
+    });
+
+    let downloaded = download_work_files(
+        client(&fake)?,
+        &job("RJ000001", &target_root, UnpackPolicy::KeepArchives),
+        &direct_plan(&fake, "RJ000001"),
+        &CancellationToken::new(),
+        |_| {},
+    )
+    .await?;
+    let ranges = fake
+        .requests_to(FakeEndpoint::File)
+        .into_iter()
+        .map(|request| request.range)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        ranges,
+        vec![
+            Some("bytes=0-0".to_owned()),
+            Some("bytes=0-".to_owned()),
+            Some("bytes=16-".to_owned()),
+        ]
+    );
+    assert_eq!(
+        std::fs::read(&downloaded.files[0].path)?,
+        zip_bytes(&[("readme.txt", b"hello from the fake server")])
+    );
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retries_throttled_redirect_and_streams_slow_chunks() -> TestResult {
+    let fake = FakeDlsite::start(config(direct_work("RJ000001"))).await?;
+    let target_root = test_dir("offline-slow");
+    fake.push_fault(FaultRule {
+        times: Some(1),
+        ..FaultRule::new(
+            FakeEndpoint::DownloadRedirect,
+            Fault::Throttled {
+                status: 503,
+                retry_after_seconds: Some(0),
+            },
+        )
+    });
+    fake.push_fault(FaultRule::new(
+        FakeEndpoint::File,
+        Fault::SlowChunks {
+            chunk_size: 48,
+            delay: Duration::from_millis(20),
+        },
+    ));
+    let mut progress_events = 0;
+
+    let downloaded = download_work_files(
+        client(&fake)?,
+        &job("RJ000001", &target_root, UnpackPolicy::KeepArchives),
+        &direct_plan(&fake, "RJ000001"),
+        &CancellationToken::new(),
+        |progress| {
+            if progress.phase == DownloadPhase::Downloading {
+                progress_events += 1;
+            }
+        },
+    )
+    .await?;
+
+    assert_eq!(downloaded.files.len(), 1);
+    assert!(progress_events > 2);
+    assert_eq!(
+        fake.requests_to(FakeEndpoint::DownloadRedirect)
+            .iter()
+            .map(|request| request.status)
+            .collect::<Vec<_>>(),
+        vec![503, 302, 302]
+    );
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_html_error_page_served_as_download() -> TestResult {
+    let fake = FakeDlsite::start(config(direct_work("RJ000001"))).await?;
+    let target_root = test_dir("offline-html");
+    fake.push_fault(FaultRule::new(
+        FakeEndpoint::File,
+        Fault::HtmlErrorPage { status: 200 },
+    ));
+
+    let error = download_work_files(
+        client(&fake)?,
+        &job("RJ000001", &target_root, UnpackPolicy::KeepArchives),
+        &direct_plan(&fake, "RJ000001"),
+        &CancellationToken::new(),
+        |_| {},
+    )
+    .await
+    .unwrap_err();
+
+    assert!(matches!(
+        error,
+        DownloadError::InvalidDownloadResponse {
+            reason: InvalidDownloadResponseReason::HtmlContentType { .. }
+        }
+    ));
+    assert!(!target_root.join("RJ000001/RJ000001.zip").exists());
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn downloads_every_split_part_from_fake_server() -> TestResult {
+    let mut work = FakeWork::new("RJ000002", "Split Work", "Maker");
+    work.download = Some(FakeDownload::Split {
+        parts: vec![
+            FakeFile::new("RJ000002.part1.exe", vec![1; 64]),
+            FakeFile::new("RJ000002.part2.rar", vec![2; 32]),
+        ],
+    });
+    let fake = FakeDlsite::start(config(work)).await?;
+    let target_root = test_dir("offline-split");
+    let plan = split_plan(&fake, "RJ000002", 2);
+
+    let downloaded = download_work_files(
+        client(&fake)?,
+        &job("RJ000002", &target_root, UnpackPolicy::KeepArchives),
+        &plan,
+        &CancellationToken::new(),
+        |_| {},
+    )
+    .await?;
+
+    assert_eq!(
+        downloaded
+            .files
+            .iter()
+            .map(|file| (file.file_name.as_str(), file.bytes_written))
+            .collect::<Vec<_>>(),
+        vec![("RJ000002.part1.exe", 64), ("RJ000002.part2.rar", 32)]
+    );
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn downloads_split_parts_in_parallel_and_aggregates_progress() -> TestResult {
+    let mut work = FakeWork::new("RJ000002", "Split Work", "Maker");
+    work.download = Some(FakeDownload::Split {
+        parts: vec![
+            FakeFile::new("RJ000002.part1.exe", vec![1; 96]),
+            FakeFile::new("RJ000002.part2.rar", vec![2; 64]),
+            FakeFile::new("RJ000002.part3.rar", vec![3; 32]),
+        ],
+    });
+    let fake = FakeDlsite::start(config(work)).await?;
+    fake.push_fault(FaultRule::new(
+        FakeEndpoint::File,
+        Fault::SlowChunks {
+            chunk_size: 16,
+            delay: Duration::from_millis(5),
+        },
+    ));
+    let target_root = test_dir("offline-split-parallel");
+    let mut received = Vec::new();
+
+    let downloaded = download_work_files(
+        client(&fake)?,
+        &DownloadJobRequest {
+            parallel_files: 2,
+            ..job("RJ000002", &target_root, UnpackPolicy::KeepArchives)
+        },
+        &split_plan(&fake, "RJ000002", 3),
+        &CancellationToken::new(),
+        |progress| {
+            if progress.phase == DownloadPhase::Downloading {
+                assert_eq!(progress.bytes_total, Some(192));
+                received.push(progress.bytes_received);
+            }
+        },
+    )
+    .await?;
+
+    assert_eq!(
+        downloaded
+            .files
+            .iter()
+            .map(|file| file.file_name.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "RJ000002.part1.exe",
+            "RJ000002.part2.rar",
+            "RJ000002.part3.rar"
+        ]
+    );
+    assert!(received.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(received.last(), Some(&192));
+    assert_eq!(
+        std::fs::read(target_root.join("RJ000002/RJ000002.part3.rar"))?,
+        vec![3; 32]
+    );
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancellation_stops_every_parallel_split_part() -> TestResult {
+    let mut work = FakeWork::new("RJ000002", "Split Work", "Maker");
+    work.download = Some(FakeDownload::Split {
+        parts: vec![
+            FakeFile::new("RJ000002.part1.exe", vec![1; 4096]),
+            FakeFile::new("RJ000002.part2.rar", vec![2; 4096]),
+        ],
+    });
+    let fake = FakeDlsite::start(config(work)).await?;
+    fake.push_fault(FaultRule::new(
+        FakeEndpoint::File,
+        Fault::SlowChunks {
+            chunk_size: 16,
+            delay: Duration::from_millis(50),
+        },
+    ));
+    let target_root = test_dir("offline-split-cancel");
+    let cancellation = CancellationToken::new();
+    let cancel_on_progress = cancellation.clone();
+
+    let error = tokio::time::timeout(
+        Duration::from_secs(5),
+        download_work_files(
+            client(&fake)?,
+            &DownloadJobRequest {
+                parallel_files: 2,
+                ..job("RJ000002", &target_root, UnpackPolicy::KeepArchives)
+            },
+            &split_plan(&fake, "RJ000002", 2),
+            &cancellation,
+            |progress| {
+                if progress.phase == DownloadPhase::Downloading {
+                    cancel_on_progress.cancel();
+                }
+            },
+        ),
+    )
+    .await?
+    .unwrap_err();
+
+    assert!(matches!(error, DownloadError::Cancelled));
+    assert!(!target_root.join("RJ000002/RJ000002.part1.exe").exists());
+    assert!(!target_root.join("RJ000002/RJ000002.part2.rar").exists());
+
+    std::fs::remove_dir_all(target_root)?;
+
+    Ok(())
+}
+
+fn split_plan(fake: &FakeDlsite, work_id: &str, part_count: u32) -> DownloadPlan {
+    DownloadPlan {
+        work_id: WorkId::from(work_id),
+        files: (1..=part_count)
+            .map(|number| DownloadFile {
+                kind: DownloadFileKind::SplitPart { number },
+                stream_request: DownloadStreamRequest {
+                    url: fake.split_part_url(work_id, number),
+                },
+            })
+            .collect(),
+        serial_numbers: Vec::new(),
+    }
+}
+
+fn config(work: FakeWork) -> FakeDlsiteConfig {
+    FakeDlsiteConfig {
+        works: vec![work],
+        ..FakeDlsiteConfig::default()
+    }
+}
+
+fn direct_work(work_id: &str) -> FakeWork {
+    let mut work = FakeWork::new(work_id, "Direct Work", "Maker");
+    work.download = Some(FakeDownload::Direct {
+        file: FakeFile::new(
+            format!("{work_id}.zip"),
+            zip_bytes(&[("readme.txt", b"hello from the fake server")]),
+        ),
+    });
+    work
+}
+
+fn direct_plan(fake: &FakeDlsite, work_id: &str) -> DownloadPlan {
+    DownloadPlan {
+        work_id: WorkId::from(work_id),
+        files: vec![DownloadFile {
+            kind: DownloadFileKind::Direct,
+            stream_request: DownloadStreamRequest {
+                url: fake.download_page_url(work_id),
+            },
+        }],
+        serial_numbers: Vec::new(),
+    }
+}
+
+fn job(
+    work_id: &str,
+    target_root: &std::path::Path,
+    unpack_policy: UnpackPolicy,
+) -> DownloadJobRequest {
+    DownloadJobRequest {
+        work_id: WorkId::from(work_id),
+        target_root: target_root.to_path_buf(),
+        unpack_policy,
+        archive_name_encoding: ArchiveNameEncoding::Auto,
+        archive_passwords: Vec::new(),
+        nested_archives: None,
+        connections_per_file: DEFAULT_CONNECTIONS_PER_FILE,
+        parallel_files: DEFAULT_PARALLEL_FILES,
+        retry_policy: RetryPolicy::default(),
+        stall_policy: StallPolicy::default(),
+    }
+}
+
+fn client(fake: &FakeDlsite) -> dm_api::Result<DlsiteClient> {
+    DlsiteClient::new(DlsiteClientConfig {
+        endpoints: DlsiteEndpoints::with_base_url(fake.base_url().clone()),
+        governor: RequestGovernor::new(RequestGovernorConfig {
+            requests_per_second: None,
+            initial_backoff: Duration::from_millis(10),
+            ..RequestGovernorConfig::default()
+        }),
+        ..DlsiteClientConfig::default()
+    })
+}
+
+fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    for (name, content) in entries {
+        zip.start_file(*name, options).unwrap();
+        zip.write_all(content).unwrap();
+    }
+
+    zip.finish().unwrap().into_inner()
+}
+
+fn test_dir(name: &str) -> PathBuf {
+    let unique = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!(
+        "dm-download-{name}-{}-{unique}",
+        std::process::id()
+    ));
+
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,74 @@
+use bytes::Bytes;
+use http_body::{Body, Frame};
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Delivery {
+    pub truncate_after: Option<u64>,
+    pub chunk_size: Option<usize>,
+    pub delay: Option<Duration>,
+}
+
+pub(crate) struct FakeBody {
+    receiver: mpsc::Receiver<io::Result<Bytes>>,
+}
+
+impl FakeBody {
+    pub fn spawn(bytes: Bytes, delivery: Delivery) -> Self {
+        let (sender, receiver) = mpsc::channel(1);
+
+        tokio::spawn(async move {
+            let limit = delivery
+                .truncate_after
+                .map_or(bytes.len(), |limit| limit.min(bytes.len() as u64) as usize);
+            let chunk_size = delivery.chunk_size.unwrap_or(bytes.len()).max(1);
+            let mut offset = 0;
+
+            while offset < limit {
+                let end = offset.saturating_add(chunk_size).min(limit);
+
+                if let Some(delay) = delivery.delay {
+                    tokio::time::sleep(delay).await;
+                }
+
+                if sender.send(Ok(bytes.slice(offset..end))).await.is_err() {
+                    return;
+                }
+
+                offset = end;
+            }
+
+            if limit < bytes.len() {
+                let _ = sender
+                    .send(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "fake dlsite truncated the response body",
+                    )))
+                    .await;
+            }
+        });
+
+        Self { receiver }
+    }
+}
+
+impl Body for FakeBody {
+    type Data = Bytes;
+    type Error = io::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        self.get_mut()
+            .receiver
+            .poll_recv(cx)
+            .map(|chunk| chunk.map(|chunk| chunk.map(Frame::data)))
+    }
+}
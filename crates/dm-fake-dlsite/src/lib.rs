@@ -0,0 +1,9 @@
+mod body;
+mod scenario;
+mod server;
+
+pub use scenario::{
+    FakeAccount, FakeDlsiteConfig, FakeDownload, FakeEndpoint, FakeFile, FakePurchase,
+    FakeSerialNumber, FakeWork, Fault, FaultRule, RecordedRequest,
+};
+pub use server::FakeDlsite;
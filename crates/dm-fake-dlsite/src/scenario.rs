@@ -0,0 +1,266 @@
+use bytes::Bytes;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, time::Duration};
+
+#[derive(Debug, Clone)]
+pub struct FakeDlsiteConfig {
+    pub accounts: Vec<FakeAccount>,
+    pub works: Vec<FakeWork>,
+    pub works_batch_limit: Option<usize>,
+    pub page_limit: Option<usize>,
+    pub concurrency: Option<usize>,
+}
+
+impl Default for FakeDlsiteConfig {
+    fn default() -> Self {
+        Self {
+            accounts: Vec::new(),
+            works: Vec::new(),
+            works_batch_limit: None,
+            page_limit: Some(50),
+            concurrency: Some(500),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeAccount {
+    pub login_id: String,
+    pub password: String,
+    pub purchases: Vec<FakePurchase>,
+}
+
+impl FakeAccount {
+    pub fn new(login_id: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            login_id: login_id.into(),
+            password: password.into(),
+            purchases: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakePurchase {
+    pub work_id: String,
+    pub sales_date: String,
+}
+
+impl FakePurchase {
+    pub fn new(work_id: impl Into<String>, sales_date: impl Into<String>) -> Self {
+        Self {
+            work_id: work_id.into(),
+            sales_date: sales_date.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeWork {
+    pub id: String,
+    pub content_json: Value,
+    pub public_json: Option<Value>,
+    /// Fields overlaid on `public_json` when the product API is asked for a locale.
+    pub localized_public_json: BTreeMap<String, Value>,
+    pub download: Option<FakeDownload>,
+}
+
+impl FakeWork {
+    pub fn new(id: impl Into<String>, title: &str, maker: &str) -> Self {
+        let id = id.into();
+        let maker_id = format!(
+            "RG{}",
+            id.trim_start_matches(|ch: char| ch.is_ascii_alphabetic())
+        );
+        let content_json = json!({
+            "workno": id,
+            "name": { "ja_JP": title },
+            "maker": {
+                "id": maker_id,
+                "name": { "ja_JP": maker },
+            },
+            "work_type": "SOU",
+            "age_category": "all",
+            "genre_ids": [],
+            "work_files": {
+                "main": format!("https://img.dlsite.jp/modpub/images2/work/doujin/{id}_img_main.jpg"),
+                "sam": format!("https://img.dlsite.jp/modpub/images2/work/doujin/{id}_img_sam.jpg"),
+            },
+            "regist_date": "2026-01-01T00:00:00+09:00",
+            "sales_date": "2026-01-01T00:00:00+09:00",
+            "upgrade_date": null,
+            "tags": [],
+        });
+        let public_json = json!({
+            "workno": id,
+            "work_name": title,
+            "maker_id": maker_id,
+            "maker_name": maker,
+            "work_type": "SOU",
+            "work_type_string": "ボイス・ASMR",
+            "age_category": 1,
+            "age_category_string": "general",
+            "image_main": {
+                "url": format!("//img.dlsite.jp/modpub/images2/work/doujin/{id}_img_main.jpg"),
+            },
+            "regist_date": "2026-01-01 00:00:00",
+            "update_date": null,
+        });
+
+        Self {
+            id,
+            content_json,
+            public_json: Some(public_json),
+            localized_public_json: BTreeMap::new(),
+            download: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakeDownload {
+    Direct {
+        file: FakeFile,
+    },
+    Split {
+        parts: Vec<FakeFile>,
+    },
+    Serial {
+        file: FakeFile,
+        serial_numbers: Vec<FakeSerialNumber>,
+    },
+}
+
+impl FakeDownload {
+    pub fn files(&self) -> &[FakeFile] {
+        match self {
+            Self::Direct { file } | Self::Serial { file, .. } => std::slice::from_ref(file),
+            Self::Split { parts } => parts,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeFile {
+    pub name: String,
+    pub bytes: Bytes,
+    pub etag: String,
+    pub last_modified: String,
+}
+
+impl FakeFile {
+    pub fn new(name: impl Into<String>, bytes: impl Into<Bytes>) -> Self {
+        let bytes = bytes.into();
+        let etag = format!("\"{:x}-{:016x}\"", bytes.len(), fnv1a(&bytes));
+
+        Self {
+            name: name.into(),
+            bytes,
+            etag,
+            last_modified: "Thu, 01 Jan 2026 00:00:00 GMT".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeSerialNumber {
+    pub label: String,
+    pub value: String,
+}
+
+impl FakeSerialNumber {
+    pub fn new(label: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            value: value.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FakeEndpoint {
+    Login,
+    LoginSkip,
+    LoginOauth,
+    LoginFinish,
+    ContentCount,
+    ContentSales,
+    ContentWorks,
+    PublicProduct,
+    DownloadProbe,
+    DownloadRedirect,
+    SplitPage,
+    SerialPage,
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    Status(u16),
+    Throttled {
+        status: u16,
+        retry_after_seconds: Option<u64>,
+    },
+    HtmlErrorPage {
+        status: u16,
+    },
+    TruncatedBody {
+        after_bytes: u64,
+    },
+    RangeNotSatisfiable,
+    SlowChunks {
+        chunk_size: usize,
+        delay: Duration,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaultRule {
+    pub endpoint: FakeEndpoint,
+    pub path_contains: Option<String>,
+    /// Limits the rule to one HTTP method, such as `POST` on [`FakeEndpoint::Login`].
+    pub method: Option<String>,
+    pub fault: Fault,
+    pub times: Option<usize>,
+}
+
+impl FaultRule {
+    pub fn new(endpoint: FakeEndpoint, fault: Fault) -> Self {
+        Self {
+            endpoint,
+            path_contains: None,
+            method: None,
+            fault,
+            times: None,
+        }
+    }
+
+    pub(crate) fn matches(&self, endpoint: FakeEndpoint, method: &str, path: &str) -> bool {
+        self.endpoint == endpoint
+            && self.times != Some(0)
+            && self
+                .method
+                .as_deref()
+                .is_none_or(|expected| expected.eq_ignore_ascii_case(method))
+            && self
+                .path_contains
+                .as_deref()
+                .is_none_or(|needle| path.contains(needle))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub endpoint: Option<FakeEndpoint>,
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub range: Option<String>,
+    pub status: u16,
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,1003 @@
+use crate::{
+    body::{Delivery, FakeBody},
+    scenario::{
+        FakeDlsiteConfig, FakeDownload, FakeEndpoint, FakeFile, FakeWork, Fault, FaultRule,
+        RecordedRequest,
+    },
+};
+use bytes::Bytes;
+use http_body_util::BodyExt;
+use hyper::{
+    body::Incoming,
+    header::{HeaderMap, HeaderValue, CONTENT_LENGTH, COOKIE, RANGE},
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+use url::Url;
+
+const SESSION_COOKIE: &str = "dm_fake_dlsite_session";
+const XSRF_COOKIE: &str = "XSRF-TOKEN";
+const INVALID_CREDENTIALS_MESSAGE: &str = "ログインIDかパスワードが間違っています。";
+
+pub struct FakeDlsite {
+    base_url: Url,
+    state: Arc<Mutex<FakeState>>,
+    server: JoinHandle<()>,
+}
+
+impl FakeDlsite {
+    pub async fn start(config: FakeDlsiteConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let address = listener.local_addr()?;
+        let base_url = Url::parse(&format!("http://{address}/"))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let state = Arc::new(Mutex::new(FakeState::new(config, base_url.clone())));
+        let server = tokio::spawn(serve(listener, state.clone()));
+
+        Ok(Self {
+            base_url,
+            state,
+            server,
+        })
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    pub fn url(&self, path: &str) -> Url {
+        self.base_url
+            .join(path)
+            .expect("fake dlsite path is a valid URL")
+    }
+
+    pub fn download_page_url(&self, work_id: &str) -> Url {
+        self.url(&download_page_path(work_id, None))
+    }
+
+    pub fn split_part_url(&self, work_id: &str, number: u32) -> Url {
+        self.url(&download_page_path(work_id, Some(number)))
+    }
+
+    pub fn file_url(&self, work_id: &str, index: usize) -> Option<Url> {
+        let state = self.lock();
+        let file = state.work(work_id)?.download.as_ref()?.files().get(index)?;
+
+        Some(self.url(&file_path(work_id, index, file)))
+    }
+
+    pub fn push_fault(&self, rule: FaultRule) {
+        self.lock().faults.push(rule);
+    }
+
+    pub fn clear_faults(&self) {
+        self.lock().faults.clear();
+    }
+
+    /// Forgets every issued session so existing cookies stop authorizing.
+    pub fn expire_sessions(&self) {
+        self.lock().sessions.clear();
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    pub fn requests_to(&self, endpoint: FakeEndpoint) -> Vec<RecordedRequest> {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|request| request.endpoint == Some(endpoint))
+            .cloned()
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().expect("fake dlsite state lock")
+    }
+}
+
+impl Drop for FakeDlsite {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<FakeState>>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let state = state.clone();
+
+        tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                let state = state.clone();
+                async move { Ok::<_, Infallible>(handle(state, request).await) }
+            });
+
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+async fn handle(state: Arc<Mutex<FakeState>>, request: Request<Incoming>) -> Response<FakeBody> {
+    let (parts, body) = request.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(|collected| collected.to_bytes())
+        .unwrap_or_default();
+    let incoming = IncomingRequest {
+        method: parts.method,
+        path: parts.uri.path().to_owned(),
+        query: parts.uri.query().map(str::to_owned),
+        range: header_string(&parts.headers, RANGE.as_str()),
+        cookies: parse_cookies(&parts.headers),
+        body,
+    };
+
+    let (response, delivery) = {
+        let mut state = state.lock().expect("fake dlsite state lock");
+        let (endpoint, response) = state.route(&incoming);
+        let (response, delivery) = match endpoint {
+            Some(endpoint) => state.apply_fault(endpoint, &incoming, response),
+            None => (response, Delivery::default()),
+        };
+
+        state.requests.push(RecordedRequest {
+            endpoint,
+            method: incoming.method.to_string(),
+            path: incoming.path.clone(),
+            query: incoming.query.clone(),
+            range: incoming.range.clone(),
+            status: response.status,
+        });
+
+        (response, delivery)
+    };
+
+    response.into_hyper(delivery)
+}
+
+struct IncomingRequest {
+    method: Method,
+    path: String,
+    query: Option<String>,
+    range: Option<String>,
+    cookies: BTreeMap<String, String>,
+    body: Bytes,
+}
+
+impl IncomingRequest {
+    fn query_value(&self, key: &str) -> Option<String> {
+        url::form_urlencoded::parse(self.query.as_deref()?.as_bytes())
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.into_owned())
+    }
+
+    fn form_value(&self, key: &str) -> Option<String> {
+        url::form_urlencoded::parse(&self.body)
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.into_owned())
+    }
+}
+
+struct FakeState {
+    config: FakeDlsiteConfig,
+    base_url: Url,
+    faults: Vec<FaultRule>,
+    requests: Vec<RecordedRequest>,
+    xsrf_tokens: BTreeSet<String>,
+    sessions: BTreeMap<String, usize>,
+    next_token: u64,
+}
+
+impl FakeState {
+    fn new(config: FakeDlsiteConfig, base_url: Url) -> Self {
+        Self {
+            config,
+            base_url,
+            faults: Vec::new(),
+            requests: Vec::new(),
+            xsrf_tokens: BTreeSet::new(),
+            sessions: BTreeMap::new(),
+            next_token: 1,
+        }
+    }
+
+    fn work(&self, work_id: &str) -> Option<&FakeWork> {
+        self.config.works.iter().find(|work| work.id == work_id)
+    }
+
+    fn url(&self, path: &str) -> String {
+        self.base_url
+            .join(path)
+            .expect("fake dlsite path is a valid URL")
+            .to_string()
+    }
+
+    fn issue_token(&mut self, prefix: &str) -> String {
+        let token = format!("{prefix}-{:08}", self.next_token);
+        self.next_token += 1;
+        token
+    }
+
+    fn session_account(&self, request: &IncomingRequest) -> Option<usize> {
+        self.sessions
+            .get(request.cookies.get(SESSION_COOKIE)?)
+            .copied()
+    }
+
+    fn owns_work(&self, account_index: usize, work_id: &str) -> bool {
+        self.config.accounts[account_index]
+            .purchases
+            .iter()
+            .any(|purchase| purchase.work_id == work_id)
+    }
+
+    fn route(&mut self, request: &IncomingRequest) -> (Option<FakeEndpoint>, FakeResponse) {
+        let path = request.path.as_str();
+
+        match (&request.method, path) {
+            (&Method::GET, "/login") => (Some(FakeEndpoint::Login), self.login_page(request)),
+            (&Method::POST, "/login") => (Some(FakeEndpoint::Login), self.login_submit(request)),
+            (&Method::GET, "/home/login/=/skip_register/1") => (
+                Some(FakeEndpoint::LoginSkip),
+                FakeResponse::redirect(self.url("/login/oauth/authorize")),
+            ),
+            (&Method::GET, "/login/oauth/authorize") => (
+                Some(FakeEndpoint::LoginOauth),
+                FakeResponse::redirect(self.url("/home/login/oauth/callback")),
+            ),
+            (&Method::GET, "/home/login/oauth/callback") => (
+                Some(FakeEndpoint::LoginOauth),
+                FakeResponse::redirect(self.url("/home/login/finish")),
+            ),
+            (&Method::GET, "/home/login/finish") => (
+                Some(FakeEndpoint::LoginFinish),
+                FakeResponse::html(200, page_html("ログイン完了", "")),
+            ),
+            (&Method::GET, "/api/v3/content/count") => (
+                Some(FakeEndpoint::ContentCount),
+                self.content_count(request),
+            ),
+            (&Method::GET, "/api/v3/content/sales") => (
+                Some(FakeEndpoint::ContentSales),
+                self.content_sales(request),
+            ),
+            (&Method::POST, "/api/v3/content/works") => (
+                Some(FakeEndpoint::ContentWorks),
+                self.content_works(request),
+            ),
+            (&Method::GET, "/home/api/=/product.json") => (
+                Some(FakeEndpoint::PublicProduct),
+                self.public_product(request),
+            ),
+            (&Method::GET, "/api/v3/download") => (
+                Some(FakeEndpoint::DownloadProbe),
+                self.download_probe(request),
+            ),
+            (&Method::GET, _) => self.download_route(request),
+            _ => (None, not_found()),
+        }
+    }
+
+    fn download_route(&self, request: &IncomingRequest) -> (Option<FakeEndpoint>, FakeResponse) {
+        let path = request.path.as_str();
+
+        if let Some(work_id) = product_id_in_path(path, "/home/download/split/=/product_id/") {
+            return (Some(FakeEndpoint::SplitPage), self.split_page(work_id));
+        }
+
+        if let Some(work_id) = product_id_in_path(path, "/home/serial/=/product_id/")
+            .or_else(|| product_id_in_path(path, "/home/download/serial/=/product_id/"))
+        {
+            return (Some(FakeEndpoint::SerialPage), self.serial_page(work_id));
+        }
+
+        if let Some(work_id) = product_id_in_path(path, "/home/download/=/product_id/") {
+            return (
+                Some(FakeEndpoint::DownloadRedirect),
+                self.download_redirect(work_id, 0),
+            );
+        }
+
+        if let Some(rest) = path.strip_prefix("/home/download/=/number/") {
+            if let Some((number, rest)) = rest.split_once('/') {
+                if let (Ok(number), Some(work_id)) = (
+                    number.parse::<usize>(),
+                    product_id_in_path(&format!("/{rest}"), "/product_id/"),
+                ) {
+                    return (
+                        Some(FakeEndpoint::DownloadRedirect),
+                        self.download_redirect(work_id, number.saturating_sub(1)),
+                    );
+                }
+            }
+        }
+
+        if let Some(rest) = path.strip_prefix("/get/=/file/") {
+            return (
+                Some(FakeEndpoint::File),
+                self.file(rest, request.range.as_deref()),
+            );
+        }
+
+        (None, not_found())
+    }
+
+    fn login_page(&mut self, request: &IncomingRequest) -> FakeResponse {
+        if self.session_account(request).is_some() {
+            return FakeResponse::redirect(self.url("/home/"));
+        }
+
+        let token = self.issue_token("xsrf");
+        self.xsrf_tokens.insert(token.clone());
+
+        FakeResponse::html(
+            200,
+            page_html(
+                "ログイン",
+                r#"<form method="post" action="/login"><input name="login_id"><input name="password" type="password"></form>"#,
+            ),
+        )
+        .with_header("set-cookie", format!("{XSRF_COOKIE}={token}; Path=/"))
+    }
+
+    fn login_submit(&mut self, request: &IncomingRequest) -> FakeResponse {
+        let token_valid = request
+            .form_value("_token")
+            .is_some_and(|token| self.xsrf_tokens.contains(&token));
+
+        if !token_valid {
+            return FakeResponse::html(419, page_html("Page Expired", "<p>Page Expired</p>"));
+        }
+
+        let login_id = request.form_value("login_id").unwrap_or_default();
+        let password = request.form_value("password").unwrap_or_default();
+        let account_index = self
+            .config
+            .accounts
+            .iter()
+            .position(|account| account.login_id == login_id && account.password == password);
+
+        let Some(account_index) = account_index else {
+            return FakeResponse::html(
+                200,
+                page_html(
+                    "ログイン",
+                    &format!(r#"<p class="error">{INVALID_CREDENTIALS_MESSAGE}</p>"#),
+                ),
+            );
+        };
+
+        let session = self.issue_token("session");
+        self.sessions.insert(session.clone(), account_index);
+
+        // Persistent like the real login cookies, so exported session snapshots keep it.
+        FakeResponse::redirect(self.url("/home/")).with_header(
+            "set-cookie",
+            format!("{SESSION_COOKIE}={session}; Path=/; Max-Age=86400"),
+        )
+    }
+
+    fn content_count(&self, request: &IncomingRequest) -> FakeResponse {
+        let Some(account_index) = self.session_account(request) else {
+            return unauthorized();
+        };
+
+        FakeResponse::json(
+            200,
+            &json!({
+                "user": self.config.accounts[account_index].purchases.len(),
+                "production": 0,
+                "page_limit": self.config.page_limit,
+                "concurrency": self.config.concurrency,
+            }),
+        )
+    }
+
+    fn content_sales(&self, request: &IncomingRequest) -> FakeResponse {
+        let Some(account_index) = self.session_account(request) else {
+            return unauthorized();
+        };
+        let purchases = self.config.accounts[account_index]
+            .purchases
+            .iter()
+            .map(|purchase| {
+                json!({
+                    "workno": purchase.work_id,
+                    "sales_date": purchase.sales_date,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        FakeResponse::json(200, &Value::Array(purchases))
+    }
+
+    fn content_works(&self, request: &IncomingRequest) -> FakeResponse {
+        if self.session_account(request).is_none() {
+            return unauthorized();
+        }
+
+        let Ok(ids) = serde_json::from_slice::<Vec<String>>(&request.body) else {
+            return FakeResponse::json(400, &json!({ "error": "invalid request body" }));
+        };
+
+        if let Some(limit) = self.config.works_batch_limit {
+            if ids.len() > limit {
+                return FakeResponse::json(
+                    400,
+                    &json!({
+                        "error": "too many works requested",
+                        "expected_batch_size": limit,
+                    }),
+                );
+            }
+        }
+
+        let works = ids
+            .iter()
+            .filter_map(|id| self.work(id))
+            .map(|work| work.content_json.clone())
+            .collect::<Vec<_>>();
+
+        FakeResponse::json(200, &json!({ "works": works }))
+    }
+
+    fn public_product(&self, request: &IncomingRequest) -> FakeResponse {
+        let locale = request.query_value("locale");
+        let products = request
+            .query_value("workno")
+            .and_then(|work_id| {
+                let work = self.work(&work_id)?;
+                let mut product = work.public_json.clone()?;
+
+                if let (Some(Value::Object(fields)), Value::Object(product)) = (
+                    locale
+                        .as_deref()
+                        .and_then(|locale| work.localized_public_json.get(locale)),
+                    &mut product,
+                ) {
+                    product.extend(fields.clone());
+                }
+
+                Some(product)
+            })
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        FakeResponse::json(200, &Value::Array(products))
+    }
+
+    fn download_probe(&self, request: &IncomingRequest) -> FakeResponse {
+        let Some(account_index) = self.session_account(request) else {
+            return unauthorized();
+        };
+        let Some(work_id) = request.query_value("workno") else {
+            return not_found();
+        };
+
+        if !self.owns_work(account_index, &work_id) {
+            return not_found();
+        }
+
+        match self.work(&work_id).and_then(|work| work.download.as_ref()) {
+            Some(FakeDownload::Direct { .. }) => {
+                FakeResponse::redirect(self.url(&download_page_path(&work_id, None)))
+            }
+            Some(FakeDownload::Split { .. }) => FakeResponse::redirect(
+                self.url(&format!("/home/download/split/=/product_id/{work_id}.html")),
+            ),
+            Some(FakeDownload::Serial { .. }) => FakeResponse::redirect(
+                self.url(&format!("/home/serial/=/product_id/{work_id}.html")),
+            ),
+            None => not_found(),
+        }
+    }
+
+    fn split_page(&self, work_id: &str) -> FakeResponse {
+        let Some(FakeDownload::Split { parts }) =
+            self.work(work_id).and_then(|work| work.download.as_ref())
+        else {
+            return not_found();
+        };
+        let links = (1..=parts.len())
+            .map(|number| {
+                format!(
+                    r#"<li><a href="{}">Part {number}</a></li>"#,
+                    self.url(&download_page_path(work_id, Some(number as u32)))
+                )
+            })
+            .collect::<String>();
+
+        FakeResponse::html(
+            200,
+            page_html("分割ダウンロード", &format!("<ul>{links}</ul>")),
+        )
+    }
+
+    fn serial_page(&self, work_id: &str) -> FakeResponse {
+        let Some(FakeDownload::Serial { serial_numbers, .. }) =
+            self.work(work_id).and_then(|work| work.download.as_ref())
+        else {
+            return not_found();
+        };
+        let rows = serial_numbers
+            .iter()
+            .map(|serial| {
+                format!(
+                    "<tr><th>{}</th><td>{}</td></tr>",
+                    escape_html(&serial.label),
+                    escape_html(&serial.value)
+                )
+            })
+            .collect::<String>();
+        let content = format!(
+            r#"<h2>シリアル番号</h2><table>{rows}</table><a href="{}">ダウンロード</a>"#,
+            self.url(&download_page_path(work_id, None))
+        );
+
+        FakeResponse::html(200, page_html("シリアル番号", &content))
+    }
+
+    fn download_redirect(&self, work_id: &str, index: usize) -> FakeResponse {
+        let file = self
+            .work(work_id)
+            .and_then(|work| work.download.as_ref())
+            .and_then(|download| download.files().get(index));
+
+        match file {
+            Some(file) => FakeResponse::redirect(self.url(&file_path(work_id, index, file))),
+            None => not_found(),
+        }
+    }
+
+    fn file(&self, rest: &str, range: Option<&str>) -> FakeResponse {
+        let mut segments = rest.split('/');
+        let (Some(_name), Some("_"), Some(work_id), Some(index)) = (
+            segments.next(),
+            segments.next(),
+            segments.next(),
+            segments
+                .next()
+                .and_then(|index| index.parse::<usize>().ok()),
+        ) else {
+            return not_found();
+        };
+        let file = self
+            .work(work_id)
+            .and_then(|work| work.download.as_ref())
+            .and_then(|download| download.files().get(index));
+
+        match file {
+            Some(file) => serve_file(file, range),
+            None => not_found(),
+        }
+    }
+
+    fn apply_fault(
+        &mut self,
+        endpoint: FakeEndpoint,
+        incoming: &IncomingRequest,
+        response: FakeResponse,
+    ) -> (FakeResponse, Delivery) {
+        let Some(rule) = self
+            .faults
+            .iter_mut()
+            .find(|rule| rule.matches(endpoint, incoming.method.as_str(), &incoming.path))
+        else {
+            return (response, Delivery::default());
+        };
+
+        if let Some(times) = rule.times.as_mut() {
+            *times -= 1;
+        }
+
+        match rule.fault.clone() {
+            Fault::Status(status) => (
+                FakeResponse::new(status, Bytes::from_static(b"fake dlsite fault"))
+                    .with_header("content-type", "text/plain; charset=UTF-8"),
+                Delivery::default(),
+            ),
+            Fault::Throttled {
+                status,
+                retry_after_seconds,
+            } => {
+                let response = FakeResponse::new(status, Bytes::from_static(b"too many requests"))
+                    .with_header("content-type", "text/plain; charset=UTF-8");
+                let response = match retry_after_seconds {
+                    Some(seconds) => response.with_header("retry-after", seconds.to_string()),
+                    None => response,
+                };
+
+                (response, Delivery::default())
+            }
+            Fault::HtmlErrorPage { status } => (
+                FakeResponse::html(
+                    status,
+                    page_html(
+                        "エラー",
+                        &format!("<h1>{status}</h1><p>ただいまアクセスが集中しています。</p>"),
+                    ),
+                ),
+                Delivery::default(),
+            ),
+            Fault::TruncatedBody { after_bytes } => (
+                response,
+                Delivery {
+                    truncate_after: Some(after_bytes),
+                    ..Delivery::default()
+                },
+            ),
+            Fault::RangeNotSatisfiable => {
+                let total = response
+                    .header("content-range")
+                    .and_then(|value| value.rsplit_once('/'))
+                    .and_then(|(_, total)| total.parse::<u64>().ok())
+                    .unwrap_or(response.body.len() as u64);
+
+                (
+                    FakeResponse::new(416, Bytes::new())
+                        .with_header("content-range", format!("bytes */{total}")),
+                    Delivery::default(),
+                )
+            }
+            Fault::SlowChunks { chunk_size, delay } => (
+                response,
+                Delivery {
+                    chunk_size: Some(chunk_size),
+                    delay: Some(delay),
+                    ..Delivery::default()
+                },
+            ),
+        }
+    }
+}
+
+struct FakeResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+}
+
+impl FakeResponse {
+    fn new(status: u16, body: Bytes) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    fn json(status: u16, value: &Value) -> Self {
+        Self::new(status, Bytes::from(value.to_string()))
+            .with_header("content-type", "application/json")
+    }
+
+    fn html(status: u16, html: String) -> Self {
+        Self::new(status, Bytes::from(html)).with_header("content-type", "text/html; charset=UTF-8")
+    }
+
+    fn redirect(location: String) -> Self {
+        Self::new(302, Bytes::new()).with_header("location", location)
+    }
+
+    fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_owned(), value.into()));
+        self
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn into_hyper(self, delivery: Delivery) -> Response<FakeBody> {
+        let mut response = Response::new(FakeBody::spawn(self.body.clone(), delivery));
+        *response.status_mut() =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        let headers = response.headers_mut();
+        for (name, value) in self.headers {
+            if let (Ok(name), Ok(value)) = (
+                hyper::header::HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(self.body.len()));
+
+        response
+    }
+}
+
+fn serve_file(file: &FakeFile, range: Option<&str>) -> FakeResponse {
+    let total = file.bytes.len() as u64;
+    let response = match range.map(parse_range) {
+        None => FakeResponse::new(200, file.bytes.clone()),
+        Some(Some((start, end))) if start < total && end.is_none_or(|end| end >= start) => {
+            let end = end.unwrap_or(total - 1).min(total - 1);
+
+            FakeResponse::new(206, file.bytes.slice(start as usize..=end as usize))
+                .with_header("content-range", format!("bytes {start}-{end}/{total}"))
+        }
+        Some(_) => {
+            return FakeResponse::new(416, Bytes::new())
+                .with_header("content-range", format!("bytes */{total}"))
+        }
+    };
+
+    response
+        .with_header("content-type", file_content_type(&file.name))
+        .with_header("accept-ranges", "bytes")
+        .with_header("etag", file.etag.clone())
+        .with_header("last-modified", file.last_modified.clone())
+        .with_header(
+            "content-disposition",
+            format!("attachment; filename=\"{}\"", file.name),
+        )
+}
+
+fn parse_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (start, end) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+    let start = start.trim().parse().ok()?;
+    let end = match end.trim() {
+        "" => None,
+        end => Some(end.parse().ok()?),
+    };
+
+    Some((start, end))
+}
+
+fn file_content_type(name: &str) -> &'static str {
+    if name.to_ascii_lowercase().ends_with(".zip") {
+        "application/zip"
+    } else {
+        "application/octet-stream"
+    }
+}
+
+fn download_page_path(work_id: &str, number: Option<u32>) -> String {
+    match number {
+        Some(number) => format!("/home/download/=/number/{number}/product_id/{work_id}.html"),
+        None => format!("/home/download/=/product_id/{work_id}.html"),
+    }
+}
+
+fn file_path(work_id: &str, index: usize, file: &FakeFile) -> String {
+    format!("/get/=/file/{}/_/{work_id}/{index}", file.name)
+}
+
+fn product_id_in_path<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    path.strip_prefix(prefix)?.strip_suffix(".html")
+}
+
+fn unauthorized() -> FakeResponse {
+    FakeResponse::json(401, &json!({ "error": "unauthorized" }))
+}
+
+fn not_found() -> FakeResponse {
+    FakeResponse::html(404, page_html("404 Not Found", "<h1>404 Not Found</h1>"))
+}
+
+fn page_html(title: &str, content: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html lang=\"ja\"><head><meta charset=\"UTF-8\"><title>{title} | DLsite</title></head><body>{content}</body></html>"
+    )
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn header_string(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
+fn parse_cookies(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            Some((name.to_owned(), value.to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::{FakeAccount, FakePurchase};
+    use reqwest::{redirect::Policy, Client};
+    use std::time::Duration;
+
+    fn config() -> FakeDlsiteConfig {
+        let mut account = FakeAccount::new("user@example.test", "secret");
+        account.purchases = vec![FakePurchase::new("RJ000001", "2026-01-01 00:00:00")];
+        let mut work = FakeWork::new("RJ000001", "First Work", "Maker One");
+        work.download = Some(FakeDownload::Direct {
+            file: FakeFile::new("RJ000001.zip", b"0123456789".to_vec()),
+        });
+
+        FakeDlsiteConfig {
+            accounts: vec![account],
+            works: vec![work],
+            works_batch_limit: Some(1),
+            ..FakeDlsiteConfig::default()
+        }
+    }
+
+    fn client() -> Client {
+        Client::builder()
+            .cookie_store(true)
+            .redirect(Policy::none())
+            .build()
+            .unwrap()
+    }
+
+    async fn login(fake: &FakeDlsite, client: &Client, password: &str) -> reqwest::Response {
+        client.get(fake.url("/login")).send().await.unwrap();
+        let token = fake.lock().xsrf_tokens.iter().next_back().cloned().unwrap();
+
+        client
+            .post(fake.url("/login"))
+            .form(&[
+                ("login_id", "user@example.test"),
+                ("password", password),
+                ("_token", token.as_str()),
+            ])
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn login_sets_session_for_play_api() {
+        let fake = FakeDlsite::start(config()).await.unwrap();
+        let client = client();
+
+        let unauthorized = client
+            .get(fake.url("/api/v3/content/count"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unauthorized.status(), 401);
+
+        let rejected = login(&fake, &client, "wrong").await;
+        assert_eq!(rejected.status(), 200);
+        assert!(rejected
+            .text()
+            .await
+            .unwrap()
+            .contains(INVALID_CREDENTIALS_MESSAGE));
+
+        let accepted = login(&fake, &client, "secret").await;
+        assert_eq!(accepted.status(), 302);
+
+        let count = client
+            .get(fake.url("/api/v3/content/count"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(count.status(), 200);
+        assert!(count.text().await.unwrap().contains("\"user\":1"));
+    }
+
+    #[tokio::test]
+    async fn rejects_works_batches_over_the_configured_limit() {
+        let fake = FakeDlsite::start(config()).await.unwrap();
+        let client = client();
+        login(&fake, &client, "secret").await;
+
+        let response = client
+            .post(fake.url("/api/v3/content/works"))
+            .body(r#"["RJ000001","RJ000002"]"#)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 400);
+        assert!(response
+            .text()
+            .await
+            .unwrap()
+            .contains("\"expected_batch_size\":1"));
+    }
+
+    #[tokio::test]
+    async fn serves_ranged_file_bodies_through_download_redirects() {
+        let fake = FakeDlsite::start(config()).await.unwrap();
+        let client = client();
+
+        let redirect = client
+            .get(fake.download_page_url("RJ000001"))
+            .send()
+            .await
+            .unwrap();
+        let location = redirect.headers()["location"].to_str().unwrap().to_owned();
+        let ranged = client
+            .get(&location)
+            .header("range", "bytes=4-")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some(location),
+            fake.file_url("RJ000001", 0).map(String::from)
+        );
+        assert_eq!(ranged.status(), 206);
+        assert_eq!(ranged.headers()["content-range"], "bytes 4-9/10");
+        assert_eq!(ranged.bytes().await.unwrap().as_ref(), b"456789");
+
+        let unsatisfiable = client
+            .get(fake.file_url("RJ000001", 0).unwrap())
+            .header("range", "bytes=10-")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unsatisfiable.status(), 416);
+        assert_eq!(unsatisfiable.headers()["content-range"], "bytes */10");
+    }
+
+    #[tokio::test]
+    async fn applies_faults_for_the_configured_number_of_requests() {
+        let fake = FakeDlsite::start(config()).await.unwrap();
+        let client = client();
+        let file_url = fake.file_url("RJ000001", 0).unwrap();
+        fake.push_fault(FaultRule {
+            times: Some(1),
+            ..FaultRule::new(FakeEndpoint::File, Fault::TruncatedBody { after_bytes: 4 })
+        });
+        fake.push_fault(FaultRule {
+            times: Some(1),
+            ..FaultRule::new(FakeEndpoint::File, Fault::HtmlErrorPage { status: 200 })
+        });
+
+        let truncated = client.get(file_url.clone()).send().await.unwrap();
+        assert_eq!(truncated.headers()["content-length"], "10");
+        assert!(truncated.bytes().await.is_err());
+
+        let html = client.get(file_url.clone()).send().await.unwrap();
+        assert!(html.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+
+        fake.push_fault(FaultRule::new(
+            FakeEndpoint::File,
+            Fault::SlowChunks {
+                chunk_size: 3,
+                delay: Duration::from_millis(1),
+            },
+        ));
+        let slow = client.get(file_url).send().await.unwrap();
+        assert_eq!(slow.bytes().await.unwrap().as_ref(), b"0123456789");
+        assert_eq!(fake.requests_to(FakeEndpoint::File).len(), 3);
+    }
+}
@@ -201,6 +201,8 @@ pub struct JobSnapshot {
     pub title: String,
     pub status: JobStatus,
     pub phase: Option<String>,
+    /// Short status shown next to the phase, such as a pending retry.
+    pub detail: Option<String>,
     pub progress: Option<JobProgress>,
     pub metadata: JobMetadata,
     pub output: Option<JobMetadata>,
@@ -317,6 +319,7 @@ impl JobManager {
             title: title.into(),
             status: JobStatus::Queued,
             phase: None,
+            detail: None,
             progress: None,
             metadata,
             output: None,
@@ -565,6 +568,24 @@ impl JobManager {
         self.emit(event);
     }
 
+    fn set_detail(&self, id: &JobId, detail: Option<String>) {
+        let event = {
+            let mut inner = self.lock_inner();
+            let Some(record) = inner.jobs.get_mut(id) else {
+                return;
+            };
+
+            if record.snapshot.status.is_terminal() || record.snapshot.detail == detail {
+                return;
+            }
+
+            record.snapshot.detail = detail;
+            let snapshot = record.snapshot.clone();
+            inner.event_from_snapshot(JobEventKind::Updated, snapshot, None, None)
+        };
+        self.emit(event);
+    }
+
     fn set_progress(&self, id: &JobId, progress: Option<JobProgress>) {
         let event = {
             let mut inner = self.lock_inner();
@@ -669,6 +690,14 @@ impl JobContext {
         self.manager.set_phase(&self.job_id, None);
     }
 
+    pub fn set_detail(&self, detail: impl Into<String>) {
+        self.manager.set_detail(&self.job_id, Some(detail.into()));
+    }
+
+    pub fn clear_detail(&self) {
+        self.manager.set_detail(&self.job_id, None);
+    }
+
     pub fn set_progress(&self, progress: JobProgress) {
         self.manager.set_progress(&self.job_id, Some(progress));
     }
@@ -808,6 +837,7 @@ mod tests {
             |context| async move {
                 context.info("starting work");
                 context.set_phase("working");
+                context.set_detail("half way");
                 context.set_progress(JobProgress::items(Some(1), Some(2)));
 
                 let mut output = JobMetadata::new();
@@ -825,6 +855,7 @@ mod tests {
             Some(&json!(true))
         );
         assert_eq!(snapshot.phase.as_deref(), Some("working"));
+        assert_eq!(snapshot.detail.as_deref(), Some("half way"));
         assert_eq!(
             manager.job_logs(&id, None, None).unwrap().entries[0].message,
             "starting work"
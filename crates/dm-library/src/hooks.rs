@@ -0,0 +1,366 @@
+use crate::CancellationToken;
+use dm_storage::PostDownloadHookSetting;
+use std::{path::PathBuf, process::Stdio, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process::Command,
+    sync::Notify,
+    time::Instant,
+};
+
+const HOOK_CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const HOOK_OUTPUT_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+/// Values a post-download hook's arguments can reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostDownloadHookContext {
+    pub work_id: String,
+    pub local_path: PathBuf,
+    pub title: String,
+    pub maker: Option<String>,
+    pub work_type: Option<String>,
+}
+
+impl PostDownloadHookContext {
+    /// Fills `{workId}`, `{localPath}`, `{title}`, `{maker}` and `{workType}` in one pass, so
+    /// substituted values are never expanded again. Unknown placeholders are kept as written.
+    pub fn render(&self, template: &str) -> String {
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            let candidate = &rest[start..];
+            let placeholder = candidate
+                .find('}')
+                .and_then(|end| Some((end, self.placeholder(&candidate[1..end])?)));
+
+            match placeholder {
+                Some((end, value)) => {
+                    rendered.push_str(&value);
+                    rest = &candidate[end + 1..];
+                }
+                None => {
+                    rendered.push('{');
+                    rest = &candidate[1..];
+                }
+            }
+        }
+
+        rendered.push_str(rest);
+        rendered
+    }
+
+    fn placeholder(&self, name: &str) -> Option<String> {
+        match name {
+            "workId" => Some(self.work_id.clone()),
+            "localPath" => Some(self.local_path.to_string_lossy().into_owned()),
+            "title" => Some(self.title.clone()),
+            "maker" => Some(self.maker.clone().unwrap_or_default()),
+            "workType" => Some(self.work_type.clone().unwrap_or_default()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookOutputStream {
+    Stdout,
+    Stderr,
+}
+
+pub trait PostDownloadHookSink: Send + Sync {
+    fn output(&self, stream: HookOutputStream, line: &str);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostDownloadHookOutcome {
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// `None` when the hook could not start, was cancelled or was ended by a signal.
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+}
+
+impl PostDownloadHookOutcome {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Runs one hook inside the work folder, forwarding its output line by line.
+///
+/// Only the arguments are rendered; the program runs exactly as configured. Failures, including
+/// a hook killed at its timeout, are reported in the outcome rather than as an error, so a
+/// broken hook never fails the download it follows.
+pub async fn run_post_download_hook(
+    hook: &PostDownloadHookSetting,
+    context: &PostDownloadHookContext,
+    cancellation: Option<&CancellationToken>,
+    sink: &dyn PostDownloadHookSink,
+) -> PostDownloadHookOutcome {
+    let mut outcome = PostDownloadHookOutcome {
+        name: hook.name.clone(),
+        program: hook.program.clone(),
+        args: hook.args.iter().map(|arg| context.render(arg)).collect(),
+        exit_code: None,
+        error: None,
+    };
+    let mut child = match Command::new(&outcome.program)
+        .args(&outcome.args)
+        .current_dir(&context.local_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(error) => {
+            outcome.error = Some(format!("failed to start: {error}"));
+            return outcome;
+        }
+    };
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let deadline = hook
+        .timeout_seconds
+        .map(|seconds| Instant::now() + Duration::from_secs(seconds.into()));
+    let stop_reason = || {
+        if cancellation.is_some_and(CancellationToken::is_cancelled) {
+            return Some("cancelled".to_owned());
+        }
+        deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+            .then(|| {
+                format!(
+                    "timed out after {} seconds",
+                    hook.timeout_seconds.unwrap_or_default()
+                )
+            })
+    };
+    let stop_output = Notify::new();
+    let output_drained = Notify::new();
+    let status = async {
+        let exited = loop {
+            tokio::select! {
+                status = child.wait() => break Ok(status?),
+                () = tokio::time::sleep(HOOK_CANCELLATION_POLL_INTERVAL) => {
+                    if let Some(reason) = stop_reason() {
+                        break Err(reason);
+                    }
+                }
+            }
+        };
+        let status = match exited {
+            Ok(status) => status,
+            Err(reason) => {
+                child.kill().await?;
+                stop_output.notify_one();
+                return Ok::<_, std::io::Error>(Err(reason));
+            }
+        };
+
+        // Processes the hook left running may keep its output open, so only drain what is
+        // already written, within the grace period and the hook's own deadline.
+        let grace_deadline = Instant::now() + HOOK_OUTPUT_GRACE_PERIOD;
+        let drain_deadline =
+            deadline.map_or(grace_deadline, |deadline| deadline.min(grace_deadline));
+        loop {
+            tokio::select! {
+                () = output_drained.notified() => break,
+                () = tokio::time::sleep(HOOK_CANCELLATION_POLL_INTERVAL) => {
+                    if Instant::now() >= drain_deadline
+                        || cancellation.is_some_and(CancellationToken::is_cancelled)
+                    {
+                        break;
+                    }
+                }
+            }
+        }
+        stop_output.notify_one();
+        Ok(Ok(status))
+    };
+    // Stop reading once the hook is done with, since its own children may still hold the pipes.
+    let output = async {
+        tokio::select! {
+            ((), ()) = async {
+                tokio::join!(
+                    forward_lines(stdout, HookOutputStream::Stdout, sink),
+                    forward_lines(stderr, HookOutputStream::Stderr, sink),
+                )
+            } => output_drained.notify_one(),
+            () = stop_output.notified() => {}
+        }
+    };
+    let (status, ()) = tokio::join!(status, output);
+
+    match status {
+        Ok(Ok(status)) => {
+            outcome.exit_code = status.code();
+            if !status.success() {
+                outcome.error = Some(format!("exited with {status}"));
+            }
+        }
+        Ok(Err(stop_reason)) => outcome.error = Some(stop_reason),
+        Err(error) => outcome.error = Some(format!("failed while running: {error}")),
+    }
+
+    outcome
+}
+
+async fn forward_lines(
+    reader: Option<impl AsyncRead + Unpin>,
+    stream: HookOutputStream,
+    sink: &dyn PostDownloadHookSink,
+) {
+    let Some(reader) = reader else {
+        return;
+    };
+    let mut lines = BufReader::new(reader).split(b'\n');
+
+    while let Ok(Some(line)) = lines.next_segment().await {
+        sink.output(
+            stream,
+            String::from_utf8_lossy(&line).trim_end_matches('\r'),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        lines: Mutex<Vec<(HookOutputStream, String)>>,
+    }
+
+    impl PostDownloadHookSink for RecordingSink {
+        fn output(&self, stream: HookOutputStream, line: &str) {
+            self.lines.lock().unwrap().push((stream, line.to_owned()));
+        }
+    }
+
+    fn context() -> PostDownloadHookContext {
+        PostDownloadHookContext {
+            work_id: "RJ000001".to_owned(),
+            local_path: std::env::temp_dir(),
+            title: "Title {maker}".to_owned(),
+            maker: Some("Maker".to_owned()),
+            work_type: None,
+        }
+    }
+
+    #[test]
+    fn renders_placeholders_without_expanding_substituted_values() {
+        assert_eq!(
+            context().render("{workId}:{title}:{workType}:{unknown}:{"),
+            "RJ000001:Title {maker}::{unknown}:{"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn captures_hook_output_and_exit_status() {
+        let sink = RecordingSink::default();
+        let hook = PostDownloadHookSetting {
+            name: "Echo".to_owned(),
+            program: "sh".to_owned(),
+            args: vec![
+                "-c".to_owned(),
+                "echo \"$1\"; echo failed >&2; exit 3".to_owned(),
+                "hook".to_owned(),
+                "{workId}".to_owned(),
+            ],
+            enabled: true,
+            timeout_seconds: None,
+        };
+
+        let outcome = run_post_download_hook(&hook, &context(), None, &sink).await;
+        let lines = sink.lines.lock().unwrap().clone();
+
+        assert_eq!(outcome.exit_code, Some(3));
+        assert!(!outcome.succeeded());
+        assert!(lines.contains(&(HookOutputStream::Stdout, "RJ000001".to_owned())));
+        assert!(lines.contains(&(HookOutputStream::Stderr, "failed".to_owned())));
+
+        let missing = run_post_download_hook(
+            &PostDownloadHookSetting {
+                program: "dm-missing-hook-program".to_owned(),
+                ..hook.clone()
+            },
+            &context(),
+            None,
+            &sink,
+        )
+        .await;
+
+        assert_eq!(missing.exit_code, None);
+        assert!(missing
+            .error
+            .is_some_and(|error| error.starts_with("failed to start")));
+
+        let unrendered = run_post_download_hook(
+            &PostDownloadHookSetting {
+                program: "{workId}".to_owned(),
+                ..hook
+            },
+            &context(),
+            None,
+            &sink,
+        )
+        .await;
+
+        assert_eq!(unrendered.program, "{workId}");
+        assert_eq!(unrendered.args[3], "RJ000001");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn kills_hooks_that_run_past_their_timeout() {
+        let hook = PostDownloadHookSetting {
+            name: "Hang".to_owned(),
+            program: "sh".to_owned(),
+            args: vec!["-c".to_owned(), "sleep 30; true".to_owned()],
+            enabled: true,
+            timeout_seconds: Some(1),
+        };
+        let started = std::time::Instant::now();
+
+        let outcome =
+            run_post_download_hook(&hook, &context(), None, &RecordingSink::default()).await;
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(outcome.exit_code, None);
+        assert!(!outcome.succeeded());
+        assert_eq!(outcome.error.as_deref(), Some("timed out after 1 seconds"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn finishes_hooks_whose_background_processes_keep_the_output_open() {
+        let sink = RecordingSink::default();
+        let hook = PostDownloadHookSetting {
+            name: "Detach".to_owned(),
+            program: "sh".to_owned(),
+            args: vec!["-c".to_owned(), "sleep 30 & echo done".to_owned()],
+            enabled: true,
+            timeout_seconds: None,
+        };
+        let started = std::time::Instant::now();
+
+        let outcome = run_post_download_hook(&hook, &context(), None, &sink).await;
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(outcome.exit_code, Some(0));
+        assert!(outcome.succeeded());
+        assert!(sink
+            .lines
+            .lock()
+            .unwrap()
+            .contains(&(HookOutputStream::Stdout, "done".to_owned())));
+    }
+}
@@ -1,25 +1,41 @@
+mod hooks;
+
+pub use hooks::{
+    run_post_download_hook, HookOutputStream, PostDownloadHookContext, PostDownloadHookOutcome,
+    PostDownloadHookSink,
+};
+
+pub use dm_archive::{
+    ArchiveFallbackName, ArchiveNameEncoding, NestedArchive, NestedArchiveOptions,
+    NestedArchiveOutcome,
+};
+
 use async_trait::async_trait;
 use chrono::{DateTime, SecondsFormat, Utc};
 use dm_api::{
     ContentCount, ContentQuery, Credentials, DlsiteClient, DmApiError, DownloadFile, DownloadPlan,
-    Language, LocalizedText, PublicWork, Purchase, SerialNumber, Work, WorkId,
+    Language, LocalizedText, ProxyConfig, PublicWork, Purchase, SerialNumber, SessionSnapshot,
+    SessionStatus, Work, WorkId,
 };
 use dm_credentials::{CredentialRef, CredentialStore, CredentialsError};
 use dm_download::{
-    DownloadFileMetadata, DownloadJobRequest, DownloadProgress, DownloadedWork, UnpackPolicy,
+    ArchivePassword, DownloadFileMetadata, DownloadJobRequest, DownloadPhase, DownloadProgress,
+    DownloadedFile, DownloadedWork, InvalidDownloadResponseReason, RetryPolicy, StallPolicy,
+    UnpackPolicy, DEFAULT_CONNECTIONS_PER_FILE, DEFAULT_PARALLEL_FILES,
 };
 pub use dm_jobs::CancellationToken;
 use dm_storage::{
-    Account, AccountSyncCommit, AccountUpsert, AccountWork, CachedWork, LocalWorkDownloadImport,
-    ProductCustomTag, ProductDetail, ProductFilterFacets, ProductListPage, ProductListQuery,
-    Storage, StorageError, SyncCancellation, SyncFailure, WorkDownloadState, WorkDownloadStatus,
-    WorkDownloadUpdate,
+    Account, AccountProxy, AccountSyncCommit, AccountUpsert, AccountWork, CachedWork,
+    LocalWorkDownloadImport, ProductCustomTag, ProductDetail, ProductFilterFacets, ProductGenre,
+    ProductListPage, ProductListQuery, ProductSeries, ProductTag, Storage, StorageError,
+    SyncCancellation, SyncFailure, WorkDownloadState, WorkDownloadStatus, WorkDownloadUpdate,
+    WorkFileManifestEntry, WorkLocalizedText, WorkMetadata,
 };
 use serde_json::{json, Map, Value};
 use std::{
     collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, RwLock},
 };
 use uuid::Uuid;
 
@@ -31,6 +47,7 @@ const DOWNLOAD_CANCELLATION_POLL_INTERVAL: std::time::Duration =
 const SERIAL_INFORMATION_FILE_NAME: &str = "dlsite-manager-serial.txt";
 const SERIAL_INFORMATION_NUMBERED_PREFIX: &str = "dlsite-manager-serial-";
 const SERIAL_INFORMATION_MARKER: &str = "# dlsite-manager serial information";
+const MANIFEST_SIDECAR_FILE_NAME: &str = "dlsite-manager-manifest.json";
 
 #[derive(Debug, thiserror::Error)]
 pub enum LibraryError {
@@ -62,6 +79,16 @@ pub enum LibraryError {
     DownloadPathOutsideRoots(PathBuf),
     #[error("download path is not a directory: {0}")]
     DownloadPathNotDirectory(PathBuf),
+    #[error("archive vault root is not configured")]
+    ArchiveVaultNotConfigured,
+    #[error("no failed download with staged archives to unpack: {0}")]
+    NoStagedDownload(String),
+    #[error("not enough free space on {path}")]
+    InsufficientDiskSpace {
+        path: PathBuf,
+        required_bytes: u64,
+        available_bytes: u64,
+    },
     #[error("I/O error")]
     Io(#[from] std::io::Error),
     #[error("json error")]
@@ -80,16 +107,36 @@ impl LibraryError {
             Self::MissingLoginName(_) => "missing_login_name",
             Self::MissingPassword(_) => "missing_password",
             Self::Cancelled => "cancelled",
+            Self::Download(dm_download::DownloadError::Archive(
+                dm_archive::ArchiveError::PasswordRequired { .. },
+            )) => "archive_password_required",
+            Self::Download(dm_download::DownloadError::Archive(
+                dm_archive::ArchiveError::WrongPassword { .. },
+            )) => "archive_wrong_password",
+            Self::Download(dm_download::DownloadError::Archive(
+                dm_archive::ArchiveError::UnsupportedEncryption { .. },
+            )) => "archive_unsupported_encryption",
             Self::Download(_) => "download",
             Self::DownloadAccountNotFound(_) => "download_account_not_found",
             Self::DownloadTargetExists(_) => "download_target_exists",
             Self::DownloadPathOutsideRoots(_) => "download_path_outside_roots",
             Self::DownloadPathNotDirectory(_) => "download_path_not_directory",
+            Self::ArchiveVaultNotConfigured => "archive_vault_not_configured",
+            Self::NoStagedDownload(_) => "no_staged_download",
+            Self::InsufficientDiskSpace { .. } => "insufficient_disk_space",
             Self::Io(_) => "io",
             Self::Json(_) => "json",
         }
     }
 
+    /// Whether unpacking stopped because no candidate password opened an archive.
+    pub fn is_archive_password_error(&self) -> bool {
+        matches!(
+            self.failure_code(),
+            "archive_password_required" | "archive_wrong_password"
+        )
+    }
+
     pub fn support_message(&self) -> String {
         match self {
             Self::Storage(error) => format!("Storage error: {error}"),
@@ -121,6 +168,21 @@ impl LibraryError {
             Self::DownloadPathNotDirectory(path) => {
                 format!("Download path is not a directory: {}", path.display())
             }
+            Self::ArchiveVaultNotConfigured => {
+                "Choose an archive vault folder before keeping archives next to unpacked downloads"
+                    .to_owned()
+            }
+            Self::NoStagedDownload(work_id) => {
+                format!("No failed download with archives left to unpack: {work_id}")
+            }
+            Self::InsufficientDiskSpace {
+                path,
+                required_bytes,
+                available_bytes,
+            } => format!(
+                "Not enough free space on {}: {required_bytes} bytes needed, {available_bytes} available",
+                path.display()
+            ),
             Self::Io(error) => format!("I/O error: {error}"),
             Self::Json(error) => format!("JSON error: {error}"),
         }
@@ -165,6 +227,10 @@ impl LibraryError {
                 "failureKind": "download_account_not_found",
                 "workId": work_id,
             }),
+            Self::NoStagedDownload(work_id) => json!({
+                "failureKind": "no_staged_download",
+                "workId": work_id,
+            }),
             Self::DownloadTargetExists(path) => json!({
                 "failureKind": "download_target_exists",
                 "path": path.to_string_lossy(),
@@ -177,6 +243,19 @@ impl LibraryError {
                 "failureKind": "download_path_not_directory",
                 "path": path.to_string_lossy(),
             }),
+            Self::ArchiveVaultNotConfigured => json!({
+                "failureKind": "archive_vault_not_configured",
+            }),
+            Self::InsufficientDiskSpace {
+                path,
+                required_bytes,
+                available_bytes,
+            } => json!({
+                "failureKind": "insufficient_disk_space",
+                "path": path.to_string_lossy(),
+                "requiredBytes": required_bytes,
+                "availableBytes": available_bytes,
+            }),
             Self::Io(error) => json!({
                 "failureKind": "io",
                 "message": error.to_string(),
@@ -215,6 +294,23 @@ fn dm_api_error_support_message(error: &DmApiError) -> String {
                 safe_url_for_log(endpoint.as_str())
             )
         }
+        DmApiError::RateLimited {
+            endpoint,
+            status,
+            retry_after,
+        } => match retry_after {
+            Some(retry_after) => format!(
+                "DLsite throttled requests with HTTP {} from {}; retry after {}s",
+                status.as_u16(),
+                safe_url_for_log(endpoint.as_str()),
+                retry_after.as_secs()
+            ),
+            None => format!(
+                "DLsite throttled requests with HTTP {} from {}",
+                status.as_u16(),
+                safe_url_for_log(endpoint.as_str())
+            ),
+        },
         DmApiError::UnexpectedResponse { endpoint, .. } => {
             format!(
                 "DLsite returned an unexpected response from {}",
@@ -251,6 +347,12 @@ fn dm_api_error_support_message(error: &DmApiError) -> String {
         DmApiError::BatchLimitExceeded { limit, .. } => {
             format!("DLsite works batch limit exceeded; detected limit: {limit}")
         }
+        DmApiError::UnsupportedProxy { url, reason } => {
+            format!(
+                "Unsupported proxy {}: {reason}",
+                safe_url_for_log(url.as_str())
+            )
+        }
         DmApiError::CookieStore(error) => format!("DLsite cookie store error: {error}"),
         DmApiError::Request(error) => format!("DLsite HTTP request failed: {error}"),
         DmApiError::Url(error) => format!("DLsite URL parse failed: {error}"),
@@ -312,6 +414,17 @@ fn dm_api_error_support_details(error: &DmApiError) -> Value {
 
             Value::Object(details)
         }
+        DmApiError::RateLimited {
+            endpoint,
+            status,
+            retry_after,
+        } => json!({
+            "failureKind": "api",
+            "apiErrorKind": "rate_limited",
+            "endpoint": safe_url_for_log(endpoint.as_str()),
+            "httpStatus": status.as_u16(),
+            "retryAfterSeconds": retry_after.map(|retry_after| retry_after.as_secs()),
+        }),
         DmApiError::UnexpectedResponse { endpoint, source } => json!({
             "failureKind": "api",
             "apiErrorKind": "unexpected_response",
@@ -368,6 +481,12 @@ fn dm_api_error_support_details(error: &DmApiError) -> Value {
 
             Value::Object(details)
         }
+        DmApiError::UnsupportedProxy { url, reason } => json!({
+            "failureKind": "api",
+            "apiErrorKind": "unsupported_proxy",
+            "proxy": safe_url_for_log(url.as_str()),
+            "reason": reason,
+        }),
         DmApiError::CookieStore(error) => json!({
             "failureKind": "api",
             "apiErrorKind": "cookie_store",
@@ -468,6 +587,32 @@ fn download_error_support_details(error: &dm_download::DownloadError) -> Value {
             "expectedBytes": expected,
             "actualBytes": actual,
         }),
+        dm_download::DownloadError::SegmentGap { offset } => json!({
+            "failureKind": "download",
+            "downloadErrorKind": "segment_gap",
+            "offset": offset,
+        }),
+        dm_download::DownloadError::NonUtf8Path { path } => json!({
+            "failureKind": "download",
+            "downloadErrorKind": "non_utf8_path",
+            "path": path.to_string_lossy(),
+        }),
+        dm_download::DownloadError::Stalled { reason } => json!({
+            "failureKind": "download",
+            "downloadErrorKind": "stalled",
+            "reason": reason.to_string(),
+        }),
+        dm_download::DownloadError::InsufficientDiskSpace {
+            path,
+            required,
+            available,
+        } => json!({
+            "failureKind": "download",
+            "downloadErrorKind": "insufficient_disk_space",
+            "path": path.to_string_lossy(),
+            "requiredBytes": required,
+            "availableBytes": available,
+        }),
         dm_download::DownloadError::Stream(error) => json!({
             "failureKind": "download",
             "downloadErrorKind": "stream",
@@ -631,11 +776,26 @@ impl Library {
             }),
         )?;
 
+        let proxy = self.save_account_proxy(
+            &account_id,
+            request.proxy_url.as_deref(),
+            request.proxy_username.as_deref(),
+            request.proxy_password.as_deref(),
+        )?;
+
+        if existing_account.as_ref().is_some_and(|account| {
+            account.login_name != request.login_name || account.proxy != proxy
+        }) {
+            self.credentials
+                .delete_session(&CredentialRef::account_session(&account_id)?)?;
+        }
+
         let account = AccountUpsert {
             id: account_id.clone(),
             label: request.label,
             login_name: request.login_name,
             credential_ref: credential_ref.map(|value| value.to_string()),
+            proxy,
             enabled: request.enabled,
         };
 
@@ -663,6 +823,10 @@ impl Library {
         } else {
             false
         };
+        self.credentials
+            .delete_session(&CredentialRef::account_session(&account.id)?)?;
+        self.credentials
+            .delete_password(&CredentialRef::account_proxy_password(&account.id)?)?;
 
         self.storage.delete_account(account_id).await?;
 
@@ -688,6 +852,23 @@ impl Library {
         Ok(self.storage.product_detail(work_id).await?)
     }
 
+    /// Collects what post-download hooks can reference for a work downloaded to `local_path`.
+    pub async fn post_download_hook_context(
+        &self,
+        work_id: &str,
+        local_path: &Path,
+    ) -> Result<PostDownloadHookContext> {
+        let detail = self.storage.product_detail(work_id).await?;
+
+        Ok(PostDownloadHookContext {
+            work_id: detail.work_id,
+            local_path: local_path.to_path_buf(),
+            title: detail.title,
+            maker: detail.maker_name,
+            work_type: detail.work_type,
+        })
+    }
+
     pub async fn set_product_custom_tags(
         &self,
         work_id: &str,
@@ -696,6 +877,78 @@ impl Library {
         Ok(self.storage.set_work_custom_tags(work_id, tags).await?)
     }
 
+    /// Forces how the work's archive entry names are read; `Auto` clears the override.
+    pub async fn set_product_archive_name_encoding(
+        &self,
+        work_id: &str,
+        name_encoding: ArchiveNameEncoding,
+    ) -> Result<()> {
+        Ok(self
+            .storage
+            .set_work_archive_name_encoding(
+                work_id,
+                archive_name_encoding_storage_value(name_encoding),
+            )
+            .await?)
+    }
+
+    /// Saves the password tried first on the work's encrypted archives; `None` forgets it.
+    pub fn set_product_archive_password(
+        &self,
+        work_id: &str,
+        password: Option<&str>,
+    ) -> Result<()> {
+        let credential_ref = CredentialRef::work_archive_password(work_id)?;
+
+        match password {
+            Some(password) => self.credentials.save_password(&credential_ref, password)?,
+            None => self.credentials.delete_password(&credential_ref)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn product_has_archive_password(&self, work_id: &str) -> Result<bool> {
+        let credential_ref = CredentialRef::work_archive_password(work_id)?;
+
+        Ok(self.credentials.load_password(&credential_ref)?.is_some())
+    }
+
+    /// The saved archive password followed by the serial numbers from the download page.
+    fn archive_passwords(
+        &self,
+        work_id: &str,
+        serial_numbers: &[SerialNumber],
+    ) -> Result<Vec<ArchivePassword>> {
+        let saved = self
+            .credentials
+            .load_password(&CredentialRef::work_archive_password(work_id)?)?;
+        let mut passwords = Vec::new();
+
+        for password in saved
+            .into_iter()
+            .chain(serial_numbers.iter().map(|serial| serial.value.clone()))
+        {
+            let password = ArchivePassword::new(password);
+
+            if !password.as_str().is_empty() && !passwords.contains(&password) {
+                passwords.push(password);
+            }
+        }
+
+        Ok(passwords)
+    }
+
+    async fn archive_name_encoding(&self, work_id: &str) -> Result<ArchiveNameEncoding> {
+        Ok(self
+            .storage
+            .work_archive_name_encoding(work_id)
+            .await?
+            .as_deref()
+            .map(archive_name_encoding_from_storage)
+            .unwrap_or_default())
+    }
+
     pub fn account_has_saved_password(&self, account: &Account) -> Result<bool> {
         let Some(credential_ref) = account.credential_ref.as_deref() else {
             return Ok(false);
@@ -705,6 +958,35 @@ impl Library {
         Ok(self.credentials.load_password(&credential_ref)?.is_some())
     }
 
+    pub fn account_has_saved_session(&self, account: &Account) -> Result<bool> {
+        let session_ref = CredentialRef::account_session(&account.id)?;
+
+        Ok(self.credentials.load_session(&session_ref)?.is_some())
+    }
+
+    pub fn account_proxy(&self, account: &Account) -> Result<Option<ProxyConfig>> {
+        let Some(proxy) = &account.proxy else {
+            return Ok(None);
+        };
+        let password = match proxy.credential_ref.as_deref() {
+            Some(credential_ref) => self
+                .credentials
+                .load_password(&CredentialRef::new(credential_ref.to_owned())?)?,
+            None => None,
+        };
+
+        Ok(Some(ProxyConfig {
+            username: proxy.username.clone(),
+            password,
+            ..ProxyConfig::parse(&proxy.url)?
+        }))
+    }
+
+    /// Whether the account can sign in without asking for a password.
+    pub fn account_has_saved_credential(&self, account: &Account) -> Result<bool> {
+        Ok(self.account_has_saved_password(account)? || self.account_has_saved_session(account)?)
+    }
+
     pub async fn download_work_with_source<S>(
         &self,
         request: WorkDownloadRequest<'_>,
@@ -713,21 +995,17 @@ impl Library {
     where
         S: WorkDownloadSource + Sync,
     {
-        let account = self
-            .storage
-            .download_account_for_work(request.work_id, request.account_id)
-            .await
-            .map_err(|error| match error {
-                StorageError::NotFound { .. } => {
-                    LibraryError::DownloadAccountNotFound(request.work_id.to_owned())
-                }
-                error => LibraryError::Storage(error),
-            })?;
+        let account = self.work_download_account(&request).await?;
         let started_at = now_string();
         let work_id = WorkId::from(request.work_id.to_owned());
         let staging_dir = request.download_root.join(request.work_id);
-        let final_dir = request.library_root.join(request.work_id);
-        let unpack_policy = request.unpack_policy;
+        let existing_dir = if request.replace_existing {
+            self.existing_download_dir(request.work_id, request.library_root)
+                .await?
+        } else {
+            None
+        };
+        let final_dir = existing_dir.unwrap_or_else(|| request.library_root.join(request.work_id));
         let result = self
             .download_work_inner(
                 &account,
@@ -741,38 +1019,99 @@ impl Library {
             .await;
 
         if let Err(error) = &result {
-            let completed_at = now_string();
-            let status = if matches!(error, LibraryError::Cancelled)
-                || matches!(
-                    error,
-                    LibraryError::Download(dm_download::DownloadError::Cancelled)
-                ) {
-                WorkDownloadStatus::Cancelled
-            } else {
-                WorkDownloadStatus::Failed
-            };
-            let _ = self
-                .storage
-                .save_work_download(&WorkDownloadUpdate {
-                    work_id: request.work_id.to_owned(),
-                    status,
-                    local_path: Some(final_dir.to_string_lossy().into_owned()),
-                    staging_path: Some(staging_dir.to_string_lossy().into_owned()),
-                    unpack_policy: unpack_policy_storage_value(unpack_policy).to_owned(),
-                    bytes_received: 0,
-                    bytes_total: None,
-                    error_code: Some(error.failure_code().to_owned()),
-                    error_message: Some(error.support_message()),
-                    started_at: Some(started_at),
-                    completed_at: Some(completed_at.clone()),
-                    updated_at: completed_at,
-                })
-                .await;
+            self.record_work_download_failure(
+                &request,
+                error,
+                &staging_dir,
+                &final_dir,
+                &started_at,
+            )
+            .await;
+        }
+
+        result
+    }
+
+    /// Unpacks the archives a failed download left in its staging folder, for example after an
+    /// archive password was saved, then finishes the download without fetching anything again.
+    pub async fn resume_work_unpack(
+        &self,
+        request: WorkDownloadRequest<'_>,
+    ) -> Result<WorkDownloadReport> {
+        let account = self.work_download_account(&request).await?;
+        let started_at = now_string();
+        let staging_dir = request.download_root.join(request.work_id);
+        let final_dir = request.library_root.join(request.work_id);
+        let result = self
+            .resume_work_unpack_inner(&account, &staging_dir, &final_dir, &started_at, request)
+            .await;
+
+        if let Err(error) = &result {
+            self.record_work_download_failure(
+                &request,
+                error,
+                &staging_dir,
+                &final_dir,
+                &started_at,
+            )
+            .await;
         }
 
         result
     }
 
+    async fn work_download_account(&self, request: &WorkDownloadRequest<'_>) -> Result<Account> {
+        self.storage
+            .download_account_for_work(request.work_id, request.account_id)
+            .await
+            .map_err(|error| match error {
+                StorageError::NotFound { .. } => {
+                    LibraryError::DownloadAccountNotFound(request.work_id.to_owned())
+                }
+                error => LibraryError::Storage(error),
+            })
+    }
+
+    async fn record_work_download_failure(
+        &self,
+        request: &WorkDownloadRequest<'_>,
+        error: &LibraryError,
+        staging_dir: &Path,
+        final_dir: &Path,
+        started_at: &str,
+    ) {
+        let completed_at = now_string();
+        let status = if matches!(error, LibraryError::Cancelled)
+            || matches!(
+                error,
+                LibraryError::Download(dm_download::DownloadError::Cancelled)
+            ) {
+            WorkDownloadStatus::Cancelled
+        } else {
+            WorkDownloadStatus::Failed
+        };
+        let _ = self
+            .storage
+            .save_work_download(&WorkDownloadUpdate {
+                work_id: request.work_id.to_owned(),
+                status,
+                local_path: Some(final_dir.to_string_lossy().into_owned()),
+                staging_path: Some(staging_dir.to_string_lossy().into_owned()),
+                archive_vault_path: request
+                    .archive_vault_dir()
+                    .map(|path| path.to_string_lossy().into_owned()),
+                unpack_policy: unpack_policy_storage_value(request.unpack_policy).to_owned(),
+                bytes_received: 0,
+                bytes_total: None,
+                error_code: Some(error.failure_code().to_owned()),
+                error_message: Some(error.support_message()),
+                started_at: Some(started_at.to_owned()),
+                completed_at: Some(completed_at.clone()),
+                updated_at: completed_at,
+            })
+            .await;
+    }
+
     pub async fn download_products_with_source<S>(
         &self,
         request: BulkWorkDownloadRequest<'_>,
@@ -830,7 +1169,15 @@ impl Library {
                         library_root: request.library_root,
                         download_root: request.download_root,
                         unpack_policy: request.unpack_policy,
+                        archive_vault_root: request.archive_vault_root,
+                        nested_archives: request.nested_archives,
+                        connections_per_file: request.connections_per_file,
+                        parallel_files: request.parallel_files,
+                        retry_policy: request.retry_policy,
+                        stall_policy: request.stall_policy,
+                        manifest_sidecar: request.manifest_sidecar,
                         replace_existing: false,
+                        previous_version: PreviousVersionPolicy::Discard,
                         cancellation_token: request.cancellation_token,
                         progress_sink: None,
                     },
@@ -846,6 +1193,7 @@ impl Library {
                         local_path: report_item.local_path,
                         file_count: report_item.file_count,
                         archive_extracted: report_item.archive_extracted,
+                        manifest_error: report_item.manifest_error,
                     });
                     request.emit(BulkWorkDownloadProgress::WorkCompleted {
                         work_id,
@@ -925,6 +1273,7 @@ impl Library {
             unknown_size_count: 0,
             works: Vec::new(),
             failed_works: Vec::new(),
+            disk_space: Vec::new(),
         };
         for (index, item) in selection.items.into_iter().enumerate() {
             request.check_cancelled()?;
@@ -975,6 +1324,15 @@ impl Library {
             });
         }
 
+        if let Some(roots) = request.disk_space {
+            preview.disk_space = check_disk_space(
+                roots,
+                preview.known_expected_bytes,
+                0,
+                roots.unpack_policy.unpacks(),
+            )?;
+        }
+
         request.emit(BulkWorkDownloadPreviewProgress::Completed {
             planned_count: preview.planned_count,
             failed_count: preview.failed_count,
@@ -995,6 +1353,13 @@ impl Library {
 
         remove_download_path_from_state(state.local_path.as_deref(), &allowed_roots).await?;
         remove_download_path_from_state(state.staging_path.as_deref(), &allowed_roots).await?;
+        if let Some(archive_vault_root) = request.archive_vault_root {
+            remove_download_path_from_state(
+                state.archive_vault_path.as_deref(),
+                &[archive_vault_root],
+            )
+            .await?;
+        }
         self.storage.delete_work_download(request.work_id).await?;
         Ok(self.storage.work_download_state(request.work_id).await?)
     }
@@ -1017,6 +1382,7 @@ impl Library {
                 status: WorkDownloadStatus::Downloaded,
                 local_path: Some(canonical_path.to_string_lossy().into_owned()),
                 staging_path: None,
+                archive_vault_path: None,
                 unpack_policy: "manual".to_owned(),
                 bytes_received: 0,
                 bytes_total: None,
@@ -1031,59 +1397,248 @@ impl Library {
         Ok(self.storage.work_download_state(request.work_id).await?)
     }
 
-    pub async fn import_local_work_downloads(
-        &self,
-        request: LocalWorkImportRequest<'_>,
-    ) -> Result<LocalWorkImportReport> {
-        self.import_local_work_downloads_internal(request, None)
-            .await
-    }
-
-    pub async fn import_local_work_downloads_with_metadata_source<S>(
+    /// Fills descriptions, genres, series and file details for up to `limit`
+    /// cached works that have not been enriched yet.
+    pub async fn enrich_work_metadata<S>(
         &self,
-        request: LocalWorkImportRequest<'_>,
-        metadata_source: &S,
-    ) -> Result<LocalWorkImportReport>
+        source: &S,
+        limit: u32,
+    ) -> Result<WorkMetadataEnrichmentReport>
     where
         S: LocalWorkMetadataSource,
     {
-        self.import_local_work_downloads_internal(
-            request,
-            Some(metadata_source as &dyn LocalWorkMetadataSource),
-        )
-        .await
+        let work_ids = self.storage.works_missing_metadata(limit).await?;
+
+        if work_ids.is_empty() {
+            return Ok(WorkMetadataEnrichmentReport::default());
+        }
+
+        let ids = work_ids.iter().map(WorkId::new).collect::<Vec<_>>();
+        let fetched_at = now_string();
+        let works_by_id = source
+            .works(&ids)
+            .await?
+            .into_iter()
+            .map(|work| (work.id.as_ref().to_owned(), work))
+            .collect::<BTreeMap<_, _>>();
+        let metadata = work_ids
+            .iter()
+            .map(|work_id| match works_by_id.get(work_id) {
+                Some(work) => work_metadata_from_public_work(work, &fetched_at),
+                None => WorkMetadata {
+                    work_id: work_id.clone(),
+                    description: None,
+                    series: None,
+                    genres: Vec::new(),
+                    sample_image_urls: Vec::new(),
+                    file_formats: Vec::new(),
+                    track_count: None,
+                    fetched_at: fetched_at.clone(),
+                },
+            })
+            .collect::<Vec<_>>();
+
+        self.storage.save_work_metadata(&metadata).await?;
+
+        Ok(WorkMetadataEnrichmentReport {
+            requested_count: work_ids.len(),
+            enriched_count: work_ids
+                .iter()
+                .filter(|work_id| works_by_id.contains_key(*work_id))
+                .count(),
+        })
     }
 
-    async fn import_local_work_downloads_internal(
+    /// Re-hashes every finished download that has a manifest and flags the corrupted ones.
+    pub async fn verify_library(
         &self,
-        request: LocalWorkImportRequest<'_>,
-        metadata_source: Option<&dyn LocalWorkMetadataSource>,
-    ) -> Result<LocalWorkImportReport> {
-        let library_root = canonicalize_existing_directory(request.library_root)?;
-        let mut entries = tokio::fs::read_dir(&library_root).await?;
-        let scanned_at = now_string();
-        let mut imports = Vec::new();
-        let mut imported_works = Vec::new();
-        let mut metadata_candidate_ids = BTreeSet::<WorkId>::new();
-        let mut scanned_directories = 0;
-        let mut skipped_no_id = 0;
-        let mut skipped_ambiguous = 0;
-        let mut skipped_non_utf8 = 0;
-        let mut skipped_existing = 0;
+        request: LibraryVerifyRequest<'_>,
+    ) -> Result<LibraryVerifyReport> {
+        let downloads = self.storage.verifiable_work_downloads().await?;
+        let total = downloads.len();
+        let mut report = LibraryVerifyReport::default();
 
-        while let Some(entry) = entries.next_entry().await? {
-            let file_type = entry.file_type().await?;
+        request.emit(LibraryVerifyProgress::Started { total });
 
-            if !file_type.is_dir() {
-                continue;
+        for (index, download) in downloads.into_iter().enumerate() {
+            request.check_cancelled()?;
+
+            let local_path = PathBuf::from(&download.local_path);
+            let manifest = self
+                .storage
+                .work_file_manifest(&download.work_id)
+                .await?
+                .into_iter()
+                .map(|entry| dm_download::FileManifestEntry {
+                    relative_path: entry.relative_path,
+                    size: entry.size_bytes,
+                    sha256: entry.sha256,
+                })
+                .collect::<Vec<_>>();
+            let verification =
+                verify_work_files(&local_path, manifest, request.cancellation_token).await?;
+            let missing_archive_vault = match download.archive_vault_path.map(PathBuf::from) {
+                Some(vault_dir) if !directory_has_files(&vault_dir).await? => Some(vault_dir),
+                _ => None,
+            };
+            let issue = WorkIntegrityIssue {
+                work_id: download.work_id.clone(),
+                local_path,
+                missing: verification.missing,
+                changed: verification.changed,
+                extra: verification.extra,
+                missing_archive_vault,
+            };
+            let corrupted = issue.is_corrupted();
+
+            if corrupted {
+                report.corrupted_count += 1;
+                self.storage
+                    .set_work_download_integrity(
+                        &download.work_id,
+                        Some(&issue.summary()),
+                        &now_string(),
+                    )
+                    .await?;
+            } else if download.status == WorkDownloadStatus::Corrupted {
+                report.restored_count += 1;
+                self.storage
+                    .set_work_download_integrity(&download.work_id, None, &now_string())
+                    .await?;
             }
 
-            scanned_directories += 1;
+            if corrupted || !issue.extra.is_empty() {
+                report.issues.push(issue);
+            }
 
-            let folder_name = match entry.file_name().into_string() {
-                Ok(folder_name) => folder_name,
-                Err(_) => {
-                    skipped_non_utf8 += 1;
+            report.verified_count += 1;
+            request.emit(LibraryVerifyProgress::WorkVerified {
+                work_id: download.work_id,
+                current: index + 1,
+                total,
+                intact: !corrupted,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Records the manifest of a work that is already in the library. The work stays usable
+    /// without one, so a failure only clears any stale manifest, which keeps verification off the
+    /// work, and is returned for the report.
+    async fn record_file_manifest_best_effort(
+        &self,
+        work_id: &str,
+        work_dir: &Path,
+        write_sidecar: bool,
+        recorded_at: &str,
+    ) -> Option<String> {
+        let error = self
+            .record_file_manifest(work_id, work_dir, write_sidecar, recorded_at)
+            .await
+            .err()?;
+        let _ = self
+            .storage
+            .replace_work_file_manifest(work_id, &[], recorded_at)
+            .await;
+
+        Some(error.support_message())
+    }
+
+    /// Hashes a finished work folder and stores the result as its manifest.
+    async fn record_file_manifest(
+        &self,
+        work_id: &str,
+        work_dir: &Path,
+        write_sidecar: bool,
+        recorded_at: &str,
+    ) -> Result<()> {
+        let root = work_dir.to_path_buf();
+        let entries = tokio::task::spawn_blocking(move || {
+            dm_download::build_file_manifest(
+                &root,
+                &[MANIFEST_SIDECAR_FILE_NAME],
+                &dm_download::CancellationToken::new(),
+            )
+        })
+        .await
+        .map_err(|err| LibraryError::Io(std::io::Error::other(err)))??
+        .into_iter()
+        .map(|entry| WorkFileManifestEntry {
+            relative_path: entry.relative_path,
+            size_bytes: entry.size,
+            sha256: entry.sha256,
+        })
+        .collect::<Vec<_>>();
+        let sidecar_path = work_dir.join(MANIFEST_SIDECAR_FILE_NAME);
+
+        if write_sidecar {
+            let content = manifest_sidecar_content(work_id, &entries, recorded_at);
+            tokio::fs::write(&sidecar_path, serde_json::to_vec_pretty(&content)?).await?;
+        } else if sidecar_path.is_file() {
+            tokio::fs::remove_file(&sidecar_path).await?;
+        }
+
+        self.storage
+            .replace_work_file_manifest(work_id, &entries, recorded_at)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn import_local_work_downloads(
+        &self,
+        request: LocalWorkImportRequest<'_>,
+    ) -> Result<LocalWorkImportReport> {
+        self.import_local_work_downloads_internal(request, None)
+            .await
+    }
+
+    pub async fn import_local_work_downloads_with_metadata_source<S>(
+        &self,
+        request: LocalWorkImportRequest<'_>,
+        metadata_source: &S,
+    ) -> Result<LocalWorkImportReport>
+    where
+        S: LocalWorkMetadataSource,
+    {
+        self.import_local_work_downloads_internal(
+            request,
+            Some(metadata_source as &dyn LocalWorkMetadataSource),
+        )
+        .await
+    }
+
+    async fn import_local_work_downloads_internal(
+        &self,
+        request: LocalWorkImportRequest<'_>,
+        metadata_source: Option<&dyn LocalWorkMetadataSource>,
+    ) -> Result<LocalWorkImportReport> {
+        let library_root = canonicalize_existing_directory(request.library_root)?;
+        let mut entries = tokio::fs::read_dir(&library_root).await?;
+        let scanned_at = now_string();
+        let mut imports = Vec::new();
+        let mut imported_works = Vec::new();
+        let mut metadata_candidate_ids = BTreeSet::<WorkId>::new();
+        let mut scanned_directories = 0;
+        let mut skipped_no_id = 0;
+        let mut skipped_ambiguous = 0;
+        let mut skipped_non_utf8 = 0;
+        let mut skipped_existing = 0;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let file_type = entry.file_type().await?;
+
+            if !file_type.is_dir() || is_set_aside_dir_name(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
+
+            scanned_directories += 1;
+
+            let folder_name = match entry.file_name().into_string() {
+                Ok(folder_name) => folder_name,
+                Err(_) => {
+                    skipped_non_utf8 += 1;
                     continue;
                 }
             };
@@ -1126,6 +1681,7 @@ impl Library {
                 status: WorkDownloadStatus::Downloaded,
                 local_path: Some(local_path.to_string_lossy().into_owned()),
                 staging_path: None,
+                archive_vault_path: None,
                 unpack_policy: "manual".to_owned(),
                 bytes_received: 0,
                 bytes_total: None,
@@ -1149,29 +1705,9 @@ impl Library {
         } else {
             0
         };
-        let mut metadata_by_id = BTreeMap::<String, CachedWork>::new();
-        let mut metadata_error = None;
-
-        if let Some(source) = metadata_source.filter(|_| !metadata_candidate_ids.is_empty()) {
-            let ids = metadata_candidate_ids.iter().cloned().collect::<Vec<_>>();
-
-            match source.works(&ids).await {
-                Ok(works) => {
-                    for work in works {
-                        if !metadata_candidate_ids.contains(&work.id) {
-                            continue;
-                        }
-
-                        let cached = cached_work_from_public_api(work, &scanned_at)?;
-                        metadata_by_id.insert(cached.work_id.clone(), cached);
-                    }
-                }
-                Err(error) => {
-                    metadata_error = Some(error.support_message());
-                }
-            }
-        }
-
+        let (metadata_by_id, metadata_error) =
+            fetch_local_work_metadata(metadata_source, &metadata_candidate_ids, &scanned_at)
+                .await?;
         let metadata_works = metadata_by_id.values().cloned().collect::<Vec<_>>();
 
         self.storage
@@ -1193,6 +1729,245 @@ impl Library {
         })
     }
 
+    pub async fn import_local_archives(
+        &self,
+        request: LocalArchiveImportRequest<'_>,
+    ) -> Result<LocalArchiveImportReport> {
+        self.import_local_archives_internal(request, None).await
+    }
+
+    pub async fn import_local_archives_with_metadata_source<S>(
+        &self,
+        request: LocalArchiveImportRequest<'_>,
+        metadata_source: &S,
+    ) -> Result<LocalArchiveImportReport>
+    where
+        S: LocalWorkMetadataSource,
+    {
+        self.import_local_archives_internal(
+            request,
+            Some(metadata_source as &dyn LocalWorkMetadataSource),
+        )
+        .await
+    }
+
+    /// Groups the archives directly inside `source_dir` by work ID and extracts every group the
+    /// unpack pipeline recognizes into its own library folder.
+    async fn import_local_archives_internal(
+        &self,
+        request: LocalArchiveImportRequest<'_>,
+        metadata_source: Option<&dyn LocalWorkMetadataSource>,
+    ) -> Result<LocalArchiveImportReport> {
+        let source_dir = canonicalize_existing_directory(request.source_dir)?;
+        let library_root = canonicalize_existing_directory(request.library_root)?;
+        let mut entries = tokio::fs::read_dir(&source_dir).await?;
+        let mut groups = BTreeMap::<WorkId, Vec<PathBuf>>::new();
+        let mut report = LocalArchiveImportReport {
+            dry_run: request.dry_run,
+            ..LocalArchiveImportReport::default()
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+
+            if !entry.file_type().await?.is_file() || !is_importable_archive(&path) {
+                continue;
+            }
+
+            report.scanned_files += 1;
+
+            let Ok(file_name) = entry.file_name().into_string() else {
+                report.skipped_non_utf8 += 1;
+                continue;
+            };
+
+            match detect_work_ids_in_text(&file_name).as_slice() {
+                [] => report.skipped_no_id += 1,
+                [work_id] => groups.entry(work_id.clone()).or_default().push(path),
+                _ => report.skipped_ambiguous += 1,
+            }
+        }
+
+        for (work_id, mut archives) in groups {
+            // A legacy split set is planned from its `.exe` first part.
+            archives.sort_by_key(|path| (!path_has_extension(path, "exe"), path.clone()));
+
+            let work_id = work_id.as_ref().to_owned();
+            let plan = dm_archive::plan_archive_handling(archives.clone());
+            let target_path = library_root.join(&work_id);
+            let mut unpacked_bytes = None;
+            let action = if !plan.is_unpackable() {
+                LocalArchiveImportAction::Unrecognized
+            } else if self.storage.work_download_state(&work_id).await?.status
+                != WorkDownloadStatus::NotDownloaded
+            {
+                LocalArchiveImportAction::AlreadyDownloaded
+            } else if target_path.try_exists()? {
+                LocalArchiveImportAction::TargetExists
+            } else {
+                match archive_unpacked_size(plan.clone()).await {
+                    Ok(size) => {
+                        unpacked_bytes = size;
+                        LocalArchiveImportAction::Extract
+                    }
+                    Err(error) => LocalArchiveImportAction::Failed {
+                        message: error.support_message(),
+                    },
+                }
+            };
+
+            report.works.push(LocalArchiveImportItem {
+                work_id,
+                kind: LocalArchiveKind::from_plan(&plan),
+                archives,
+                target_path,
+                unpacked_bytes,
+                action,
+                manifest_error: None,
+            });
+        }
+
+        if request.dry_run {
+            return Ok(report);
+        }
+
+        let imported_at = now_string();
+        let total = report
+            .works
+            .iter()
+            .filter(|item| item.action == LocalArchiveImportAction::Extract)
+            .count();
+        let mut metadata_candidate_ids = BTreeSet::<WorkId>::new();
+
+        request.emit(LocalArchiveImportProgress::Started { total });
+
+        for (index, item) in report
+            .works
+            .iter_mut()
+            .filter(|item| item.action == LocalArchiveImportAction::Extract)
+            .enumerate()
+        {
+            request.check_cancelled()?;
+            request.emit(LocalArchiveImportProgress::WorkStarted {
+                work_id: item.work_id.clone(),
+                current: index + 1,
+                total,
+            });
+
+            item.action = match self
+                .import_local_archive(item, &request, &imported_at)
+                .await
+            {
+                Ok(manifest_error) => {
+                    report.imported_count += 1;
+                    item.manifest_error = manifest_error;
+                    metadata_candidate_ids.insert(WorkId::from(item.work_id.as_str()));
+                    LocalArchiveImportAction::Imported
+                }
+                Err(error) => {
+                    report.failed_count += 1;
+                    LocalArchiveImportAction::Failed {
+                        message: error.support_message(),
+                    }
+                }
+            };
+
+            request.emit(LocalArchiveImportProgress::WorkFinished {
+                work_id: item.work_id.clone(),
+                current: index + 1,
+                total,
+                imported: item.action == LocalArchiveImportAction::Imported,
+            });
+        }
+
+        let (metadata_by_id, metadata_error) =
+            fetch_local_work_metadata(metadata_source, &metadata_candidate_ids, &imported_at)
+                .await?;
+        let metadata_works = metadata_by_id.values().cloned().collect::<Vec<_>>();
+
+        self.storage
+            .import_local_work_downloads_with_metadata(&[], &metadata_works)
+            .await?;
+
+        report.metadata_candidate_count = if metadata_source.is_some() {
+            metadata_candidate_ids.len()
+        } else {
+            0
+        };
+        report.metadata_updated_count = metadata_by_id.len();
+        report.metadata_missing_count = report
+            .metadata_candidate_count
+            .saturating_sub(metadata_by_id.len());
+        report.metadata_error = metadata_error;
+
+        Ok(report)
+    }
+
+    /// Extracts and records one work; returns why its file manifest couldn't be recorded.
+    async fn import_local_archive(
+        &self,
+        item: &LocalArchiveImportItem,
+        request: &LocalArchiveImportRequest<'_>,
+        imported_at: &str,
+    ) -> Result<Option<String>> {
+        let plan = dm_archive::plan_archive_handling(item.archives.clone());
+        let target_path = item.target_path.clone();
+        // A serial information file next to the archives supplies their passwords.
+        let archive_dir = item
+            .archives
+            .first()
+            .and_then(|archive| archive.parent())
+            .unwrap_or(request.source_dir);
+        let options = dm_archive::ArchiveExtractOptions {
+            remove_sources: request.remove_sources,
+            name_encoding: self.archive_name_encoding(&item.work_id).await?,
+            passwords: self.archive_passwords(
+                &item.work_id,
+                &read_serial_information(archive_dir, &item.work_id).await?,
+            )?,
+            nested: request.nested_archives,
+            ..dm_archive::ArchiveExtractOptions::default()
+        };
+
+        tokio::task::spawn_blocking(move || extract_local_archive(&plan, &target_path, options))
+            .await
+            .map_err(|err| LibraryError::Io(std::io::Error::other(err)))??;
+
+        let local_path = item.target_path.canonicalize()?;
+        let work = cached_work_from_local_folder(&item.work_id, &item.work_id, imported_at)?;
+        let download = WorkDownloadUpdate {
+            work_id: item.work_id.clone(),
+            status: WorkDownloadStatus::Downloaded,
+            local_path: Some(local_path.to_string_lossy().into_owned()),
+            staging_path: None,
+            archive_vault_path: None,
+            unpack_policy: unpack_policy_storage_value(UnpackPolicy::UnpackWhenRecognized)
+                .to_owned(),
+            bytes_received: 0,
+            bytes_total: None,
+            error_code: None,
+            error_message: None,
+            started_at: Some(imported_at.to_owned()),
+            completed_at: Some(imported_at.to_owned()),
+            updated_at: imported_at.to_owned(),
+        };
+
+        self.storage
+            .import_local_work_downloads_with_metadata(
+                &[LocalWorkDownloadImport { work, download }],
+                &[],
+            )
+            .await?;
+        Ok(self
+            .record_file_manifest_best_effort(
+                &item.work_id,
+                &local_path,
+                request.manifest_sidecar,
+                imported_at,
+            )
+            .await)
+    }
+
     pub async fn sync_account_with_source<S>(
         &self,
         request: AccountSyncRequest<'_>,
@@ -1273,7 +2048,12 @@ impl Library {
             let page_len = page.products.len();
 
             for product in page.products {
-                if skip_downloaded && product.download.status == WorkDownloadStatus::Downloaded {
+                if skip_downloaded
+                    && matches!(
+                        product.download.status,
+                        WorkDownloadStatus::Downloaded | WorkDownloadStatus::Corrupted
+                    )
+                {
                     skipped_downloaded_count += 1;
                     continue;
                 }
@@ -1350,17 +2130,7 @@ impl Library {
     {
         request.check_cancelled()?;
         request.emit(SyncProgress::LoggingIn);
-
-        let login_name = account
-            .login_name
-            .as_deref()
-            .ok_or_else(|| LibraryError::MissingLoginName(account.id.clone()))?;
-        let password = self.password_for_account(account, request.password)?;
-        let credentials = Credentials::new(login_name, password);
-
-        source.login(&credentials).await?;
-        self.storage
-            .record_account_login(&account.id, &now_string())
+        self.authenticate_account(account, request.password, &SyncSourceLogin(source))
             .await?;
 
         request.check_cancelled()?;
@@ -1382,7 +2152,10 @@ impl Library {
         let works = source.works(&purchased_ids).await?;
 
         let completed_at = now_string();
-        let storage_sync = build_storage_sync(
+        let localized = self
+            .load_localized_texts(&works, &completed_at, &request, source)
+            .await?;
+        let mut storage_sync = build_storage_sync(
             &account.id,
             sync_run_id,
             started_at,
@@ -1390,6 +2163,7 @@ impl Library {
             purchases,
             works,
         )?;
+        storage_sync.commit.localized_texts = localized.texts;
 
         request.check_cancelled()?;
         request.emit(SyncProgress::Committing {
@@ -1398,6 +2172,19 @@ impl Library {
         self.storage
             .commit_account_sync(&storage_sync.commit)
             .await?;
+        let purchased_work_ids = storage_sync
+            .commit
+            .account_works
+            .iter()
+            .map(|work| work.work_id.as_str())
+            .collect::<BTreeSet<_>>();
+        let update_available_work_ids = self
+            .storage
+            .works_with_update_available()
+            .await?
+            .into_iter()
+            .filter(|work_id| purchased_work_ids.contains(work_id.as_str()))
+            .collect();
 
         let report = AccountSyncReport {
             account_id: account.id.clone(),
@@ -1407,6 +2194,8 @@ impl Library {
             missing_detail_count: storage_sync.missing_detail_count,
             page_limit: count.page_limit,
             concurrency: count.concurrency,
+            localization_error: localized.error,
+            update_available_work_ids,
         };
 
         request.emit(SyncProgress::Completed {
@@ -1417,6 +2206,77 @@ impl Library {
         Ok(report)
     }
 
+    /// Fetches translated public metadata for works that have none stored yet.
+    ///
+    /// Translations are best effort: a failing language is reported and retried next sync.
+    async fn load_localized_texts<S>(
+        &self,
+        works: &[Work],
+        fetched_at: &str,
+        request: &AccountSyncRequest<'_>,
+        source: &S,
+    ) -> Result<LocalizedTextSync>
+    where
+        S: AccountSyncSource + Sync,
+    {
+        let work_ids = works
+            .iter()
+            .map(|work| work.id.as_ref().to_owned())
+            .collect::<Vec<_>>();
+        let mut sync = LocalizedTextSync::default();
+
+        for language in LOCALIZED_SYNC_LANGUAGES {
+            let missing = self
+                .storage
+                .works_missing_localized_text(&work_ids, language.code())
+                .await?
+                .into_iter()
+                .collect::<BTreeSet<_>>();
+            let pending = works
+                .iter()
+                .filter(|work| missing.contains(work.id.as_ref()))
+                .collect::<Vec<_>>();
+
+            if pending.is_empty() {
+                continue;
+            }
+
+            request.check_cancelled()?;
+            request.emit(SyncProgress::LoadingLocalizedWorks {
+                language: language.code().to_owned(),
+                work_count: pending.len(),
+            });
+            let ids = pending
+                .iter()
+                .map(|work| work.id.clone())
+                .collect::<Vec<_>>();
+            let localized = match source.localized_works(&ids, &language).await {
+                Ok(localized) => localized,
+                Err(error) => {
+                    sync.error.get_or_insert_with(|| {
+                        format!("{}: {}", language.code(), error.support_message())
+                    });
+                    continue;
+                }
+            };
+            let localized_by_id = localized
+                .into_iter()
+                .map(|work| (work.id.as_ref().to_owned(), work))
+                .collect::<BTreeMap<_, _>>();
+
+            sync.texts.extend(pending.into_iter().map(|work| {
+                localized_text_from_public_work(
+                    work,
+                    localized_by_id.get(work.id.as_ref()),
+                    &language,
+                    fetched_at,
+                )
+            }));
+        }
+
+        Ok(sync)
+    }
+
     async fn download_work_inner<S>(
         &self,
         account: &Account,
@@ -1430,24 +2290,24 @@ impl Library {
     where
         S: WorkDownloadSource + Sync,
     {
+        let archive_vault_dir = request.archive_vault_dir();
+        if request.unpack_policy == UnpackPolicy::UnpackAndKeepArchives
+            && archive_vault_dir.is_none()
+        {
+            return Err(LibraryError::ArchiveVaultNotConfigured);
+        }
+
         request.check_cancelled()?;
         request.emit(WorkDownloadProgress::LoggingIn);
-
-        let login_name = account
-            .login_name
-            .as_deref()
-            .ok_or_else(|| LibraryError::MissingLoginName(account.id.clone()))?;
-        let password = self.password_for_account(account, request.password)?;
-        let credentials = Credentials::new(login_name, password);
-
-        source.login(&credentials).await?;
-        self.storage
-            .record_account_login(&account.id, &now_string())
+        self.authenticate_account(account, request.password, &DownloadSourceLogin(source))
             .await?;
 
         request.check_cancelled()?;
         request.emit(WorkDownloadProgress::ResolvingPlan);
         let plan = source.download_plan(work_id).await?;
+        let archive_vault_path = archive_vault_dir
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned());
 
         request.check_cancelled()?;
         self.storage
@@ -1456,6 +2316,7 @@ impl Library {
                 status: WorkDownloadStatus::Downloading,
                 local_path: Some(final_dir.to_string_lossy().into_owned()),
                 staging_path: Some(staging_dir.to_string_lossy().into_owned()),
+                archive_vault_path: archive_vault_path.clone(),
                 unpack_policy: unpack_policy_storage_value(request.unpack_policy).to_owned(),
                 bytes_received: 0,
                 bytes_total: None,
@@ -1471,6 +2332,10 @@ impl Library {
             remove_existing_download_path(staging_dir, &[request.download_root]).await?;
         }
 
+        self.ensure_download_disk_space(&plan, staging_dir, &request, source)
+            .await?;
+
+        request.check_cancelled()?;
         let download_cancellation = dm_download::CancellationToken::new();
         let _download_cancellation_forwarder =
             request.forward_download_cancellation(&download_cancellation);
@@ -1478,8 +2343,15 @@ impl Library {
             work_id: work_id.clone(),
             target_root: request.download_root.to_path_buf(),
             unpack_policy: request.unpack_policy,
+            archive_name_encoding: self.archive_name_encoding(request.work_id).await?,
+            archive_passwords: self.archive_passwords(request.work_id, &plan.serial_numbers)?,
+            nested_archives: request.nested_archives,
+            connections_per_file: request.connections_per_file,
+            parallel_files: request.parallel_files,
+            retry_policy: request.retry_policy,
+            stall_policy: request.stall_policy,
         };
-        let downloaded = source
+        let downloaded = match source
             .download_files(&job, &plan, &download_cancellation, &mut |progress| {
                 if request.is_cancelled() {
                     download_cancellation.cancel();
@@ -1487,22 +2359,208 @@ impl Library {
 
                 request.emit(WorkDownloadProgress::Download(progress));
             })
-            .await?;
+            .await
+        {
+            Ok(downloaded) => downloaded,
+            Err(LibraryError::Download(dm_download::DownloadError::InsufficientDiskSpace {
+                path,
+                required,
+                available,
+            })) => {
+                return Err(LibraryError::InsufficientDiskSpace {
+                    path,
+                    required_bytes: required,
+                    available_bytes: available,
+                });
+            }
+            Err(error) => {
+                // Keeps the serial numbers next to the staged archives for a later unpack retry.
+                if error.is_archive_password_error() {
+                    write_serial_information_file(
+                        staging_dir,
+                        work_id.as_ref(),
+                        &plan.serial_numbers,
+                        &now_string(),
+                    )
+                    .await?;
+                }
 
-        request.check_cancelled()?;
-        request.emit(WorkDownloadProgress::Finalizing);
-        write_serial_information_file(
-            staging_dir,
-            work_id.as_ref(),
-            &plan.serial_numbers,
-            &now_string(),
-        )
-        .await?;
+                return Err(error);
+            }
+        };
 
-        if request.replace_existing {
-            remove_existing_download_path(final_dir, &[request.library_root]).await?;
+        for file in &downloaded.files {
+            if let Some(reason) = &file.discarded_partial {
+                request.emit(WorkDownloadProgress::PartialDiscarded {
+                    file_name: file.file_name.clone(),
+                    reason: reason.clone(),
+                });
+            }
+        }
+
+        self.finish_work_download(
+            account,
+            &request,
+            final_dir,
+            started_at,
+            downloaded,
+            Some(&plan.serial_numbers),
+        )
+        .await
+    }
+
+    async fn resume_work_unpack_inner(
+        &self,
+        account: &Account,
+        staging_dir: &Path,
+        final_dir: &Path,
+        started_at: &str,
+        request: WorkDownloadRequest<'_>,
+    ) -> Result<WorkDownloadReport> {
+        if request.unpack_policy == UnpackPolicy::UnpackAndKeepArchives
+            && request.archive_vault_dir().is_none()
+        {
+            return Err(LibraryError::ArchiveVaultNotConfigured);
+        }
+
+        let state = self.storage.work_download_state(request.work_id).await?;
+        let files = if state.status == WorkDownloadStatus::Failed {
+            staged_archive_files(staging_dir).await?
+        } else {
+            Vec::new()
+        };
+
+        if files.is_empty() {
+            return Err(LibraryError::NoStagedDownload(request.work_id.to_owned()));
+        }
+
+        request.check_cancelled()?;
+        let bytes_received = files.iter().map(|file| file.bytes_written).sum();
+        request.emit(WorkDownloadProgress::Download(DownloadProgress {
+            phase: DownloadPhase::Unpacking,
+            file_index: None,
+            file_kind: None,
+            bytes_received,
+            bytes_total: Some(bytes_received),
+            retry: None,
+        }));
+
+        let options = dm_archive::ArchiveExtractOptions {
+            remove_sources: request.unpack_policy != UnpackPolicy::UnpackAndKeepArchives,
+            name_encoding: self.archive_name_encoding(request.work_id).await?,
+            passwords: self.archive_passwords(
+                request.work_id,
+                &read_serial_information(staging_dir, request.work_id).await?,
+            )?,
+            nested: request.nested_archives,
+            ..dm_archive::ArchiveExtractOptions::default()
+        };
+        let unpack_policy = request.unpack_policy;
+        let target_dir = staging_dir.to_path_buf();
+        let unpack_files = files.clone();
+        let archive_extraction = tokio::task::spawn_blocking(move || {
+            dm_download::unpack_downloaded_files(&unpack_files, &target_dir, unpack_policy, options)
+        })
+        .await
+        .map_err(|err| LibraryError::Io(std::io::Error::other(err)))??;
+
+        self.finish_work_download(
+            account,
+            &request,
+            final_dir,
+            started_at,
+            DownloadedWork {
+                work_id: WorkId::from(request.work_id.to_owned()),
+                target_dir: staging_dir.to_path_buf(),
+                files,
+                archive_extraction,
+            },
+            None,
+        )
+        .await
+    }
+
+    /// Moves a downloaded and unpacked work into the library and records it. `serial_numbers` is
+    /// `None` when the serial information file is already in the staging folder.
+    async fn finish_work_download(
+        &self,
+        account: &Account,
+        request: &WorkDownloadRequest<'_>,
+        final_dir: &Path,
+        started_at: &str,
+        downloaded: DownloadedWork,
+        serial_numbers: Option<&[SerialNumber]>,
+    ) -> Result<WorkDownloadReport> {
+        let staging_dir = downloaded.target_dir.as_path();
+        let archive_vault_dir = request.archive_vault_dir();
+
+        request.check_cancelled()?;
+        request.emit(WorkDownloadProgress::Finalizing);
+        if let Some(serial_numbers) = serial_numbers {
+            write_serial_information_file(
+                staging_dir,
+                request.work_id,
+                serial_numbers,
+                &now_string(),
+            )
+            .await?;
+        }
+
+        let previous_version_path = if request.replace_existing {
+            set_aside_previous_version(final_dir, request.library_root, request.previous_version)
+                .await?
+        } else {
+            None
+        };
+        if let Err(error) = move_downloaded_work_dir(staging_dir, final_dir).await {
+            if let Some(previous_version_path) = &previous_version_path {
+                let _ = tokio::fs::rename(previous_version_path, final_dir).await;
+            }
+            return Err(error);
         }
-        move_downloaded_work_dir(staging_dir, final_dir).await?;
+
+        // The archives travel with the library folder and only move into the vault once it is in
+        // place, so a failed swap leaves them staged for a later unpack retry.
+        let archive_vault_path = match (
+            &archive_vault_dir,
+            request.archive_vault_root,
+            &downloaded.archive_extraction,
+        ) {
+            (Some(archive_vault_dir), Some(archive_vault_root), Some(_)) => {
+                let archives = downloaded
+                    .files
+                    .iter()
+                    .filter_map(|file| file.path.strip_prefix(staging_dir).ok())
+                    .map(|relative| final_dir.join(relative))
+                    .collect::<Vec<_>>();
+                let vaulted = keep_archives_in_vault(
+                    &archives,
+                    archive_vault_dir,
+                    archive_vault_root,
+                    if request.replace_existing {
+                        request.previous_version
+                    } else {
+                        PreviousVersionPolicy::Discard
+                    },
+                )
+                .await;
+
+                if let Err(error) = vaulted {
+                    if move_downloaded_work_dir(final_dir, staging_dir)
+                        .await
+                        .is_ok()
+                    {
+                        if let Some(previous_version_path) = &previous_version_path {
+                            let _ = tokio::fs::rename(previous_version_path, final_dir).await;
+                        }
+                    }
+                    return Err(error);
+                }
+
+                Some(archive_vault_dir.to_string_lossy().into_owned())
+            }
+            _ => None,
+        };
 
         let completed_at = now_string();
         let bytes_received = downloaded
@@ -1517,6 +2575,7 @@ impl Library {
                 status: WorkDownloadStatus::Downloaded,
                 local_path: Some(final_dir.to_string_lossy().into_owned()),
                 staging_path: Some(staging_dir.to_string_lossy().into_owned()),
+                archive_vault_path,
                 unpack_policy: unpack_policy_storage_value(request.unpack_policy).to_owned(),
                 bytes_received,
                 bytes_total: Some(bytes_received),
@@ -1524,9 +2583,17 @@ impl Library {
                 error_message: None,
                 started_at: Some(started_at.to_owned()),
                 completed_at: Some(completed_at.clone()),
-                updated_at: completed_at,
+                updated_at: completed_at.clone(),
             })
             .await?;
+        let manifest_error = self
+            .record_file_manifest_best_effort(
+                request.work_id,
+                final_dir,
+                request.manifest_sidecar,
+                &completed_at,
+            )
+            .await;
 
         request.emit(WorkDownloadProgress::Completed);
 
@@ -1536,10 +2603,103 @@ impl Library {
             local_path: final_dir.to_path_buf(),
             file_count: downloaded.files.len(),
             archive_extracted: downloaded.archive_extraction.is_some(),
+            archive_name_encoding: downloaded
+                .archive_extraction
+                .as_ref()
+                .and_then(|extraction| extraction.name_encoding),
+            archive_fallback_names: downloaded
+                .archive_extraction
+                .as_ref()
+                .map(|extraction| extraction.fallback_names.clone())
+                .unwrap_or_default(),
+            nested_archives: downloaded
+                .archive_extraction
+                .map(|extraction| rebase_nested_archives(extraction.nested, staging_dir, final_dir))
+                .unwrap_or_default(),
+            previous_version_path,
+            manifest_error,
             download_state: self.storage.work_download_state(request.work_id).await?,
         })
     }
 
+    /// Refuses a download whose probed archive sizes, plus their estimated unpacked size, don't
+    /// fit on the download and library volumes.
+    async fn ensure_download_disk_space<S>(
+        &self,
+        plan: &DownloadPlan,
+        staging_dir: &Path,
+        request: &WorkDownloadRequest<'_>,
+        source: &S,
+    ) -> Result<()>
+    where
+        S: WorkDownloadSource + Sync,
+    {
+        let mut archive_bytes = 0u64;
+        let mut file_names = Vec::with_capacity(plan.files.len());
+
+        for (file_index, file) in plan.files.iter().enumerate() {
+            request.check_cancelled()?;
+            let metadata = source.download_file_metadata(file_index, file).await?;
+            archive_bytes = archive_bytes.saturating_add(metadata.expected_size.unwrap_or(0));
+            file_names.push(PathBuf::from(metadata.file_name));
+        }
+
+        let unpacks = request.unpack_policy.unpacks()
+            && dm_archive::plan_archive_handling(file_names).is_unpackable();
+        let checks = check_disk_space(
+            DiskSpaceRoots {
+                library_root: request.library_root,
+                download_root: request.download_root,
+                unpack_policy: request.unpack_policy,
+            },
+            archive_bytes,
+            directory_size(staging_dir).await?,
+            unpacks,
+        )?;
+
+        match checks.into_iter().find(|check| check.shortfall_bytes() > 0) {
+            Some(check) => Err(LibraryError::InsufficientDiskSpace {
+                path: check.path,
+                required_bytes: check.required_bytes,
+                available_bytes: check.available_bytes,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the folder of a finished download when it lives inside the library root.
+    async fn existing_download_dir(
+        &self,
+        work_id: &str,
+        library_root: &Path,
+    ) -> Result<Option<PathBuf>> {
+        let state = self.storage.work_download_state(work_id).await?;
+        let Some(local_path) = state
+            .local_path
+            .filter(|_| {
+                matches!(
+                    state.status,
+                    WorkDownloadStatus::Downloaded | WorkDownloadStatus::Corrupted
+                )
+            })
+            .map(PathBuf::from)
+        else {
+            return Ok(None);
+        };
+
+        if !local_path.is_dir() {
+            return Ok(None);
+        }
+
+        let canonical_path = local_path.canonicalize()?;
+        let canonical_root = library_root.canonicalize()?;
+
+        Ok(
+            path_is_download_child_of_any_root(&canonical_path, &[canonical_root])
+                .then_some(canonical_path),
+        )
+    }
+
     async fn find_account(&self, account_id: &str) -> Result<Account> {
         self.storage
             .accounts()
@@ -1582,6 +2742,109 @@ impl Library {
         }
     }
 
+    fn save_account_proxy(
+        &self,
+        account_id: &str,
+        url: Option<&str>,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<Option<AccountProxy>> {
+        let credential_ref = CredentialRef::account_proxy_password(account_id)?;
+        let url = url.map(str::trim).filter(|url| !url.is_empty());
+        let username = username
+            .map(str::trim)
+            .filter(|username| !username.is_empty());
+        let (Some(url), Some(username)) = (url, username) else {
+            self.credentials.delete_password(&credential_ref)?;
+
+            return Ok(url
+                .map(|url| {
+                    ProxyConfig::parse(url).map(|proxy| AccountProxy {
+                        url: proxy.url.to_string(),
+                        username: None,
+                        credential_ref: None,
+                    })
+                })
+                .transpose()?);
+        };
+        let proxy = ProxyConfig::parse(url)?;
+
+        if let Some(password) = password {
+            self.credentials.save_password(&credential_ref, password)?;
+        }
+        let has_password = self.credentials.load_password(&credential_ref)?.is_some();
+
+        Ok(Some(AccountProxy {
+            url: proxy.url.to_string(),
+            username: Some(username.to_owned()),
+            credential_ref: has_password.then(|| credential_ref.to_string()),
+        }))
+    }
+
+    async fn authenticate_account<L>(
+        &self,
+        account: &Account,
+        password: Option<&str>,
+        source: &L,
+    ) -> Result<()>
+    where
+        L: AccountLogin + Sync,
+    {
+        let session_ref = CredentialRef::account_session(&account.id)?;
+
+        source
+            .use_proxy(self.account_proxy(account)?.as_ref())
+            .await?;
+
+        let mut restore_error = None;
+
+        if let Some(cookies_json) = self.credentials.load_session(&session_ref)? {
+            // A session that can't be checked right now only falls back to the password; it is
+            // dropped once DLsite says it has expired or it can't be read at all.
+            match source
+                .restore_session(&SessionSnapshot { cookies_json })
+                .await
+            {
+                Ok(true) => {
+                    if let Some(session) = source.export_session().await? {
+                        self.credentials
+                            .save_session(&session_ref, &session.cookies_json)?;
+                    }
+
+                    return Ok(());
+                }
+                Ok(false) => self.credentials.delete_session(&session_ref)?,
+                Err(error) => restore_error = Some(error),
+            }
+        }
+
+        // Without a password to fall back on, the failed restore is why the account can't sign in.
+        let login_name = match account.login_name.as_deref() {
+            Some(login_name) => login_name,
+            None => {
+                return Err(restore_error
+                    .unwrap_or_else(|| LibraryError::MissingLoginName(account.id.clone())))
+            }
+        };
+        let password = match self.password_for_account(account, password) {
+            Ok(password) => password,
+            Err(error) => return Err(restore_error.unwrap_or(error)),
+        };
+        let credentials = Credentials::new(login_name, password);
+
+        source.login(&credentials).await?;
+        self.storage
+            .record_account_login(&account.id, &now_string())
+            .await?;
+
+        if let Some(session) = source.export_session().await? {
+            self.credentials
+                .save_session(&session_ref, &session.cookies_json)?;
+        }
+
+        Ok(())
+    }
+
     fn password_for_account(&self, account: &Account, password: Option<&str>) -> Result<String> {
         if let Some(password) = password {
             return Ok(password.to_owned());
@@ -1606,6 +2869,10 @@ pub struct SaveAccountRequest {
     pub login_name: Option<String>,
     pub password: Option<String>,
     pub remember_password: bool,
+    pub proxy_url: Option<String>,
+    pub proxy_username: Option<String>,
+    /// Replaces the stored proxy password; `None` keeps the saved one.
+    pub proxy_password: Option<String>,
     pub enabled: bool,
 }
 
@@ -1617,6 +2884,9 @@ impl SaveAccountRequest {
             login_name: None,
             password: None,
             remember_password: false,
+            proxy_url: None,
+            proxy_username: None,
+            proxy_password: None,
             enabled: true,
         }
     }
@@ -1667,6 +2937,9 @@ pub struct AccountSyncReport {
     pub missing_detail_count: usize,
     pub page_limit: Option<usize>,
     pub concurrency: Option<usize>,
+    pub localization_error: Option<String>,
+    /// Downloaded works of this account whose DLsite version is newer than the local copy.
+    pub update_available_work_ids: Vec<String>,
 }
 
 #[derive(Clone, Copy)]
@@ -1677,11 +2950,34 @@ pub struct WorkDownloadRequest<'a> {
     pub library_root: &'a Path,
     pub download_root: &'a Path,
     pub unpack_policy: UnpackPolicy,
+    /// Required by `UnpackPolicy::UnpackAndKeepArchives`; archives move to `<root>/<work_id>`.
+    pub archive_vault_root: Option<&'a Path>,
+    /// Unset leaves archives found inside the work's archives packed.
+    pub nested_archives: Option<NestedArchiveOptions>,
+    pub connections_per_file: usize,
+    pub parallel_files: usize,
+    pub retry_policy: RetryPolicy,
+    pub stall_policy: StallPolicy,
+    /// Also writes the file manifest into the work folder as JSON.
+    pub manifest_sidecar: bool,
+    /// Replaces an existing download in place, handling the old folder per `previous_version`.
     pub replace_existing: bool,
+    pub previous_version: PreviousVersionPolicy,
     pub cancellation_token: Option<&'a CancellationToken>,
     pub progress_sink: Option<&'a dyn WorkDownloadProgressSink>,
 }
 
+/// What happens to the folder a replacing download swaps out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PreviousVersionPolicy {
+    #[default]
+    Discard,
+    /// Renames the old folder next to the new one.
+    Keep,
+    /// Moves the old folder under `<library_root>/.previous-versions`.
+    Archive,
+}
+
 impl<'a> WorkDownloadRequest<'a> {
     pub fn new(work_id: &'a str, library_root: &'a Path, download_root: &'a Path) -> Self {
         Self {
@@ -1691,12 +2987,28 @@ impl<'a> WorkDownloadRequest<'a> {
             library_root,
             download_root,
             unpack_policy: UnpackPolicy::UnpackWhenRecognized,
+            archive_vault_root: None,
+            nested_archives: None,
+            connections_per_file: DEFAULT_CONNECTIONS_PER_FILE,
+            parallel_files: DEFAULT_PARALLEL_FILES,
+            retry_policy: RetryPolicy::default(),
+            stall_policy: StallPolicy::default(),
+            manifest_sidecar: false,
             replace_existing: false,
+            previous_version: PreviousVersionPolicy::Discard,
             cancellation_token: None,
             progress_sink: None,
         }
     }
 
+    /// Folder that receives the original archives when the policy keeps them.
+    fn archive_vault_dir(&self) -> Option<PathBuf> {
+        (self.unpack_policy == UnpackPolicy::UnpackAndKeepArchives)
+            .then_some(self.archive_vault_root)
+            .flatten()
+            .map(|root| root.join(self.work_id))
+    }
+
     fn is_cancelled(&self) -> bool {
         self.cancellation_token
             .is_some_and(CancellationToken::is_cancelled)
@@ -1720,23 +3032,30 @@ impl<'a> WorkDownloadRequest<'a> {
         &self,
         download_cancellation: &dm_download::CancellationToken,
     ) -> Option<DownloadCancellationForwarder> {
-        let job_cancellation = self.cancellation_token?.clone();
-        let download_cancellation = download_cancellation.clone();
+        forward_download_cancellation(self.cancellation_token, download_cancellation)
+    }
+}
 
-        if job_cancellation.is_cancelled() {
-            download_cancellation.cancel();
-        }
+fn forward_download_cancellation(
+    job_cancellation: Option<&CancellationToken>,
+    download_cancellation: &dm_download::CancellationToken,
+) -> Option<DownloadCancellationForwarder> {
+    let job_cancellation = job_cancellation?.clone();
+    let download_cancellation = download_cancellation.clone();
 
-        let handle = tokio::spawn(async move {
-            while !job_cancellation.is_cancelled() {
-                tokio::time::sleep(DOWNLOAD_CANCELLATION_POLL_INTERVAL).await;
-            }
+    if job_cancellation.is_cancelled() {
+        download_cancellation.cancel();
+    }
 
-            download_cancellation.cancel();
-        });
+    let handle = tokio::spawn(async move {
+        while !job_cancellation.is_cancelled() {
+            tokio::time::sleep(DOWNLOAD_CANCELLATION_POLL_INTERVAL).await;
+        }
 
-        Some(DownloadCancellationForwarder { handle })
-    }
+        download_cancellation.cancel();
+    });
+
+    Some(DownloadCancellationForwarder { handle })
 }
 
 struct DownloadCancellationForwarder {
@@ -1753,6 +3072,8 @@ pub struct WorkDownloadRemovalRequest<'a> {
     pub work_id: &'a str,
     pub library_root: &'a Path,
     pub download_root: &'a Path,
+    /// Also removes the work's kept archives when they live under this root.
+    pub archive_vault_root: Option<&'a Path>,
 }
 
 impl<'a> WorkDownloadRemovalRequest<'a> {
@@ -1761,6 +3082,7 @@ impl<'a> WorkDownloadRemovalRequest<'a> {
             work_id,
             library_root,
             download_root,
+            archive_vault_root: None,
         }
     }
 }
@@ -1785,81 +3107,364 @@ pub struct LocalWorkImportRequest<'a> {
     pub library_root: &'a Path,
 }
 
-impl<'a> LocalWorkImportRequest<'a> {
-    pub fn new(library_root: &'a Path) -> Self {
-        Self { library_root }
+pub struct LibraryVerifyRequest<'a> {
+    pub cancellation_token: Option<&'a CancellationToken>,
+    pub progress_sink: Option<&'a dyn LibraryVerifyProgressSink>,
+}
+
+impl LibraryVerifyRequest<'_> {
+    pub fn new() -> Self {
+        Self {
+            cancellation_token: None,
+            progress_sink: None,
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self
+            .cancellation_token
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            Err(LibraryError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn emit(&self, progress: LibraryVerifyProgress) {
+        if let Some(sink) = self.progress_sink {
+            sink.emit(progress);
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct LocalWorkImportReport {
-    pub scanned_directories: usize,
-    pub imported_count: usize,
-    pub skipped_no_id: usize,
-    pub skipped_ambiguous: usize,
-    pub skipped_non_utf8: usize,
-    pub skipped_existing: usize,
-    pub metadata_candidate_count: usize,
-    pub metadata_updated_count: usize,
-    pub metadata_missing_count: usize,
-    pub metadata_error: Option<String>,
-    pub imported_works: Vec<LocalWorkImportItem>,
+impl Default for LibraryVerifyRequest<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct LocalWorkImportItem {
-    pub work_id: String,
-    pub local_path: PathBuf,
+pub enum LibraryVerifyProgress {
+    Started {
+        total: usize,
+    },
+    WorkVerified {
+        work_id: String,
+        current: usize,
+        total: usize,
+        intact: bool,
+    },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct AccountRemovalReport {
-    pub account_id: String,
-    pub label: String,
-    pub credential_deleted: bool,
+pub trait LibraryVerifyProgressSink: Send + Sync {
+    fn emit(&self, progress: LibraryVerifyProgress);
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LibraryVerifyReport {
+    pub verified_count: usize,
+    pub corrupted_count: usize,
+    /// Works flagged as corrupted by an earlier run that match their manifest again.
+    pub restored_count: usize,
+    /// Every work with missing, changed or extra files.
+    pub issues: Vec<WorkIntegrityIssue>,
 }
 
+/// Extra files alone are reported but do not mark a work as corrupted.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct WorkDownloadReport {
+pub struct WorkIntegrityIssue {
     pub work_id: String,
-    pub account_id: String,
     pub local_path: PathBuf,
-    pub file_count: usize,
-    pub archive_extracted: bool,
-    pub download_state: WorkDownloadState,
+    pub missing: Vec<String>,
+    pub changed: Vec<String>,
+    pub extra: Vec<String>,
+    /// Vault folder recorded for the work's kept archives when it is gone or empty.
+    pub missing_archive_vault: Option<PathBuf>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum WorkDownloadProgress {
-    LoggingIn,
-    ResolvingPlan,
-    Download(DownloadProgress),
-    Finalizing,
-    Completed,
-}
+impl WorkIntegrityIssue {
+    pub fn is_corrupted(&self) -> bool {
+        !self.missing.is_empty() || !self.changed.is_empty() || self.missing_archive_vault.is_some()
+    }
 
-pub trait WorkDownloadProgressSink: Send + Sync {
-    fn emit(&self, progress: WorkDownloadProgress);
-}
+    fn summary(&self) -> String {
+        let summary = format!(
+            "{} missing, {} changed and {} extra files",
+            self.missing.len(),
+            self.changed.len(),
+            self.extra.len()
+        );
 
-struct BulkWorkDownloadSelection {
-    total_count: u64,
-    skipped_downloaded_count: usize,
-    items: Vec<BulkWorkDownloadSelectionItem>,
+        match &self.missing_archive_vault {
+            Some(vault_dir) => format!("{summary}; archives missing from {}", vault_dir.display()),
+            None => summary,
+        }
+    }
 }
 
-struct BulkWorkDownloadSelectionItem {
-    work_id: String,
-    content_size_bytes: Option<u64>,
+impl<'a> LocalWorkImportRequest<'a> {
+    pub fn new(library_root: &'a Path) -> Self {
+        Self { library_root }
+    }
 }
 
-#[derive(Clone)]
-pub struct BulkWorkDownloadRequest<'a> {
-    pub query: ProductListQuery,
-    pub work_ids: Option<Vec<String>>,
+pub struct LocalArchiveImportRequest<'a> {
+    pub source_dir: &'a Path,
     pub library_root: &'a Path,
-    pub download_root: &'a Path,
-    pub unpack_policy: UnpackPolicy,
+    /// Only plans the import; nothing is extracted or recorded.
+    pub dry_run: bool,
+    /// Deletes the archives once they have been extracted.
+    pub remove_sources: bool,
+    pub nested_archives: Option<NestedArchiveOptions>,
+    pub manifest_sidecar: bool,
+    pub cancellation_token: Option<&'a CancellationToken>,
+    pub progress_sink: Option<&'a dyn LocalArchiveImportProgressSink>,
+}
+
+impl<'a> LocalArchiveImportRequest<'a> {
+    pub fn new(source_dir: &'a Path, library_root: &'a Path) -> Self {
+        Self {
+            source_dir,
+            library_root,
+            dry_run: true,
+            remove_sources: false,
+            nested_archives: None,
+            manifest_sidecar: false,
+            cancellation_token: None,
+            progress_sink: None,
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self
+            .cancellation_token
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            Err(LibraryError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn emit(&self, progress: LocalArchiveImportProgress) {
+        if let Some(sink) = self.progress_sink {
+            sink.emit(progress);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalArchiveImportProgress {
+    Started {
+        total: usize,
+    },
+    WorkStarted {
+        work_id: String,
+        current: usize,
+        total: usize,
+    },
+    WorkFinished {
+        work_id: String,
+        current: usize,
+        total: usize,
+        imported: bool,
+    },
+}
+
+pub trait LocalArchiveImportProgressSink: Send + Sync {
+    fn emit(&self, progress: LocalArchiveImportProgress);
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkMetadataEnrichmentReport {
+    pub requested_count: usize,
+    pub enriched_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalWorkImportReport {
+    pub scanned_directories: usize,
+    pub imported_count: usize,
+    pub skipped_no_id: usize,
+    pub skipped_ambiguous: usize,
+    pub skipped_non_utf8: usize,
+    pub skipped_existing: usize,
+    pub metadata_candidate_count: usize,
+    pub metadata_updated_count: usize,
+    pub metadata_missing_count: usize,
+    pub metadata_error: Option<String>,
+    pub imported_works: Vec<LocalWorkImportItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalWorkImportItem {
+    pub work_id: String,
+    pub local_path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalArchiveImportReport {
+    pub dry_run: bool,
+    /// `.zip`, `.exe` and `.rar` files found directly inside the source folder.
+    pub scanned_files: usize,
+    pub skipped_no_id: usize,
+    pub skipped_ambiguous: usize,
+    pub skipped_non_utf8: usize,
+    pub imported_count: usize,
+    pub failed_count: usize,
+    pub metadata_candidate_count: usize,
+    pub metadata_updated_count: usize,
+    pub metadata_missing_count: usize,
+    pub metadata_error: Option<String>,
+    /// One entry per detected work ID, in work ID order.
+    pub works: Vec<LocalArchiveImportItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalArchiveImportItem {
+    pub work_id: String,
+    pub kind: LocalArchiveKind,
+    pub archives: Vec<PathBuf>,
+    pub target_path: PathBuf,
+    /// Known up front for ZIP (including split ZIP) and 7z archives only.
+    pub unpacked_bytes: Option<u64>,
+    pub action: LocalArchiveImportAction,
+    /// Why no file manifest was recorded for an imported work.
+    pub manifest_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalArchiveKind {
+    SingleZip,
+    SevenZip,
+    LegacySplitRar,
+    MultiVolumeRar,
+    SplitZip,
+    Unrecognized,
+}
+
+impl LocalArchiveKind {
+    fn from_plan(plan: &dm_archive::ArchivePlan) -> Self {
+        match plan {
+            dm_archive::ArchivePlan::SingleZip { .. } => Self::SingleZip,
+            dm_archive::ArchivePlan::SevenZip { .. } => Self::SevenZip,
+            dm_archive::ArchivePlan::LegacySplitRar { .. } => Self::LegacySplitRar,
+            dm_archive::ArchivePlan::MultiVolumeRar { .. } => Self::MultiVolumeRar,
+            dm_archive::ArchivePlan::SplitZip { .. } => Self::SplitZip,
+            dm_archive::ArchivePlan::KeepArchives { .. } => Self::Unrecognized,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SingleZip => "single_zip",
+            Self::SevenZip => "seven_zip",
+            Self::LegacySplitRar => "legacy_split_rar",
+            Self::MultiVolumeRar => "multi_volume_rar",
+            Self::SplitZip => "split_zip",
+            Self::Unrecognized => "unrecognized",
+        }
+    }
+}
+
+/// What the import does, or did, with one work's archives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalArchiveImportAction {
+    /// Planned by a dry run.
+    Extract,
+    Imported,
+    AlreadyDownloaded,
+    /// The work's library folder already exists.
+    TargetExists,
+    /// The files don't form a single ZIP or a legacy split RAR set.
+    Unrecognized,
+    Failed {
+        message: String,
+    },
+}
+
+impl LocalArchiveImportAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Extract => "extract",
+            Self::Imported => "imported",
+            Self::AlreadyDownloaded => "already_downloaded",
+            Self::TargetExists => "target_exists",
+            Self::Unrecognized => "unrecognized",
+            Self::Failed { .. } => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountRemovalReport {
+    pub account_id: String,
+    pub label: String,
+    pub credential_deleted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkDownloadReport {
+    pub work_id: String,
+    pub account_id: String,
+    pub local_path: PathBuf,
+    pub file_count: usize,
+    pub archive_extracted: bool,
+    /// How ZIP entry names were read, when a ZIP archive was extracted.
+    pub archive_name_encoding: Option<ArchiveNameEncoding>,
+    pub archive_fallback_names: Vec<ArchiveFallbackName>,
+    /// Archives found inside the work's archives, with paths in the library folder.
+    pub nested_archives: Vec<NestedArchive>,
+    pub previous_version_path: Option<PathBuf>,
+    /// Why no file manifest was recorded; the work is in the library either way.
+    pub manifest_error: Option<String>,
+    pub download_state: WorkDownloadState,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkDownloadProgress {
+    LoggingIn,
+    ResolvingPlan,
+    Download(DownloadProgress),
+    /// A stale partial file was thrown away and the file was downloaded again from the start.
+    PartialDiscarded {
+        file_name: String,
+        reason: InvalidDownloadResponseReason,
+    },
+    Finalizing,
+    Completed,
+}
+
+pub trait WorkDownloadProgressSink: Send + Sync {
+    fn emit(&self, progress: WorkDownloadProgress);
+}
+
+struct BulkWorkDownloadSelection {
+    total_count: u64,
+    skipped_downloaded_count: usize,
+    items: Vec<BulkWorkDownloadSelectionItem>,
+}
+
+struct BulkWorkDownloadSelectionItem {
+    work_id: String,
+    content_size_bytes: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct BulkWorkDownloadRequest<'a> {
+    pub query: ProductListQuery,
+    pub work_ids: Option<Vec<String>>,
+    pub library_root: &'a Path,
+    pub download_root: &'a Path,
+    pub unpack_policy: UnpackPolicy,
+    pub archive_vault_root: Option<&'a Path>,
+    pub nested_archives: Option<NestedArchiveOptions>,
+    pub connections_per_file: usize,
+    pub parallel_files: usize,
+    pub retry_policy: RetryPolicy,
+    pub stall_policy: StallPolicy,
+    pub manifest_sidecar: bool,
     pub skip_downloaded: bool,
     pub cancellation_token: Option<&'a CancellationToken>,
     pub progress_sink: Option<&'a dyn BulkWorkDownloadProgressSink>,
@@ -1873,6 +3478,13 @@ impl<'a> BulkWorkDownloadRequest<'a> {
             library_root,
             download_root,
             unpack_policy: UnpackPolicy::UnpackWhenRecognized,
+            archive_vault_root: None,
+            nested_archives: None,
+            connections_per_file: DEFAULT_CONNECTIONS_PER_FILE,
+            parallel_files: DEFAULT_PARALLEL_FILES,
+            retry_policy: RetryPolicy::default(),
+            stall_policy: StallPolicy::default(),
+            manifest_sidecar: false,
             skip_downloaded: true,
             cancellation_token: None,
             progress_sink: None,
@@ -1916,6 +3528,7 @@ pub struct BulkWorkDownloadSuccess {
     pub local_path: PathBuf,
     pub file_count: usize,
     pub archive_extracted: bool,
+    pub manifest_error: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2005,6 +3618,8 @@ pub struct BulkWorkDownloadPreviewRequest<'a> {
     pub query: ProductListQuery,
     pub work_ids: Option<Vec<String>>,
     pub skip_downloaded: bool,
+    /// Roots to check for free space against the planned download size.
+    pub disk_space: Option<DiskSpaceRoots<'a>>,
     pub cancellation_token: Option<&'a CancellationToken>,
     pub progress_sink: Option<&'a dyn BulkWorkDownloadPreviewProgressSink>,
 }
@@ -2042,6 +3657,37 @@ pub struct BulkWorkDownloadPreview {
     pub unknown_size_count: usize,
     pub works: Vec<BulkWorkDownloadPreviewWork>,
     pub failed_works: Vec<BulkWorkDownloadFailure>,
+    pub disk_space: Vec<DiskSpaceCheck>,
+}
+
+impl BulkWorkDownloadPreview {
+    pub fn disk_space_shortfall_bytes(&self) -> u64 {
+        self.disk_space
+            .iter()
+            .map(DiskSpaceCheck::shortfall_bytes)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiskSpaceRoots<'a> {
+    pub library_root: &'a Path,
+    pub download_root: &'a Path,
+    pub unpack_policy: UnpackPolicy,
+}
+
+/// Free space on one volume compared with what a download is expected to need there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskSpaceCheck {
+    pub path: PathBuf,
+    pub required_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl DiskSpaceCheck {
+    pub fn shortfall_bytes(&self) -> u64 {
+        self.required_bytes.saturating_sub(self.available_bytes)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2064,6 +3710,19 @@ pub struct BulkWorkDownloadPreviewFile {
 #[async_trait]
 pub trait WorkDownloadSource {
     async fn login(&self, credentials: &Credentials) -> Result<()>;
+
+    async fn restore_session(&self, _session: &SessionSnapshot) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn export_session(&self) -> Result<Option<SessionSnapshot>> {
+        Ok(None)
+    }
+
+    async fn use_proxy(&self, _proxy: Option<&ProxyConfig>) -> Result<()> {
+        Ok(())
+    }
+
     async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan>;
     async fn download_file_metadata(
         &self,
@@ -2081,24 +3740,42 @@ pub trait WorkDownloadSource {
 
 #[derive(Clone)]
 pub struct DlsiteWorkDownloadSource {
-    client: DlsiteClient,
+    client: Arc<RwLock<DlsiteClient>>,
 }
 
 impl DlsiteWorkDownloadSource {
     pub fn new(client: DlsiteClient) -> Self {
-        Self { client }
+        Self {
+            client: Arc::new(RwLock::new(client)),
+        }
+    }
+
+    pub fn client(&self) -> DlsiteClient {
+        current_client(&self.client)
     }
 }
 
 #[async_trait]
 impl WorkDownloadSource for DlsiteWorkDownloadSource {
     async fn login(&self, credentials: &Credentials) -> Result<()> {
-        self.client.login(credentials).await?;
+        self.client().login(credentials).await?;
         Ok(())
     }
 
+    async fn restore_session(&self, session: &SessionSnapshot) -> Result<bool> {
+        restore_client_session(&self.client(), session).await
+    }
+
+    async fn export_session(&self) -> Result<Option<SessionSnapshot>> {
+        Ok(Some(self.client().export_session()?))
+    }
+
+    async fn use_proxy(&self, proxy: Option<&ProxyConfig>) -> Result<()> {
+        switch_client_proxy(&self.client, proxy)
+    }
+
     async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan> {
-        Ok(self.client.download_plan(work_id).await?)
+        Ok(self.client().download_plan(work_id).await?)
     }
 
     async fn download_file_metadata(
@@ -2106,7 +3783,7 @@ impl WorkDownloadSource for DlsiteWorkDownloadSource {
         file_index: usize,
         file: &DownloadFile,
     ) -> Result<DownloadFileMetadata> {
-        Ok(dm_download::probe_download_file_metadata(&self.client, file_index, file).await?)
+        Ok(dm_download::probe_download_file_metadata(&self.client(), file_index, file).await?)
     }
 
     async fn download_files(
@@ -2116,14 +3793,10 @@ impl WorkDownloadSource for DlsiteWorkDownloadSource {
         cancellation: &dm_download::CancellationToken,
         progress_sink: &mut (dyn FnMut(DownloadProgress) + Send),
     ) -> Result<DownloadedWork> {
-        Ok(dm_download::download_work_files(
-            self.client.clone(),
-            job,
-            plan,
-            cancellation,
-            progress_sink,
+        Ok(
+            dm_download::download_work_files(self.client(), job, plan, cancellation, progress_sink)
+                .await?,
         )
-        .await?)
     }
 }
 
@@ -2158,6 +3831,10 @@ pub enum SyncProgress {
     LoadingWorks {
         work_count: usize,
     },
+    LoadingLocalizedWorks {
+        language: String,
+        work_count: usize,
+    },
     Committing {
         work_count: usize,
     },
@@ -2174,43 +3851,176 @@ pub trait SyncProgressSink: Send + Sync {
 #[async_trait]
 pub trait AccountSyncSource {
     async fn login(&self, credentials: &Credentials) -> Result<()>;
+
+    async fn restore_session(&self, _session: &SessionSnapshot) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn export_session(&self) -> Result<Option<SessionSnapshot>> {
+        Ok(None)
+    }
+
+    async fn use_proxy(&self, _proxy: Option<&ProxyConfig>) -> Result<()> {
+        Ok(())
+    }
+
     async fn content_count(&self) -> Result<ContentCount>;
     async fn purchases(&self) -> Result<Vec<Purchase>>;
     async fn works(&self, ids: &[WorkId]) -> Result<Vec<Work>>;
+
+    async fn localized_works(
+        &self,
+        _ids: &[WorkId],
+        _language: &Language,
+    ) -> Result<Vec<PublicWork>> {
+        Ok(Vec::new())
+    }
 }
 
 #[derive(Clone)]
 pub struct DlsiteSyncSource {
-    client: DlsiteClient,
+    client: Arc<RwLock<DlsiteClient>>,
 }
 
 impl DlsiteSyncSource {
     pub fn new(client: DlsiteClient) -> Self {
-        Self { client }
+        Self {
+            client: Arc::new(RwLock::new(client)),
+        }
     }
 
-    pub fn client(&self) -> &DlsiteClient {
-        &self.client
+    pub fn client(&self) -> DlsiteClient {
+        current_client(&self.client)
     }
 }
 
 #[async_trait]
 impl AccountSyncSource for DlsiteSyncSource {
     async fn login(&self, credentials: &Credentials) -> Result<()> {
-        self.client.login(credentials).await?;
+        self.client().login(credentials).await?;
         Ok(())
     }
 
+    async fn restore_session(&self, session: &SessionSnapshot) -> Result<bool> {
+        restore_client_session(&self.client(), session).await
+    }
+
+    async fn export_session(&self) -> Result<Option<SessionSnapshot>> {
+        Ok(Some(self.client().export_session()?))
+    }
+
+    async fn use_proxy(&self, proxy: Option<&ProxyConfig>) -> Result<()> {
+        switch_client_proxy(&self.client, proxy)
+    }
+
     async fn content_count(&self) -> Result<ContentCount> {
-        Ok(self.client.content_count(ContentQuery::default()).await?)
+        Ok(self.client().content_count(ContentQuery::default()).await?)
     }
 
     async fn purchases(&self) -> Result<Vec<Purchase>> {
-        Ok(self.client.sales(ContentQuery::default()).await?)
+        Ok(self.client().sales(ContentQuery::default()).await?)
     }
 
     async fn works(&self, ids: &[WorkId]) -> Result<Vec<Work>> {
-        Ok(self.client.works(ids).await?)
+        Ok(self.client().works(ids).await?)
+    }
+
+    async fn localized_works(
+        &self,
+        ids: &[WorkId],
+        language: &Language,
+    ) -> Result<Vec<PublicWork>> {
+        Ok(self.client().localized_public_works(ids, language).await?)
+    }
+}
+
+fn current_client(client: &RwLock<DlsiteClient>) -> DlsiteClient {
+    client
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .clone()
+}
+
+fn switch_client_proxy(client: &RwLock<DlsiteClient>, proxy: Option<&ProxyConfig>) -> Result<()> {
+    let mut client = client.write().unwrap_or_else(|error| error.into_inner());
+
+    if client.proxy() != proxy {
+        *client = client.with_proxy(proxy.cloned())?;
+    }
+
+    Ok(())
+}
+
+/// Returns `false` for a session DLsite has expired and for a snapshot that can't be read, since
+/// neither will ever sign in.
+async fn restore_client_session(client: &DlsiteClient, session: &SessionSnapshot) -> Result<bool> {
+    if client.import_session(session).is_err() {
+        return Ok(false);
+    }
+
+    match client.validate_session().await {
+        Ok(status) => Ok(status == SessionStatus::Authorized),
+        Err(err) => {
+            // Cookies of a session that may still be live would turn the password login away.
+            client.clear_session()?;
+            Err(err.into())
+        }
+    }
+}
+
+#[async_trait]
+trait AccountLogin {
+    async fn login(&self, credentials: &Credentials) -> Result<()>;
+    async fn restore_session(&self, session: &SessionSnapshot) -> Result<bool>;
+    async fn export_session(&self) -> Result<Option<SessionSnapshot>>;
+    async fn use_proxy(&self, proxy: Option<&ProxyConfig>) -> Result<()>;
+}
+
+struct SyncSourceLogin<'a, S>(&'a S);
+
+#[async_trait]
+impl<S> AccountLogin for SyncSourceLogin<'_, S>
+where
+    S: AccountSyncSource + Sync,
+{
+    async fn login(&self, credentials: &Credentials) -> Result<()> {
+        self.0.login(credentials).await
+    }
+
+    async fn restore_session(&self, session: &SessionSnapshot) -> Result<bool> {
+        self.0.restore_session(session).await
+    }
+
+    async fn export_session(&self) -> Result<Option<SessionSnapshot>> {
+        self.0.export_session().await
+    }
+
+    async fn use_proxy(&self, proxy: Option<&ProxyConfig>) -> Result<()> {
+        self.0.use_proxy(proxy).await
+    }
+}
+
+struct DownloadSourceLogin<'a, S>(&'a S);
+
+#[async_trait]
+impl<S> AccountLogin for DownloadSourceLogin<'_, S>
+where
+    S: WorkDownloadSource + Sync,
+{
+    async fn login(&self, credentials: &Credentials) -> Result<()> {
+        self.0.login(credentials).await
+    }
+
+    async fn restore_session(&self, session: &SessionSnapshot) -> Result<bool> {
+        self.0.restore_session(session).await
+    }
+
+    async fn export_session(&self) -> Result<Option<SessionSnapshot>> {
+        self.0.export_session().await
+    }
+
+    async fn use_proxy(&self, proxy: Option<&ProxyConfig>) -> Result<()> {
+        self.0.use_proxy(proxy).await
     }
 }
 
@@ -2257,6 +4067,7 @@ fn build_storage_sync(
             completed_at: completed_at.to_owned(),
             works: storage_works,
             account_works,
+            localized_texts: Vec::new(),
         },
         missing_detail_count,
     })
@@ -2267,9 +4078,69 @@ struct AccountSyncBuild {
     missing_detail_count: usize,
 }
 
-fn cached_work_from_api(work: Work, synced_at: &str) -> Result<CachedWork> {
-    let title = preferred_localized_text(&work.name)
-        .cloned()
+const LOCALIZED_SYNC_LANGUAGES: [Language; 4] = [
+    Language::English,
+    Language::Korean,
+    Language::Taiwanese,
+    Language::Chinese,
+];
+
+#[derive(Default)]
+struct LocalizedTextSync {
+    texts: Vec<WorkLocalizedText>,
+    error: Option<String>,
+}
+
+/// Keeps only text that differs from the Japanese original, so untranslated
+/// works fall back to it. A row is stored even when nothing was returned to
+/// avoid asking again on every sync.
+fn localized_text_from_public_work(
+    work: &Work,
+    localized: Option<&PublicWork>,
+    language: &Language,
+    fetched_at: &str,
+) -> WorkLocalizedText {
+    let translated = |value: Option<&str>, original: Option<&String>| {
+        value
+            .and_then(non_empty_trimmed)
+            .filter(|value| original.map(String::as_str) != Some(*value))
+            .map(str::to_owned)
+    };
+
+    WorkLocalizedText {
+        work_id: work.id.as_ref().to_owned(),
+        language: language.code().to_owned(),
+        title: localized.and_then(|localized| {
+            translated(
+                localized.name.as_deref(),
+                work.name.get(&Language::Japanese),
+            )
+        }),
+        maker_name: localized.and_then(|localized| {
+            translated(
+                localized.maker_name.as_deref(),
+                work.maker.name.get(&Language::Japanese),
+            )
+        }),
+        tags: localized
+            .map(|localized| {
+                localized
+                    .genre_names()
+                    .into_iter()
+                    .map(|name| ProductTag {
+                        class: "genre".to_owned(),
+                        name: name.to_owned(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        fetched_at: fetched_at.to_owned(),
+    }
+}
+
+fn cached_work_from_api(work: Work, synced_at: &str) -> Result<CachedWork> {
+    let title = preferred_localized_text(&work.name)
+        .cloned()
         .unwrap_or_else(|| work.id.to_string());
     let maker_name = preferred_localized_text(&work.maker.name).cloned();
     let age_category = serde_json::to_value(&work.age_category)?
@@ -2380,6 +4251,37 @@ fn cached_work_from_purchase_placeholder(
     })
 }
 
+async fn fetch_local_work_metadata(
+    metadata_source: Option<&dyn LocalWorkMetadataSource>,
+    candidate_ids: &BTreeSet<WorkId>,
+    fetched_at: &str,
+) -> Result<(BTreeMap<String, CachedWork>, Option<String>)> {
+    let mut metadata_by_id = BTreeMap::<String, CachedWork>::new();
+    let mut metadata_error = None;
+
+    if let Some(source) = metadata_source.filter(|_| !candidate_ids.is_empty()) {
+        let ids = candidate_ids.iter().cloned().collect::<Vec<_>>();
+
+        match source.works(&ids).await {
+            Ok(works) => {
+                for work in works {
+                    if !candidate_ids.contains(&work.id) {
+                        continue;
+                    }
+
+                    let cached = cached_work_from_public_api(work, fetched_at)?;
+                    metadata_by_id.insert(cached.work_id.clone(), cached);
+                }
+            }
+            Err(error) => {
+                metadata_error = Some(error.support_message());
+            }
+        }
+    }
+
+    Ok((metadata_by_id, metadata_error))
+}
+
 fn cached_work_from_local_folder(
     work_id: &str,
     folder_name: &str,
@@ -2546,6 +4448,88 @@ fn public_work_raw_json(work: &PublicWork, content_size: Option<u64>) -> Result<
     Ok(serde_json::to_string(&value)?)
 }
 
+fn work_metadata_from_public_work(work: &PublicWork, fetched_at: &str) -> WorkMetadata {
+    let text = |key: &str| {
+        work.extra
+            .get(key)
+            .and_then(Value::as_str)
+            .and_then(non_empty_trimmed)
+    };
+    let mut file_formats = Vec::<String>::new();
+
+    for format in [
+        text("file_type_string").or_else(|| text("file_type")),
+        text("file_type_special"),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if !file_formats.iter().any(|existing| existing == format) {
+            file_formats.push(format.to_owned());
+        }
+    }
+
+    WorkMetadata {
+        work_id: work.id.as_ref().to_owned(),
+        description: text("intro_s").or_else(|| text("intro")).map(str::to_owned),
+        series: text("title_id").map(|id| ProductSeries {
+            id: id.to_owned(),
+            name: text("title_name").unwrap_or(id).to_owned(),
+        }),
+        genres: public_work_genres(work),
+        sample_image_urls: work
+            .extra
+            .get("image_samples")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .filter_map(normalize_public_url)
+            .collect(),
+        file_formats,
+        track_count: work
+            .extra
+            .get("track_list")
+            .and_then(Value::as_array)
+            .and_then(|tracks| u32::try_from(tracks.len()).ok()),
+        fetched_at: fetched_at.to_owned(),
+    }
+}
+
+fn public_work_genres(work: &PublicWork) -> Vec<ProductGenre> {
+    let mut genres = Vec::<ProductGenre>::new();
+
+    for genre in work
+        .extra
+        .get("genres")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let id = match genre.get("id") {
+            Some(Value::String(id)) => id.trim().to_owned(),
+            Some(Value::Number(id)) => id.to_string(),
+            _ => continue,
+        };
+        let name = genre
+            .get("name")
+            .or_else(|| genre.get("name_base"))
+            .and_then(Value::as_str)
+            .and_then(non_empty_trimmed);
+
+        if let Some(name) = name.filter(|_| !id.is_empty()) {
+            if !genres.iter().any(|existing| existing.id == id) {
+                genres.push(ProductGenre {
+                    id,
+                    name: name.to_owned(),
+                });
+            }
+        }
+    }
+
+    genres
+}
+
 fn public_work_tags(value: &Value) -> Vec<Value> {
     let mut tags = BTreeSet::<(String, String)>::new();
 
@@ -2647,10 +4631,68 @@ fn datetime_to_string(value: DateTime<Utc>) -> String {
     value.to_rfc3339_opts(SecondsFormat::Millis, true)
 }
 
+fn archive_name_encoding_storage_value(name_encoding: ArchiveNameEncoding) -> Option<&'static str> {
+    match name_encoding {
+        ArchiveNameEncoding::Auto => None,
+        ArchiveNameEncoding::Utf8 => Some("utf8"),
+        ArchiveNameEncoding::Cp932 => Some("cp932"),
+        ArchiveNameEncoding::Cp437 => Some("cp437"),
+    }
+}
+
+fn archive_name_encoding_from_storage(value: &str) -> ArchiveNameEncoding {
+    match value {
+        "utf8" => ArchiveNameEncoding::Utf8,
+        "cp932" => ArchiveNameEncoding::Cp932,
+        "cp437" => ArchiveNameEncoding::Cp437,
+        _ => ArchiveNameEncoding::Auto,
+    }
+}
+
+/// Points the paths recorded while unpacking in the staging folder at the library folder.
+fn rebase_nested_archives(
+    nested: Vec<NestedArchive>,
+    staging_dir: &Path,
+    final_dir: &Path,
+) -> Vec<NestedArchive> {
+    let rebase = |path: PathBuf| match path.strip_prefix(staging_dir) {
+        Ok(relative) => final_dir.join(relative),
+        Err(_) => path,
+    };
+
+    nested
+        .into_iter()
+        .map(|archive| NestedArchive {
+            sources: archive.sources.into_iter().map(rebase).collect(),
+            outcome: match archive.outcome {
+                NestedArchiveOutcome::Extracted(extraction) => {
+                    NestedArchiveOutcome::Extracted(dm_archive::ArchiveExtraction {
+                        output_dir: rebase(extraction.output_dir),
+                        extracted_paths: extraction
+                            .extracted_paths
+                            .into_iter()
+                            .map(rebase)
+                            .collect(),
+                        removed_sources: extraction
+                            .removed_sources
+                            .into_iter()
+                            .map(rebase)
+                            .collect(),
+                        nested: rebase_nested_archives(extraction.nested, staging_dir, final_dir),
+                        ..extraction
+                    })
+                }
+                outcome => outcome,
+            },
+        })
+        .collect()
+}
+
 fn unpack_policy_storage_value(policy: UnpackPolicy) -> &'static str {
     match policy {
         UnpackPolicy::KeepArchives => "keep_archives",
         UnpackPolicy::UnpackWhenRecognized => "unpack_when_recognized",
+        UnpackPolicy::UnpackAndKeepArchives => "unpack_and_keep_archives",
     }
 }
 
@@ -2753,6 +4795,62 @@ fn serial_information_content(
     content
 }
 
+/// Reads back the serial numbers a serial information file in `target_dir` recorded for
+/// `work_id`, so a later unpack can still try them as archive passwords.
+async fn read_serial_information(target_dir: &Path, work_id: &str) -> Result<Vec<SerialNumber>> {
+    let mut paths = vec![target_dir.join(SERIAL_INFORMATION_FILE_NAME)];
+    paths.extend(
+        (1..100).map(|index| {
+            target_dir.join(format!("{SERIAL_INFORMATION_NUMBERED_PREFIX}{index}.txt"))
+        }),
+    );
+
+    for path in paths {
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => {
+                if let Some(serial_numbers) = parse_serial_information(&content, work_id) {
+                    return Ok(serial_numbers);
+                }
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+fn parse_serial_information(content: &str, work_id: &str) -> Option<Vec<SerialNumber>> {
+    let mut lines = content.lines();
+
+    if lines.next() != Some(SERIAL_INFORMATION_MARKER) {
+        return None;
+    }
+
+    let mut serial_numbers = Vec::new();
+    let mut matches_work = false;
+    let mut label = None;
+
+    for line in lines {
+        if let Some(value) = line.strip_prefix("Work ID: ") {
+            matches_work = value == clean_serial_text(work_id);
+        } else if let Some(value) = line.strip_prefix("   Value: ") {
+            if let Some(label) = label.take() {
+                serial_numbers.push(SerialNumber {
+                    label,
+                    value: value.to_owned(),
+                });
+            }
+        } else if let Some((index, rest)) = line.split_once(". ") {
+            if !index.is_empty() && index.bytes().all(|byte| byte.is_ascii_digit()) {
+                label = Some(rest.to_owned());
+            }
+        }
+    }
+
+    matches_work.then_some(serial_numbers)
+}
+
 fn clean_serial_text(value: &str) -> String {
     value
         .split_whitespace()
@@ -2798,6 +4896,135 @@ async fn remove_existing_download_path(path: &Path, allowed_roots: &[&Path]) ->
     Ok(())
 }
 
+/// Bytes a download needs on each volume.
+///
+/// Archives are assumed to unpack to about their own size; the real uncompressed total is checked
+/// again before unpacking. Moving into the library is a rename when both roots share a volume.
+fn disk_space_requirements(
+    roots: DiskSpaceRoots<'_>,
+    archive_bytes: u64,
+    staged_bytes: u64,
+    unpacks: bool,
+    same_volume: bool,
+) -> Vec<(PathBuf, u64)> {
+    let unpacked_bytes = if unpacks { archive_bytes } else { 0 };
+    let download_bytes = archive_bytes
+        .saturating_sub(staged_bytes)
+        .saturating_add(unpacked_bytes);
+
+    if same_volume {
+        return vec![(roots.download_root.to_path_buf(), download_bytes)];
+    }
+
+    let library_bytes = if unpacks {
+        unpacked_bytes
+    } else {
+        archive_bytes
+    };
+
+    vec![
+        (roots.download_root.to_path_buf(), download_bytes),
+        (roots.library_root.to_path_buf(), library_bytes),
+    ]
+}
+
+fn check_disk_space(
+    roots: DiskSpaceRoots<'_>,
+    archive_bytes: u64,
+    staged_bytes: u64,
+    unpacks: bool,
+) -> Result<Vec<DiskSpaceCheck>> {
+    let same_volume = dm_download::same_volume(roots.download_root, roots.library_root)?;
+
+    disk_space_requirements(roots, archive_bytes, staged_bytes, unpacks, same_volume)
+        .into_iter()
+        .map(|(path, required_bytes)| {
+            Ok(DiskSpaceCheck {
+                available_bytes: dm_download::available_space(&path)?,
+                path,
+                required_bytes,
+            })
+        })
+        .collect()
+}
+
+async fn directory_has_files(path: &Path) -> Result<bool> {
+    Ok(path.is_dir() && directory_size(path).await? > 0)
+}
+
+async fn directory_size(path: &Path) -> Result<u64> {
+    let mut total = 0u64;
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error.into()),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let file_type = entry.file_type().await?;
+
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                total = total.saturating_add(entry.metadata().await?.len());
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+const PREVIOUS_VERSIONS_DIR: &str = ".previous-versions";
+const PREVIOUS_VERSION_MARKER: &str = ".previous-";
+
+/// Whether a library folder holds data set aside by the app, such as replaced versions, rather
+/// than a work.
+fn is_set_aside_dir_name(name: &str) -> bool {
+    name.starts_with('.') || name.contains(PREVIOUS_VERSION_MARKER)
+}
+
+async fn set_aside_previous_version(
+    path: &Path,
+    library_root: &Path,
+    policy: PreviousVersionPolicy,
+) -> Result<Option<PathBuf>> {
+    if policy == PreviousVersionPolicy::Discard {
+        remove_existing_download_path(path, &[library_root]).await?;
+        return Ok(None);
+    }
+
+    if !path.try_exists()? {
+        return Ok(None);
+    }
+
+    let canonical_path = path.canonicalize()?;
+    let canonical_root = library_root.canonicalize()?;
+
+    if !path_is_download_child_of_any_root(&canonical_path, std::slice::from_ref(&canonical_root)) {
+        return Err(LibraryError::DownloadPathOutsideRoots(canonical_path));
+    }
+
+    let name = canonical_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stamp = Utc::now().format("%Y%m%dT%H%M%S");
+    let destination = match policy {
+        PreviousVersionPolicy::Keep => {
+            canonical_path.with_file_name(format!("{name}{PREVIOUS_VERSION_MARKER}{stamp}"))
+        }
+        _ => canonical_root
+            .join(PREVIOUS_VERSIONS_DIR)
+            .join(format!("{name}-{stamp}")),
+    };
+
+    move_downloaded_work_dir(&canonical_path, &destination).await?;
+    Ok(Some(destination))
+}
+
 fn canonicalize_existing_directory(path: &Path) -> Result<PathBuf> {
     let canonical_path = path.canonicalize()?;
     let metadata = std::fs::metadata(&canonical_path)?;
@@ -2809,12 +5036,154 @@ fn canonicalize_existing_directory(path: &Path) -> Result<PathBuf> {
     Ok(canonical_path)
 }
 
+/// Archives a download left in its staging folder, in name order.
+async fn staged_archive_files(staging_dir: &Path) -> Result<Vec<DownloadedFile>> {
+    let mut entries = match tokio::fs::read_dir(staging_dir).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+    let mut files = Vec::new();
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let metadata = entry.metadata().await?;
+
+        if !metadata.is_file() || !is_importable_archive(&path) {
+            continue;
+        }
+
+        files.push(DownloadedFile {
+            file_name: entry.file_name().to_string_lossy().into_owned(),
+            path,
+            bytes_written: metadata.len(),
+            resumed_from: metadata.len(),
+            discarded_partial: None,
+        });
+    }
+
+    files.sort_by(|left, right| left.file_name.cmp(&right.file_name));
+    Ok(files)
+}
+
+fn is_importable_archive(path: &Path) -> bool {
+    ["zip", "7z", "exe", "rar"]
+        .into_iter()
+        .any(|extension| path_has_extension(path, extension))
+        || dm_archive::is_archive_volume(path)
+}
+
+fn path_has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .and_then(|value| value.to_str())
+        .is_some_and(|value| value.eq_ignore_ascii_case(extension))
+}
+
+async fn archive_unpacked_size(plan: dm_archive::ArchivePlan) -> Result<Option<u64>> {
+    tokio::task::spawn_blocking(move || dm_archive::uncompressed_size(&plan))
+        .await
+        .map_err(|err| LibraryError::Io(std::io::Error::other(err)))?
+        .map_err(|err| LibraryError::Download(err.into()))
+}
+
+/// Extracts into a library folder that must not exist yet, removing it again if extraction fails.
+fn extract_local_archive(
+    plan: &dm_archive::ArchivePlan,
+    target_path: &Path,
+    options: dm_archive::ArchiveExtractOptions,
+) -> Result<()> {
+    if target_path.try_exists()? {
+        return Err(LibraryError::DownloadTargetExists(target_path.to_owned()));
+    }
+
+    let archive_bytes = plan
+        .source_files()
+        .into_iter()
+        .map(|path| std::fs::metadata(path).map(|metadata| metadata.len()))
+        .sum::<std::io::Result<u64>>()?;
+    let required_bytes = dm_archive::uncompressed_size(plan)
+        .map_err(|err| LibraryError::Download(err.into()))?
+        .unwrap_or(archive_bytes);
+    let space_path = target_path.parent().unwrap_or(target_path);
+    let available_bytes = dm_download::available_space(space_path)?;
+
+    if available_bytes < required_bytes {
+        return Err(LibraryError::InsufficientDiskSpace {
+            path: space_path.to_owned(),
+            required_bytes,
+            available_bytes,
+        });
+    }
+
+    dm_archive::extract_archive_plan(plan, target_path, options).map_err(|err| {
+        std::fs::remove_dir_all(target_path).ok();
+        LibraryError::Download(err.into())
+    })?;
+
+    Ok(())
+}
+
 fn path_is_download_child_of_any_root(path: &Path, roots: &[PathBuf]) -> bool {
     roots
         .iter()
         .any(|root| path != root.as_path() && path.starts_with(root))
 }
 
+async fn verify_work_files(
+    work_dir: &Path,
+    manifest: Vec<dm_download::FileManifestEntry>,
+    job_cancellation: Option<&CancellationToken>,
+) -> Result<dm_download::ManifestVerification> {
+    if !work_dir.is_dir() {
+        return Ok(dm_download::ManifestVerification {
+            missing: manifest
+                .into_iter()
+                .map(|entry| entry.relative_path)
+                .collect(),
+            ..dm_download::ManifestVerification::default()
+        });
+    }
+
+    let cancellation = dm_download::CancellationToken::new();
+    let _cancellation_forwarder = forward_download_cancellation(job_cancellation, &cancellation);
+    let root = work_dir.to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        dm_download::verify_file_manifest(
+            &root,
+            &manifest,
+            &[MANIFEST_SIDECAR_FILE_NAME],
+            &cancellation,
+        )
+    })
+    .await
+    .map_err(|err| LibraryError::Io(std::io::Error::other(err)))?
+    .map_err(|err| match err {
+        dm_download::DownloadError::Cancelled => LibraryError::Cancelled,
+        other => other.into(),
+    })
+}
+
+fn manifest_sidecar_content(
+    work_id: &str,
+    entries: &[WorkFileManifestEntry],
+    recorded_at: &str,
+) -> Value {
+    json!({
+        "workId": work_id,
+        "recordedAt": recorded_at,
+        "algorithm": "sha256",
+        "files": entries
+            .iter()
+            .map(|entry| json!({
+                "path": entry.relative_path,
+                "size": entry.size_bytes,
+                "sha256": entry.sha256,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
 async fn move_downloaded_work_dir(source: &Path, destination: &Path) -> Result<()> {
     if destination.try_exists()? {
         return Err(LibraryError::DownloadTargetExists(
@@ -2844,6 +5213,77 @@ async fn move_downloaded_work_dir(source: &Path, destination: &Path) -> Result<(
     }
 }
 
+/// Moves the kept archives of an unpacked download into the work's vault folder. An earlier copy
+/// is set aside under `policy` first, the way the library folder is, and put back when the
+/// archives can't all be moved.
+async fn keep_archives_in_vault(
+    archives: &[PathBuf],
+    vault_dir: &Path,
+    vault_root: &Path,
+    policy: PreviousVersionPolicy,
+) -> Result<()> {
+    tokio::fs::create_dir_all(vault_root).await?;
+
+    // Discarding waits until the new archives are in, so a failure can still restore the old ones.
+    let previous_vault_dir = match policy {
+        PreviousVersionPolicy::Discard => {
+            let name = vault_dir
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let replaced = vault_dir.with_file_name(format!(".{name}.replaced"));
+
+            remove_existing_download_path(&replaced, &[vault_root]).await?;
+            if vault_dir.try_exists()? {
+                tokio::fs::rename(vault_dir, &replaced).await?;
+                Some(replaced)
+            } else {
+                None
+            }
+        }
+        policy => set_aside_previous_version(vault_dir, vault_root, policy).await?,
+    };
+
+    if let Err(error) = move_archives_to_vault(archives, vault_dir).await {
+        for archive in archives {
+            if let Some(file_name) = archive.file_name() {
+                let _ = tokio::fs::rename(vault_dir.join(file_name), archive).await;
+            }
+        }
+        let _ = tokio::fs::remove_dir(vault_dir).await;
+        if let Some(previous_vault_dir) = &previous_vault_dir {
+            let _ = tokio::fs::rename(previous_vault_dir, vault_dir).await;
+        }
+        return Err(error);
+    }
+
+    if let (PreviousVersionPolicy::Discard, Some(previous_vault_dir)) =
+        (policy, &previous_vault_dir)
+    {
+        remove_existing_download_path(previous_vault_dir, &[vault_root]).await?;
+    }
+
+    Ok(())
+}
+
+async fn move_archives_to_vault(archives: &[PathBuf], vault_dir: &Path) -> Result<()> {
+    tokio::fs::create_dir_all(vault_dir).await?;
+
+    for archive in archives {
+        let Some(file_name) = archive.file_name() else {
+            continue;
+        };
+        let destination = vault_dir.join(file_name);
+
+        if tokio::fs::rename(archive, &destination).await.is_err() {
+            tokio::fs::copy(archive, &destination).await?;
+            tokio::fs::remove_file(archive).await?;
+        }
+    }
+
+    Ok(())
+}
+
 fn copy_dir_recursively(source: &Path, destination: &Path) -> std::io::Result<()> {
     if destination.exists() {
         return Err(std::io::Error::new(
@@ -2967,7 +5407,6 @@ mod tests {
         PublicWorkImage, PublicWorkImageObject, WorkKind, WorkThumbnail,
     };
     use dm_credentials::InMemoryCredentialStore;
-    use dm_download::{DownloadPhase, DownloadedFile};
     use dm_storage::{ProductSort, SyncRunStatus, WorkDownloadStatus};
     use std::{
         sync::{
@@ -3172,6 +5611,7 @@ mod tests {
                 file_kind: Some(DownloadFileKind::Direct),
                 bytes_received: 10,
                 bytes_total: Some(10),
+                retry: None,
             });
 
             Ok(DownloadedWork {
@@ -3182,6 +5622,7 @@ mod tests {
                     path,
                     bytes_written: 10,
                     resumed_from: 0,
+                    discarded_partial: None,
                 }],
                 archive_extraction: None,
             })
@@ -3189,22 +5630,16 @@ mod tests {
     }
 
     #[derive(Debug, Clone, Default)]
-    struct SerialDownloadSource;
+    struct SidecarBlockingDownloadSource;
 
     #[async_trait]
-    impl WorkDownloadSource for SerialDownloadSource {
+    impl WorkDownloadSource for SidecarBlockingDownloadSource {
         async fn login(&self, credentials: &Credentials) -> Result<()> {
             FakeDownloadSource.login(credentials).await
         }
 
         async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan> {
-            let mut plan = FakeDownloadSource.download_plan(work_id).await?;
-            plan.serial_numbers = vec![SerialNumber {
-                label: "Serial number".to_owned(),
-                value: "ABC-123-SECRET".to_owned(),
-            }];
-
-            Ok(plan)
+            FakeDownloadSource.download_plan(work_id).await
         }
 
         async fn download_file_metadata(
@@ -3224,47 +5659,304 @@ mod tests {
             cancellation: &dm_download::CancellationToken,
             progress_sink: &mut (dyn FnMut(DownloadProgress) + Send),
         ) -> Result<DownloadedWork> {
-            FakeDownloadSource
+            let downloaded = FakeDownloadSource
                 .download_files(job, plan, cancellation, progress_sink)
-                .await
-        }
-    }
-
-    #[derive(Debug, Clone)]
-    struct WaitingDownloadSource {
-        entered_download: Arc<AtomicBool>,
-    }
-
-    impl WaitingDownloadSource {
-        fn new() -> Self {
-            Self {
-                entered_download: Arc::new(AtomicBool::new(false)),
-            }
-        }
+                .await?;
+            tokio::fs::create_dir_all(downloaded.target_dir.join(MANIFEST_SIDECAR_FILE_NAME))
+                .await?;
 
-        fn entered_download(&self) -> bool {
-            self.entered_download.load(Ordering::SeqCst)
+            Ok(downloaded)
         }
     }
 
+    #[derive(Debug, Clone, Default)]
+    struct UnpackedArchiveDownloadSource;
+
     #[async_trait]
-    impl WorkDownloadSource for WaitingDownloadSource {
+    impl WorkDownloadSource for UnpackedArchiveDownloadSource {
         async fn login(&self, credentials: &Credentials) -> Result<()> {
-            assert_eq!(credentials.username, "user@example.test");
-            assert_eq!(credentials.password, "secret");
-            Ok(())
+            FakeDownloadSource.login(credentials).await
         }
 
         async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan> {
-            Ok(DownloadPlan {
-                work_id: work_id.clone(),
-                files: vec![DownloadFile {
-                    kind: DownloadFileKind::Direct,
-                    stream_request: DownloadStreamRequest {
-                        url: Url::parse(
-                            "https://download.example.test/get/=/file/RJ000001.zip/_/1",
-                        )
-                        .unwrap(),
+            FakeDownloadSource.download_plan(work_id).await
+        }
+
+        async fn download_file_metadata(
+            &self,
+            file_index: usize,
+            file: &DownloadFile,
+        ) -> Result<DownloadFileMetadata> {
+            FakeDownloadSource
+                .download_file_metadata(file_index, file)
+                .await
+        }
+
+        async fn download_files(
+            &self,
+            job: &DownloadJobRequest,
+            _plan: &DownloadPlan,
+            _cancellation: &dm_download::CancellationToken,
+            _progress_sink: &mut (dyn FnMut(DownloadProgress) + Send),
+        ) -> Result<DownloadedWork> {
+            let target_dir = job.target_root.join(job.work_id.as_ref());
+            let archive_path = target_dir.join("RJ000001.zip");
+            let extracted_path = target_dir.join("RJ000001.txt");
+            tokio::fs::create_dir_all(&target_dir).await?;
+            tokio::fs::write(&archive_path, b"archive").await?;
+            tokio::fs::write(&extracted_path, b"downloaded").await?;
+
+            Ok(DownloadedWork {
+                work_id: job.work_id.clone(),
+                target_dir: target_dir.clone(),
+                files: vec![DownloadedFile {
+                    file_name: "RJ000001.zip".to_owned(),
+                    path: archive_path,
+                    bytes_written: 7,
+                    resumed_from: 0,
+                    discarded_partial: None,
+                }],
+                archive_extraction: Some(dm_archive::ArchiveExtraction {
+                    output_dir: target_dir,
+                    extracted_paths: vec![extracted_path],
+                    removed_sources: Vec::new(),
+                    name_encoding: None,
+                    fallback_names: Vec::new(),
+                    nested: Vec::new(),
+                }),
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct OversizedDownloadSource;
+
+    #[async_trait]
+    impl WorkDownloadSource for OversizedDownloadSource {
+        async fn login(&self, credentials: &Credentials) -> Result<()> {
+            FakeDownloadSource.login(credentials).await
+        }
+
+        async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan> {
+            FakeDownloadSource.download_plan(work_id).await
+        }
+
+        async fn download_file_metadata(
+            &self,
+            file_index: usize,
+            file: &DownloadFile,
+        ) -> Result<DownloadFileMetadata> {
+            let mut metadata = FakeDownloadSource
+                .download_file_metadata(file_index, file)
+                .await?;
+            metadata.expected_size = Some(u64::MAX / 4);
+
+            Ok(metadata)
+        }
+
+        async fn download_files(
+            &self,
+            _job: &DownloadJobRequest,
+            _plan: &DownloadPlan,
+            _cancellation: &dm_download::CancellationToken,
+            _progress_sink: &mut (dyn FnMut(DownloadProgress) + Send),
+        ) -> Result<DownloadedWork> {
+            panic!("download should be refused before it starts");
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct SerialDownloadSource;
+
+    #[async_trait]
+    impl WorkDownloadSource for SerialDownloadSource {
+        async fn login(&self, credentials: &Credentials) -> Result<()> {
+            FakeDownloadSource.login(credentials).await
+        }
+
+        async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan> {
+            let mut plan = FakeDownloadSource.download_plan(work_id).await?;
+            plan.serial_numbers = vec![SerialNumber {
+                label: "Serial number".to_owned(),
+                value: "ABC-123-SECRET".to_owned(),
+            }];
+
+            Ok(plan)
+        }
+
+        async fn download_file_metadata(
+            &self,
+            file_index: usize,
+            file: &DownloadFile,
+        ) -> Result<DownloadFileMetadata> {
+            FakeDownloadSource
+                .download_file_metadata(file_index, file)
+                .await
+        }
+
+        async fn download_files(
+            &self,
+            job: &DownloadJobRequest,
+            plan: &DownloadPlan,
+            cancellation: &dm_download::CancellationToken,
+            progress_sink: &mut (dyn FnMut(DownloadProgress) + Send),
+        ) -> Result<DownloadedWork> {
+            FakeDownloadSource
+                .download_files(job, plan, cancellation, progress_sink)
+                .await
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct NestedArchiveDownloadSource;
+
+    #[async_trait]
+    impl WorkDownloadSource for NestedArchiveDownloadSource {
+        async fn login(&self, credentials: &Credentials) -> Result<()> {
+            FakeDownloadSource.login(credentials).await
+        }
+
+        async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan> {
+            FakeDownloadSource.download_plan(work_id).await
+        }
+
+        async fn download_file_metadata(
+            &self,
+            file_index: usize,
+            file: &DownloadFile,
+        ) -> Result<DownloadFileMetadata> {
+            FakeDownloadSource
+                .download_file_metadata(file_index, file)
+                .await
+        }
+
+        async fn download_files(
+            &self,
+            job: &DownloadJobRequest,
+            _plan: &DownloadPlan,
+            _cancellation: &dm_download::CancellationToken,
+            _progress_sink: &mut (dyn FnMut(DownloadProgress) + Send),
+        ) -> Result<DownloadedWork> {
+            let target_dir = job.target_root.join(job.work_id.as_ref());
+            let archive = target_dir.join("RJ000001.zip");
+            let inner = target_dir.join("EN.zip");
+            tokio::fs::create_dir_all(&target_dir).await?;
+            write_test_zip(&inner, &[("readme.txt", b"english")]);
+            let inner_bytes = std::fs::read(&inner)?;
+            std::fs::remove_file(&inner)?;
+            write_test_zip(&archive, &[("EN.zip", &inner_bytes)]);
+            let files = vec![DownloadedFile {
+                file_name: "RJ000001.zip".to_owned(),
+                path: archive,
+                bytes_written: 0,
+                resumed_from: 0,
+                discarded_partial: None,
+            }];
+            let archive_extraction = dm_download::unpack_downloaded_files(
+                &files,
+                &target_dir,
+                job.unpack_policy,
+                dm_archive::ArchiveExtractOptions {
+                    nested: job.nested_archives,
+                    ..dm_archive::ArchiveExtractOptions::default()
+                },
+            )?;
+
+            Ok(DownloadedWork {
+                work_id: job.work_id.clone(),
+                target_dir,
+                files,
+                archive_extraction,
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct PasswordProtectedDownloadSource;
+
+    #[async_trait]
+    impl WorkDownloadSource for PasswordProtectedDownloadSource {
+        async fn login(&self, credentials: &Credentials) -> Result<()> {
+            FakeDownloadSource.login(credentials).await
+        }
+
+        async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan> {
+            SerialDownloadSource.download_plan(work_id).await
+        }
+
+        async fn download_file_metadata(
+            &self,
+            file_index: usize,
+            file: &DownloadFile,
+        ) -> Result<DownloadFileMetadata> {
+            FakeDownloadSource
+                .download_file_metadata(file_index, file)
+                .await
+        }
+
+        async fn download_files(
+            &self,
+            job: &DownloadJobRequest,
+            _plan: &DownloadPlan,
+            _cancellation: &dm_download::CancellationToken,
+            _progress_sink: &mut (dyn FnMut(DownloadProgress) + Send),
+        ) -> Result<DownloadedWork> {
+            let target_dir = job.target_root.join(job.work_id.as_ref());
+            let archive = target_dir.join("RJ000001.zip");
+            tokio::fs::create_dir_all(&target_dir).await?;
+            write_test_zip(&archive, &[("RJ000001.txt", b"unlocked")]);
+            let passwords = job
+                .archive_passwords
+                .iter()
+                .map(ArchivePassword::as_str)
+                .collect::<Vec<_>>();
+            assert_eq!(passwords, ["ABC-123-SECRET"]);
+
+            Err(
+                dm_download::DownloadError::Archive(dm_archive::ArchiveError::WrongPassword {
+                    archive,
+                    tried: passwords.len(),
+                })
+                .into(),
+            )
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct WaitingDownloadSource {
+        entered_download: Arc<AtomicBool>,
+    }
+
+    impl WaitingDownloadSource {
+        fn new() -> Self {
+            Self {
+                entered_download: Arc::new(AtomicBool::new(false)),
+            }
+        }
+
+        fn entered_download(&self) -> bool {
+            self.entered_download.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl WorkDownloadSource for WaitingDownloadSource {
+        async fn login(&self, credentials: &Credentials) -> Result<()> {
+            assert_eq!(credentials.username, "user@example.test");
+            assert_eq!(credentials.password, "secret");
+            Ok(())
+        }
+
+        async fn download_plan(&self, work_id: &WorkId) -> Result<DownloadPlan> {
+            Ok(DownloadPlan {
+                work_id: work_id.clone(),
+                files: vec![DownloadFile {
+                    kind: DownloadFileKind::Direct,
+                    stream_request: DownloadStreamRequest {
+                        url: Url::parse(
+                            "https://download.example.test/get/=/file/RJ000001.zip/_/1",
+                        )
+                        .unwrap(),
                     },
                 }],
                 serial_numbers: Vec::new(),
@@ -3407,7 +6099,7 @@ mod tests {
             login_name: Some("user@example.test".to_owned()),
             password: Some("secret".to_owned()),
             remember_password,
-            enabled: true,
+            ..SaveAccountRequest::new("Account A")
         }
     }
 
@@ -3530,6 +6222,40 @@ mod tests {
         }
     }
 
+    fn write_test_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+
+        for (name, content) in entries {
+            zip.start_file(*name, options).unwrap();
+            std::io::Write::write_all(&mut zip, content).unwrap();
+        }
+
+        zip.finish().unwrap();
+    }
+
+    fn directory_snapshot(root: &Path) -> Vec<(PathBuf, Option<Vec<u8>>)> {
+        let mut snapshot = Vec::new();
+        let mut pending = vec![root.to_path_buf()];
+
+        while let Some(dir) = pending.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+
+                if path.is_dir() {
+                    snapshot.push((path.clone(), None));
+                    pending.push(path);
+                } else {
+                    snapshot.push((path.clone(), Some(std::fs::read(&path).unwrap())));
+                }
+            }
+        }
+
+        snapshot.sort();
+        snapshot
+    }
+
     fn test_dir(name: &str) -> PathBuf {
         let unique = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -3625,6 +6351,59 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn saves_account_proxy_with_password_in_credential_store() -> Result<()> {
+        let library = migrated_library().await?;
+
+        let account = library
+            .save_account(SaveAccountRequest {
+                proxy_url: Some("socks5://127.0.0.1:1080".to_owned()),
+                proxy_username: Some("proxy-user".to_owned()),
+                proxy_password: Some("proxy-secret".to_owned()),
+                ..save_account_request(true)
+            })
+            .await?;
+        let proxy = library.account_proxy(&account)?.expect("proxy");
+
+        assert_eq!(proxy.url.as_str(), "socks5://127.0.0.1:1080");
+        assert_eq!(proxy.username.as_deref(), Some("proxy-user"));
+        assert_eq!(proxy.password.as_deref(), Some("proxy-secret"));
+
+        let account = library
+            .save_account(SaveAccountRequest {
+                proxy_url: Some("socks5://127.0.0.1:1080".to_owned()),
+                proxy_username: Some("proxy-user".to_owned()),
+                ..save_account_request(true)
+            })
+            .await?;
+
+        assert_eq!(
+            library
+                .account_proxy(&account)?
+                .and_then(|proxy| proxy.password),
+            Some("proxy-secret".to_owned())
+        );
+
+        let account = library.save_account(save_account_request(true)).await?;
+
+        assert_eq!(account.proxy, None);
+        assert!(library
+            .credentials
+            .load_password(&CredentialRef::account_proxy_password(&account.id)?)?
+            .is_none());
+        assert!(matches!(
+            library
+                .save_account(SaveAccountRequest {
+                    proxy_url: Some("ftp://127.0.0.1".to_owned()),
+                    ..save_account_request(true)
+                })
+                .await,
+            Err(LibraryError::Api(DmApiError::UnsupportedProxy { .. }))
+        ));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn removes_account_and_saved_credential() -> Result<()> {
         let storage = Storage::open_in_memory().await?;
@@ -3790,12 +6569,182 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn download_cancellation_reaches_download_source_without_progress() -> Result<()> {
+    async fn reports_nested_archives_at_their_library_paths() -> Result<()> {
         let library = migrated_library().await?;
-        let root = test_dir("download-cancellation-forwarding");
+        let root = test_dir("download-nested-archives");
         let library_root = root.join("library");
         let download_root = root.join("downloads");
-        let source = WaitingDownloadSource::new();
+        let work_dir = library_root.join("RJ000001");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+
+        let report = library
+            .download_work_with_source(
+                WorkDownloadRequest {
+                    nested_archives: Some(NestedArchiveOptions::default()),
+                    ..WorkDownloadRequest::new("RJ000001", &library_root, &download_root)
+                },
+                &NestedArchiveDownloadSource,
+            )
+            .await?;
+
+        assert_eq!(
+            std::fs::read(work_dir.join("EN").join("readme.txt"))?,
+            b"english"
+        );
+        assert_eq!(report.nested_archives.len(), 1);
+        assert_eq!(report.nested_archives[0].sources, [work_dir.join("EN.zip")]);
+        let NestedArchiveOutcome::Extracted(extraction) = &report.nested_archives[0].outcome else {
+            panic!("expected EN.zip to be extracted");
+        };
+        assert_eq!(extraction.output_dir, work_dir.join("EN"));
+        assert_eq!(
+            extraction.extracted_paths,
+            [work_dir.join("EN").join("readme.txt")]
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn resumes_password_failed_download_at_the_unpack_step() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("download-resume-unpack");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        let staged_archive = download_root.join("RJ000001").join("RJ000001.zip");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+
+        let err = library
+            .download_work_with_source(
+                WorkDownloadRequest::new("RJ000001", &library_root, &download_root),
+                &PasswordProtectedDownloadSource,
+            )
+            .await
+            .expect_err("archive password should be rejected");
+        let state = library.storage.work_download_state("RJ000001").await?;
+
+        assert!(err.is_archive_password_error());
+        assert_eq!(state.status, WorkDownloadStatus::Failed);
+        assert_eq!(state.error_code.as_deref(), Some("archive_wrong_password"));
+        assert!(staged_archive.exists());
+        assert!(download_root
+            .join("RJ000001")
+            .join(SERIAL_INFORMATION_FILE_NAME)
+            .exists());
+
+        library.set_product_archive_password("RJ000001", Some("letmein"))?;
+        assert!(library.product_has_archive_password("RJ000001")?);
+        assert_eq!(
+            library
+                .archive_passwords("RJ000001", &[])?
+                .iter()
+                .map(ArchivePassword::as_str)
+                .collect::<Vec<_>>(),
+            ["letmein"]
+        );
+        let report = library
+            .resume_work_unpack(WorkDownloadRequest::new(
+                "RJ000001",
+                &library_root,
+                &download_root,
+            ))
+            .await?;
+
+        assert_eq!(report.download_state.status, WorkDownloadStatus::Downloaded);
+        assert_eq!(
+            std::fs::read(library_root.join("RJ000001").join("RJ000001.txt"))?,
+            b"unlocked"
+        );
+        assert!(library_root
+            .join("RJ000001")
+            .join(SERIAL_INFORMATION_FILE_NAME)
+            .exists());
+
+        library.set_product_archive_password("RJ000001", None)?;
+        assert!(!library.product_has_archive_password("RJ000001")?);
+        let err = library
+            .resume_work_unpack(WorkDownloadRequest::new(
+                "RJ000001",
+                &library_root,
+                &download_root,
+            ))
+            .await
+            .expect_err("nothing is staged after a finished unpack");
+        assert!(matches!(err, LibraryError::NoStagedDownload(_)));
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn resumed_unpack_tries_the_staged_serial_numbers() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("download-resume-unpack-serials");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        let staging_dir = download_root.join("RJ000001");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+
+        library
+            .download_work_with_source(
+                WorkDownloadRequest::new("RJ000001", &library_root, &download_root),
+                &PasswordProtectedDownloadSource,
+            )
+            .await
+            .expect_err("archive password should be rejected");
+        let serial_numbers = vec![SerialNumber {
+            label: "Archive password".to_owned(),
+            value: "letmein".to_owned(),
+        }];
+        write_serial_information_file(&staging_dir, "RJ000001", &serial_numbers, "now").await?;
+
+        assert_eq!(
+            read_serial_information(&staging_dir, "RJ000001").await?,
+            serial_numbers
+        );
+        assert!(read_serial_information(&staging_dir, "RJ000002")
+            .await?
+            .is_empty());
+
+        let report = library
+            .resume_work_unpack(WorkDownloadRequest::new(
+                "RJ000001",
+                &library_root,
+                &download_root,
+            ))
+            .await?;
+
+        assert!(!library.product_has_archive_password("RJ000001")?);
+        assert_eq!(report.download_state.status, WorkDownloadStatus::Downloaded);
+        assert_eq!(
+            std::fs::read(library_root.join("RJ000001").join("RJ000001.txt"))?,
+            b"unlocked"
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn download_cancellation_reaches_download_source_without_progress() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("download-cancellation-forwarding");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        let source = WaitingDownloadSource::new();
         let cancellation = CancellationToken::new();
         library.save_account(save_account_request(true)).await?;
         library
@@ -3837,6 +6786,65 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn refuses_download_that_does_not_fit_on_disk() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("download-disk-space");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+
+        let err = library
+            .download_work_with_source(
+                WorkDownloadRequest::new("RJ000001", &library_root, &download_root),
+                &OversizedDownloadSource,
+            )
+            .await
+            .expect_err("download should not fit");
+        let state = library.storage.work_download_state("RJ000001").await?;
+
+        let LibraryError::InsufficientDiskSpace {
+            path,
+            required_bytes,
+            available_bytes,
+        } = err
+        else {
+            panic!("expected insufficient disk space, got {err:?}");
+        };
+        assert_eq!(path, download_root);
+        assert!(required_bytes > available_bytes);
+        assert_eq!(state.status, WorkDownloadStatus::Failed);
+        assert_eq!(state.error_code.as_deref(), Some("insufficient_disk_space"));
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn counts_unpacked_size_on_both_volumes_unless_they_match() {
+        let roots = DiskSpaceRoots {
+            library_root: Path::new("/library"),
+            download_root: Path::new("/downloads"),
+            unpack_policy: UnpackPolicy::UnpackWhenRecognized,
+        };
+
+        assert_eq!(
+            disk_space_requirements(roots, 100, 30, true, false),
+            vec![
+                (PathBuf::from("/downloads"), 170),
+                (PathBuf::from("/library"), 100),
+            ]
+        );
+        assert_eq!(
+            disk_space_requirements(roots, 100, 0, false, true),
+            vec![(PathBuf::from("/downloads"), 100)]
+        );
+    }
+
     #[tokio::test]
     async fn removes_downloaded_work_and_clears_state() -> Result<()> {
         let library = migrated_library().await?;
@@ -4053,6 +7061,67 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn enriches_cached_works_with_public_metadata() -> Result<()> {
+        let library = migrated_library().await?;
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+        let mut enriched = public_work("RJ000001", "First Work", "Maker One");
+        enriched.extra.extend([
+            ("intro_s".to_owned(), json!(" A calm night. ")),
+            ("title_id".to_owned(), json!("SRI0000001")),
+            ("title_name".to_owned(), json!("Night Series")),
+            (
+                "image_samples".to_owned(),
+                json!(["//img.example.test/RJ000001/smp1.jpg"]),
+            ),
+            ("file_type_string".to_owned(), json!("WAV")),
+            ("file_type_special".to_owned(), json!("MP3")),
+            ("track_list".to_owned(), json!([{}, {}, {}])),
+        ]);
+        let source = FakeLocalWorkMetadataSource {
+            works: vec![enriched],
+            fail: false,
+        };
+
+        let report = library.enrich_work_metadata(&source, 10).await?;
+        let detail = library.product_detail("RJ000001").await?;
+        let second = library.enrich_work_metadata(&source, 10).await?;
+        let series = library
+            .list_products(&ProductListQuery {
+                series_ids: vec!["SRI0000001".to_owned()],
+                ..ProductListQuery::default()
+            })
+            .await?;
+
+        assert_eq!(report.requested_count, 2);
+        assert_eq!(report.enriched_count, 1);
+        assert_eq!(second, WorkMetadataEnrichmentReport::default());
+        assert_eq!(detail.description.as_deref(), Some("A calm night."));
+        assert_eq!(
+            detail.series.map(|series| series.name),
+            Some("Night Series".to_owned())
+        );
+        assert_eq!(
+            detail.genres,
+            vec![ProductGenre {
+                id: "497".to_owned(),
+                name: "ASMR".to_owned(),
+            }]
+        );
+        assert_eq!(
+            detail.sample_image_urls,
+            vec!["https://img.example.test/RJ000001/smp1.jpg"]
+        );
+        assert_eq!(detail.file_formats, vec!["WAV", "MP3"]);
+        assert_eq!(detail.track_count, Some(3));
+        assert_eq!(series.total_count, 1);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn local_work_import_hydrates_existing_placeholder_metadata() -> Result<()> {
         let library = migrated_library().await?;
@@ -4127,37 +7196,33 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn redownload_replaces_existing_local_work_after_staging() -> Result<()> {
+    async fn local_work_import_skips_previous_versions() -> Result<()> {
         let library = migrated_library().await?;
-        let root = test_dir("redownload-replaces-work");
+        let root = test_dir("import-local-skips-previous-versions");
         let library_root = root.join("library");
-        let download_root = root.join("downloads");
-        let local_file = library_root.join("RJ000001/RJ000001.txt");
-        library.save_account(save_account_request(true)).await?;
-        library
-            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
-            .await?;
-        library
-            .download_work_with_source(
-                WorkDownloadRequest::new("RJ000001", &library_root, &download_root),
-                &FakeDownloadSource,
-            )
-            .await?;
-        tokio::fs::write(&local_file, b"user edit").await?;
+        let local_path = library_root.join("[RJ123456] Local Work");
+        std::fs::create_dir_all(&local_path).unwrap();
+        std::fs::create_dir_all(
+            library_root.join("[RJ123456] Local Work.previous-20260101T000000"),
+        )
+        .unwrap();
+        std::fs::create_dir_all(
+            library_root
+                .join(PREVIOUS_VERSIONS_DIR)
+                .join("RJ123456-20260101T000000"),
+        )
+        .unwrap();
 
         let report = library
-            .download_work_with_source(
-                WorkDownloadRequest {
-                    replace_existing: true,
-                    ..WorkDownloadRequest::new("RJ000001", &library_root, &download_root)
-                },
-                &FakeDownloadSource,
-            )
+            .import_local_work_downloads(LocalWorkImportRequest::new(&library_root))
             .await?;
 
-        assert_eq!(report.download_state.status, WorkDownloadStatus::Downloaded);
-        assert_eq!(tokio::fs::read(&local_file).await?, b"downloaded");
-        assert!(!download_root.join("RJ000001").exists());
+        assert_eq!(report.scanned_directories, 1);
+        assert_eq!(report.imported_count, 1);
+        assert_eq!(
+            report.imported_works[0].local_path,
+            local_path.canonicalize().unwrap()
+        );
 
         std::fs::remove_dir_all(root).unwrap();
 
@@ -4165,32 +7230,656 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn bulk_download_skips_downloaded_works() -> Result<()> {
+    async fn imports_local_archives_after_a_dry_run() -> Result<()> {
         let library = migrated_library().await?;
-        let root = test_dir("bulk-download-skips");
+        let root = test_dir("import-local-archives");
+        let source_dir = root.join("archives");
         let library_root = root.join("library");
-        let download_root = root.join("downloads");
-        library.save_account(save_account_request(true)).await?;
-        library
-            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
-            .await?;
-        library
-            .download_work_with_source(
-                WorkDownloadRequest::new("RJ000001", &library_root, &download_root),
-                &FakeDownloadSource,
+        let source = FakeLocalWorkMetadataSource {
+            works: vec![public_work(
+                "RJ123456",
+                "Public Metadata Work",
+                "Public Maker",
+            )],
+            fail: false,
+        };
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::create_dir_all(library_root.join("RJ333333")).unwrap();
+        write_test_zip(
+            &source_dir.join("RJ123456.zip"),
+            &[("RJ123456/track.txt", b"audio")],
+        );
+        write_test_zip(&source_dir.join("RJ333333.zip"), &[("a.txt", b"a")]);
+        write_test_zip(&source_dir.join("RJ444444 bonus.zip"), &[("b.txt", b"b")]);
+        write_test_zip(&source_dir.join("RJ444444.zip"), &[("c.txt", b"c")]);
+        write_test_zip(&source_dir.join("notes.zip"), &[("d.txt", b"d")]);
+        write_test_zip(
+            &source_dir.join("RJ000001 RJ000002.zip"),
+            &[("e.txt", b"e")],
+        );
+        std::fs::write(source_dir.join("RJ555555.txt"), b"not an archive").unwrap();
+
+        let preview = library
+            .import_local_archives_with_metadata_source(
+                LocalArchiveImportRequest::new(&source_dir, &library_root),
+                &source,
             )
             .await?;
-        let sink = RecordingBulkDownloadProgressSink::default();
+        let actions = |report: &LocalArchiveImportReport| {
+            report
+                .works
+                .iter()
+                .map(|item| (item.work_id.clone(), item.action.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(preview.dry_run);
+        assert_eq!(preview.scanned_files, 6);
+        assert_eq!(preview.skipped_no_id, 1);
+        assert_eq!(preview.skipped_ambiguous, 1);
+        assert_eq!(
+            actions(&preview),
+            vec![
+                ("RJ123456".to_owned(), LocalArchiveImportAction::Extract),
+                (
+                    "RJ333333".to_owned(),
+                    LocalArchiveImportAction::TargetExists
+                ),
+                (
+                    "RJ444444".to_owned(),
+                    LocalArchiveImportAction::Unrecognized
+                ),
+            ]
+        );
+        assert_eq!(preview.works[0].kind, LocalArchiveKind::SingleZip);
+        assert_eq!(preview.works[0].unpacked_bytes, Some(5));
+        assert_eq!(preview.works[2].archives.len(), 2);
+        assert!(!library_root.join("RJ123456").exists());
+        assert_eq!(
+            library
+                .list_products(&ProductListQuery::default())
+                .await?
+                .total_count,
+            0
+        );
 
         let report = library
-            .download_products_with_source(
-                BulkWorkDownloadRequest {
-                    progress_sink: Some(&sink),
-                    ..BulkWorkDownloadRequest::new(
-                        ProductListQuery::default(),
-                        &library_root,
-                        &download_root,
-                    )
+            .import_local_archives_with_metadata_source(
+                LocalArchiveImportRequest {
+                    dry_run: false,
+                    ..LocalArchiveImportRequest::new(&source_dir, &library_root)
+                },
+                &source,
+            )
+            .await?;
+        let page = library.list_products(&ProductListQuery::default()).await?;
+        let manifest = library.storage().work_file_manifest("RJ123456").await?;
+
+        assert_eq!(report.imported_count, 1);
+        assert_eq!(report.failed_count, 0);
+        assert_eq!(report.metadata_updated_count, 1);
+        assert_eq!(report.works[0].action, LocalArchiveImportAction::Imported);
+        assert_eq!(
+            std::fs::read(library_root.join("RJ123456").join("track.txt")).unwrap(),
+            b"audio"
+        );
+        assert!(source_dir.join("RJ123456.zip").exists());
+        assert_eq!(page.total_count, 1);
+        assert_eq!(page.products[0].title, "Public Metadata Work");
+        assert_eq!(
+            page.products[0].download.status,
+            WorkDownloadStatus::Downloaded
+        );
+        assert_eq!(manifest.len(), 1);
+        assert_eq!(manifest[0].relative_path, "track.txt");
+
+        let rerun = library
+            .import_local_archives(LocalArchiveImportRequest::new(&source_dir, &library_root))
+            .await?;
+
+        assert_eq!(
+            rerun.works[0].action,
+            LocalArchiveImportAction::AlreadyDownloaded
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn local_archive_dry_run_leaves_disk_and_database_untouched() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("import-local-archives-dry-run");
+        let source_dir = root.join("archives");
+        let library_root = root.join("library");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::create_dir_all(&library_root).unwrap();
+        write_test_zip(&source_dir.join("RJ123456.zip"), &[("track.txt", b"audio")]);
+        let before = directory_snapshot(&root);
+
+        let preview = library
+            .import_local_archives(LocalArchiveImportRequest {
+                remove_sources: true,
+                manifest_sidecar: true,
+                ..LocalArchiveImportRequest::new(&source_dir, &library_root)
+            })
+            .await?;
+
+        assert_eq!(preview.works[0].action, LocalArchiveImportAction::Extract);
+        assert_eq!(preview.imported_count, 0);
+        assert_eq!(directory_snapshot(&root), before);
+        assert_eq!(
+            library
+                .storage()
+                .work_download_state("RJ123456")
+                .await?
+                .status,
+            WorkDownloadStatus::NotDownloaded
+        );
+        assert!(library
+            .storage()
+            .work_file_manifest("RJ123456")
+            .await?
+            .is_empty());
+        assert_eq!(
+            library
+                .list_products(&ProductListQuery::default())
+                .await?
+                .total_count,
+            0
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn groups_local_archive_sets_by_work_id() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("import-local-archive-sets");
+        let source_dir = root.join("archives");
+        let library_root = root.join("library");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::create_dir_all(&library_root).unwrap();
+        std::fs::write(source_dir.join("[RJ111111] Voice.part2.rar"), b"part").unwrap();
+        std::fs::write(source_dir.join("[RJ111111] Voice.part1.exe"), b"part").unwrap();
+        std::fs::write(source_dir.join("[RJ111111] Voice.part3.rar"), b"part").unwrap();
+        write_test_zip(
+            &source_dir.join("RJ01005844 Bonus.zip"),
+            &[("bonus.txt", b"bonus")],
+        );
+        write_test_zip(&source_dir.join("VJ222222.zip"), &[("game.txt", b"game")]);
+
+        let preview = library
+            .import_local_archives(LocalArchiveImportRequest::new(&source_dir, &library_root))
+            .await?;
+        let groups = preview
+            .works
+            .iter()
+            .map(|item| {
+                (
+                    item.work_id.as_str(),
+                    item.kind,
+                    item.archives
+                        .iter()
+                        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(preview.scanned_files, 5);
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "RJ01005844",
+                    LocalArchiveKind::SingleZip,
+                    vec!["RJ01005844 Bonus.zip".to_owned()],
+                ),
+                (
+                    "RJ111111",
+                    LocalArchiveKind::LegacySplitRar,
+                    vec![
+                        "[RJ111111] Voice.part1.exe".to_owned(),
+                        "[RJ111111] Voice.part2.rar".to_owned(),
+                        "[RJ111111] Voice.part3.rar".to_owned(),
+                    ],
+                ),
+                (
+                    "VJ222222",
+                    LocalArchiveKind::SingleZip,
+                    vec!["VJ222222.zip".to_owned()],
+                ),
+            ]
+        );
+        assert!(preview
+            .works
+            .iter()
+            .all(|item| item.action == LocalArchiveImportAction::Extract));
+        assert_eq!(
+            preview.works[0].target_path,
+            library_root.canonicalize()?.join("RJ01005844")
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_importing_when_one_local_archive_fails_to_extract() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("import-local-archive-failure");
+        let source_dir = root.join("archives");
+        let library_root = root.join("library");
+        let broken_archive = source_dir.join("RJ111111.zip");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::create_dir_all(&library_root).unwrap();
+        write_test_zip(&broken_archive, &[("track.txt", b"audio")]);
+        write_test_zip(&source_dir.join("RJ222222.zip"), &[("track.txt", b"audio")]);
+        let mut bytes = std::fs::read(&broken_archive).unwrap();
+        let data_start = bytes
+            .windows(5)
+            .position(|window| window == b"audio")
+            .unwrap();
+        bytes[data_start..data_start + 5].copy_from_slice(b"AUDIO");
+        std::fs::write(&broken_archive, bytes).unwrap();
+
+        let report = library
+            .import_local_archives(LocalArchiveImportRequest {
+                dry_run: false,
+                remove_sources: true,
+                ..LocalArchiveImportRequest::new(&source_dir, &library_root)
+            })
+            .await?;
+
+        assert_eq!(report.imported_count, 1);
+        assert_eq!(report.failed_count, 1);
+        assert!(matches!(
+            report.works[0].action,
+            LocalArchiveImportAction::Failed { .. }
+        ));
+        assert_eq!(report.works[1].action, LocalArchiveImportAction::Imported);
+        assert!(broken_archive.is_file());
+        assert!(!library_root.join("RJ111111").exists());
+        assert_eq!(
+            library
+                .storage()
+                .work_download_state("RJ111111")
+                .await?
+                .status,
+            WorkDownloadStatus::NotDownloaded
+        );
+        assert_eq!(
+            library
+                .storage()
+                .work_download_state("RJ222222")
+                .await?
+                .status,
+            WorkDownloadStatus::Downloaded
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redownload_replaces_existing_local_work_after_staging() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("redownload-replaces-work");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        let local_file = library_root.join("RJ000001/RJ000001.txt");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+        library
+            .download_work_with_source(
+                WorkDownloadRequest::new("RJ000001", &library_root, &download_root),
+                &FakeDownloadSource,
+            )
+            .await?;
+        tokio::fs::write(&local_file, b"user edit").await?;
+
+        let report = library
+            .download_work_with_source(
+                WorkDownloadRequest {
+                    replace_existing: true,
+                    ..WorkDownloadRequest::new("RJ000001", &library_root, &download_root)
+                },
+                &FakeDownloadSource,
+            )
+            .await?;
+
+        assert_eq!(report.download_state.status, WorkDownloadStatus::Downloaded);
+        assert_eq!(tokio::fs::read(&local_file).await?, b"downloaded");
+        assert!(!download_root.join("RJ000001").exists());
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn redownload_archives_the_previous_version() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("redownload-archives-work");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        let local_file = library_root.join("RJ000001/RJ000001.txt");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+        library
+            .download_work_with_source(
+                WorkDownloadRequest::new("RJ000001", &library_root, &download_root),
+                &FakeDownloadSource,
+            )
+            .await?;
+        tokio::fs::write(&local_file, b"old version").await?;
+
+        let report = library
+            .download_work_with_source(
+                WorkDownloadRequest {
+                    replace_existing: true,
+                    previous_version: PreviousVersionPolicy::Archive,
+                    ..WorkDownloadRequest::new("RJ000001", &library_root, &download_root)
+                },
+                &FakeDownloadSource,
+            )
+            .await?;
+        let previous_version_path = report.previous_version_path.expect("previous version");
+
+        assert_eq!(tokio::fs::read(&local_file).await?, b"downloaded");
+        assert!(previous_version_path
+            .starts_with(library_root.canonicalize()?.join(PREVIOUS_VERSIONS_DIR)));
+        assert_eq!(
+            tokio::fs::read(previous_version_path.join("RJ000001.txt")).await?,
+            b"old version"
+        );
+        assert!(!report.download_state.update_available);
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn verify_library_flags_and_restores_corrupted_downloads() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("verify-library");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        let work_dir = library_root.join("RJ000001");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+        library
+            .download_work_with_source(
+                WorkDownloadRequest {
+                    manifest_sidecar: true,
+                    ..WorkDownloadRequest::new("RJ000001", &library_root, &download_root)
+                },
+                &FakeDownloadSource,
+            )
+            .await?;
+
+        assert!(work_dir.join(MANIFEST_SIDECAR_FILE_NAME).is_file());
+        assert!(library
+            .storage
+            .work_file_manifest("RJ000001")
+            .await?
+            .iter()
+            .any(|entry| entry.relative_path == "RJ000001.txt" && entry.size_bytes == 10));
+
+        let intact = library.verify_library(LibraryVerifyRequest::new()).await?;
+
+        assert_eq!(intact.verified_count, 1);
+        assert_eq!(intact.corrupted_count, 0);
+        assert!(intact.issues.is_empty());
+
+        tokio::fs::write(work_dir.join("RJ000001.txt"), b"edited by hand").await?;
+        tokio::fs::write(work_dir.join("save.dat"), b"progress").await?;
+
+        let corrupted = library.verify_library(LibraryVerifyRequest::new()).await?;
+        let state = library.storage.work_download_state("RJ000001").await?;
+
+        assert_eq!(corrupted.corrupted_count, 1);
+        assert_eq!(corrupted.issues[0].changed, vec!["RJ000001.txt"]);
+        assert_eq!(corrupted.issues[0].extra, vec!["save.dat"]);
+        assert_eq!(state.status, WorkDownloadStatus::Corrupted);
+        assert_eq!(state.error_code.as_deref(), Some("integrity"));
+
+        tokio::fs::write(work_dir.join("RJ000001.txt"), b"downloaded").await?;
+
+        let restored = library.verify_library(LibraryVerifyRequest::new()).await?;
+
+        assert_eq!(restored.corrupted_count, 0);
+        assert_eq!(restored.restored_count, 1);
+        assert!(!restored.issues[0].is_corrupted());
+        assert_eq!(
+            library
+                .storage
+                .work_download_state("RJ000001")
+                .await?
+                .status,
+            WorkDownloadStatus::Downloaded
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_the_download_when_the_manifest_cannot_be_recorded() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("manifest-best-effort");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+
+        let report = library
+            .download_work_with_source(
+                WorkDownloadRequest {
+                    manifest_sidecar: true,
+                    ..WorkDownloadRequest::new("RJ000001", &library_root, &download_root)
+                },
+                &SidecarBlockingDownloadSource,
+            )
+            .await?;
+
+        assert!(report.manifest_error.is_some());
+        assert_eq!(report.download_state.status, WorkDownloadStatus::Downloaded);
+        assert!(library
+            .storage
+            .work_file_manifest("RJ000001")
+            .await?
+            .is_empty());
+        assert!(library_root.join("RJ000001/RJ000001.txt").is_file());
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_archives_in_the_vault_next_to_the_unpacked_download() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("archive-vault");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        let vault_root = root.join("vault");
+        let vault_dir = vault_root.join("RJ000001");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+        let request = WorkDownloadRequest {
+            unpack_policy: UnpackPolicy::UnpackAndKeepArchives,
+            ..WorkDownloadRequest::new("RJ000001", &library_root, &download_root)
+        };
+
+        assert!(matches!(
+            library
+                .download_work_with_source(request, &UnpackedArchiveDownloadSource)
+                .await,
+            Err(LibraryError::ArchiveVaultNotConfigured)
+        ));
+
+        let report = library
+            .download_work_with_source(
+                WorkDownloadRequest {
+                    archive_vault_root: Some(&vault_root),
+                    ..request
+                },
+                &UnpackedArchiveDownloadSource,
+            )
+            .await?;
+
+        assert!(library_root.join("RJ000001/RJ000001.txt").is_file());
+        assert!(!library_root.join("RJ000001/RJ000001.zip").exists());
+        assert_eq!(std::fs::read(vault_dir.join("RJ000001.zip"))?, b"archive");
+        assert_eq!(
+            report.download_state.archive_vault_path,
+            Some(vault_dir.to_string_lossy().into_owned())
+        );
+        assert_eq!(
+            report.download_state.unpack_policy.as_deref(),
+            Some("unpack_and_keep_archives")
+        );
+
+        tokio::fs::remove_file(vault_dir.join("RJ000001.zip")).await?;
+
+        let verified = library.verify_library(LibraryVerifyRequest::new()).await?;
+
+        assert_eq!(verified.corrupted_count, 1);
+        assert_eq!(
+            verified.issues[0].missing_archive_vault,
+            Some(vault_dir.clone())
+        );
+
+        library
+            .remove_work_download(WorkDownloadRemovalRequest {
+                archive_vault_root: Some(&vault_root),
+                ..WorkDownloadRemovalRequest::new("RJ000001", &library_root, &download_root)
+            })
+            .await?;
+
+        assert!(!library_root.join("RJ000001").exists());
+        assert!(!vault_dir.exists());
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keeps_the_previous_vault_archives_with_the_previous_version() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("archive-vault-previous");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        let vault_root = root.join("vault");
+        let vault_dir = vault_root.join("RJ000001");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+        let request = WorkDownloadRequest {
+            unpack_policy: UnpackPolicy::UnpackAndKeepArchives,
+            archive_vault_root: Some(&vault_root),
+            ..WorkDownloadRequest::new("RJ000001", &library_root, &download_root)
+        };
+        library
+            .download_work_with_source(request, &UnpackedArchiveDownloadSource)
+            .await?;
+        tokio::fs::write(vault_dir.join("RJ000001.zip"), b"old archive").await?;
+        let previous_vaults = || -> Result<Vec<PathBuf>> {
+            let mut paths = std::fs::read_dir(&vault_root)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<std::io::Result<Vec<_>>>()?;
+            paths.retain(|path| path.file_name() != vault_dir.file_name());
+            Ok(paths)
+        };
+
+        let report = library
+            .download_work_with_source(
+                WorkDownloadRequest {
+                    replace_existing: true,
+                    previous_version: PreviousVersionPolicy::Keep,
+                    ..request
+                },
+                &UnpackedArchiveDownloadSource,
+            )
+            .await?;
+        let previous_vaults_after_keep = previous_vaults()?;
+
+        assert!(report
+            .previous_version_path
+            .is_some_and(|path| path.join("RJ000001.txt").is_file()));
+        assert_eq!(std::fs::read(vault_dir.join("RJ000001.zip"))?, b"archive");
+        assert_eq!(previous_vaults_after_keep.len(), 1);
+        assert_eq!(
+            std::fs::read(previous_vaults_after_keep[0].join("RJ000001.zip"))?,
+            b"old archive"
+        );
+
+        library
+            .download_work_with_source(
+                WorkDownloadRequest {
+                    replace_existing: true,
+                    ..request
+                },
+                &UnpackedArchiveDownloadSource,
+            )
+            .await?;
+
+        assert_eq!(std::fs::read(vault_dir.join("RJ000001.zip"))?, b"archive");
+        assert_eq!(previous_vaults()?, previous_vaults_after_keep);
+
+        std::fs::remove_dir_all(root).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bulk_download_skips_downloaded_works() -> Result<()> {
+        let library = migrated_library().await?;
+        let root = test_dir("bulk-download-skips");
+        let library_root = root.join("library");
+        let download_root = root.join("downloads");
+        library.save_account(save_account_request(true)).await?;
+        library
+            .sync_account_with_source(AccountSyncRequest::new("account-a"), &sync_source())
+            .await?;
+        library
+            .download_work_with_source(
+                WorkDownloadRequest::new("RJ000001", &library_root, &download_root),
+                &FakeDownloadSource,
+            )
+            .await?;
+        let sink = RecordingBulkDownloadProgressSink::default();
+
+        let report = library
+            .download_products_with_source(
+                BulkWorkDownloadRequest {
+                    progress_sink: Some(&sink),
+                    ..BulkWorkDownloadRequest::new(
+                        ProductListQuery::default(),
+                        &library_root,
+                        &download_root,
+                    )
                 },
                 &FakeDownloadSource,
             )
@@ -4245,6 +7934,11 @@ mod tests {
                     query: ProductListQuery::default(),
                     work_ids: None,
                     skip_downloaded: true,
+                    disk_space: Some(DiskSpaceRoots {
+                        library_root: &library_root,
+                        download_root: &download_root,
+                        unpack_policy: UnpackPolicy::KeepArchives,
+                    }),
                     cancellation_token: None,
                     progress_sink: None,
                 },
@@ -4262,6 +7956,10 @@ mod tests {
         assert_eq!(preview.unknown_size_count, 0);
         assert_eq!(preview.works[0].work_id, "RJ000002");
         assert_eq!(preview.works[0].file_count, 1);
+        assert_eq!(preview.disk_space.len(), 1);
+        assert_eq!(preview.disk_space[0].path, download_root);
+        assert_eq!(preview.disk_space[0].required_bytes, 10);
+        assert_eq!(preview.disk_space_shortfall_bytes(), 0);
 
         std::fs::remove_dir_all(root).unwrap();
 
@@ -4292,6 +7990,7 @@ mod tests {
                     query: ProductListQuery::default(),
                     work_ids: None,
                     skip_downloaded: true,
+                    disk_space: None,
                     cancellation_token: None,
                     progress_sink: Some(&sink),
                 },
@@ -0,0 +1,301 @@
+use dm_api::{
+    DlsiteClient, DlsiteClientConfig, DlsiteEndpoints, RequestGovernor, RequestGovernorConfig,
+};
+use dm_credentials::{CredentialRef, CredentialStore, InMemoryCredentialStore};
+use dm_fake_dlsite::{
+    FakeAccount, FakeDlsite, FakeDlsiteConfig, FakeEndpoint, FakePurchase, FakeWork, Fault,
+    FaultRule,
+};
+use dm_library::{AccountSyncRequest, DlsiteSyncSource, Library, LibraryError, SaveAccountRequest};
+use dm_storage::{AppSettings, ProductListQuery, Storage};
+use serde_json::json;
+use std::{error::Error, sync::Arc, time::Duration};
+use url::Url;
+
+type TestResult = Result<(), Box<dyn Error>>;
+
+#[tokio::test]
+async fn reuses_saved_session_until_it_expires() -> TestResult {
+    let fake = FakeDlsite::start(fake_config()).await?;
+    let credentials = Arc::new(InMemoryCredentialStore::new());
+    let library = library(credentials.clone()).await?;
+    let account = library
+        .save_account(SaveAccountRequest {
+            id: Some("account-a".to_owned()),
+            login_name: Some("fake-user".to_owned()),
+            ..SaveAccountRequest::new("Account A")
+        })
+        .await?;
+    let session_ref = CredentialRef::account_session(&account.id)?;
+
+    let report = library
+        .sync_account_with_source(
+            AccountSyncRequest {
+                password: Some("fake-password"),
+                ..AccountSyncRequest::new(&account.id)
+            },
+            &sync_source(&fake)?,
+        )
+        .await?;
+
+    assert_eq!(report.purchased_count, 1);
+    assert!(credentials.load_session(&session_ref)?.is_some());
+    assert!(library.account_has_saved_session(&account)?);
+    assert_eq!(login_posts(&fake), 1);
+
+    library
+        .sync_account_with_source(AccountSyncRequest::new(&account.id), &sync_source(&fake)?)
+        .await?;
+
+    assert_eq!(login_posts(&fake), 1);
+
+    fake.expire_sessions();
+    let error = library
+        .sync_account_with_source(AccountSyncRequest::new(&account.id), &sync_source(&fake)?)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, LibraryError::MissingPassword(_)));
+    assert!(credentials.load_session(&session_ref)?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn logs_in_with_the_password_when_the_saved_session_cannot_be_restored() -> TestResult {
+    let fake = FakeDlsite::start(fake_config()).await?;
+    let credentials = Arc::new(InMemoryCredentialStore::new());
+    let library = library(credentials.clone()).await?;
+    let account = library
+        .save_account(SaveAccountRequest {
+            id: Some("account-a".to_owned()),
+            login_name: Some("fake-user".to_owned()),
+            password: Some("fake-password".to_owned()),
+            remember_password: true,
+            ..SaveAccountRequest::new("Account A")
+        })
+        .await?;
+    let session_ref = CredentialRef::account_session(&account.id)?;
+    credentials.save_session(&session_ref, "not a cookie jar")?;
+
+    library
+        .sync_account_with_source(AccountSyncRequest::new(&account.id), &sync_source(&fake)?)
+        .await?;
+
+    assert_eq!(login_posts(&fake), 1);
+    let saved = credentials
+        .load_session(&session_ref)?
+        .expect("session saved after login");
+    assert_ne!(saved, "not a cookie jar");
+
+    fake.push_fault(FaultRule {
+        times: Some(1),
+        ..FaultRule::new(FakeEndpoint::ContentCount, Fault::Status(400))
+    });
+    library
+        .sync_account_with_source(AccountSyncRequest::new(&account.id), &sync_source(&fake)?)
+        .await?;
+
+    assert_eq!(login_posts(&fake), 2);
+    assert!(credentials.load_session(&session_ref)?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reports_why_a_session_only_account_could_not_be_restored() -> TestResult {
+    let fake = FakeDlsite::start(fake_config()).await?;
+    let credentials = Arc::new(InMemoryCredentialStore::new());
+    let library = library(credentials.clone()).await?;
+    let account = library
+        .save_account(SaveAccountRequest {
+            id: Some("account-a".to_owned()),
+            login_name: Some("fake-user".to_owned()),
+            ..SaveAccountRequest::new("Account A")
+        })
+        .await?;
+    let session_ref = CredentialRef::account_session(&account.id)?;
+    library
+        .sync_account_with_source(
+            AccountSyncRequest {
+                password: Some("fake-password"),
+                ..AccountSyncRequest::new(&account.id)
+            },
+            &sync_source(&fake)?,
+        )
+        .await?;
+
+    fake.push_fault(FaultRule {
+        times: Some(1),
+        ..FaultRule::new(FakeEndpoint::ContentCount, Fault::Status(400))
+    });
+    let error = library
+        .sync_account_with_source(AccountSyncRequest::new(&account.id), &sync_source(&fake)?)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, LibraryError::Api(_)), "{error:?}");
+    assert!(credentials.load_session(&session_ref)?.is_some());
+
+    credentials.save_session(&session_ref, "not a cookie jar")?;
+    let error = library
+        .sync_account_with_source(AccountSyncRequest::new(&account.id), &sync_source(&fake)?)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, LibraryError::MissingPassword(_)));
+    assert!(credentials.load_session(&session_ref)?.is_none());
+
+    Ok(())
+}
+
+fn login_posts(fake: &FakeDlsite) -> usize {
+    fake.requests_to(FakeEndpoint::Login)
+        .iter()
+        .filter(|request| request.method == "POST")
+        .count()
+}
+
+#[tokio::test]
+async fn routes_account_traffic_through_its_proxy() -> TestResult {
+    let fake = FakeDlsite::start(fake_config()).await?;
+    let library = library(Arc::new(InMemoryCredentialStore::new())).await?;
+    let account = library
+        .save_account(SaveAccountRequest {
+            id: Some("account-a".to_owned()),
+            login_name: Some("fake-user".to_owned()),
+            proxy_url: Some(fake.base_url().to_string()),
+            ..SaveAccountRequest::new("Account A")
+        })
+        .await?;
+    let unreachable = DlsiteEndpoints::with_base_url(Url::parse("http://dlsite.invalid/")?);
+    let source = DlsiteSyncSource::new(DlsiteClient::new(DlsiteClientConfig {
+        endpoints: unreachable,
+        ..client_config(&fake)
+    })?);
+
+    let report = library
+        .sync_account_with_source(
+            AccountSyncRequest {
+                password: Some("fake-password"),
+                ..AccountSyncRequest::new(&account.id)
+            },
+            &source,
+        )
+        .await?;
+
+    assert_eq!(report.purchased_count, 1);
+    assert_eq!(
+        source.client().proxy().map(|proxy| proxy.url.clone()),
+        Some(fake.base_url().clone())
+    );
+    assert_eq!(login_posts(&fake), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn syncs_translated_titles_once_and_displays_the_preferred_language() -> TestResult {
+    let mut config = fake_config();
+    config.works[0].localized_public_json.insert(
+        "en_US".to_owned(),
+        json!({
+            "work_name": "Translated Work",
+            "maker_name": "Maker",
+            "genres": [{ "name": "Healing" }],
+        }),
+    );
+    let fake = FakeDlsite::start(config).await?;
+    let library = library(Arc::new(InMemoryCredentialStore::new())).await?;
+    let account = library
+        .save_account(SaveAccountRequest {
+            id: Some("account-a".to_owned()),
+            login_name: Some("fake-user".to_owned()),
+            ..SaveAccountRequest::new("Account A")
+        })
+        .await?;
+
+    for _ in 0..2 {
+        let report = library
+            .sync_account_with_source(
+                AccountSyncRequest {
+                    password: Some("fake-password"),
+                    ..AccountSyncRequest::new(&account.id)
+                },
+                &sync_source(&fake)?,
+            )
+            .await?;
+
+        assert_eq!(report.localization_error, None);
+    }
+
+    assert_eq!(fake.requests_to(FakeEndpoint::PublicProduct).len(), 4);
+
+    let search = ProductListQuery {
+        search: Some("Translated".to_owned()),
+        ..ProductListQuery::default()
+    };
+    let page = library.list_products(&search).await?;
+
+    assert_eq!(page.total_count, 1);
+    assert_eq!(page.products[0].title, "Session Work");
+
+    library
+        .storage()
+        .save_app_settings(&AppSettings {
+            display_language: Some("en_US".to_owned()),
+            ..AppSettings::default()
+        })
+        .await?;
+    let page = library.list_products(&search).await?;
+    let detail = library.product_detail("RJ000001").await?;
+
+    assert_eq!(page.products[0].title, "Translated Work");
+    assert_eq!(detail.maker_names.len(), 1);
+    assert_eq!(
+        detail
+            .tags
+            .iter()
+            .map(|tag| tag.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Healing"]
+    );
+
+    Ok(())
+}
+
+async fn library(credentials: Arc<InMemoryCredentialStore>) -> Result<Library, Box<dyn Error>> {
+    let storage = Storage::open_in_memory().await?;
+    storage.run_migrations().await?;
+
+    Ok(Library::new(storage, credentials))
+}
+
+fn sync_source(fake: &FakeDlsite) -> dm_api::Result<DlsiteSyncSource> {
+    Ok(DlsiteSyncSource::new(DlsiteClient::new(client_config(
+        fake,
+    ))?))
+}
+
+fn client_config(fake: &FakeDlsite) -> DlsiteClientConfig {
+    DlsiteClientConfig {
+        endpoints: DlsiteEndpoints::with_base_url(fake.base_url().clone()),
+        governor: RequestGovernor::new(RequestGovernorConfig {
+            requests_per_second: None,
+            initial_backoff: Duration::from_millis(10),
+            ..RequestGovernorConfig::default()
+        }),
+        ..DlsiteClientConfig::default()
+    }
+}
+
+fn fake_config() -> FakeDlsiteConfig {
+    let mut account = FakeAccount::new("fake-user", "fake-password");
+    account.purchases = vec![FakePurchase::new("RJ000001", "2026-01-02T00:00:00+09:00")];
+
+    FakeDlsiteConfig {
+        accounts: vec![account],
+        works: vec![FakeWork::new("RJ000001", "Session Work", "Maker")],
+        ..FakeDlsiteConfig::default()
+    }
+}
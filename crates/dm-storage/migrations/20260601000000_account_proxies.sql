@@ -0,0 +1,3 @@
+ALTER TABLE accounts ADD COLUMN proxy_url TEXT;
+ALTER TABLE accounts ADD COLUMN proxy_username TEXT;
+ALTER TABLE accounts ADD COLUMN proxy_credential_ref TEXT;
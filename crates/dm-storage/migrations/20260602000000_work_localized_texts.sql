@@ -0,0 +1,12 @@
+CREATE TABLE work_localized_texts (
+    work_id TEXT NOT NULL REFERENCES works(work_id) ON DELETE CASCADE,
+    language TEXT NOT NULL,
+    title TEXT,
+    maker_name TEXT,
+    tags_json TEXT NOT NULL DEFAULT '[]',
+    fetched_at TEXT NOT NULL,
+    CHECK (trim(language) <> ''),
+    PRIMARY KEY(work_id, language)
+);
+
+CREATE INDEX work_localized_texts_language_idx ON work_localized_texts(language, work_id);
@@ -0,0 +1,23 @@
+CREATE TABLE work_metadata (
+    work_id TEXT PRIMARY KEY NOT NULL REFERENCES works(work_id) ON DELETE CASCADE,
+    description TEXT,
+    series_id TEXT,
+    series_name TEXT,
+    sample_images_json TEXT NOT NULL DEFAULT '[]',
+    file_formats_json TEXT NOT NULL DEFAULT '[]',
+    track_count INTEGER CHECK (track_count IS NULL OR track_count >= 0),
+    fetched_at TEXT NOT NULL
+);
+
+CREATE INDEX work_metadata_series_idx ON work_metadata(series_id, work_id);
+
+CREATE TABLE work_genres (
+    work_id TEXT NOT NULL REFERENCES works(work_id) ON DELETE CASCADE,
+    genre_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    CHECK (trim(genre_id) <> ''),
+    CHECK (trim(name) <> ''),
+    PRIMARY KEY(work_id, genre_id)
+);
+
+CREATE INDEX work_genres_genre_idx ON work_genres(genre_id, work_id);
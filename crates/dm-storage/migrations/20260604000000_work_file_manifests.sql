@@ -0,0 +1,34 @@
+CREATE TABLE work_downloads_new (
+    work_id TEXT PRIMARY KEY NOT NULL REFERENCES works(work_id) ON DELETE CASCADE,
+    status TEXT NOT NULL CHECK (status IN ('downloading', 'downloaded', 'failed', 'cancelled', 'corrupted')),
+    local_path TEXT NULL,
+    staging_path TEXT NULL,
+    unpack_policy TEXT NOT NULL DEFAULT 'unpack_when_recognized',
+    bytes_received INTEGER NOT NULL DEFAULT 0 CHECK (bytes_received >= 0),
+    bytes_total INTEGER NULL CHECK (bytes_total IS NULL OR bytes_total >= 0),
+    error_code TEXT NULL,
+    error_message TEXT NULL,
+    started_at TEXT NULL,
+    completed_at TEXT NULL,
+    updated_at TEXT NOT NULL
+);
+
+INSERT INTO work_downloads_new
+SELECT work_id, status, local_path, staging_path, unpack_policy,
+       bytes_received, bytes_total, error_code, error_message,
+       started_at, completed_at, updated_at
+FROM work_downloads;
+
+DROP TABLE work_downloads;
+
+ALTER TABLE work_downloads_new RENAME TO work_downloads;
+
+CREATE TABLE work_file_manifests (
+    work_id TEXT NOT NULL REFERENCES works(work_id) ON DELETE CASCADE,
+    relative_path TEXT NOT NULL,
+    size_bytes INTEGER NOT NULL CHECK (size_bytes >= 0),
+    sha256 TEXT NOT NULL CHECK (length(sha256) = 64),
+    recorded_at TEXT NOT NULL,
+    CHECK (trim(relative_path) <> ''),
+    PRIMARY KEY(work_id, relative_path)
+);
@@ -0,0 +1 @@
+ALTER TABLE work_downloads ADD COLUMN archive_vault_path TEXT NULL;
@@ -0,0 +1,6 @@
+CREATE TABLE work_archive_settings (
+    work_id TEXT PRIMARY KEY REFERENCES works(work_id) ON DELETE CASCADE,
+    name_encoding TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    CHECK (name_encoding IN ('utf8', 'cp932', 'cp437'))
+);
@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sqlx::{
     migrate::Migrator,
     sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteQueryResult},
@@ -12,6 +12,43 @@ use std::{
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 const LIBRARY_ROOT_KEY: &str = "library_root";
 const DOWNLOAD_ROOT_KEY: &str = "download_root";
+const ARCHIVE_VAULT_ROOT_KEY: &str = "archive_vault_root";
+const DISPLAY_LANGUAGE_KEY: &str = "display_language";
+const DOWNLOAD_CONNECTIONS_KEY: &str = "download_connections_per_file";
+const DOWNLOAD_PARALLEL_FILES_KEY: &str = "download_parallel_files";
+const BANDWIDTH_LIMIT_KEY: &str = "bandwidth_limit_bytes_per_second";
+const BANDWIDTH_SCHEDULES_KEY: &str = "bandwidth_schedules";
+const DOWNLOAD_STALL_TIMEOUT_KEY: &str = "download_stall_timeout_seconds";
+const DOWNLOAD_MIN_SPEED_KEY: &str = "download_min_speed";
+const DOWNLOAD_RETRY_POLICY_KEY: &str = "download_retry_policy";
+const MANIFEST_SIDECAR_KEY: &str = "manifest_sidecar";
+const NESTED_ARCHIVES_KEY: &str = "nested_archives";
+const POST_DOWNLOAD_HOOKS_KEY: &str = "post_download_hooks";
+/// Title in the preferred display language, falling back to the synced title.
+const DISPLAY_TITLE_SQL: &str = "COALESCE(
+    (
+        SELECT COALESCE(
+            CASE
+                WHEN json_valid(w.title_json)
+                    THEN json_extract(w.title_json, '$.\"' || display_language.value || '\"')
+            END,
+            (
+                SELECT display_wlt.title
+                FROM work_localized_texts display_wlt
+                WHERE display_wlt.work_id = w.work_id
+                    AND display_wlt.language = display_language.value
+            )
+        )
+        FROM app_settings display_language
+        WHERE display_language.key = 'display_language'
+    ),
+    w.title
+)";
+/// Whether DLsite published a newer version after the work was downloaded.
+const UPDATE_AVAILABLE_SQL: &str = "COALESCE(
+    wd.status = 'downloaded' AND w.updated_at > wd.completed_at,
+    0
+)";
 const MISSING_WORK_DETAIL_STATUS: &str = "missing_from_content_works";
 const CUSTOM_TAG_MAX_CHARS: usize = 64;
 pub const LOCAL_PRODUCT_OWNER_ID: &str = "__local__";
@@ -42,6 +79,8 @@ pub enum WorkDownloadStatus {
     Downloaded,
     Failed,
     Cancelled,
+    /// Downloaded, but the files no longer match the recorded manifest.
+    Corrupted,
 }
 
 impl WorkDownloadStatus {
@@ -52,6 +91,7 @@ impl WorkDownloadStatus {
             Self::Downloaded => "downloaded",
             Self::Failed => "failed",
             Self::Cancelled => "cancelled",
+            Self::Corrupted => "corrupted",
         }
     }
 
@@ -61,6 +101,7 @@ impl WorkDownloadStatus {
             "downloaded" => Ok(Self::Downloaded),
             "failed" => Ok(Self::Failed),
             "cancelled" => Ok(Self::Cancelled),
+            "corrupted" => Ok(Self::Corrupted),
             _ => Err(StorageError::InvalidStoredValue {
                 field: "work_downloads.status",
                 value: value.to_owned(),
@@ -74,6 +115,8 @@ pub struct WorkDownloadState {
     pub status: WorkDownloadStatus,
     pub local_path: Option<String>,
     pub staging_path: Option<String>,
+    /// Folder in the archive vault holding the original archives kept next to the unpacked copy.
+    pub archive_vault_path: Option<String>,
     pub unpack_policy: Option<String>,
     pub bytes_received: u64,
     pub bytes_total: Option<u64>,
@@ -82,6 +125,7 @@ pub struct WorkDownloadState {
     pub started_at: Option<String>,
     pub completed_at: Option<String>,
     pub updated_at: Option<String>,
+    pub update_available: bool,
 }
 
 impl Default for WorkDownloadState {
@@ -90,6 +134,7 @@ impl Default for WorkDownloadState {
             status: WorkDownloadStatus::NotDownloaded,
             local_path: None,
             staging_path: None,
+            archive_vault_path: None,
             unpack_policy: None,
             bytes_received: 0,
             bytes_total: None,
@@ -98,6 +143,7 @@ impl Default for WorkDownloadState {
             started_at: None,
             completed_at: None,
             updated_at: None,
+            update_available: false,
         }
     }
 }
@@ -108,6 +154,7 @@ pub struct WorkDownloadUpdate {
     pub status: WorkDownloadStatus,
     pub local_path: Option<String>,
     pub staging_path: Option<String>,
+    pub archive_vault_path: Option<String>,
     pub unpack_policy: String,
     pub bytes_received: u64,
     pub bytes_total: Option<u64>,
@@ -118,6 +165,22 @@ pub struct WorkDownloadUpdate {
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkFileManifestEntry {
+    pub relative_path: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+/// A finished download with a recorded manifest to check its folder against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiableWorkDownload {
+    pub work_id: String,
+    pub local_path: String,
+    pub archive_vault_path: Option<String>,
+    pub status: WorkDownloadStatus,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LocalWorkDownloadImport {
     pub work: CachedWork,
@@ -128,6 +191,87 @@ pub struct LocalWorkDownloadImport {
 pub struct AppSettings {
     pub library_root: Option<String>,
     pub download_root: Option<String>,
+    /// Where original archives go when a download unpacks them and keeps the archives too.
+    pub archive_vault_root: Option<String>,
+    /// DLsite language code such as `en_US` used for product titles.
+    pub display_language: Option<String>,
+    /// Ranged connections opened per downloaded file; unset keeps single-stream downloads.
+    pub download_connections_per_file: Option<u32>,
+    /// Files of one work fetched at once; unset downloads them one after another.
+    pub download_parallel_files: Option<u32>,
+    /// Download bandwidth cap outside every schedule; unset means unlimited.
+    pub bandwidth_limit_bytes_per_second: Option<u64>,
+    pub bandwidth_schedules: Vec<BandwidthScheduleSetting>,
+    /// Seconds without data before a download connection is reopened; unset keeps the default.
+    pub download_stall_timeout_seconds: Option<u32>,
+    /// Throughput below which a download connection is reopened; unset disables the check.
+    pub download_min_speed_bytes_per_second: Option<u64>,
+    /// Unset keeps the built-in download retry policy.
+    pub download_retry_policy: Option<RetryPolicySetting>,
+    /// Writes each work's file manifest into its folder as well as into the database.
+    pub manifest_sidecar: bool,
+    /// Unset leaves archives found inside a work's archives packed.
+    pub nested_archives: Option<NestedArchiveSetting>,
+    /// Commands run, in order, after each work finishes downloading.
+    pub post_download_hooks: Vec<PostDownloadHookSetting>,
+}
+
+/// Time-of-day window, in minutes since local midnight, with its own bandwidth cap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthScheduleSetting {
+    pub start_minute: u32,
+    pub end_minute: u32,
+    /// Unset lifts the cap inside the window.
+    pub bytes_per_second: Option<u64>,
+}
+
+/// How failed download connections are retried, stored as JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicySetting {
+    pub max_retries: u32,
+    pub backoff: RetryBackoffSetting,
+    pub initial_delay_seconds: u32,
+    pub max_delay_seconds: u32,
+    pub jitter_percent: u32,
+    pub retry_network: bool,
+    pub retry_stalled: bool,
+    pub retry_incomplete: bool,
+    pub retry_invalid_response: bool,
+    /// Unset keeps retrying until `max_retries` is spent.
+    pub deadline_seconds: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RetryBackoffSetting {
+    Constant,
+    Linear,
+    Exponential,
+}
+
+/// Limits for unpacking archives found inside a work's archives, stored as JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NestedArchiveSetting {
+    pub max_depth: u32,
+    pub max_unpacked_bytes: u64,
+    pub keep_inner_archives: bool,
+}
+
+/// Command run after a work finishes downloading, stored as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostDownloadHookSetting {
+    pub name: String,
+    pub program: String,
+    /// May reference `{workId}`, `{localPath}`, `{title}`, `{maker}` and `{workType}`.
+    pub args: Vec<String>,
+    pub enabled: bool,
+    /// Kills the hook once it runs this long; `None` waits for it to exit.
+    #[serde(default)]
+    pub timeout_seconds: Option<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -136,6 +280,7 @@ pub struct Account {
     pub label: String,
     pub login_name: Option<String>,
     pub credential_ref: Option<String>,
+    pub proxy: Option<AccountProxy>,
     pub enabled: bool,
     pub created_at: String,
     pub updated_at: String,
@@ -149,9 +294,17 @@ pub struct AccountUpsert {
     pub label: String,
     pub login_name: Option<String>,
     pub credential_ref: Option<String>,
+    pub proxy: Option<AccountProxy>,
     pub enabled: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountProxy {
+    pub url: String,
+    pub username: Option<String>,
+    pub credential_ref: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CachedWork {
     pub work_id: String,
@@ -184,6 +337,31 @@ pub struct AccountSyncCommit {
     pub completed_at: String,
     pub works: Vec<CachedWork>,
     pub account_works: Vec<AccountWork>,
+    pub localized_texts: Vec<WorkLocalizedText>,
+}
+
+/// Title, maker and tags of a work as DLsite presents them in one language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkLocalizedText {
+    pub work_id: String,
+    pub language: String,
+    pub title: Option<String>,
+    pub maker_name: Option<String>,
+    pub tags: Vec<ProductTag>,
+    pub fetched_at: String,
+}
+
+/// Descriptive metadata gathered by the enrichment pass after a work is cached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkMetadata {
+    pub work_id: String,
+    pub description: Option<String>,
+    pub series: Option<ProductSeries>,
+    pub genres: Vec<ProductGenre>,
+    pub sample_image_urls: Vec<String>,
+    pub file_formats: Vec<String>,
+    pub track_count: Option<u32>,
+    pub fetched_at: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -311,6 +489,9 @@ pub struct ProductListQuery {
     pub maker_names: Vec<String>,
     pub custom_tag_names: Vec<String>,
     pub excluded_custom_tag_names: Vec<String>,
+    pub genre_ids: Vec<String>,
+    pub series_ids: Vec<String>,
+    pub update_available_only: bool,
     pub sort: ProductSort,
     pub limit: u32,
     pub offset: u32,
@@ -330,6 +511,9 @@ impl Default for ProductListQuery {
             maker_names: Vec::new(),
             custom_tag_names: Vec::new(),
             excluded_custom_tag_names: Vec::new(),
+            genre_ids: Vec::new(),
+            series_ids: Vec::new(),
+            update_available_only: false,
             sort: ProductSort::LatestPurchaseDesc,
             limit: 100,
             offset: 0,
@@ -347,6 +531,8 @@ pub struct ProductListPage {
 pub struct ProductFilterFacets {
     pub makers: Vec<ProductMakerFacet>,
     pub custom_tags: Vec<ProductCustomTagFacet>,
+    pub genres: Vec<ProductGenreFacet>,
+    pub series: Vec<ProductSeriesFacet>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -361,6 +547,20 @@ pub struct ProductCustomTagFacet {
     pub count: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductGenreFacet {
+    pub id: String,
+    pub name: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductSeriesFacet {
+    pub id: String,
+    pub name: String,
+    pub count: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProductListItem {
     pub work_id: String,
@@ -401,6 +601,14 @@ pub struct ProductDetail {
     pub credit_groups: Vec<ProductCreditGroup>,
     pub tags: Vec<ProductTag>,
     pub custom_tags: Vec<ProductCustomTag>,
+    /// Per-work override for reading archive entry names; `None` detects per archive.
+    pub archive_name_encoding: Option<String>,
+    pub description: Option<String>,
+    pub series: Option<ProductSeries>,
+    pub genres: Vec<ProductGenre>,
+    pub sample_image_urls: Vec<String>,
+    pub file_formats: Vec<String>,
+    pub track_count: Option<u32>,
     pub download: WorkDownloadState,
     pub owners: Vec<ProductOwner>,
 }
@@ -424,6 +632,18 @@ pub struct ProductTag {
     pub name: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductGenre {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductSeries {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProductCustomTag {
     pub name: String,
@@ -493,6 +713,36 @@ impl Storage {
             match key.as_str() {
                 LIBRARY_ROOT_KEY => settings.library_root = Some(value),
                 DOWNLOAD_ROOT_KEY => settings.download_root = Some(value),
+                ARCHIVE_VAULT_ROOT_KEY => settings.archive_vault_root = Some(value),
+                DISPLAY_LANGUAGE_KEY => settings.display_language = Some(value),
+                DOWNLOAD_CONNECTIONS_KEY => {
+                    settings.download_connections_per_file = value.parse().ok();
+                }
+                DOWNLOAD_PARALLEL_FILES_KEY => {
+                    settings.download_parallel_files = value.parse().ok();
+                }
+                BANDWIDTH_LIMIT_KEY => {
+                    settings.bandwidth_limit_bytes_per_second = value.parse().ok();
+                }
+                BANDWIDTH_SCHEDULES_KEY => {
+                    settings.bandwidth_schedules = serde_json::from_str(&value).unwrap_or_default();
+                }
+                DOWNLOAD_STALL_TIMEOUT_KEY => {
+                    settings.download_stall_timeout_seconds = value.parse().ok();
+                }
+                DOWNLOAD_MIN_SPEED_KEY => {
+                    settings.download_min_speed_bytes_per_second = value.parse().ok();
+                }
+                DOWNLOAD_RETRY_POLICY_KEY => {
+                    settings.download_retry_policy = serde_json::from_str(&value).ok();
+                }
+                MANIFEST_SIDECAR_KEY => settings.manifest_sidecar = value == "true",
+                NESTED_ARCHIVES_KEY => {
+                    settings.nested_archives = serde_json::from_str(&value).ok();
+                }
+                POST_DOWNLOAD_HOOKS_KEY => {
+                    settings.post_download_hooks = serde_json::from_str(&value).unwrap_or_default();
+                }
                 _ => {}
             }
         }
@@ -509,6 +759,100 @@ impl Storage {
         transaction
             .set_setting(DOWNLOAD_ROOT_KEY, settings.download_root.as_deref())
             .await?;
+        transaction
+            .set_setting(
+                ARCHIVE_VAULT_ROOT_KEY,
+                settings.archive_vault_root.as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(DISPLAY_LANGUAGE_KEY, settings.display_language.as_deref())
+            .await?;
+        transaction
+            .set_setting(
+                DOWNLOAD_CONNECTIONS_KEY,
+                settings
+                    .download_connections_per_file
+                    .map(|connections| connections.to_string())
+                    .as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                DOWNLOAD_PARALLEL_FILES_KEY,
+                settings
+                    .download_parallel_files
+                    .map(|files| files.to_string())
+                    .as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                BANDWIDTH_LIMIT_KEY,
+                settings
+                    .bandwidth_limit_bytes_per_second
+                    .map(|limit| limit.to_string())
+                    .as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                BANDWIDTH_SCHEDULES_KEY,
+                (!settings.bandwidth_schedules.is_empty())
+                    .then(|| serde_json::json!(settings.bandwidth_schedules).to_string())
+                    .as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                DOWNLOAD_STALL_TIMEOUT_KEY,
+                settings
+                    .download_stall_timeout_seconds
+                    .map(|seconds| seconds.to_string())
+                    .as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                DOWNLOAD_MIN_SPEED_KEY,
+                settings
+                    .download_min_speed_bytes_per_second
+                    .map(|speed| speed.to_string())
+                    .as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                DOWNLOAD_RETRY_POLICY_KEY,
+                settings
+                    .download_retry_policy
+                    .map(|policy| serde_json::json!(policy).to_string())
+                    .as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                MANIFEST_SIDECAR_KEY,
+                settings.manifest_sidecar.then_some("true"),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                NESTED_ARCHIVES_KEY,
+                settings
+                    .nested_archives
+                    .map(|nested| serde_json::json!(nested).to_string())
+                    .as_deref(),
+            )
+            .await?;
+        transaction
+            .set_setting(
+                POST_DOWNLOAD_HOOKS_KEY,
+                (!settings.post_download_hooks.is_empty())
+                    .then(|| serde_json::json!(settings.post_download_hooks).to_string())
+                    .as_deref(),
+            )
+            .await?;
         transaction.commit().await?;
 
         Ok(())
@@ -516,8 +860,9 @@ impl Storage {
 
     pub async fn accounts(&self) -> Result<Vec<Account>> {
         let rows = sqlx::query(
-            "SELECT id, label, login_name, credential_ref, enabled, created_at,
-                    updated_at, last_login_at, last_sync_at
+            "SELECT id, label, login_name, credential_ref, proxy_url, proxy_username,
+                    proxy_credential_ref, enabled, created_at, updated_at, last_login_at,
+                    last_sync_at
              FROM accounts
              ORDER BY label COLLATE NOCASE ASC, id ASC",
         )
@@ -606,8 +951,8 @@ impl Storage {
         account_id: Option<&str>,
     ) -> Result<Account> {
         let mut builder = QueryBuilder::<Sqlite>::new(
-            "SELECT a.id, a.label, a.login_name, a.credential_ref, a.enabled,
-                    a.created_at, a.updated_at, a.last_login_at, a.last_sync_at
+            "SELECT a.id, a.label, a.login_name, a.credential_ref, a.proxy_url,
+                    a.proxy_username, a.proxy_credential_ref, a.enabled, a.created_at, a.updated_at, a.last_login_at, a.last_sync_at
              FROM account_works aw
              JOIN accounts a ON a.id = aw.account_id
              WHERE aw.work_id = ",
@@ -639,22 +984,42 @@ impl Storage {
     }
 
     pub async fn work_download_state(&self, work_id: &str) -> Result<WorkDownloadState> {
-        let row = sqlx::query(
-            "SELECT status, local_path, staging_path, unpack_policy, bytes_received,
-                    bytes_total, error_code, error_message, started_at, completed_at,
-                    updated_at
-             FROM work_downloads
-             WHERE work_id = ?1",
-        )
-        .bind(work_id)
-        .fetch_optional(&self.pool)
-        .await?;
+        let mut builder = QueryBuilder::<Sqlite>::new(format!(
+            "SELECT wd.status, wd.local_path, wd.staging_path, wd.archive_vault_path,
+                    wd.unpack_policy,
+                    wd.bytes_received, wd.bytes_total, wd.error_code, wd.error_message,
+                    wd.started_at, wd.completed_at, wd.updated_at,
+                    {UPDATE_AVAILABLE_SQL} AS update_available
+             FROM work_downloads wd
+             LEFT JOIN works w ON w.work_id = wd.work_id
+             WHERE wd.work_id = "
+        ));
+        builder.push_bind(work_id);
+        let row = builder.build().fetch_optional(&self.pool).await?;
 
         row.map(work_download_state_from_row)
             .transpose()
             .map(|state| state.unwrap_or_default())
     }
 
+    /// Returns downloaded works whose upstream version is newer than the local copy.
+    pub async fn works_with_update_available(&self) -> Result<Vec<String>> {
+        let rows = QueryBuilder::<Sqlite>::new(format!(
+            "SELECT w.work_id
+             FROM work_downloads wd
+             JOIN works w ON w.work_id = wd.work_id
+             WHERE {UPDATE_AVAILABLE_SQL}
+             ORDER BY w.work_id ASC"
+        ))
+        .build()
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(row.try_get("work_id")?))
+            .collect()
+    }
+
     pub async fn save_work_download(&self, download: &WorkDownloadUpdate) -> Result<()> {
         let mut transaction = self.begin_write().await?;
 
@@ -669,6 +1034,99 @@ impl Storage {
         transaction.commit().await
     }
 
+    pub async fn replace_work_file_manifest(
+        &self,
+        work_id: &str,
+        entries: &[WorkFileManifestEntry],
+        recorded_at: &str,
+    ) -> Result<()> {
+        let mut transaction = self.begin_write().await?;
+
+        transaction
+            .replace_work_file_manifest(work_id, entries, recorded_at)
+            .await?;
+        transaction.commit().await
+    }
+
+    pub async fn work_file_manifest(&self, work_id: &str) -> Result<Vec<WorkFileManifestEntry>> {
+        let rows = sqlx::query(
+            "SELECT relative_path, size_bytes, sha256
+             FROM work_file_manifests
+             WHERE work_id = ?1
+             ORDER BY relative_path ASC",
+        )
+        .bind(work_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(WorkFileManifestEntry {
+                    relative_path: row.try_get("relative_path")?,
+                    size_bytes: i64_to_u64(
+                        row.try_get("size_bytes")?,
+                        "work_file_manifests.size_bytes",
+                    )?,
+                    sha256: row.try_get("sha256")?,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn verifiable_work_downloads(&self) -> Result<Vec<VerifiableWorkDownload>> {
+        let rows = sqlx::query(
+            "SELECT wd.work_id, wd.local_path, wd.archive_vault_path, wd.status
+             FROM work_downloads wd
+             WHERE wd.status IN ('downloaded', 'corrupted')
+                AND wd.local_path IS NOT NULL
+                AND EXISTS (
+                    SELECT 1
+                    FROM work_file_manifests wfm
+                    WHERE wfm.work_id = wd.work_id
+                )
+             ORDER BY wd.work_id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let status: String = row.try_get("status")?;
+
+                Ok(VerifiableWorkDownload {
+                    work_id: row.try_get("work_id")?,
+                    local_path: row.try_get("local_path")?,
+                    archive_vault_path: row.try_get("archive_vault_path")?,
+                    status: WorkDownloadStatus::from_storage_value(&status)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Flags a finished download as corrupted with `problem`, or clears the flag when `None`.
+    pub async fn set_work_download_integrity(
+        &self,
+        work_id: &str,
+        problem: Option<&str>,
+        updated_at: &str,
+    ) -> Result<()> {
+        let result = sqlx::query(
+            "UPDATE work_downloads
+             SET status = CASE WHEN ?2 IS NULL THEN 'downloaded' ELSE 'corrupted' END,
+                 error_code = CASE WHEN ?2 IS NULL THEN NULL ELSE 'integrity' END,
+                 error_message = ?2,
+                 updated_at = ?3
+             WHERE work_id = ?1 AND status IN ('downloaded', 'corrupted')",
+        )
+        .bind(work_id)
+        .bind(problem)
+        .bind(updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        ensure_changed(result, "finished work download", work_id)
+    }
+
     pub async fn import_local_work_downloads(
         &self,
         imports: &[LocalWorkDownloadImport],
@@ -749,6 +1207,60 @@ impl Storage {
         Ok(tags_by_work_id.remove(work_id).unwrap_or_default())
     }
 
+    /// Sets or, with `None`, clears the encoding archive entry names of the work are read with.
+    pub async fn set_work_archive_name_encoding(
+        &self,
+        work_id: &str,
+        name_encoding: Option<&str>,
+    ) -> Result<()> {
+        let mut transaction = self.begin_write().await?;
+
+        transaction
+            .set_work_archive_name_encoding(work_id, name_encoding)
+            .await?;
+        transaction.commit().await
+    }
+
+    pub async fn work_archive_name_encoding(&self, work_id: &str) -> Result<Option<String>> {
+        Ok(
+            sqlx::query_scalar(
+                "SELECT name_encoding FROM work_archive_settings WHERE work_id = ?1",
+            )
+            .bind(work_id)
+            .fetch_optional(&self.pool)
+            .await?,
+        )
+    }
+
+    pub async fn save_work_metadata(&self, metadata: &[WorkMetadata]) -> Result<()> {
+        let mut transaction = self.begin_write().await?;
+
+        for metadata in metadata {
+            transaction.save_work_metadata(metadata).await?;
+        }
+
+        transaction.commit().await
+    }
+
+    /// Returns up to `limit` cached works that the enrichment pass has not visited yet.
+    pub async fn works_missing_metadata(&self, limit: u32) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT w.work_id
+             FROM works w
+             LEFT JOIN work_metadata wm ON wm.work_id = w.work_id
+             WHERE wm.work_id IS NULL
+             ORDER BY w.work_id ASC
+             LIMIT ?1",
+        )
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(row.try_get("work_id")?))
+            .collect()
+    }
+
     pub async fn list_products(&self, query: &ProductListQuery) -> Result<ProductListPage> {
         let total_count = self.count_products(query).await?;
         let products = self.fetch_product_page(query).await?;
@@ -772,7 +1284,7 @@ impl Storage {
         );
 
         push_product_visibility_filter(&mut builder, query);
-        push_product_filters_internal(&mut builder, query, false, true);
+        push_product_filters_internal(&mut builder, query, Some(ProductFacet::Maker));
         builder.push(
             " AND w.maker_name IS NOT NULL
               AND trim(w.maker_name) <> ''
@@ -802,7 +1314,7 @@ impl Storage {
         );
 
         push_product_visibility_filter(&mut builder, query);
-        push_product_filters_internal(&mut builder, query, true, false);
+        push_product_filters_internal(&mut builder, query, Some(ProductFacet::CustomTag));
         builder.push(
             " GROUP BY wct.normalized_name
              ORDER BY work_count DESC, lower(tag_name) ASC, tag_name ASC",
@@ -820,17 +1332,79 @@ impl Storage {
             })
             .collect::<Result<Vec<_>>>()?;
 
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT
+                wg.genre_id AS genre_id,
+                MIN(wg.name) AS genre_name,
+                COUNT(DISTINCT w.work_id) AS work_count
+             FROM works w
+             JOIN work_genres wg ON wg.work_id = w.work_id
+             WHERE 1 = 1",
+        );
+
+        push_product_visibility_filter(&mut builder, query);
+        push_product_filters_internal(&mut builder, query, Some(ProductFacet::Genre));
+        builder.push(
+            " GROUP BY wg.genre_id
+             ORDER BY work_count DESC, lower(genre_name) ASC, wg.genre_id ASC",
+        );
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+        let genres = rows
+            .into_iter()
+            .map(|row| {
+                let count: i64 = row.try_get("work_count")?;
+                Ok(ProductGenreFacet {
+                    id: row.try_get("genre_id")?,
+                    name: row.try_get("genre_name")?,
+                    count: count.max(0) as u64,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT
+                wm.series_id AS series_id,
+                MIN(COALESCE(wm.series_name, wm.series_id)) AS series_name,
+                COUNT(DISTINCT w.work_id) AS work_count
+             FROM works w
+             JOIN work_metadata wm ON wm.work_id = w.work_id
+             WHERE wm.series_id IS NOT NULL",
+        );
+
+        push_product_visibility_filter(&mut builder, query);
+        push_product_filters_internal(&mut builder, query, Some(ProductFacet::Series));
+        builder.push(
+            " GROUP BY wm.series_id
+             ORDER BY work_count DESC, lower(series_name) ASC, wm.series_id ASC",
+        );
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+        let series = rows
+            .into_iter()
+            .map(|row| {
+                let count: i64 = row.try_get("work_count")?;
+                Ok(ProductSeriesFacet {
+                    id: row.try_get("series_id")?,
+                    name: row.try_get("series_name")?,
+                    count: count.max(0) as u64,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(ProductFilterFacets {
             makers,
             custom_tags,
+            genres,
+            series,
         })
     }
 
     pub async fn product_detail(&self, work_id: &str) -> Result<ProductDetail> {
-        let row = sqlx::query(
+        let mut builder = QueryBuilder::<Sqlite>::new(format!(
             "SELECT
                 w.work_id,
-                w.title,
+                {DISPLAY_TITLE_SQL} AS title,
                 w.title_json,
                 w.maker_id,
                 w.maker_name,
@@ -862,6 +1436,7 @@ impl Storage {
                 wd.status AS download_status,
                 wd.local_path AS download_local_path,
                 wd.staging_path AS download_staging_path,
+                wd.archive_vault_path AS download_archive_vault_path,
                 wd.unpack_policy AS download_unpack_policy,
                 wd.bytes_received AS download_bytes_received,
                 wd.bytes_total AS download_bytes_total,
@@ -869,30 +1444,56 @@ impl Storage {
                 wd.error_message AS download_error_message,
                 wd.started_at AS download_started_at,
                 wd.completed_at AS download_completed_at,
-                wd.updated_at AS download_updated_at
+                wd.updated_at AS download_updated_at,
+                {UPDATE_AVAILABLE_SQL} AS download_update_available
              FROM works w
              LEFT JOIN work_downloads wd ON wd.work_id = w.work_id
-             WHERE w.work_id = ?1
-                AND COALESCE(
-                    CASE
-                        WHEN json_valid(w.raw_json) THEN json_extract(w.raw_json, '$.detail_status')
-                    END,
-                    ''
-                ) <> ?2",
-        )
-        .bind(work_id)
-        .bind(MISSING_WORK_DETAIL_STATUS)
-        .fetch_optional(&self.pool)
-        .await?
-        .ok_or_else(|| StorageError::NotFound {
-            entity: "product detail",
-            id: work_id.to_owned(),
-        })?;
+             WHERE w.work_id = "
+        ));
+        builder.push_bind(work_id.to_owned());
+        builder.push(
+            " AND COALESCE(
+                CASE
+                    WHEN json_valid(w.raw_json) THEN json_extract(w.raw_json, '$.detail_status')
+                END,
+                ''
+            ) <> ",
+        );
+        builder.push_bind(MISSING_WORK_DETAIL_STATUS);
+        let row = builder
+            .build()
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| StorageError::NotFound {
+                entity: "product detail",
+                id: work_id.to_owned(),
+            })?;
 
         let raw_json: String = row.try_get("raw_json")?;
         let download = work_download_state_from_product_row(&row)?;
         let mut owners = self.product_owners(work_id).await?;
         let custom_tags = self.work_custom_tags(work_id).await?;
+        let archive_name_encoding = self.work_archive_name_encoding(work_id).await?;
+        let display_language = self.app_settings().await?.display_language;
+        let localized_texts = self.work_localized_texts(work_id).await?;
+        let metadata = self.work_metadata(work_id).await?;
+        let mut title_variants =
+            product_text_values_from_json(&row.try_get::<String, _>("title_json")?);
+        let mut maker_names = row
+            .try_get::<Option<String>, _>("maker_json")?
+            .as_deref()
+            .map(product_text_values_from_json)
+            .unwrap_or_default();
+        let mut tags = product_tags_from_raw_json(&raw_json);
+
+        for text in localized_texts {
+            push_missing_text_value(&mut title_variants, &text.language, text.title);
+            push_missing_text_value(&mut maker_names, &text.language, text.maker_name);
+
+            if display_language.as_deref() == Some(text.language.as_str()) {
+                tags = merge_localized_tags(tags, text.tags);
+            }
+        }
 
         if owners.is_empty() {
             owners.push(local_product_owner());
@@ -901,14 +1502,10 @@ impl Storage {
         Ok(ProductDetail {
             work_id: row.try_get("work_id")?,
             title: row.try_get("title")?,
-            title_variants: product_text_values_from_json(&row.try_get::<String, _>("title_json")?),
+            title_variants,
             maker_id: row.try_get("maker_id")?,
             maker_name: row.try_get("maker_name")?,
-            maker_names: row
-                .try_get::<Option<String>, _>("maker_json")?
-                .as_deref()
-                .map(product_text_values_from_json)
-                .unwrap_or_default(),
+            maker_names,
             work_type: row.try_get("work_type")?,
             age_category: row.try_get("age_category")?,
             thumbnail_url: row.try_get("thumbnail_url")?,
@@ -920,8 +1517,28 @@ impl Storage {
             earliest_purchased_at: row.try_get("earliest_purchased_at")?,
             latest_purchased_at: row.try_get("latest_purchased_at")?,
             credit_groups: product_credit_groups_from_raw_json(&raw_json),
-            tags: product_tags_from_raw_json(&raw_json),
+            tags,
             custom_tags,
+            archive_name_encoding,
+            description: metadata
+                .as_ref()
+                .and_then(|metadata| metadata.description.clone()),
+            series: metadata
+                .as_ref()
+                .and_then(|metadata| metadata.series.clone()),
+            genres: metadata
+                .as_ref()
+                .map(|metadata| metadata.genres.clone())
+                .unwrap_or_default(),
+            sample_image_urls: metadata
+                .as_ref()
+                .map(|metadata| metadata.sample_image_urls.clone())
+                .unwrap_or_default(),
+            file_formats: metadata
+                .as_ref()
+                .map(|metadata| metadata.file_formats.clone())
+                .unwrap_or_default(),
+            track_count: metadata.and_then(|metadata| metadata.track_count),
             download,
             owners,
         })
@@ -971,11 +1588,11 @@ impl Storage {
     }
 
     async fn fetch_product_page(&self, query: &ProductListQuery) -> Result<Vec<ProductListItem>> {
-        let mut builder = QueryBuilder::<Sqlite>::new(
+        let mut builder = QueryBuilder::<Sqlite>::new(format!(
             "WITH visible_works AS (
                  SELECT
                     w.work_id,
-                    lower(w.title) AS sort_title,
+                    lower({DISPLAY_TITLE_SQL}) AS sort_title,
                     COALESCE(w.published_at, '') AS sort_published_at,
                     (
                         SELECT MIN(owned_aw.purchased_at)
@@ -994,8 +1611,8 @@ impl Storage {
                             AND owned_a.enabled = 1
                     ) AS latest_purchased_at
                  FROM works w
-                 WHERE 1 = 1",
-        );
+                 WHERE 1 = 1"
+        ));
 
         push_product_visibility_filter(&mut builder, query);
         push_product_filters(&mut builder, query);
@@ -1005,11 +1622,11 @@ impl Storage {
         builder.push_bind(i64::from(query.limit));
         builder.push(" OFFSET ");
         builder.push_bind(i64::from(query.offset));
-        builder.push(
+        builder.push(format!(
             ")
              SELECT
                 w.work_id,
-                w.title,
+                {DISPLAY_TITLE_SQL} AS title,
                 w.maker_name,
                 w.work_type,
                 w.age_category,
@@ -1022,6 +1639,7 @@ impl Storage {
                 wd.status AS download_status,
                 wd.local_path AS download_local_path,
                 wd.staging_path AS download_staging_path,
+                wd.archive_vault_path AS download_archive_vault_path,
                 wd.unpack_policy AS download_unpack_policy,
                 wd.bytes_received AS download_bytes_received,
                 wd.bytes_total AS download_bytes_total,
@@ -1030,6 +1648,7 @@ impl Storage {
                 wd.started_at AS download_started_at,
                 wd.completed_at AS download_completed_at,
                 wd.updated_at AS download_updated_at,
+                {UPDATE_AVAILABLE_SQL} AS download_update_available,
                 a.id AS account_id,
                 a.label AS account_label,
                 aw.purchased_at
@@ -1046,8 +1665,8 @@ impl Storage {
                     AND visible_aw.is_current = 1
                     AND visible_a.enabled = 1
              )
-             ORDER BY ",
-        );
+             ORDER BY "
+        ));
         push_outer_product_sort(&mut builder, query.sort);
         builder.push(", lower(a.label) ASC, aw.account_id ASC");
 
@@ -1112,6 +1731,125 @@ impl Storage {
         Ok(products)
     }
 
+    /// Returns the ids among `work_ids` that have no localized text for `language` yet.
+    pub async fn works_missing_localized_text(
+        &self,
+        work_ids: &[String],
+        language: &str,
+    ) -> Result<Vec<String>> {
+        if work_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT work_id
+             FROM work_localized_texts
+             WHERE language = ",
+        );
+        builder.push_bind(language.to_owned());
+        builder.push(" AND work_id IN (");
+
+        for (index, work_id) in work_ids.iter().enumerate() {
+            if index > 0 {
+                builder.push(", ");
+            }
+            builder.push_bind(work_id);
+        }
+
+        builder.push(")");
+
+        let localized = builder
+            .build()
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| row.try_get::<String, _>("work_id"))
+            .collect::<std::result::Result<BTreeSet<_>, _>>()?;
+
+        Ok(work_ids
+            .iter()
+            .filter(|work_id| !localized.contains(*work_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn work_localized_texts(&self, work_id: &str) -> Result<Vec<WorkLocalizedText>> {
+        let rows = sqlx::query(
+            "SELECT work_id, language, title, maker_name, tags_json, fetched_at
+             FROM work_localized_texts
+             WHERE work_id = ?1
+             ORDER BY language ASC",
+        )
+        .bind(work_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(WorkLocalizedText {
+                    work_id: row.try_get("work_id")?,
+                    language: row.try_get("language")?,
+                    title: row.try_get("title")?,
+                    maker_name: row.try_get("maker_name")?,
+                    tags: product_tags_from_json(&row.try_get::<String, _>("tags_json")?),
+                    fetched_at: row.try_get("fetched_at")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn work_metadata(&self, work_id: &str) -> Result<Option<WorkMetadata>> {
+        let Some(row) = sqlx::query(
+            "SELECT
+                work_id, description, series_id, series_name, sample_images_json,
+                file_formats_json, track_count, fetched_at
+             FROM work_metadata
+             WHERE work_id = ?1",
+        )
+        .bind(work_id)
+        .fetch_optional(&self.pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+        let genres = sqlx::query(
+            "SELECT genre_id, name
+             FROM work_genres
+             WHERE work_id = ?1
+             ORDER BY lower(name) ASC, genre_id ASC",
+        )
+        .bind(work_id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| {
+            Ok(ProductGenre {
+                id: row.try_get("genre_id")?,
+                name: row.try_get("name")?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+        let series_id: Option<String> = row.try_get("series_id")?;
+        let series_name: Option<String> = row.try_get("series_name")?;
+        let track_count: Option<i64> = row.try_get("track_count")?;
+
+        Ok(Some(WorkMetadata {
+            work_id: row.try_get("work_id")?,
+            description: row.try_get("description")?,
+            series: series_id.map(|id| ProductSeries {
+                name: series_name.unwrap_or_else(|| id.clone()),
+                id,
+            }),
+            genres,
+            sample_image_urls: string_list_from_json(
+                &row.try_get::<String, _>("sample_images_json")?,
+            ),
+            file_formats: string_list_from_json(&row.try_get::<String, _>("file_formats_json")?),
+            track_count: track_count.and_then(|count| u32::try_from(count).ok()),
+            fetched_at: row.try_get("fetched_at")?,
+        }))
+    }
+
     async fn work_custom_tags_for_work_ids(
         &self,
         work_ids: &[String],
@@ -1223,10 +1961,11 @@ impl WriteTransaction<'_> {
 
         sqlx::query(
             "INSERT INTO accounts (
-                id, label, login_name, credential_ref, enabled, created_at, updated_at
+                id, label, login_name, credential_ref, proxy_url, proxy_username,
+                proxy_credential_ref, enabled, created_at, updated_at
              )
              VALUES (
-                ?1, ?2, ?3, ?4, ?5,
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8,
                 strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
                 strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
              )
@@ -1234,6 +1973,9 @@ impl WriteTransaction<'_> {
                 label = excluded.label,
                 login_name = excluded.login_name,
                 credential_ref = excluded.credential_ref,
+                proxy_url = excluded.proxy_url,
+                proxy_username = excluded.proxy_username,
+                proxy_credential_ref = excluded.proxy_credential_ref,
                 enabled = excluded.enabled,
                 updated_at = excluded.updated_at",
         )
@@ -1241,6 +1983,19 @@ impl WriteTransaction<'_> {
         .bind(&account.label)
         .bind(&account.login_name)
         .bind(&account.credential_ref)
+        .bind(account.proxy.as_ref().map(|proxy| &proxy.url))
+        .bind(
+            account
+                .proxy
+                .as_ref()
+                .and_then(|proxy| proxy.username.as_ref()),
+        )
+        .bind(
+            account
+                .proxy
+                .as_ref()
+                .and_then(|proxy| proxy.credential_ref.as_ref()),
+        )
         .bind(bool_to_i64(account.enabled))
         .execute(&mut **transaction)
         .await?;
@@ -1323,6 +2078,10 @@ impl WriteTransaction<'_> {
             self.upsert_work(work).await?;
         }
 
+        for localized_text in &sync.localized_texts {
+            self.upsert_work_localized_text(localized_text).await?;
+        }
+
         for account_work in &sync.account_works {
             self.upsert_account_work(
                 &sync.account_id,
@@ -1381,15 +2140,16 @@ impl WriteTransaction<'_> {
 
         sqlx::query(
             "INSERT INTO work_downloads (
-                work_id, status, local_path, staging_path, unpack_policy,
+                work_id, status, local_path, staging_path, archive_vault_path, unpack_policy,
                 bytes_received, bytes_total, error_code, error_message,
                 started_at, completed_at, updated_at
              )
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
              ON CONFLICT(work_id) DO UPDATE SET
                 status = excluded.status,
                 local_path = excluded.local_path,
                 staging_path = excluded.staging_path,
+                archive_vault_path = excluded.archive_vault_path,
                 unpack_policy = excluded.unpack_policy,
                 bytes_received = excluded.bytes_received,
                 bytes_total = excluded.bytes_total,
@@ -1403,6 +2163,7 @@ impl WriteTransaction<'_> {
         .bind(download.status.as_str())
         .bind(&download.local_path)
         .bind(&download.staging_path)
+        .bind(&download.archive_vault_path)
         .bind(&download.unpack_policy)
         .bind(u64_to_i64(
             download.bytes_received,
@@ -1436,6 +2197,49 @@ impl WriteTransaction<'_> {
             .bind(work_id)
             .execute(&mut **transaction)
             .await?;
+        sqlx::query("DELETE FROM work_file_manifests WHERE work_id = ?1")
+            .bind(work_id)
+            .execute(&mut **transaction)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn replace_work_file_manifest(
+        &mut self,
+        work_id: &str,
+        entries: &[WorkFileManifestEntry],
+        recorded_at: &str,
+    ) -> Result<()> {
+        self.ensure_work_exists(work_id).await?;
+        let transaction = self
+            .transaction
+            .as_mut()
+            .ok_or(StorageError::TransactionFinished)?;
+
+        sqlx::query("DELETE FROM work_file_manifests WHERE work_id = ?1")
+            .bind(work_id)
+            .execute(&mut **transaction)
+            .await?;
+
+        for entry in entries {
+            sqlx::query(
+                "INSERT INTO work_file_manifests (
+                    work_id, relative_path, size_bytes, sha256, recorded_at
+                 )
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )
+            .bind(work_id)
+            .bind(&entry.relative_path)
+            .bind(u64_to_i64(
+                entry.size_bytes,
+                "work_file_manifests.size_bytes",
+            )?)
+            .bind(&entry.sha256)
+            .bind(recorded_at)
+            .execute(&mut **transaction)
+            .await?;
+        }
 
         Ok(())
     }
@@ -1473,6 +2277,96 @@ impl WriteTransaction<'_> {
         Ok(())
     }
 
+    pub async fn set_work_archive_name_encoding(
+        &mut self,
+        work_id: &str,
+        name_encoding: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_work_exists(work_id).await?;
+        let transaction = self
+            .transaction
+            .as_mut()
+            .ok_or(StorageError::TransactionFinished)?;
+
+        match name_encoding {
+            Some(name_encoding) => {
+                sqlx::query(
+                    "INSERT INTO work_archive_settings (work_id, name_encoding, updated_at)
+                     VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+                     ON CONFLICT(work_id) DO UPDATE SET
+                        name_encoding = excluded.name_encoding,
+                        updated_at = excluded.updated_at",
+                )
+                .bind(work_id)
+                .bind(name_encoding)
+                .execute(&mut **transaction)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM work_archive_settings WHERE work_id = ?1")
+                    .bind(work_id)
+                    .execute(&mut **transaction)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn save_work_metadata(&mut self, metadata: &WorkMetadata) -> Result<()> {
+        self.ensure_work_exists(&metadata.work_id).await?;
+        let transaction = self
+            .transaction
+            .as_mut()
+            .ok_or(StorageError::TransactionFinished)?;
+
+        sqlx::query(
+            "INSERT INTO work_metadata (
+                work_id, description, series_id, series_name, sample_images_json,
+                file_formats_json, track_count, fetched_at
+             )
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(work_id) DO UPDATE SET
+                description = excluded.description,
+                series_id = excluded.series_id,
+                series_name = excluded.series_name,
+                sample_images_json = excluded.sample_images_json,
+                file_formats_json = excluded.file_formats_json,
+                track_count = excluded.track_count,
+                fetched_at = excluded.fetched_at",
+        )
+        .bind(&metadata.work_id)
+        .bind(&metadata.description)
+        .bind(metadata.series.as_ref().map(|series| &series.id))
+        .bind(metadata.series.as_ref().map(|series| &series.name))
+        .bind(string_list_to_json(&metadata.sample_image_urls))
+        .bind(string_list_to_json(&metadata.file_formats))
+        .bind(metadata.track_count.map(i64::from))
+        .bind(&metadata.fetched_at)
+        .execute(&mut **transaction)
+        .await?;
+
+        sqlx::query("DELETE FROM work_genres WHERE work_id = ?1")
+            .bind(&metadata.work_id)
+            .execute(&mut **transaction)
+            .await?;
+
+        for genre in &metadata.genres {
+            sqlx::query(
+                "INSERT INTO work_genres (work_id, genre_id, name)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(work_id, genre_id) DO UPDATE SET name = excluded.name",
+            )
+            .bind(&metadata.work_id)
+            .bind(&genre.id)
+            .bind(&genre.name)
+            .execute(&mut **transaction)
+            .await?;
+        }
+
+        Ok(())
+    }
+
     async fn insert_work_download_if_missing(
         &mut self,
         download: &WorkDownloadUpdate,
@@ -1485,17 +2379,18 @@ impl WriteTransaction<'_> {
 
         sqlx::query(
             "INSERT INTO work_downloads (
-                work_id, status, local_path, staging_path, unpack_policy,
+                work_id, status, local_path, staging_path, archive_vault_path, unpack_policy,
                 bytes_received, bytes_total, error_code, error_message,
                 started_at, completed_at, updated_at
              )
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
              ON CONFLICT(work_id) DO NOTHING",
         )
         .bind(&download.work_id)
         .bind(download.status.as_str())
         .bind(&download.local_path)
         .bind(&download.staging_path)
+        .bind(&download.archive_vault_path)
         .bind(&download.unpack_policy)
         .bind(u64_to_i64(
             download.bytes_received,
@@ -1643,6 +2538,42 @@ impl WriteTransaction<'_> {
         Ok(())
     }
 
+    async fn upsert_work_localized_text(&mut self, text: &WorkLocalizedText) -> Result<()> {
+        let transaction = self
+            .transaction
+            .as_mut()
+            .ok_or(StorageError::TransactionFinished)?;
+        let tags_json = serde_json::Value::Array(
+            text.tags
+                .iter()
+                .map(|tag| serde_json::json!({ "class": tag.class, "name": tag.name }))
+                .collect(),
+        )
+        .to_string();
+
+        sqlx::query(
+            "INSERT INTO work_localized_texts (
+                work_id, language, title, maker_name, tags_json, fetched_at
+             )
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(work_id, language) DO UPDATE SET
+                title = excluded.title,
+                maker_name = excluded.maker_name,
+                tags_json = excluded.tags_json,
+                fetched_at = excluded.fetched_at",
+        )
+        .bind(&text.work_id)
+        .bind(&text.language)
+        .bind(&text.title)
+        .bind(&text.maker_name)
+        .bind(tags_json)
+        .bind(&text.fetched_at)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
     async fn insert_work_if_missing(&mut self, work: &CachedWork) -> Result<()> {
         let transaction = self
             .transaction
@@ -1769,11 +2700,21 @@ impl WriteTransaction<'_> {
 }
 
 fn account_from_row(row: sqlx::sqlite::SqliteRow) -> Result<Account> {
+    let proxy_url: Option<String> = row.try_get("proxy_url")?;
+
     Ok(Account {
         id: row.try_get("id")?,
         label: row.try_get("label")?,
         login_name: row.try_get("login_name")?,
         credential_ref: row.try_get("credential_ref")?,
+        proxy: match proxy_url {
+            Some(url) => Some(AccountProxy {
+                url,
+                username: row.try_get("proxy_username")?,
+                credential_ref: row.try_get("proxy_credential_ref")?,
+            }),
+            None => None,
+        },
         enabled: i64_to_bool(row.try_get("enabled")?, "accounts.enabled")?,
         created_at: row.try_get("created_at")?,
         updated_at: row.try_get("updated_at")?,
@@ -1803,6 +2744,7 @@ fn work_download_state_from_row(row: sqlx::sqlite::SqliteRow) -> Result<WorkDown
         status: WorkDownloadStatus::from_storage_value(&status)?,
         local_path: row.try_get("local_path")?,
         staging_path: row.try_get("staging_path")?,
+        archive_vault_path: row.try_get("archive_vault_path")?,
         unpack_policy: row.try_get("unpack_policy")?,
         bytes_received: i64_to_u64(
             row.try_get("bytes_received")?,
@@ -1817,6 +2759,7 @@ fn work_download_state_from_row(row: sqlx::sqlite::SqliteRow) -> Result<WorkDown
         started_at: row.try_get("started_at")?,
         completed_at: row.try_get("completed_at")?,
         updated_at: row.try_get("updated_at")?,
+        update_available: row.try_get("update_available")?,
     })
 }
 
@@ -1831,6 +2774,7 @@ fn work_download_state_from_product_row(
         status: WorkDownloadStatus::from_storage_value(&status)?,
         local_path: row.try_get("download_local_path")?,
         staging_path: row.try_get("download_staging_path")?,
+        archive_vault_path: row.try_get("download_archive_vault_path")?,
         unpack_policy: row.try_get("download_unpack_policy")?,
         bytes_received: i64_to_u64(
             row.try_get("download_bytes_received")?,
@@ -1845,6 +2789,7 @@ fn work_download_state_from_product_row(
         started_at: row.try_get("download_started_at")?,
         completed_at: row.try_get("download_completed_at")?,
         updated_at: row.try_get("download_updated_at")?,
+        update_available: row.try_get("download_update_available")?,
     })
 }
 
@@ -1862,15 +2807,23 @@ fn push_product_visibility_filter(builder: &mut QueryBuilder<Sqlite>, query: &Pr
     builder.push(")");
 }
 
+/// A facet whose own selection is left out while its counts are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProductFacet {
+    Maker,
+    CustomTag,
+    Genre,
+    Series,
+}
+
 fn push_product_filters(builder: &mut QueryBuilder<Sqlite>, query: &ProductListQuery) {
-    push_product_filters_internal(builder, query, true, true);
+    push_product_filters_internal(builder, query, None);
 }
 
 fn push_product_filters_internal(
     builder: &mut QueryBuilder<Sqlite>,
     query: &ProductListQuery,
-    include_maker_filter: bool,
-    include_custom_tag_filters: bool,
+    counted_facet: Option<ProductFacet>,
 ) {
     builder.push(
         " AND COALESCE(
@@ -1924,8 +2877,8 @@ fn push_product_filters_internal(
         builder.push(")");
     }
 
-    let maker_names = product_maker_names(query);
-    if include_maker_filter && !maker_names.is_empty() {
+    let maker_names = product_filter_values(&query.maker_names);
+    if counted_facet != Some(ProductFacet::Maker) && !maker_names.is_empty() {
         builder.push(" AND w.maker_name IN (");
         for (index, maker_name) in maker_names.iter().enumerate() {
             if index > 0 {
@@ -1936,7 +2889,54 @@ fn push_product_filters_internal(
         builder.push(")");
     }
 
-    if include_custom_tag_filters {
+    if query.update_available_only {
+        builder.push(format!(
+            " AND EXISTS (
+                SELECT 1
+                FROM work_downloads wd
+                WHERE wd.work_id = w.work_id
+                    AND {UPDATE_AVAILABLE_SQL}
+            )"
+        ));
+    }
+
+    let genre_ids = product_filter_values(&query.genre_ids);
+    if counted_facet != Some(ProductFacet::Genre) && !genre_ids.is_empty() {
+        builder.push(
+            " AND EXISTS (
+                SELECT 1
+                FROM work_genres filter_wg
+                WHERE filter_wg.work_id = w.work_id
+                    AND filter_wg.genre_id IN (",
+        );
+        for (index, genre_id) in genre_ids.iter().enumerate() {
+            if index > 0 {
+                builder.push(", ");
+            }
+            builder.push_bind((*genre_id).to_owned());
+        }
+        builder.push("))");
+    }
+
+    let series_ids = product_filter_values(&query.series_ids);
+    if counted_facet != Some(ProductFacet::Series) && !series_ids.is_empty() {
+        builder.push(
+            " AND EXISTS (
+                SELECT 1
+                FROM work_metadata filter_wm
+                WHERE filter_wm.work_id = w.work_id
+                    AND filter_wm.series_id IN (",
+        );
+        for (index, series_id) in series_ids.iter().enumerate() {
+            if index > 0 {
+                builder.push(", ");
+            }
+            builder.push_bind((*series_id).to_owned());
+        }
+        builder.push("))");
+    }
+
+    if counted_facet != Some(ProductFacet::CustomTag) {
         let custom_tag_names = product_custom_tag_names(query);
         if !custom_tag_names.is_empty() {
             builder.push(
@@ -1998,6 +2998,49 @@ fn push_product_filters_internal(
         builder.push_bind(pattern.clone());
         builder.push(
             " ESCAPE '\\'
+                OR EXISTS (
+                    SELECT 1
+                    FROM json_each(CASE WHEN json_valid(w.title_json) THEN w.title_json ELSE '{}' END)
+                        AS title_variant
+                    WHERE title_variant.value LIKE ",
+        );
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " ESCAPE '\\')
+                OR EXISTS (
+                    SELECT 1
+                    FROM json_each(CASE WHEN json_valid(w.maker_json) THEN w.maker_json ELSE '{}' END)
+                        AS maker_variant
+                    WHERE maker_variant.value LIKE ",
+        );
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " ESCAPE '\\')
+                OR EXISTS (
+                    SELECT 1
+                    FROM work_localized_texts search_wlt
+                    WHERE search_wlt.work_id = w.work_id
+                        AND (
+                            search_wlt.title LIKE ",
+        );
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " ESCAPE '\\'
+                            OR search_wlt.maker_name LIKE ",
+        );
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " ESCAPE '\\'
+                            OR EXISTS (
+                                SELECT 1
+                                FROM json_each(search_wlt.tags_json) AS localized_tag
+                                WHERE json_extract(localized_tag.value, '$.name') LIKE ",
+        );
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " ESCAPE '\\')
+                        )
+                )
                 OR EXISTS (
                     SELECT 1
                     FROM json_each(
@@ -2018,6 +3061,24 @@ fn push_product_filters_internal(
                     WHERE search_wct.work_id = w.work_id
                         AND search_wct.name LIKE ",
         );
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " ESCAPE '\\')
+                OR EXISTS (
+                    SELECT 1
+                    FROM work_genres search_wg
+                    WHERE search_wg.work_id = w.work_id
+                        AND search_wg.name LIKE ",
+        );
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " ESCAPE '\\')
+                OR EXISTS (
+                    SELECT 1
+                    FROM work_metadata search_wm
+                    WHERE search_wm.work_id = w.work_id
+                        AND search_wm.series_name LIKE ",
+        );
         builder.push_bind(pattern);
         builder.push(" ESCAPE '\\')");
 
@@ -2061,7 +3122,7 @@ fn push_product_has_download_condition(builder: &mut QueryBuilder<Sqlite>) {
             SELECT 1
             FROM work_downloads source_wd
             WHERE source_wd.work_id = w.work_id
-                AND source_wd.status = 'downloaded'
+                AND source_wd.status IN ('downloaded', 'corrupted')
         )",
     );
 }
@@ -2126,17 +3187,17 @@ fn product_source_groups(query: &ProductListQuery) -> Vec<ProductSourceGroup> {
     values
 }
 
-fn product_maker_names(query: &ProductListQuery) -> Vec<&str> {
-    let mut names = Vec::new();
+fn product_filter_values(values: &[String]) -> Vec<&str> {
+    let mut unique = Vec::new();
 
-    for maker_name in &query.maker_names {
-        let maker_name = maker_name.trim();
-        if !maker_name.is_empty() {
-            push_unique_str(&mut names, maker_name);
+    for value in values {
+        let value = value.trim();
+        if !value.is_empty() {
+            push_unique_str(&mut unique, value);
         }
     }
 
-    names
+    unique
 }
 
 fn product_custom_tag_names(query: &ProductListQuery) -> Vec<String> {
@@ -2456,13 +3517,46 @@ fn product_text_values_from_json(raw_json: &str) -> Vec<ProductTextValue> {
         .collect()
 }
 
+fn push_missing_text_value(
+    values: &mut Vec<ProductTextValue>,
+    language: &str,
+    value: Option<String>,
+) {
+    let Some(value) = value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    else {
+        return;
+    };
+
+    if !values.iter().any(|existing| existing.language == language) {
+        values.push(ProductTextValue {
+            language: language.to_owned(),
+            value: value.to_owned(),
+        });
+        values.sort_by(|left, right| left.language.cmp(&right.language));
+    }
+}
+
 fn product_tags_from_raw_json(raw_json: &str) -> Vec<ProductTag> {
     let Ok(work) = serde_json::from_str::<RawWorkTags>(raw_json) else {
         return Vec::new();
     };
 
-    let mut tags = work
-        .tags
+    normalize_product_tags(work.tags)
+}
+
+fn product_tags_from_json(tags_json: &str) -> Vec<ProductTag> {
+    let Ok(tags) = serde_json::from_str::<Vec<RawWorkCreditTag>>(tags_json) else {
+        return Vec::new();
+    };
+
+    normalize_product_tags(tags)
+}
+
+fn normalize_product_tags(tags: Vec<RawWorkCreditTag>) -> Vec<ProductTag> {
+    let mut tags = tags
         .into_iter()
         .filter_map(|tag| {
             let class = tag.class.trim();
@@ -2479,13 +3573,45 @@ fn product_tags_from_raw_json(raw_json: &str) -> Vec<ProductTag> {
         })
         .collect::<Vec<_>>();
 
-    tags.sort_by(|left, right| {
-        left.class
+    sort_product_tags(&mut tags);
+    tags
+}
+
+/// Replaces tags of every class the localized set covers, keeping the rest.
+fn merge_localized_tags(mut tags: Vec<ProductTag>, localized: Vec<ProductTag>) -> Vec<ProductTag> {
+    let classes = localized
+        .iter()
+        .map(|tag| tag.class.clone())
+        .collect::<BTreeSet<_>>();
+
+    tags.retain(|tag| !classes.contains(&tag.class));
+    tags.extend(localized);
+    sort_product_tags(&mut tags);
+    tags
+}
+
+fn sort_product_tags(tags: &mut Vec<ProductTag>) {
+    tags.sort_by(|left, right| {
+        left.class
             .cmp(&right.class)
             .then_with(|| left.name.to_lowercase().cmp(&right.name.to_lowercase()))
     });
     tags.dedup();
-    tags
+}
+
+fn string_list_from_json(value: &str) -> Vec<String> {
+    serde_json::from_str(value).unwrap_or_default()
+}
+
+fn string_list_to_json(values: &[String]) -> String {
+    serde_json::Value::Array(
+        values
+            .iter()
+            .cloned()
+            .map(serde_json::Value::String)
+            .collect(),
+    )
+    .to_string()
 }
 
 fn json_value_as_u64(value: &serde_json::Value) -> Option<u64> {
@@ -2608,6 +3734,7 @@ mod tests {
             label: label.to_owned(),
             login_name: Some(format!("{id}@example.test")),
             credential_ref: Some(format!("account:{id}:password")),
+            proxy: None,
             enabled: true,
         }
     }
@@ -2687,6 +3814,7 @@ mod tests {
             completed_at: "2026-05-09T00:01:00.000Z".to_owned(),
             works,
             account_works,
+            localized_texts: Vec::new(),
         }
     }
 
@@ -2701,7 +3829,7 @@ mod tests {
             .fetch_one(&storage.pool)
             .await?;
 
-        assert_eq!(migration_count, 5);
+        assert_eq!(migration_count, 11);
 
         Ok(())
     }
@@ -2783,6 +3911,43 @@ mod tests {
         let settings = AppSettings {
             library_root: Some("/library".to_owned()),
             download_root: Some("/downloads".to_owned()),
+            archive_vault_root: Some("/vault".to_owned()),
+            display_language: Some("en_US".to_owned()),
+            download_connections_per_file: Some(4),
+            download_parallel_files: Some(3),
+            bandwidth_limit_bytes_per_second: Some(2 * 1024 * 1024),
+            bandwidth_schedules: vec![BandwidthScheduleSetting {
+                start_minute: 60,
+                end_minute: 420,
+                bytes_per_second: None,
+            }],
+            download_stall_timeout_seconds: Some(90),
+            download_min_speed_bytes_per_second: Some(10 * 1024),
+            download_retry_policy: Some(RetryPolicySetting {
+                max_retries: 5,
+                backoff: RetryBackoffSetting::Linear,
+                initial_delay_seconds: 2,
+                max_delay_seconds: 30,
+                jitter_percent: 10,
+                retry_network: true,
+                retry_stalled: true,
+                retry_incomplete: false,
+                retry_invalid_response: false,
+                deadline_seconds: Some(600),
+            }),
+            manifest_sidecar: true,
+            nested_archives: Some(NestedArchiveSetting {
+                max_depth: 2,
+                max_unpacked_bytes: 8 * 1024 * 1024 * 1024,
+                keep_inner_archives: true,
+            }),
+            post_download_hooks: vec![PostDownloadHookSetting {
+                name: "Playlist".to_owned(),
+                program: "make-playlist".to_owned(),
+                args: vec!["{localPath}".to_owned()],
+                enabled: true,
+                timeout_seconds: Some(300),
+            }],
         };
 
         storage.save_app_settings(&settings).await?;
@@ -2800,12 +3965,36 @@ mod tests {
             .save_app_settings(&AppSettings {
                 library_root: Some("/library".to_owned()),
                 download_root: Some("/downloads".to_owned()),
+                archive_vault_root: Some("/vault".to_owned()),
+                display_language: None,
+                download_connections_per_file: None,
+                download_parallel_files: None,
+                bandwidth_limit_bytes_per_second: None,
+                bandwidth_schedules: Vec::new(),
+                download_stall_timeout_seconds: None,
+                download_min_speed_bytes_per_second: None,
+                download_retry_policy: None,
+                manifest_sidecar: false,
+                nested_archives: None,
+                post_download_hooks: Vec::new(),
             })
             .await?;
         storage
             .save_app_settings(&AppSettings {
                 library_root: Some("/library".to_owned()),
                 download_root: None,
+                archive_vault_root: None,
+                display_language: None,
+                download_connections_per_file: None,
+                download_parallel_files: None,
+                bandwidth_limit_bytes_per_second: None,
+                bandwidth_schedules: Vec::new(),
+                download_stall_timeout_seconds: None,
+                download_min_speed_bytes_per_second: None,
+                download_retry_policy: None,
+                manifest_sidecar: false,
+                nested_archives: None,
+                post_download_hooks: Vec::new(),
             })
             .await?;
 
@@ -2814,6 +4003,18 @@ mod tests {
             AppSettings {
                 library_root: Some("/library".to_owned()),
                 download_root: None,
+                archive_vault_root: None,
+                display_language: None,
+                download_connections_per_file: None,
+                download_parallel_files: None,
+                bandwidth_limit_bytes_per_second: None,
+                bandwidth_schedules: Vec::new(),
+                download_stall_timeout_seconds: None,
+                download_min_speed_bytes_per_second: None,
+                download_retry_policy: None,
+                manifest_sidecar: false,
+                nested_archives: None,
+                post_download_hooks: Vec::new(),
             }
         );
 
@@ -2836,6 +4037,11 @@ mod tests {
                 label: "Renamed".to_owned(),
                 login_name: Some("renamed@example.test".to_owned()),
                 credential_ref: None,
+                proxy: Some(AccountProxy {
+                    url: "socks5://127.0.0.1:1080".to_owned(),
+                    username: Some("proxy-user".to_owned()),
+                    credential_ref: Some("account:account-a:proxy".to_owned()),
+                }),
                 enabled: true,
             })
             .await?;
@@ -2851,6 +4057,13 @@ mod tests {
             Some("renamed@example.test".to_owned())
         );
         assert_eq!(accounts[0].credential_ref, None);
+        assert_eq!(
+            accounts[0]
+                .proxy
+                .as_ref()
+                .map(|proxy| (proxy.url.as_str(), proxy.username.as_deref())),
+            Some(("socks5://127.0.0.1:1080", Some("proxy-user")))
+        );
         assert!(!accounts[0].enabled);
         assert_eq!(
             accounts[0].last_login_at,
@@ -2919,6 +4132,7 @@ mod tests {
                 status: WorkDownloadStatus::Downloaded,
                 local_path: Some("/library/RJ000002".to_owned()),
                 staging_path: None,
+                archive_vault_path: None,
                 unpack_policy: "manual".to_owned(),
                 bytes_received: 0,
                 bytes_total: None,
@@ -3317,6 +4531,7 @@ mod tests {
                 status: WorkDownloadStatus::Downloaded,
                 local_path: Some("/library/RJ000001".to_owned()),
                 staging_path: None,
+                archive_vault_path: None,
                 unpack_policy: "unpack_when_recognized".to_owned(),
                 bytes_received: 2048,
                 bytes_total: Some(2048),
@@ -3622,6 +4837,163 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn product_list_uses_display_language_and_searches_every_language() -> Result<()> {
+        let storage = migrated_storage().await?;
+        storage
+            .save_account(&account("account-a", "Account A"))
+            .await?;
+        storage
+            .commit_account_sync(&AccountSyncCommit {
+                localized_texts: vec![WorkLocalizedText {
+                    work_id: "RJ000002".to_owned(),
+                    language: "en_US".to_owned(),
+                    title: Some("Translated Release".to_owned()),
+                    maker_name: Some("Circle Two EN".to_owned()),
+                    tags: vec![ProductTag {
+                        class: "genre".to_owned(),
+                        name: "Healing".to_owned(),
+                    }],
+                    fetched_at: "2026-05-09T00:01:00.000Z".to_owned(),
+                }],
+                ..sync_commit(
+                    "account-a",
+                    "sync-a-1",
+                    vec![
+                        CachedWork {
+                            title_json: r#"{"ja_JP":"日本語タイトル","en_US":"English Title"}"#
+                                .to_owned(),
+                            ..work(
+                                "RJ000001",
+                                "日本語タイトル",
+                                "Circle One",
+                                "2026-01-01T00:00:00Z",
+                            )
+                        },
+                        work("RJ000002", "翻訳作品", "Circle Two", "2026-01-02T00:00:00Z"),
+                    ],
+                    vec![
+                        account_work("RJ000001", "2026-02-01T00:00:00Z"),
+                        account_work("RJ000002", "2026-02-02T00:00:00Z"),
+                    ],
+                )
+            })
+            .await?;
+
+        let titles = |page: &ProductListPage| {
+            page.products
+                .iter()
+                .map(|product| product.title.clone())
+                .collect::<Vec<_>>()
+        };
+        let query = ProductListQuery {
+            sort: ProductSort::TitleAsc,
+            ..ProductListQuery::default()
+        };
+
+        assert_eq!(
+            titles(&storage.list_products(&query).await?),
+            vec!["日本語タイトル", "翻訳作品"]
+        );
+
+        storage
+            .save_app_settings(&AppSettings {
+                display_language: Some("en_US".to_owned()),
+                ..AppSettings::default()
+            })
+            .await?;
+
+        assert_eq!(
+            titles(&storage.list_products(&query).await?),
+            vec!["English Title", "Translated Release"]
+        );
+        assert_eq!(
+            storage.product_detail("RJ000002").await?.tags,
+            vec![ProductTag {
+                class: "genre".to_owned(),
+                name: "Healing".to_owned(),
+            }]
+        );
+
+        for search in ["English Title", "Translated", "Circle Two EN", "Healing"] {
+            let page = storage
+                .list_products(&ProductListQuery {
+                    search: Some(search.to_owned()),
+                    ..ProductListQuery::default()
+                })
+                .await?;
+
+            assert_eq!(page.total_count, 1, "{search}");
+        }
+
+        assert_eq!(
+            storage
+                .works_missing_localized_text(
+                    &["RJ000001".to_owned(), "RJ000002".to_owned()],
+                    "en_US"
+                )
+                .await?,
+            vec!["RJ000001".to_owned()]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn work_archive_name_encoding_is_set_replaced_and_cleared() -> Result<()> {
+        let storage = migrated_storage().await?;
+        storage
+            .save_account(&account("account-a", "Account A"))
+            .await?;
+        storage
+            .commit_account_sync(&sync_commit(
+                "account-a",
+                "sync-a-1",
+                vec![work(
+                    "RJ000001",
+                    "First Work",
+                    "Circle One",
+                    "2026-01-01T00:00:00Z",
+                )],
+                vec![account_work("RJ000001", "2026-02-01T00:00:00Z")],
+            ))
+            .await?;
+
+        assert_eq!(storage.work_archive_name_encoding("RJ000001").await?, None);
+
+        storage
+            .set_work_archive_name_encoding("RJ000001", Some("cp437"))
+            .await?;
+        storage
+            .set_work_archive_name_encoding("RJ000001", Some("cp932"))
+            .await?;
+
+        assert_eq!(
+            storage
+                .product_detail("RJ000001")
+                .await?
+                .archive_name_encoding
+                .as_deref(),
+            Some("cp932")
+        );
+        assert!(storage
+            .set_work_archive_name_encoding("RJ000001", Some("latin1"))
+            .await
+            .is_err());
+        assert!(storage
+            .set_work_archive_name_encoding("RJ999999", Some("cp932"))
+            .await
+            .is_err());
+
+        storage
+            .set_work_archive_name_encoding("RJ000001", None)
+            .await?;
+
+        assert_eq!(storage.work_archive_name_encoding("RJ000001").await?, None);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn product_custom_tags_are_searchable_filterable_and_excludable() -> Result<()> {
         let storage = migrated_storage().await?;
@@ -3775,6 +5147,118 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn work_metadata_drives_detail_genre_and_series_browsing() -> Result<()> {
+        let storage = migrated_storage().await?;
+        storage
+            .save_account(&account("account-a", "Account A"))
+            .await?;
+        storage
+            .commit_account_sync(&sync_commit(
+                "account-a",
+                "sync-a-1",
+                vec![
+                    work("RJ000001", "Volume One", "Circle", "2026-01-01T00:00:00Z"),
+                    work("RJ000002", "Volume Two", "Circle", "2026-01-02T00:00:00Z"),
+                    work("RJ000003", "Standalone", "Circle", "2026-01-03T00:00:00Z"),
+                ],
+                vec![
+                    account_work("RJ000001", "2026-02-01T00:00:00Z"),
+                    account_work("RJ000002", "2026-02-02T00:00:00Z"),
+                    account_work("RJ000003", "2026-02-03T00:00:00Z"),
+                ],
+            ))
+            .await?;
+
+        assert_eq!(storage.works_missing_metadata(2).await?.len(), 2);
+
+        let genre = |id: &str, name: &str| ProductGenre {
+            id: id.to_owned(),
+            name: name.to_owned(),
+        };
+        let series = ProductSeries {
+            id: "SRI0001".to_owned(),
+            name: "Sleep Series".to_owned(),
+        };
+        let metadata = |work_id: &str, series: Option<ProductSeries>, genres| WorkMetadata {
+            work_id: work_id.to_owned(),
+            description: Some(format!("About {work_id}")),
+            series,
+            genres,
+            sample_image_urls: vec![format!("https://img.dlsite.jp/{work_id}_smp1.jpg")],
+            file_formats: vec!["WAV".to_owned(), "MP3".to_owned()],
+            track_count: Some(4),
+            fetched_at: "2026-05-09T00:00:00.000Z".to_owned(),
+        };
+        storage
+            .save_work_metadata(&[
+                metadata(
+                    "RJ000001",
+                    Some(series.clone()),
+                    vec![genre("497", "ASMR"), genre("044", "Healing")],
+                ),
+                metadata("RJ000002", Some(series.clone()), vec![genre("497", "ASMR")]),
+                metadata("RJ000003", None, Vec::new()),
+            ])
+            .await?;
+
+        assert!(storage.works_missing_metadata(10).await?.is_empty());
+
+        let detail = storage.product_detail("RJ000001").await?;
+        assert_eq!(detail.description.as_deref(), Some("About RJ000001"));
+        assert_eq!(detail.series, Some(series));
+        assert_eq!(
+            detail.genres,
+            vec![genre("497", "ASMR"), genre("044", "Healing")]
+        );
+        assert_eq!(detail.file_formats, vec!["WAV", "MP3"]);
+        assert_eq!(detail.sample_image_urls.len(), 1);
+        assert_eq!(detail.track_count, Some(4));
+
+        let healing = storage
+            .list_products(&ProductListQuery {
+                genre_ids: vec!["044".to_owned()],
+                ..ProductListQuery::default()
+            })
+            .await?;
+        let in_series = storage
+            .list_products(&ProductListQuery {
+                series_ids: vec!["SRI0001".to_owned()],
+                ..ProductListQuery::default()
+            })
+            .await?;
+        let searched = storage
+            .list_products(&ProductListQuery {
+                search: Some("Sleep Series".to_owned()),
+                ..ProductListQuery::default()
+            })
+            .await?;
+
+        assert_eq!(healing.total_count, 1);
+        assert_eq!(in_series.total_count, 2);
+        assert_eq!(searched.total_count, 2);
+
+        let facets = storage
+            .product_filter_facets(&ProductListQuery {
+                genre_ids: vec!["044".to_owned()],
+                ..ProductListQuery::default()
+            })
+            .await?;
+
+        assert_eq!(
+            facets
+                .genres
+                .iter()
+                .map(|facet| (facet.name.as_str(), facet.count))
+                .collect::<Vec<_>>(),
+            vec![("ASMR", 2), ("Healing", 1)]
+        );
+        assert_eq!(facets.series.len(), 1);
+        assert_eq!(facets.series[0].count, 1);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn product_list_can_filter_by_age_category() -> Result<()> {
         let storage = migrated_storage().await?;
@@ -3990,6 +5474,7 @@ mod tests {
                 status: WorkDownloadStatus::Downloaded,
                 local_path: Some("/library/RJ000001".to_owned()),
                 staging_path: Some("/downloads/RJ000001".to_owned()),
+                archive_vault_path: None,
                 unpack_policy: "unpack_when_recognized".to_owned(),
                 bytes_received: 42,
                 bytes_total: Some(42),
@@ -4021,6 +5506,192 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn flags_downloads_older_than_the_upstream_version() -> Result<()> {
+        let storage = migrated_storage().await?;
+        storage
+            .save_account(&account("account-a", "Account A"))
+            .await?;
+        let sync = |updated_at: &str| {
+            sync_commit(
+                "account-a",
+                "sync-a",
+                vec![
+                    CachedWork {
+                        updated_at: Some(updated_at.to_owned()),
+                        ..work("RJ000001", "Game", "Circle", "2026-01-01T00:00:00.000Z")
+                    },
+                    work("RJ000002", "Voice", "Circle", "2026-01-01T00:00:00.000Z"),
+                ],
+                vec![
+                    account_work("RJ000001", "2026-02-01T00:00:00Z"),
+                    account_work("RJ000002", "2026-02-01T00:00:00Z"),
+                ],
+            )
+        };
+        storage
+            .commit_account_sync(&sync("2026-01-01T00:00:00.000Z"))
+            .await?;
+        for work_id in ["RJ000001", "RJ000002"] {
+            storage
+                .save_work_download(&WorkDownloadUpdate {
+                    work_id: work_id.to_owned(),
+                    status: WorkDownloadStatus::Downloaded,
+                    local_path: Some(format!("/library/{work_id}")),
+                    staging_path: None,
+                    archive_vault_path: None,
+                    unpack_policy: "manual".to_owned(),
+                    bytes_received: 0,
+                    bytes_total: None,
+                    error_code: None,
+                    error_message: None,
+                    started_at: Some("2026-05-11T00:00:00.000Z".to_owned()),
+                    completed_at: Some("2026-05-11T00:00:00.000Z".to_owned()),
+                    updated_at: "2026-05-11T00:00:00.000Z".to_owned(),
+                })
+                .await?;
+        }
+
+        assert!(storage.works_with_update_available().await?.is_empty());
+
+        storage
+            .commit_account_sync(&AccountSyncCommit {
+                sync_run_id: "sync-a-2".to_owned(),
+                ..sync("2026-06-01T00:00:00.000Z")
+            })
+            .await?;
+        let updates = storage
+            .list_products(&ProductListQuery {
+                update_available_only: true,
+                ..ProductListQuery::default()
+            })
+            .await?;
+
+        assert_eq!(
+            storage.works_with_update_available().await?,
+            vec!["RJ000001"]
+        );
+        assert!(
+            storage
+                .work_download_state("RJ000001")
+                .await?
+                .update_available
+        );
+        assert!(
+            !storage
+                .work_download_state("RJ000002")
+                .await?
+                .update_available
+        );
+        assert_eq!(updates.total_count, 1);
+        assert!(updates.products[0].download.update_available);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn records_file_manifests_and_flags_corrupted_downloads() -> Result<()> {
+        let storage = migrated_storage().await?;
+        storage
+            .save_account(&account("account-a", "Account A"))
+            .await?;
+        storage
+            .commit_account_sync(&sync_commit(
+                "account-a",
+                "sync-a",
+                vec![work(
+                    "RJ000001",
+                    "Game",
+                    "Circle",
+                    "2026-01-01T00:00:00.000Z",
+                )],
+                vec![account_work("RJ000001", "2026-02-01T00:00:00Z")],
+            ))
+            .await?;
+        storage
+            .save_work_download(&WorkDownloadUpdate {
+                work_id: "RJ000001".to_owned(),
+                status: WorkDownloadStatus::Downloaded,
+                local_path: Some("/library/RJ000001".to_owned()),
+                staging_path: None,
+                archive_vault_path: None,
+                unpack_policy: "manual".to_owned(),
+                bytes_received: 0,
+                bytes_total: None,
+                error_code: None,
+                error_message: None,
+                started_at: Some("2026-05-11T00:00:00.000Z".to_owned()),
+                completed_at: Some("2026-05-11T00:00:00.000Z".to_owned()),
+                updated_at: "2026-05-11T00:00:00.000Z".to_owned(),
+            })
+            .await?;
+
+        assert!(storage.verifiable_work_downloads().await?.is_empty());
+
+        let manifest = vec![
+            WorkFileManifestEntry {
+                relative_path: "data/game.exe".to_owned(),
+                size_bytes: 3,
+                sha256: "a".repeat(64),
+            },
+            WorkFileManifestEntry {
+                relative_path: "readme.txt".to_owned(),
+                size_bytes: 0,
+                sha256: "b".repeat(64),
+            },
+        ];
+        storage
+            .replace_work_file_manifest("RJ000001", &manifest, "2026-05-11T00:00:00.000Z")
+            .await?;
+        storage
+            .replace_work_file_manifest("RJ000001", &manifest, "2026-05-12T00:00:00.000Z")
+            .await?;
+
+        assert_eq!(storage.work_file_manifest("RJ000001").await?, manifest);
+        assert_eq!(
+            storage.verifiable_work_downloads().await?,
+            vec![VerifiableWorkDownload {
+                work_id: "RJ000001".to_owned(),
+                local_path: "/library/RJ000001".to_owned(),
+                archive_vault_path: None,
+                status: WorkDownloadStatus::Downloaded,
+            }]
+        );
+
+        storage
+            .set_work_download_integrity(
+                "RJ000001",
+                Some("1 changed file"),
+                "2026-05-13T00:00:00.000Z",
+            )
+            .await?;
+        let corrupted = storage.work_download_state("RJ000001").await?;
+
+        assert_eq!(corrupted.status, WorkDownloadStatus::Corrupted);
+        assert_eq!(corrupted.error_code.as_deref(), Some("integrity"));
+        assert_eq!(corrupted.error_message.as_deref(), Some("1 changed file"));
+
+        storage
+            .set_work_download_integrity("RJ000001", None, "2026-05-14T00:00:00.000Z")
+            .await?;
+        let repaired = storage.work_download_state("RJ000001").await?;
+
+        assert_eq!(repaired.status, WorkDownloadStatus::Downloaded);
+        assert_eq!(repaired.error_message, None);
+
+        storage.delete_work_download("RJ000001").await?;
+
+        assert!(storage.work_file_manifest("RJ000001").await?.is_empty());
+        assert!(matches!(
+            storage
+                .set_work_download_integrity("RJ000001", None, "2026-05-15T00:00:00.000Z")
+                .await,
+            Err(StorageError::NotFound { .. })
+        ));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn product_list_includes_local_only_download_imports() -> Result<()> {
         let storage = migrated_storage().await?;
@@ -4048,6 +5719,7 @@ mod tests {
                     status: WorkDownloadStatus::Downloaded,
                     local_path: Some("/library/[RJ123456] Local Folder".to_owned()),
                     staging_path: None,
+                    archive_vault_path: None,
                     unpack_policy: "manual".to_owned(),
                     bytes_received: 0,
                     bytes_total: None,
@@ -4114,6 +5786,7 @@ mod tests {
                     status: WorkDownloadStatus::Downloaded,
                     local_path: Some("/library/RJ123456".to_owned()),
                     staging_path: None,
+                    archive_vault_path: None,
                     unpack_policy: "manual".to_owned(),
                     bytes_received: 0,
                     bytes_total: None,
@@ -4200,6 +5873,7 @@ mod tests {
                     status: WorkDownloadStatus::Downloaded,
                     local_path: Some("/library/[RJ123456] Local Folder".to_owned()),
                     staging_path: None,
+                    archive_vault_path: None,
                     unpack_policy: "manual".to_owned(),
                     bytes_received: 0,
                     bytes_total: None,
@@ -4278,6 +5952,7 @@ mod tests {
                 status: WorkDownloadStatus::Downloaded,
                 local_path: Some("/library/RJ000001".to_owned()),
                 staging_path: None,
+                archive_vault_path: None,
                 unpack_policy: "unpack_when_recognized".to_owned(),
                 bytes_received: 42,
                 bytes_total: Some(42),
@@ -4313,6 +5988,7 @@ mod tests {
                     status: WorkDownloadStatus::Downloaded,
                     local_path: Some("/library/[RJ000001] Local Folder".to_owned()),
                     staging_path: None,
+                    archive_vault_path: None,
                     unpack_policy: "manual".to_owned(),
                     bytes_received: 0,
                     bytes_total: None,
@@ -5,20 +5,26 @@ use dm_jobs::{
     JobStatus,
 };
 use dm_library::{
-    AccountRemovalReport, AccountSyncRequest, BulkWorkDownloadPreview,
+    AccountRemovalReport, AccountSyncRequest, ArchiveNameEncoding, BulkWorkDownloadPreview,
     BulkWorkDownloadPreviewProgress, BulkWorkDownloadPreviewProgressSink,
     BulkWorkDownloadPreviewRequest, BulkWorkDownloadProgress, BulkWorkDownloadProgressSink,
-    BulkWorkDownloadReport, BulkWorkDownloadRequest, DlsitePublicMetadataSource, DlsiteSyncSource,
-    DlsiteWorkDownloadSource, Library, LocalWorkImportReport, LocalWorkImportRequest,
-    SaveAccountRequest, SyncProgress, SyncProgressSink, WorkDownloadMarkRequest,
-    WorkDownloadProgress, WorkDownloadProgressSink, WorkDownloadRemovalRequest,
-    WorkDownloadRequest,
+    BulkWorkDownloadReport, BulkWorkDownloadRequest, DiskSpaceCheck, DiskSpaceRoots,
+    DlsitePublicMetadataSource, DlsiteSyncSource, DlsiteWorkDownloadSource, Library,
+    LibraryVerifyProgress, LibraryVerifyProgressSink, LibraryVerifyReport, LibraryVerifyRequest,
+    LocalArchiveImportAction, LocalArchiveImportProgress, LocalArchiveImportProgressSink,
+    LocalArchiveImportReport, LocalArchiveImportRequest, LocalWorkImportReport,
+    LocalWorkImportRequest, NestedArchive, NestedArchiveOptions, NestedArchiveOutcome,
+    PostDownloadHookOutcome, PostDownloadHookSink, PreviousVersionPolicy, SaveAccountRequest,
+    SyncProgress, SyncProgressSink, WorkDownloadMarkRequest, WorkDownloadProgress,
+    WorkDownloadProgressSink, WorkDownloadRemovalRequest, WorkDownloadRequest,
 };
 use dm_storage::{
-    Account, AppSettings, ProductAgeCategory, ProductCreditGroup, ProductCustomTag,
-    ProductCustomTagFacet, ProductDetail, ProductFilterFacets, ProductListItem, ProductListPage,
-    ProductListQuery, ProductMakerFacet, ProductOwner, ProductSort, ProductSourceGroup, ProductTag,
-    ProductTextValue, ProductTypeGroup, Storage, WorkDownloadState, WorkDownloadStatus,
+    Account, AppSettings, BandwidthScheduleSetting, NestedArchiveSetting, PostDownloadHookSetting,
+    ProductAgeCategory, ProductCreditGroup, ProductCustomTag, ProductCustomTagFacet, ProductDetail,
+    ProductFilterFacets, ProductGenre, ProductGenreFacet, ProductListItem, ProductListPage,
+    ProductListQuery, ProductMakerFacet, ProductOwner, ProductSeries, ProductSeriesFacet,
+    ProductSort, ProductSourceGroup, ProductTag, ProductTextValue, ProductTypeGroup,
+    RetryBackoffSetting, RetryPolicySetting, Storage, WorkDownloadState, WorkDownloadStatus,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -35,6 +41,7 @@ use tokio::sync::broadcast::error::RecvError;
 struct AppState {
     storage: Storage,
     library: Library,
+    api_config: dm_api::DlsiteClientConfig,
     jobs: JobManager,
     audit: AuditLogger,
     download_reservations: DownloadReservations,
@@ -43,6 +50,7 @@ struct AppState {
 
 const WORK_DOWNLOAD_PROGRESS_EVENT_INTERVAL: Duration = Duration::from_secs(1);
 const BULK_DOWNLOAD_PAGE_LIMIT: u32 = 500;
+const METADATA_ENRICHMENT_BATCH_LIMIT: u32 = 200;
 const DOWNLOAD_RESERVATION_METADATA_KEY: &str = "downloadReservationId";
 
 #[derive(Clone, Default)]
@@ -173,11 +181,27 @@ async fn save_settings(
 
     match result {
         Ok(()) => {
+            dm_download::BandwidthLimiter::global()
+                .set_policy(bandwidth_policy(&settings))
+                .await;
             record_audit(
                 &state.audit,
                 AuditEvent::succeeded("settings.save", "Saved settings").with_details(json!({
                     "libraryRootSet": settings.library_root.is_some(),
                     "downloadRootSet": settings.download_root.is_some(),
+                    "archiveVaultRootSet": settings.archive_vault_root.is_some(),
+                    "displayLanguage": settings.display_language,
+                    "downloadConnectionsPerFile": settings.download_connections_per_file,
+                    "downloadParallelFiles": settings.download_parallel_files,
+                    "bandwidthLimitBytesPerSecond": settings.bandwidth_limit_bytes_per_second,
+                    "bandwidthScheduleCount": settings.bandwidth_schedules.len(),
+                    "downloadStallTimeoutSeconds": settings.download_stall_timeout_seconds,
+                    "downloadMinSpeedBytesPerSecond":
+                        settings.download_min_speed_bytes_per_second,
+                    "downloadRetryPolicy": settings.download_retry_policy,
+                    "manifestSidecar": settings.manifest_sidecar,
+                    "nestedArchives": settings.nested_archives,
+                    "postDownloadHookCount": settings.post_download_hooks.len(),
                 })),
             )
             .await;
@@ -204,7 +228,7 @@ async fn list_accounts(state: State<'_, AppState>) -> Result<Vec<AccountDto>, St
     for account in accounts {
         let has_credential = state
             .library
-            .account_has_saved_password(&account)
+            .account_has_saved_credential(&account)
             .map_err(command_error)?;
 
         dtos.push(AccountDto::from_account(account, has_credential));
@@ -257,6 +281,7 @@ async fn save_account(
         "accountId": request.id.clone(),
         "hasLoginName": request.login_name.is_some(),
         "hasPassword": request.password.is_some(),
+        "hasProxy": request.proxy_url.is_some(),
         "enabled": request.enabled,
     });
     let result = state.library.save_account(request).await;
@@ -265,7 +290,7 @@ async fn save_account(
         Ok(account) => {
             let has_credential = state
                 .library
-                .account_has_saved_password(&account)
+                .account_has_saved_credential(&account)
                 .map_err(command_error)?;
 
             record_audit(
@@ -426,13 +451,19 @@ async fn get_product_detail(
     request: GetProductDetailRequest,
 ) -> Result<ProductDetailDto, String> {
     let work_id = normalize_required_id(request.work_id)?;
-
-    state
+    let mut detail = state
         .library
         .product_detail(&work_id)
         .await
         .map(ProductDetailDto::from)
-        .map_err(command_error)
+        .map_err(command_error)?;
+
+    detail.archive_password_saved = state
+        .library
+        .product_has_archive_password(&work_id)
+        .map_err(command_error)?;
+
+    Ok(detail)
 }
 
 #[tauri::command]
@@ -500,6 +531,145 @@ async fn set_product_custom_tags(
     }
 }
 
+#[tauri::command]
+async fn set_product_archive_name_encoding(
+    state: State<'_, AppState>,
+    request: SetProductArchiveNameEncodingRequest,
+) -> Result<(), String> {
+    let work_id = match normalize_required_id(request.work_id) {
+        Ok(work_id) => work_id,
+        Err(error) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "product.archiveEncoding.update",
+                    "Failed to validate archive name encoding",
+                )
+                .with_error(Some("validation"), error.clone()),
+            )
+            .await;
+            return Err(error);
+        }
+    };
+    let name_encoding = ArchiveNameEncoding::from(request.name_encoding);
+    let result = state
+        .library
+        .set_product_archive_name_encoding(&work_id, name_encoding)
+        .await;
+
+    match result {
+        Ok(()) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::succeeded(
+                    "product.archiveEncoding.update",
+                    "Updated product archive name encoding",
+                )
+                .with_details(json!({
+                    "workId": work_id,
+                    "nameEncoding": archive_name_encoding_label(name_encoding),
+                })),
+            )
+            .await;
+            Ok(())
+        }
+        Err(error) => {
+            let message = command_error(error);
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "product.archiveEncoding.update",
+                    "Failed to update product archive name encoding",
+                )
+                .with_error(Some("library"), message.clone())
+                .with_details(json!({
+                    "workId": work_id,
+                    "nameEncoding": archive_name_encoding_label(name_encoding),
+                })),
+            )
+            .await;
+            Err(message)
+        }
+    }
+}
+
+#[tauri::command]
+async fn set_product_archive_password(
+    state: State<'_, AppState>,
+    request: SetProductArchivePasswordRequest,
+) -> Result<(), String> {
+    let work_id = match normalize_required_id(request.work_id) {
+        Ok(work_id) => work_id,
+        Err(error) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "product.archivePassword.update",
+                    "Failed to validate archive password",
+                )
+                .with_error(Some("validation"), error.clone()),
+            )
+            .await;
+            return Err(error);
+        }
+    };
+    let password = match normalize_secret(request.password) {
+        Ok(password) => password,
+        Err(error) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "product.archivePassword.update",
+                    "Failed to validate archive password",
+                )
+                .with_error(Some("validation"), error.clone())
+                .with_details(json!({ "workId": work_id })),
+            )
+            .await;
+            return Err(error);
+        }
+    };
+    let saved = password.is_some();
+    let result = state
+        .library
+        .set_product_archive_password(&work_id, password.as_deref());
+
+    match result {
+        Ok(()) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::succeeded(
+                    "product.archivePassword.update",
+                    "Updated product archive password",
+                )
+                .with_details(json!({
+                    "workId": work_id,
+                    "saved": saved,
+                })),
+            )
+            .await;
+            Ok(())
+        }
+        Err(error) => {
+            let message = command_error(error);
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "product.archivePassword.update",
+                    "Failed to update product archive password",
+                )
+                .with_error(Some("library"), message.clone())
+                .with_details(json!({
+                    "workId": work_id,
+                    "saved": saved,
+                })),
+            )
+            .await;
+            Err(message)
+        }
+    }
+}
+
 #[tauri::command]
 async fn start_account_sync(
     state: State<'_, AppState>,
@@ -534,6 +704,7 @@ async fn start_account_sync(
         }
     };
     let library = state.library.clone();
+    let api_config = state.api_config.clone();
     let (local_scan_library_root, local_scan_skip_reason) = match state.storage.app_settings().await
     {
         Ok(settings) => match required_library_root(&settings) {
@@ -560,10 +731,9 @@ async fn start_account_sync(
         metadata,
         move |context| async move {
             context.info("Preparing account sync");
-            let client = dm_api::DlsiteClient::new(dm_api::DlsiteClientConfig::default())
+            let client = dm_api::DlsiteClient::new(api_config)
                 .map_err(|error| JobFailure::with_code("api_client", error.to_string()))?;
-            let source = DlsiteSyncSource::new(client.clone());
-            let metadata_source = DlsitePublicMetadataSource::new(client);
+            let source = DlsiteSyncSource::new(client);
             let progress_sink = JobSyncProgressSink {
                 context: context.clone(),
             };
@@ -579,6 +749,8 @@ async fn start_account_sync(
                 )
                 .await
                 .map_err(account_sync_failure)?;
+            // Reuse the account's proxied client for public metadata lookups.
+            let metadata_source = DlsitePublicMetadataSource::new(source.client());
             let local_scan_output = match &local_scan_library_root {
                 Some(library_root) => {
                     context.set_phase("scanningLocalDownloads");
@@ -640,6 +812,35 @@ async fn start_account_sync(
                     })
                 }
             };
+            context.set_phase("enrichingMetadata");
+            context.clear_progress();
+            context.info("Loading product descriptions, genres, and series");
+            let metadata_output = match library
+                .enrich_work_metadata(&metadata_source, METADATA_ENRICHMENT_BATCH_LIMIT)
+                .await
+            {
+                Ok(report) => {
+                    context.info(format!(
+                        "Enriched metadata for {} of {} works",
+                        report.enriched_count, report.requested_count
+                    ));
+                    json!({
+                        "status": "succeeded",
+                        "requestedCount": report.requested_count,
+                        "enrichedCount": report.enriched_count,
+                    })
+                }
+                Err(error) => {
+                    let message = error.support_message();
+
+                    context.warn(format!("Metadata enrichment failed after sync: {message}"));
+                    json!({
+                        "status": "failed",
+                        "errorCode": error.failure_code(),
+                        "errorMessage": message,
+                    })
+                }
+            };
             let mut output = JobMetadata::new();
 
             output.insert("accountId".to_owned(), json!(report.account_id));
@@ -655,7 +856,27 @@ async fn start_account_sync(
             );
             output.insert("pageLimit".to_owned(), json!(report.page_limit));
             output.insert("concurrency".to_owned(), json!(report.concurrency));
+            output.insert(
+                "localizationError".to_owned(),
+                json!(report.localization_error),
+            );
+            output.insert(
+                "updateAvailableWorkIds".to_owned(),
+                json!(report.update_available_work_ids),
+            );
             output.insert("localScan".to_owned(), local_scan_output);
+            output.insert("metadataEnrichment".to_owned(), metadata_output);
+            if !report.update_available_work_ids.is_empty() {
+                context.info(format!(
+                    "{} downloaded works have a newer version on DLsite",
+                    report.update_available_work_ids.len()
+                ));
+            }
+            if let Some(error) = &report.localization_error {
+                context.warn(format!(
+                    "Some translated titles could not be loaded: {error}"
+                ));
+            }
             if report.missing_detail_count > 0 {
                 context.warn(format!(
                     "{} purchased works were missing details from content/works",
@@ -778,11 +999,26 @@ async fn start_work_download(
         }
     };
     let unpack_policy = request.unpack_policy.unwrap_or_default().into();
+    let archive_vault_root = settings.archive_vault_root.as_ref().map(PathBuf::from);
     let replace_existing = request.replace_existing.unwrap_or(false);
+    let resume_unpack = request.resume_unpack.unwrap_or(false);
+    let previous_version = request.previous_version.unwrap_or_default().into();
+    let connections_per_file = download_connections_per_file(&settings);
+    let parallel_files = download_parallel_files(&settings);
+    let retry_policy = download_retry_policy(settings.download_retry_policy);
+    let stall_policy = download_stall_policy(&settings);
+    let manifest_sidecar = settings.manifest_sidecar;
+    let nested_archives = nested_archive_options(settings.nested_archives);
+    let post_download_hooks = settings.post_download_hooks;
     let library = state.library.clone();
+    let api_config = state.api_config.clone();
+    let audit = state.audit.clone();
     let mut metadata = JobMetadata::new();
 
     metadata.insert("workId".to_owned(), json!(work_id.clone()));
+    if resume_unpack {
+        metadata.insert("resumeUnpack".to_owned(), json!(true));
+    }
     if let Some(account_id) = &account_id {
         metadata.insert("accountId".to_owned(), json!(account_id));
     }
@@ -849,28 +1085,40 @@ async fn start_work_download(
         format!("Download {job_work_id}"),
         metadata,
         move |context| async move {
-            context.info("Preparing download");
-            let client = dm_api::DlsiteClient::new(dm_api::DlsiteClientConfig::default())
-                .map_err(|error| JobFailure::with_code("api_client", error.to_string()))?;
-            let source = DlsiteWorkDownloadSource::new(client);
             let progress_sink = JobWorkDownloadProgressSink::new(context.clone());
-            let report = library
-                .download_work_with_source(
-                    WorkDownloadRequest {
-                        work_id: &job_work_id,
-                        account_id: account_id.as_deref(),
-                        password: password.as_deref(),
-                        library_root: &library_root,
-                        download_root: &download_root,
-                        unpack_policy,
-                        replace_existing,
-                        cancellation_token: Some(context.cancellation_token()),
-                        progress_sink: Some(&progress_sink),
-                    },
-                    &source,
-                )
-                .await
-                .map_err(work_download_failure)?;
+            let download_request = WorkDownloadRequest {
+                work_id: &job_work_id,
+                account_id: account_id.as_deref(),
+                password: password.as_deref(),
+                library_root: &library_root,
+                download_root: &download_root,
+                unpack_policy,
+                archive_vault_root: archive_vault_root.as_deref(),
+                nested_archives,
+                connections_per_file,
+                parallel_files,
+                retry_policy,
+                stall_policy,
+                manifest_sidecar,
+                replace_existing,
+                previous_version,
+                cancellation_token: Some(context.cancellation_token()),
+                progress_sink: Some(&progress_sink),
+            };
+            let report = if resume_unpack {
+                context.info("Unpacking staged download again");
+                library.resume_work_unpack(download_request).await
+            } else {
+                context.info("Preparing download");
+                let client = dm_api::DlsiteClient::new(api_config)
+                    .map_err(|error| JobFailure::with_code("api_client", error.to_string()))?;
+                let source = DlsiteWorkDownloadSource::new(client);
+
+                library
+                    .download_work_with_source(download_request, &source)
+                    .await
+            }
+            .map_err(work_download_failure)?;
             let mut output = JobMetadata::new();
 
             output.insert("workId".to_owned(), json!(report.work_id));
@@ -884,8 +1132,78 @@ async fn start_work_download(
                 "archiveExtracted".to_owned(),
                 json!(report.archive_extracted),
             );
+            if let Some(name_encoding) = report.archive_name_encoding {
+                output.insert(
+                    "archiveNameEncoding".to_owned(),
+                    json!(archive_name_encoding_label(name_encoding)),
+                );
+            }
+            if !report.archive_fallback_names.is_empty() {
+                context.warn(format!(
+                    "Extracted {} archive entries under undecoded fallback names",
+                    report.archive_fallback_names.len()
+                ));
+                output.insert(
+                    "archiveFallbackNames".to_owned(),
+                    json!(report
+                        .archive_fallback_names
+                        .iter()
+                        .map(|name| {
+                            json!({
+                                "rawName": name.raw_name,
+                                "extractedName": name.extracted_name,
+                            })
+                        })
+                        .collect::<Vec<_>>()),
+                );
+            }
+            if !report.nested_archives.is_empty() {
+                let left_packed = count_nested_archives_left_packed(&report.nested_archives);
+
+                if left_packed > 0 {
+                    context.warn(format!(
+                        "Left {left_packed} nested archives packed at the depth or size limit"
+                    ));
+                }
+                for failure in nested_archive_failures(&report.nested_archives) {
+                    context.warn(format!("Couldn't unpack nested archive {failure}"));
+                }
+                output.insert(
+                    "nestedArchives".to_owned(),
+                    nested_archive_output(&report.nested_archives),
+                );
+            }
+            if let Some(error) = &report.manifest_error {
+                context.warn(format!("Couldn't record the file manifest: {error}"));
+                output.insert("manifestError".to_owned(), json!(error));
+            }
+            if let Some(previous_version_path) = &report.previous_version_path {
+                let previous_version_path = previous_version_path.to_string_lossy().to_string();
+
+                context.info(format!("Kept previous version at {previous_version_path}"));
+                output.insert(
+                    "previousVersionPath".to_owned(),
+                    json!(previous_version_path),
+                );
+            }
             context.info(format!("Downloaded {}", job_work_id));
 
+            let hook_outcomes = run_post_download_hooks(
+                &context,
+                &library,
+                &audit,
+                &post_download_hooks,
+                &report.work_id,
+                &report.local_path,
+            )
+            .await;
+            if !hook_outcomes.is_empty() {
+                output.insert(
+                    "postDownloadHooks".to_owned(),
+                    post_download_hook_output(&hook_outcomes),
+                );
+            }
+
             Ok(output)
         },
     );
@@ -897,6 +1215,8 @@ async fn start_work_download(
             "accountId": audit_account_id,
             "jobId": job_id.to_string(),
             "replaceExisting": replace_existing,
+            "resumeUnpack": resume_unpack,
+            "previousVersion": previous_version_label(previous_version),
             "unpackPolicy": unpack_policy_label(unpack_policy),
         })),
     )
@@ -928,6 +1248,21 @@ async fn start_bulk_work_download(
             return Err(error);
         }
     };
+    let retry_policy_override = match normalize_retry_policy(request.retry_policy) {
+        Ok(policy) => policy,
+        Err(error) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "work.bulkDownload.queue",
+                    "Failed to validate bulk download",
+                )
+                .with_error(Some("validation"), error.clone()),
+            )
+            .await;
+            return Err(error);
+        }
+    };
     let settings = match state.storage.app_settings().await {
         Ok(settings) => settings,
         Err(error) => {
@@ -975,7 +1310,16 @@ async fn start_bulk_work_download(
         }
     };
     let unpack_policy = request.unpack_policy.unwrap_or_default().into();
+    let archive_vault_root = settings.archive_vault_root.as_ref().map(PathBuf::from);
     let skip_downloaded = request.skip_downloaded.unwrap_or(true);
+    let connections_per_file = download_connections_per_file(&settings);
+    let parallel_files = download_parallel_files(&settings);
+    let retry_policy =
+        download_retry_policy(retry_policy_override.or(settings.download_retry_policy));
+    let stall_policy = download_stall_policy(&settings);
+    let manifest_sidecar = settings.manifest_sidecar;
+    let nested_archives = nested_archive_options(settings.nested_archives);
+    let post_download_hooks = settings.post_download_hooks;
     let candidates = match bulk_download_candidates(&state.library, &query, skip_downloaded).await {
         Ok(candidates) => candidates,
         Err(error) => {
@@ -999,6 +1343,8 @@ async fn start_bulk_work_download(
     let skipped_queued_count = reservation.reserved.len();
     let reserved_work_ids = reservation.available.clone();
     let library = state.library.clone();
+    let api_config = state.api_config.clone();
+    let audit = state.audit.clone();
     let mut metadata = JobMetadata::new();
 
     metadata.insert("search".to_owned(), json!(query.search.clone()));
@@ -1077,7 +1423,7 @@ async fn start_bulk_work_download(
                     "Skipping {skipped_queued_count} works already queued or downloading"
                 ));
             }
-            let client = dm_api::DlsiteClient::new(dm_api::DlsiteClientConfig::default())
+            let client = dm_api::DlsiteClient::new(api_config)
                 .map_err(|error| JobFailure::with_code("api_client", error.to_string()))?;
             let source = DlsiteWorkDownloadSource::new(client);
             let progress_sink = JobBulkWorkDownloadProgressSink {
@@ -1091,6 +1437,13 @@ async fn start_bulk_work_download(
                         library_root: &library_root,
                         download_root: &download_root,
                         unpack_policy,
+                        archive_vault_root: archive_vault_root.as_deref(),
+                        nested_archives,
+                        connections_per_file,
+                        parallel_files,
+                        retry_policy,
+                        stall_policy,
+                        manifest_sidecar,
                         skip_downloaded,
                         cancellation_token: Some(context.cancellation_token()),
                         progress_sink: Some(&progress_sink),
@@ -1099,7 +1452,34 @@ async fn start_bulk_work_download(
                 )
                 .await
                 .map_err(work_download_failure)?;
-            let output = bulk_download_output(&report, skipped_queued_count);
+            let mut output = bulk_download_output(&report, skipped_queued_count);
+            let mut hook_outcomes = Vec::new();
+
+            for success in &report.succeeded_works {
+                if let Some(error) = &success.manifest_error {
+                    context.warn(format!(
+                        "{}: couldn't record the file manifest: {error}",
+                        success.work_id
+                    ));
+                }
+                hook_outcomes.extend(
+                    run_post_download_hooks(
+                        &context,
+                        &library,
+                        &audit,
+                        &post_download_hooks,
+                        &success.work_id,
+                        &success.local_path,
+                    )
+                    .await,
+                );
+            }
+            if !hook_outcomes.is_empty() {
+                output.insert(
+                    "postDownloadHooks".to_owned(),
+                    post_download_hook_output(&hook_outcomes),
+                );
+            }
 
             context.info(format!(
                 "Bulk download finished: {} downloaded, {} failed, {} downloaded skips, {} queued skips",
@@ -1176,31 +1556,37 @@ async fn preview_bulk_work_download(
         }
     };
 
-    if let Err(error) = required_library_root(&settings) {
-        record_audit(
-            &state.audit,
-            AuditEvent::failed(
-                "work.bulkDownload.preview",
-                "Failed to resolve library folder",
+    let library_root = match required_library_root(&settings) {
+        Ok(library_root) => library_root,
+        Err(error) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "work.bulkDownload.preview",
+                    "Failed to resolve library folder",
+                )
+                .with_error(Some("settings"), error.clone()),
             )
-            .with_error(Some("settings"), error.clone()),
-        )
-        .await;
-        return Err(error);
-    }
-
-    if let Err(error) = effective_download_root(&app, &settings) {
-        record_audit(
-            &state.audit,
-            AuditEvent::failed(
-                "work.bulkDownload.preview",
-                "Failed to resolve download staging folder",
+            .await;
+            return Err(error);
+        }
+    };
+    let download_root = match effective_download_root(&app, &settings) {
+        Ok(download_root) => download_root,
+        Err(error) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "work.bulkDownload.preview",
+                    "Failed to resolve download staging folder",
+                )
+                .with_error(Some("settings"), error.clone()),
             )
-            .with_error(Some("settings"), error.clone()),
-        )
-        .await;
-        return Err(error);
-    }
+            .await;
+            return Err(error);
+        }
+    };
+    let unpack_policy = request.unpack_policy.unwrap_or_default().into();
 
     let skip_downloaded = request.skip_downloaded.unwrap_or(true);
     let candidates = match bulk_download_candidates(&state.library, &query, skip_downloaded).await {
@@ -1225,6 +1611,7 @@ async fn preview_bulk_work_download(
     let skipped_queued_count = reservation.reserved.len();
     let available_work_ids = reservation.available;
     let library = state.library.clone();
+    let api_config = state.api_config.clone();
     let (result_tx, result_rx) =
         tokio::sync::oneshot::channel::<Result<BulkWorkDownloadPreviewDto, String>>();
     let mut metadata = JobMetadata::new();
@@ -1253,7 +1640,7 @@ async fn preview_bulk_work_download(
         metadata,
         move |context| async move {
             context.info("Preparing bulk download plan");
-            let client = match dm_api::DlsiteClient::new(dm_api::DlsiteClientConfig::default()) {
+            let client = match dm_api::DlsiteClient::new(api_config) {
                 Ok(client) => client,
                 Err(error) => {
                     let message = error.to_string();
@@ -1271,6 +1658,11 @@ async fn preview_bulk_work_download(
                         query,
                         work_ids: Some(available_work_ids),
                         skip_downloaded,
+                        disk_space: Some(DiskSpaceRoots {
+                            library_root: &library_root,
+                            download_root: &download_root,
+                            unpack_policy,
+                        }),
                         cancellation_token: Some(context.cancellation_token()),
                         progress_sink: Some(&progress_sink),
                     },
@@ -1348,7 +1740,10 @@ async fn open_work_download(
         }
     };
 
-    if download.status != WorkDownloadStatus::Downloaded {
+    if !matches!(
+        download.status,
+        WorkDownloadStatus::Downloaded | WorkDownloadStatus::Corrupted
+    ) {
         let message = format!("{work_id} is not downloaded");
         record_audit(
             &state.audit,
@@ -1511,13 +1906,14 @@ async fn delete_work_download(
         }
     };
 
+    let archive_vault_root = settings.archive_vault_root.as_ref().map(PathBuf::from);
+
     let result = state
         .library
-        .remove_work_download(WorkDownloadRemovalRequest::new(
-            &work_id,
-            &library_root,
-            &download_root,
-        ))
+        .remove_work_download(WorkDownloadRemovalRequest {
+            archive_vault_root: archive_vault_root.as_deref(),
+            ..WorkDownloadRemovalRequest::new(&work_id, &library_root, &download_root)
+        })
         .await;
 
     match result {
@@ -1676,7 +2072,7 @@ async fn scan_local_work_downloads(
             return Err(error);
         }
     };
-    let metadata_source = match dm_api::DlsiteClient::new(dm_api::DlsiteClientConfig::default()) {
+    let metadata_source = match dm_api::DlsiteClient::new(state.api_config.clone()) {
         Ok(client) => DlsitePublicMetadataSource::new(client),
         Err(error) => {
             let message = command_error(error);
@@ -1724,15 +2120,251 @@ async fn scan_local_work_downloads(
 }
 
 #[tauri::command]
-async fn list_jobs(state: State<'_, AppState>) -> Result<Vec<dm_jobs::JobSnapshot>, String> {
-    Ok(state.jobs.list_jobs())
-}
+async fn start_library_verify(state: State<'_, AppState>) -> Result<StartJobResponse, String> {
+    let library = state.library.clone();
+    let job_id = state.jobs.spawn(
+        "libraryVerify",
+        "Verify Library",
+        JobMetadata::new(),
+        move |context| async move {
+            context.info("Verifying downloaded works against their file manifests");
+            let progress_sink = JobLibraryVerifyProgressSink {
+                context: context.clone(),
+            };
+            let report = library
+                .verify_library(LibraryVerifyRequest {
+                    cancellation_token: Some(context.cancellation_token()),
+                    progress_sink: Some(&progress_sink),
+                })
+                .await
+                .map_err(library_job_failure)?;
+
+            for issue in &report.issues {
+                let mut message = format!(
+                    "{}: {} missing, {} changed, {} extra files",
+                    issue.work_id,
+                    issue.missing.len(),
+                    issue.changed.len(),
+                    issue.extra.len()
+                );
 
-#[tauri::command]
-async fn get_job(
-    state: State<'_, AppState>,
-    request: JobIdRequest,
-) -> Result<dm_jobs::JobSnapshot, String> {
+                if let Some(vault_dir) = &issue.missing_archive_vault {
+                    message.push_str(&format!(", archives missing from {}", vault_dir.display()));
+                }
+
+                if issue.is_corrupted() {
+                    context.warn(message);
+                } else {
+                    context.info(message);
+                }
+            }
+            context.set_phase("completed");
+            context.info(format!(
+                "Verified {} works: {} corrupted, {} restored",
+                report.verified_count, report.corrupted_count, report.restored_count
+            ));
+
+            Ok(library_verify_output(&report))
+        },
+    );
+
+    record_audit(
+        &state.audit,
+        AuditEvent::queued("library.verify", "Queued library verification")
+            .with_details(json!({ "jobId": job_id.to_string() })),
+    )
+    .await;
+
+    Ok(StartJobResponse {
+        job_id: job_id.to_string(),
+    })
+}
+
+#[tauri::command]
+async fn preview_local_archive_import(
+    state: State<'_, AppState>,
+    request: LocalArchiveImportCommandRequest,
+) -> Result<LocalArchiveImportReportDto, String> {
+    let (source_dir, library_root, _) =
+        local_archive_import_paths(&state, request, "work.archive.preview").await?;
+
+    match state
+        .library
+        .import_local_archives(LocalArchiveImportRequest::new(&source_dir, &library_root))
+        .await
+    {
+        Ok(report) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::succeeded("work.archive.preview", "Planned local archive import")
+                    .with_details(local_archive_import_report_details(&report)),
+            )
+            .await;
+            Ok(LocalArchiveImportReportDto::from(report))
+        }
+        Err(error) => {
+            let message = command_error(error);
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "work.archive.preview",
+                    "Failed to plan local archive import",
+                )
+                .with_error(Some("library"), message.clone()),
+            )
+            .await;
+            Err(message)
+        }
+    }
+}
+
+#[tauri::command]
+async fn start_local_archive_import(
+    state: State<'_, AppState>,
+    request: LocalArchiveImportCommandRequest,
+) -> Result<StartJobResponse, String> {
+    let (source_dir, library_root, settings) =
+        local_archive_import_paths(&state, request, "work.archive.import").await?;
+    let metadata_source = match dm_api::DlsiteClient::new(state.api_config.clone()) {
+        Ok(client) => DlsitePublicMetadataSource::new(client),
+        Err(error) => {
+            let message = command_error(error);
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(
+                    "work.archive.import",
+                    "Failed to prepare DLsite product metadata lookup",
+                )
+                .with_error(Some("api_client"), message.clone()),
+            )
+            .await;
+            return Err(message);
+        }
+    };
+    let library = state.library.clone();
+    let manifest_sidecar = settings.manifest_sidecar;
+    let nested_archives = nested_archive_options(settings.nested_archives);
+    let mut metadata = JobMetadata::new();
+    metadata.insert("sourceDir".to_owned(), json!(source_dir.to_string_lossy()));
+    let job_id = state.jobs.spawn(
+        "localArchiveImport",
+        "Import Archives",
+        metadata,
+        move |context| async move {
+            context.info(format!("Importing archives from {}", source_dir.display()));
+            let progress_sink = JobLocalArchiveImportProgressSink {
+                context: context.clone(),
+            };
+            let report = library
+                .import_local_archives_with_metadata_source(
+                    LocalArchiveImportRequest {
+                        dry_run: false,
+                        nested_archives,
+                        manifest_sidecar,
+                        cancellation_token: Some(context.cancellation_token()),
+                        progress_sink: Some(&progress_sink),
+                        ..LocalArchiveImportRequest::new(&source_dir, &library_root)
+                    },
+                    &metadata_source,
+                )
+                .await
+                .map_err(library_job_failure)?;
+
+            for item in &report.works {
+                if let LocalArchiveImportAction::Failed { message } = &item.action {
+                    context.warn(format!("{}: {message}", item.work_id));
+                }
+                if let Some(error) = &item.manifest_error {
+                    context.warn(format!(
+                        "{}: couldn't record the file manifest: {error}",
+                        item.work_id
+                    ));
+                }
+            }
+
+            if let Some(error) = &report.metadata_error {
+                context.warn(format!("Metadata lookup failed: {error}"));
+            }
+
+            context.set_phase("completed");
+            context.info(format!(
+                "Imported {} works, {} failed",
+                report.imported_count, report.failed_count
+            ));
+
+            Ok(local_archive_import_output(&report))
+        },
+    );
+
+    record_audit(
+        &state.audit,
+        AuditEvent::queued("work.archive.import", "Queued local archive import")
+            .with_details(json!({ "jobId": job_id.to_string() })),
+    )
+    .await;
+
+    Ok(StartJobResponse {
+        job_id: job_id.to_string(),
+    })
+}
+
+/// Validates the archive folder and resolves the library folder it is imported into.
+async fn local_archive_import_paths(
+    state: &AppState,
+    request: LocalArchiveImportCommandRequest,
+    action: &str,
+) -> Result<(PathBuf, PathBuf, AppSettings), String> {
+    let source_dir = match normalize_required_path(request.source_dir) {
+        Ok(path) => path,
+        Err(error) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(action, "Failed to validate archive folder")
+                    .with_error(Some("validation"), error.clone()),
+            )
+            .await;
+            return Err(error);
+        }
+    };
+    let settings = match state.storage.app_settings().await {
+        Ok(settings) => settings,
+        Err(error) => {
+            let message = command_error(error);
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(action, "Failed to load settings")
+                    .with_error(Some("storage"), message.clone()),
+            )
+            .await;
+            return Err(message);
+        }
+    };
+    let library_root = match required_library_root(&settings) {
+        Ok(root) => root,
+        Err(error) => {
+            record_audit(
+                &state.audit,
+                AuditEvent::failed(action, "Failed to resolve library folder")
+                    .with_error(Some("settings"), error.clone()),
+            )
+            .await;
+            return Err(error);
+        }
+    };
+
+    Ok((source_dir, library_root, settings))
+}
+
+#[tauri::command]
+async fn list_jobs(state: State<'_, AppState>) -> Result<Vec<dm_jobs::JobSnapshot>, String> {
+    Ok(state.jobs.list_jobs())
+}
+
+#[tauri::command]
+async fn get_job(
+    state: State<'_, AppState>,
+    request: JobIdRequest,
+) -> Result<dm_jobs::JobSnapshot, String> {
     let job_id = normalize_required_id(request.job_id)?;
 
     state
@@ -1835,6 +2467,37 @@ async fn open_audit_log_dir(app: AppHandle, state: State<'_, AppState>) -> Resul
 struct AppSettingsDto {
     library_root: Option<String>,
     download_root: Option<String>,
+    archive_vault_root: Option<String>,
+    display_language: Option<String>,
+    download_connections_per_file: Option<u32>,
+    download_parallel_files: Option<u32>,
+    bandwidth_limit_bytes_per_second: Option<u64>,
+    bandwidth_schedules: Vec<BandwidthScheduleDto>,
+    download_stall_timeout_seconds: Option<u32>,
+    download_min_speed_bytes_per_second: Option<u64>,
+    download_retry_policy: Option<RetryPolicySetting>,
+    manifest_sidecar: bool,
+    nested_archives: Option<NestedArchiveSetting>,
+    post_download_hooks: Vec<PostDownloadHookSetting>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BandwidthScheduleDto {
+    /// Local time as `HH:MM`.
+    start: String,
+    end: String,
+    bytes_per_second: Option<u64>,
+}
+
+impl From<BandwidthScheduleSetting> for BandwidthScheduleDto {
+    fn from(schedule: BandwidthScheduleSetting) -> Self {
+        Self {
+            start: format_minute_of_day(schedule.start_minute),
+            end: format_minute_of_day(schedule.end_minute),
+            bytes_per_second: schedule.bytes_per_second,
+        }
+    }
 }
 
 impl From<AppSettings> for AppSettingsDto {
@@ -1842,6 +2505,22 @@ impl From<AppSettings> for AppSettingsDto {
         Self {
             library_root: settings.library_root,
             download_root: settings.download_root,
+            archive_vault_root: settings.archive_vault_root,
+            display_language: settings.display_language,
+            download_connections_per_file: settings.download_connections_per_file,
+            download_parallel_files: settings.download_parallel_files,
+            bandwidth_limit_bytes_per_second: settings.bandwidth_limit_bytes_per_second,
+            bandwidth_schedules: settings
+                .bandwidth_schedules
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            download_stall_timeout_seconds: settings.download_stall_timeout_seconds,
+            download_min_speed_bytes_per_second: settings.download_min_speed_bytes_per_second,
+            download_retry_policy: settings.download_retry_policy,
+            manifest_sidecar: settings.manifest_sidecar,
+            nested_archives: settings.nested_archives,
+            post_download_hooks: settings.post_download_hooks,
         }
     }
 }
@@ -1851,6 +2530,18 @@ impl From<AppSettings> for AppSettingsDto {
 struct SaveSettingsRequest {
     library_root: Option<String>,
     download_root: Option<String>,
+    archive_vault_root: Option<String>,
+    display_language: Option<String>,
+    download_connections_per_file: Option<u32>,
+    download_parallel_files: Option<u32>,
+    bandwidth_limit_bytes_per_second: Option<u64>,
+    bandwidth_schedules: Option<Vec<BandwidthScheduleDto>>,
+    download_stall_timeout_seconds: Option<u32>,
+    download_min_speed_bytes_per_second: Option<u64>,
+    download_retry_policy: Option<RetryPolicySetting>,
+    manifest_sidecar: Option<bool>,
+    nested_archives: Option<NestedArchiveSetting>,
+    post_download_hooks: Option<Vec<PostDownloadHookSetting>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -1860,6 +2551,7 @@ struct AccountDto {
     label: String,
     login_name: Option<String>,
     has_credential: bool,
+    proxy: Option<AccountProxyDto>,
     enabled: bool,
     created_at: String,
     updated_at: String,
@@ -1874,6 +2566,11 @@ impl AccountDto {
             label: account.label,
             login_name: account.login_name,
             has_credential,
+            proxy: account.proxy.map(|proxy| AccountProxyDto {
+                url: proxy.url,
+                username: proxy.username,
+                has_password: proxy.credential_ref.is_some(),
+            }),
             enabled: account.enabled,
             created_at: account.created_at,
             updated_at: account.updated_at,
@@ -1883,6 +2580,14 @@ impl AccountDto {
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountProxyDto {
+    url: String,
+    username: Option<String>,
+    has_password: bool,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SaveAccountCommandRequest {
@@ -1890,6 +2595,12 @@ struct SaveAccountCommandRequest {
     label: String,
     login_name: Option<String>,
     password: Option<String>,
+    #[serde(default)]
+    proxy_url: Option<String>,
+    #[serde(default)]
+    proxy_username: Option<String>,
+    #[serde(default)]
+    proxy_password: Option<String>,
 }
 
 impl SaveAccountCommandRequest {
@@ -1900,6 +2611,9 @@ impl SaveAccountCommandRequest {
             login_name: normalize_optional_string(self.login_name)?,
             password: normalize_secret(self.password)?,
             remember_password: true,
+            proxy_url: normalize_optional_string(self.proxy_url)?,
+            proxy_username: normalize_optional_string(self.proxy_username)?,
+            proxy_password: normalize_secret(self.proxy_password)?,
             enabled: true,
         })
     }
@@ -1950,6 +2664,9 @@ struct ListProductsRequest {
     maker_names: Option<Vec<String>>,
     custom_tag_names: Option<Vec<String>>,
     excluded_custom_tag_names: Option<Vec<String>>,
+    genre_ids: Option<Vec<String>>,
+    series_ids: Option<Vec<String>>,
+    update_available_only: Option<bool>,
     sort: Option<ProductSortDto>,
     limit: Option<u32>,
     offset: Option<u32>,
@@ -1984,6 +2701,9 @@ impl ListProductsRequest {
             maker_names: normalize_optional_strings(self.maker_names)?,
             custom_tag_names: normalize_optional_strings(self.custom_tag_names)?,
             excluded_custom_tag_names: normalize_optional_strings(self.excluded_custom_tag_names)?,
+            genre_ids: normalize_optional_strings(self.genre_ids)?,
+            series_ids: normalize_optional_strings(self.series_ids)?,
+            update_available_only: self.update_available_only.unwrap_or(false),
             sort: self.sort.unwrap_or_default().into(),
             limit: self.limit.unwrap_or(100).clamp(1, 500),
             offset: self.offset.unwrap_or(0),
@@ -2004,6 +2724,40 @@ struct SetProductCustomTagsRequest {
     tags: Vec<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetProductArchiveNameEncodingRequest {
+    work_id: String,
+    name_encoding: ArchiveNameEncodingDto,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetProductArchivePasswordRequest {
+    work_id: String,
+    password: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum ArchiveNameEncodingDto {
+    Auto,
+    Utf8,
+    Cp932,
+    Cp437,
+}
+
+impl From<ArchiveNameEncodingDto> for ArchiveNameEncoding {
+    fn from(name_encoding: ArchiveNameEncodingDto) -> Self {
+        match name_encoding {
+            ArchiveNameEncodingDto::Auto => Self::Auto,
+            ArchiveNameEncodingDto::Utf8 => Self::Utf8,
+            ArchiveNameEncodingDto::Cp932 => Self::Cp932,
+            ArchiveNameEncodingDto::Cp437 => Self::Cp437,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 enum ProductSortDto {
@@ -2111,11 +2865,39 @@ struct BulkWorkDownloadPreviewDto {
     known_expected_bytes: u64,
     total_expected_bytes: Option<u64>,
     unknown_size_count: usize,
+    disk_space_shortfall_bytes: u64,
+    disk_space: Vec<DiskSpaceCheckDto>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiskSpaceCheckDto {
+    path: String,
+    required_bytes: u64,
+    available_bytes: u64,
+    shortfall_bytes: u64,
+}
+
+impl From<DiskSpaceCheck> for DiskSpaceCheckDto {
+    fn from(check: DiskSpaceCheck) -> Self {
+        Self {
+            shortfall_bytes: check.shortfall_bytes(),
+            path: check.path.to_string_lossy().into_owned(),
+            required_bytes: check.required_bytes,
+            available_bytes: check.available_bytes,
+        }
+    }
 }
 
 impl BulkWorkDownloadPreviewDto {
     fn from_preview(preview: BulkWorkDownloadPreview, skipped_queued_count: usize) -> Self {
         Self {
+            disk_space_shortfall_bytes: preview.disk_space_shortfall_bytes(),
+            disk_space: preview
+                .disk_space
+                .into_iter()
+                .map(DiskSpaceCheckDto::from)
+                .collect(),
             total_count: preview.total_count,
             requested_count: preview.requested_count,
             skipped_downloaded_count: preview.skipped_downloaded_count,
@@ -2134,6 +2916,8 @@ impl BulkWorkDownloadPreviewDto {
 struct ProductFilterFacetsDto {
     makers: Vec<ProductMakerFacetDto>,
     custom_tags: Vec<ProductCustomTagFacetDto>,
+    genres: Vec<ProductGenreFacetDto>,
+    series: Vec<ProductSeriesFacetDto>,
 }
 
 impl From<ProductFilterFacets> for ProductFilterFacetsDto {
@@ -2149,6 +2933,16 @@ impl From<ProductFilterFacets> for ProductFilterFacetsDto {
                 .into_iter()
                 .map(ProductCustomTagFacetDto::from)
                 .collect(),
+            genres: facets
+                .genres
+                .into_iter()
+                .map(ProductGenreFacetDto::from)
+                .collect(),
+            series: facets
+                .series
+                .into_iter()
+                .map(ProductSeriesFacetDto::from)
+                .collect(),
         }
     }
 }
@@ -2185,6 +2979,42 @@ impl From<ProductCustomTagFacet> for ProductCustomTagFacetDto {
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductGenreFacetDto {
+    id: String,
+    name: String,
+    count: u64,
+}
+
+impl From<ProductGenreFacet> for ProductGenreFacetDto {
+    fn from(facet: ProductGenreFacet) -> Self {
+        Self {
+            id: facet.id,
+            name: facet.name,
+            count: facet.count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductSeriesFacetDto {
+    id: String,
+    name: String,
+    count: u64,
+}
+
+impl From<ProductSeriesFacet> for ProductSeriesFacetDto {
+    fn from(facet: ProductSeriesFacet) -> Self {
+        Self {
+            id: facet.id,
+            name: facet.name,
+            count: facet.count,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ProductListItemDto {
@@ -2259,6 +3089,14 @@ struct ProductDetailDto {
     credit_groups: Vec<ProductCreditGroupDto>,
     tags: Vec<ProductTagDto>,
     custom_tags: Vec<ProductCustomTagDto>,
+    archive_name_encoding: Option<String>,
+    archive_password_saved: bool,
+    description: Option<String>,
+    series: Option<ProductSeriesDto>,
+    genres: Vec<ProductGenreDto>,
+    sample_image_urls: Vec<String>,
+    file_formats: Vec<String>,
+    track_count: Option<u32>,
     download: WorkDownloadStateDto,
     owners: Vec<ProductOwnerDto>,
 }
@@ -2301,6 +3139,18 @@ impl From<ProductDetail> for ProductDetailDto {
                 .into_iter()
                 .map(ProductCustomTagDto::from)
                 .collect(),
+            archive_name_encoding: detail.archive_name_encoding,
+            archive_password_saved: false,
+            description: detail.description,
+            series: detail.series.map(ProductSeriesDto::from),
+            genres: detail
+                .genres
+                .into_iter()
+                .map(ProductGenreDto::from)
+                .collect(),
+            sample_image_urls: detail.sample_image_urls,
+            file_formats: detail.file_formats,
+            track_count: detail.track_count,
             download: WorkDownloadStateDto::from(detail.download),
             owners: detail
                 .owners
@@ -2311,6 +3161,38 @@ impl From<ProductDetail> for ProductDetailDto {
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductGenreDto {
+    id: String,
+    name: String,
+}
+
+impl From<ProductGenre> for ProductGenreDto {
+    fn from(genre: ProductGenre) -> Self {
+        Self {
+            id: genre.id,
+            name: genre.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductSeriesDto {
+    id: String,
+    name: String,
+}
+
+impl From<ProductSeries> for ProductSeriesDto {
+    fn from(series: ProductSeries) -> Self {
+        Self {
+            id: series.id,
+            name: series.name,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ProductTextValueDto {
@@ -2333,6 +3215,7 @@ struct WorkDownloadStateDto {
     status: WorkDownloadStatusDto,
     local_path: Option<String>,
     staging_path: Option<String>,
+    archive_vault_path: Option<String>,
     unpack_policy: Option<String>,
     bytes_received: u64,
     bytes_total: Option<u64>,
@@ -2341,6 +3224,7 @@ struct WorkDownloadStateDto {
     started_at: Option<String>,
     completed_at: Option<String>,
     updated_at: Option<String>,
+    update_available: bool,
 }
 
 impl From<WorkDownloadState> for WorkDownloadStateDto {
@@ -2349,6 +3233,7 @@ impl From<WorkDownloadState> for WorkDownloadStateDto {
             status: WorkDownloadStatusDto::from(state.status),
             local_path: state.local_path,
             staging_path: state.staging_path,
+            archive_vault_path: state.archive_vault_path,
             unpack_policy: state.unpack_policy,
             bytes_received: state.bytes_received,
             bytes_total: state.bytes_total,
@@ -2357,6 +3242,7 @@ impl From<WorkDownloadState> for WorkDownloadStateDto {
             started_at: state.started_at,
             completed_at: state.completed_at,
             updated_at: state.updated_at,
+            update_available: state.update_available,
         }
     }
 }
@@ -2369,6 +3255,7 @@ enum WorkDownloadStatusDto {
     Downloaded,
     Failed,
     Cancelled,
+    Corrupted,
 }
 
 impl From<WorkDownloadStatus> for WorkDownloadStatusDto {
@@ -2379,6 +3266,7 @@ impl From<WorkDownloadStatus> for WorkDownloadStatusDto {
             WorkDownloadStatus::Downloaded => Self::Downloaded,
             WorkDownloadStatus::Failed => Self::Failed,
             WorkDownloadStatus::Cancelled => Self::Cancelled,
+            WorkDownloadStatus::Corrupted => Self::Corrupted,
         }
     }
 }
@@ -2437,6 +3325,85 @@ impl From<dm_library::LocalWorkImportItem> for LocalWorkImportItemDto {
     }
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocalArchiveImportCommandRequest {
+    source_dir: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LocalArchiveImportReportDto {
+    dry_run: bool,
+    scanned_files: usize,
+    skipped_no_id: usize,
+    skipped_ambiguous: usize,
+    skipped_non_utf8: usize,
+    imported_count: usize,
+    failed_count: usize,
+    metadata_updated_count: usize,
+    metadata_error: Option<String>,
+    works: Vec<LocalArchiveImportItemDto>,
+}
+
+impl From<LocalArchiveImportReport> for LocalArchiveImportReportDto {
+    fn from(report: LocalArchiveImportReport) -> Self {
+        Self {
+            dry_run: report.dry_run,
+            scanned_files: report.scanned_files,
+            skipped_no_id: report.skipped_no_id,
+            skipped_ambiguous: report.skipped_ambiguous,
+            skipped_non_utf8: report.skipped_non_utf8,
+            imported_count: report.imported_count,
+            failed_count: report.failed_count,
+            metadata_updated_count: report.metadata_updated_count,
+            metadata_error: report.metadata_error,
+            works: report
+                .works
+                .into_iter()
+                .map(LocalArchiveImportItemDto::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LocalArchiveImportItemDto {
+    work_id: String,
+    kind: &'static str,
+    archives: Vec<String>,
+    target_path: String,
+    unpacked_bytes: Option<u64>,
+    action: &'static str,
+    error_message: Option<String>,
+    manifest_error: Option<String>,
+}
+
+impl From<dm_library::LocalArchiveImportItem> for LocalArchiveImportItemDto {
+    fn from(item: dm_library::LocalArchiveImportItem) -> Self {
+        let error_message = match &item.action {
+            LocalArchiveImportAction::Failed { message } => Some(message.clone()),
+            _ => None,
+        };
+
+        Self {
+            work_id: item.work_id,
+            kind: item.kind.as_str(),
+            archives: item
+                .archives
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+            target_path: item.target_path.to_string_lossy().into_owned(),
+            unpacked_bytes: item.unpacked_bytes,
+            action: item.action.as_str(),
+            error_message,
+            manifest_error: item.manifest_error,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ProductCreditGroupDto {
@@ -2516,6 +3483,8 @@ struct StartWorkDownloadRequest {
     password: Option<String>,
     unpack_policy: Option<UnpackPolicyDto>,
     replace_existing: Option<bool>,
+    resume_unpack: Option<bool>,
+    previous_version: Option<PreviousVersionPolicyDto>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -2532,9 +3501,14 @@ struct BulkWorkDownloadCommandRequest {
     maker_names: Option<Vec<String>>,
     custom_tag_names: Option<Vec<String>>,
     excluded_custom_tag_names: Option<Vec<String>>,
+    genre_ids: Option<Vec<String>>,
+    series_ids: Option<Vec<String>>,
+    update_available_only: Option<bool>,
     sort: Option<ProductSortDto>,
     unpack_policy: Option<UnpackPolicyDto>,
     skip_downloaded: Option<bool>,
+    /// Overrides the retry policy from settings for this bulk download only.
+    retry_policy: Option<RetryPolicySetting>,
 }
 
 impl BulkWorkDownloadCommandRequest {
@@ -2571,6 +3545,9 @@ impl BulkWorkDownloadCommandRequest {
             excluded_custom_tag_names: normalize_optional_strings(
                 self.excluded_custom_tag_names.clone(),
             )?,
+            genre_ids: normalize_optional_strings(self.genre_ids.clone())?,
+            series_ids: normalize_optional_strings(self.series_ids.clone())?,
+            update_available_only: self.update_available_only.unwrap_or(false),
             sort: self.sort.unwrap_or_default().into(),
             limit: BULK_DOWNLOAD_PAGE_LIMIT,
             offset: 0,
@@ -2603,6 +3580,7 @@ enum UnpackPolicyDto {
     KeepArchives,
     #[default]
     UnpackWhenRecognized,
+    UnpackAndKeepArchives,
 }
 
 impl From<UnpackPolicyDto> for dm_download::UnpackPolicy {
@@ -2610,6 +3588,26 @@ impl From<UnpackPolicyDto> for dm_download::UnpackPolicy {
         match policy {
             UnpackPolicyDto::KeepArchives => Self::KeepArchives,
             UnpackPolicyDto::UnpackWhenRecognized => Self::UnpackWhenRecognized,
+            UnpackPolicyDto::UnpackAndKeepArchives => Self::UnpackAndKeepArchives,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum PreviousVersionPolicyDto {
+    #[default]
+    Discard,
+    Keep,
+    Archive,
+}
+
+impl From<PreviousVersionPolicyDto> for PreviousVersionPolicy {
+    fn from(policy: PreviousVersionPolicyDto) -> Self {
+        match policy {
+            PreviousVersionPolicyDto::Discard => Self::Discard,
+            PreviousVersionPolicyDto::Keep => Self::Keep,
+            PreviousVersionPolicyDto::Archive => Self::Archive,
         }
     }
 }
@@ -2681,6 +3679,16 @@ impl SyncProgressSink for JobSyncProgressSink {
                 self.context
                     .info(format!("Loading {work_count} work details"));
             }
+            SyncProgress::LoadingLocalizedWorks {
+                language,
+                work_count,
+            } => {
+                self.context.set_phase("loadingLocalizedWorks");
+                self.context
+                    .set_progress(JobProgress::items(None, Some(work_count as u64)));
+                self.context
+                    .info(format!("Loading {language} titles for {work_count} works"));
+            }
             SyncProgress::Committing { work_count } => {
                 self.context.set_phase("committing");
                 self.context.set_progress(JobProgress::items(
@@ -2788,6 +3796,26 @@ impl WorkDownloadProgressSink for JobWorkDownloadProgressSink {
                     return;
                 }
 
+                if let Some(retry) = &progress.retry {
+                    let delay = format_retry_delay(retry.delay);
+
+                    self.context.warn(format!(
+                        "Reconnecting in {delay} (retry {}/{}): {}",
+                        retry.attempt, retry.max_retries, retry.reason
+                    ));
+                    self.context.set_phase("retrying");
+                    self.context.set_detail(format!(
+                        "Retry {}/{} in {delay}",
+                        retry.attempt, retry.max_retries
+                    ));
+                    // The next progress update switches the phase back to downloading.
+                    self.throttle
+                        .lock()
+                        .expect("download progress throttle lock")
+                        .reset();
+                    return;
+                }
+
                 let Some(phase_changed) = self
                     .throttle
                     .lock()
@@ -2798,6 +3826,8 @@ impl WorkDownloadProgressSink for JobWorkDownloadProgressSink {
                 };
 
                 if phase_changed {
+                    self.context.clear_detail();
+
                     match progress.phase {
                         dm_download::DownloadPhase::ResolvingPlan => {
                             self.context.set_phase("resolvingDownload")
@@ -2820,6 +3850,11 @@ impl WorkDownloadProgressSink for JobWorkDownloadProgressSink {
                     progress.bytes_total,
                 ));
             }
+            WorkDownloadProgress::PartialDiscarded { file_name, reason } => {
+                self.context.warn(format!(
+                    "Discarded partial download of {file_name} and started over: {reason}"
+                ));
+            }
             WorkDownloadProgress::Finalizing => {
                 self.throttle
                     .lock()
@@ -2829,25 +3864,100 @@ impl WorkDownloadProgressSink for JobWorkDownloadProgressSink {
                 self.context.clear_progress();
                 self.context.info("Moving files into the library");
             }
-            WorkDownloadProgress::Completed => {
-                self.throttle
-                    .lock()
-                    .expect("download progress throttle lock")
-                    .reset();
-                self.context.set_phase("completed");
-                self.context.clear_progress();
-                self.context.info("Download completed");
+            WorkDownloadProgress::Completed => {
+                self.throttle
+                    .lock()
+                    .expect("download progress throttle lock")
+                    .reset();
+                self.context.set_phase("completed");
+                self.context.clear_progress();
+                self.context.info("Download completed");
+            }
+        }
+    }
+}
+
+struct JobBulkWorkDownloadProgressSink {
+    context: JobContext,
+}
+
+struct JobPostDownloadHookSink {
+    context: JobContext,
+    hook_name: String,
+}
+
+impl PostDownloadHookSink for JobPostDownloadHookSink {
+    fn output(&self, stream: dm_library::HookOutputStream, line: &str) {
+        let message = format!("[{}] {line}", self.hook_name);
+
+        match stream {
+            dm_library::HookOutputStream::Stdout => self.context.info(message),
+            dm_library::HookOutputStream::Stderr => self.context.warn(message),
+        }
+    }
+}
+
+struct JobBulkWorkDownloadPreviewProgressSink {
+    context: JobContext,
+}
+
+struct JobLibraryVerifyProgressSink {
+    context: JobContext,
+}
+
+impl LibraryVerifyProgressSink for JobLibraryVerifyProgressSink {
+    fn emit(&self, progress: LibraryVerifyProgress) {
+        match progress {
+            LibraryVerifyProgress::Started { total } => {
+                self.context.set_phase("verifying");
+                self.context
+                    .set_progress(JobProgress::items(Some(0), Some(total as u64)));
+            }
+            LibraryVerifyProgress::WorkVerified { current, total, .. } => {
+                self.context
+                    .set_progress(JobProgress::items(Some(current as u64), Some(total as u64)));
             }
         }
     }
 }
 
-struct JobBulkWorkDownloadProgressSink {
+struct JobLocalArchiveImportProgressSink {
     context: JobContext,
 }
 
-struct JobBulkWorkDownloadPreviewProgressSink {
-    context: JobContext,
+impl LocalArchiveImportProgressSink for JobLocalArchiveImportProgressSink {
+    fn emit(&self, progress: LocalArchiveImportProgress) {
+        match progress {
+            LocalArchiveImportProgress::Started { total } => {
+                self.context.set_phase("unpacking");
+                self.context
+                    .set_progress(JobProgress::items(Some(0), Some(total as u64)));
+            }
+            LocalArchiveImportProgress::WorkStarted {
+                work_id,
+                current,
+                total,
+            } => {
+                self.context.set_detail(format!("Unpacking {work_id}"));
+                self.context.set_progress(JobProgress::items(
+                    Some(current.saturating_sub(1) as u64),
+                    Some(total as u64),
+                ));
+            }
+            LocalArchiveImportProgress::WorkFinished {
+                work_id,
+                current,
+                total,
+                imported,
+            } => {
+                if imported {
+                    self.context.info(format!("Imported {work_id}"));
+                }
+                self.context
+                    .set_progress(JobProgress::items(Some(current as u64), Some(total as u64)));
+            }
+        }
+    }
 }
 
 impl BulkWorkDownloadPreviewProgressSink for JobBulkWorkDownloadPreviewProgressSink {
@@ -2981,8 +4091,103 @@ impl SaveSettingsRequest {
         Ok(AppSettings {
             library_root: normalize_path_setting(self.library_root)?,
             download_root: normalize_path_setting(self.download_root)?,
+            archive_vault_root: normalize_path_setting(self.archive_vault_root)?,
+            display_language: normalize_display_language(self.display_language)?,
+            download_connections_per_file: normalize_download_connections(
+                self.download_connections_per_file,
+            )?,
+            download_parallel_files: normalize_parallel_files(self.download_parallel_files)?,
+            bandwidth_limit_bytes_per_second: self
+                .bandwidth_limit_bytes_per_second
+                .filter(|limit| *limit > 0),
+            bandwidth_schedules: self
+                .bandwidth_schedules
+                .unwrap_or_default()
+                .into_iter()
+                .map(|schedule| {
+                    Ok(BandwidthScheduleSetting {
+                        start_minute: parse_minute_of_day(&schedule.start)?,
+                        end_minute: parse_minute_of_day(&schedule.end)?,
+                        bytes_per_second: schedule.bytes_per_second.filter(|limit| *limit > 0),
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+            download_stall_timeout_seconds: normalize_stall_timeout(
+                self.download_stall_timeout_seconds,
+            )?,
+            download_min_speed_bytes_per_second: self
+                .download_min_speed_bytes_per_second
+                .filter(|speed| *speed > 0),
+            download_retry_policy: normalize_retry_policy(self.download_retry_policy)?,
+            manifest_sidecar: self.manifest_sidecar.unwrap_or(false),
+            nested_archives: normalize_nested_archives(self.nested_archives)?,
+            post_download_hooks: normalize_post_download_hooks(self.post_download_hooks)?,
+        })
+    }
+}
+
+fn normalize_post_download_hooks(
+    hooks: Option<Vec<PostDownloadHookSetting>>,
+) -> Result<Vec<PostDownloadHookSetting>, String> {
+    hooks
+        .unwrap_or_default()
+        .into_iter()
+        .map(|hook| {
+            let program = hook.program.trim().to_owned();
+
+            if program.is_empty() {
+                return Err("Post-download hooks need a program to run".to_owned());
+            }
+            if hook.timeout_seconds == Some(0) {
+                return Err("Post-download hook timeouts must be at least one second".to_owned());
+            }
+
+            Ok(PostDownloadHookSetting {
+                name: match hook.name.trim() {
+                    "" => program.clone(),
+                    name => name.to_owned(),
+                },
+                program,
+                args: hook.args,
+                enabled: hook.enabled,
+                timeout_seconds: hook.timeout_seconds,
+            })
         })
+        .collect()
+}
+
+fn normalize_download_connections(value: Option<u32>) -> Result<Option<u32>, String> {
+    match value {
+        None | Some(1) => Ok(None),
+        Some(connections)
+            if (2..=dm_download::MAX_CONNECTIONS_PER_FILE as u32).contains(&connections) =>
+        {
+            Ok(Some(connections))
+        }
+        Some(connections) => Err(format!(
+            "download connections must be between 1 and {}, got {connections}",
+            dm_download::MAX_CONNECTIONS_PER_FILE
+        )),
+    }
+}
+
+const DISPLAY_LANGUAGES: [&str; 5] = ["ja_JP", "en_US", "ko_KR", "zh_TW", "zh_CN"];
+
+fn normalize_display_language(value: Option<String>) -> Result<Option<String>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let value = value.trim();
+
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    if !DISPLAY_LANGUAGES.contains(&value) {
+        return Err(format!("unsupported display language: {value}"));
     }
+
+    Ok(Some(value.to_owned()))
 }
 
 fn normalize_path_setting(value: Option<String>) -> Result<Option<String>, String> {
@@ -3002,6 +4207,200 @@ fn normalize_path_setting(value: Option<String>) -> Result<Option<String>, Strin
     Ok(Some(value))
 }
 
+fn parse_minute_of_day(value: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .split_once(':')
+        .and_then(|(hour, minute)| Some((hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?)))
+        .filter(|(hour, minute)| *hour < 24 && *minute < 60)
+        .map(|(hour, minute)| hour * 60 + minute)
+        .ok_or_else(|| format!("invalid schedule time: {value}"))
+}
+
+fn format_minute_of_day(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60 % 24, minute % 60)
+}
+
+fn format_retry_delay(delay: Duration) -> String {
+    let seconds = delay.as_secs() + u64::from(delay.subsec_nanos() > 0);
+
+    if seconds < 60 {
+        format!("{seconds}s")
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+fn bandwidth_policy(settings: &AppSettings) -> dm_download::BandwidthPolicy {
+    dm_download::BandwidthPolicy {
+        bytes_per_second: settings.bandwidth_limit_bytes_per_second,
+        schedules: settings
+            .bandwidth_schedules
+            .iter()
+            .map(|schedule| dm_download::BandwidthSchedule {
+                start_minute: schedule.start_minute,
+                end_minute: schedule.end_minute,
+                bytes_per_second: schedule.bytes_per_second,
+            })
+            .collect(),
+    }
+}
+
+fn normalize_parallel_files(value: Option<u32>) -> Result<Option<u32>, String> {
+    match value {
+        None | Some(1) => Ok(None),
+        Some(files) if (2..=dm_download::MAX_PARALLEL_FILES as u32).contains(&files) => {
+            Ok(Some(files))
+        }
+        Some(files) => Err(format!(
+            "parallel downloads must be between 1 and {}, got {files}",
+            dm_download::MAX_PARALLEL_FILES
+        )),
+    }
+}
+
+const MIN_STALL_TIMEOUT_SECONDS: u32 = 5;
+const MAX_STALL_TIMEOUT_SECONDS: u32 = 3600;
+
+fn normalize_stall_timeout(value: Option<u32>) -> Result<Option<u32>, String> {
+    match value {
+        None => Ok(None),
+        Some(seconds)
+            if (MIN_STALL_TIMEOUT_SECONDS..=MAX_STALL_TIMEOUT_SECONDS).contains(&seconds) =>
+        {
+            Ok(Some(seconds))
+        }
+        Some(seconds) => Err(format!(
+            "stall timeout must be between {MIN_STALL_TIMEOUT_SECONDS} and {MAX_STALL_TIMEOUT_SECONDS} seconds, got {seconds}"
+        )),
+    }
+}
+
+const MAX_NESTED_ARCHIVE_DEPTH: u32 = 5;
+
+fn normalize_nested_archives(
+    value: Option<NestedArchiveSetting>,
+) -> Result<Option<NestedArchiveSetting>, String> {
+    let Some(setting) = value else {
+        return Ok(None);
+    };
+
+    if !(1..=MAX_NESTED_ARCHIVE_DEPTH).contains(&setting.max_depth) {
+        return Err(format!(
+            "nested archive depth must be between 1 and {MAX_NESTED_ARCHIVE_DEPTH}, got {}",
+            setting.max_depth
+        ));
+    }
+
+    if setting.max_unpacked_bytes == 0 {
+        return Err("nested archive size limit must be greater than zero".to_owned());
+    }
+
+    Ok(Some(setting))
+}
+
+fn nested_archive_options(setting: Option<NestedArchiveSetting>) -> Option<NestedArchiveOptions> {
+    setting.map(|setting| NestedArchiveOptions {
+        max_depth: setting.max_depth,
+        max_unpacked_bytes: setting.max_unpacked_bytes,
+        keep_inner_archives: setting.keep_inner_archives,
+    })
+}
+
+const MAX_DOWNLOAD_RETRIES: u32 = 20;
+const MAX_RETRY_DELAY_SECONDS: u32 = 3600;
+
+fn normalize_retry_policy(
+    value: Option<RetryPolicySetting>,
+) -> Result<Option<RetryPolicySetting>, String> {
+    let Some(policy) = value else {
+        return Ok(None);
+    };
+
+    if policy.max_retries > MAX_DOWNLOAD_RETRIES {
+        return Err(format!(
+            "download retries must be at most {MAX_DOWNLOAD_RETRIES}, got {}",
+            policy.max_retries
+        ));
+    }
+
+    if policy.max_delay_seconds > MAX_RETRY_DELAY_SECONDS
+        || policy.initial_delay_seconds > policy.max_delay_seconds
+    {
+        return Err(format!(
+            "retry delays must satisfy initial <= maximum <= {MAX_RETRY_DELAY_SECONDS} seconds, got {} and {}",
+            policy.initial_delay_seconds, policy.max_delay_seconds
+        ));
+    }
+
+    if policy.jitter_percent > 100 {
+        return Err(format!(
+            "retry jitter must be at most 100%, got {}",
+            policy.jitter_percent
+        ));
+    }
+
+    Ok(Some(RetryPolicySetting {
+        deadline_seconds: policy.deadline_seconds.filter(|seconds| *seconds > 0),
+        ..policy
+    }))
+}
+
+fn download_retry_policy(setting: Option<RetryPolicySetting>) -> dm_download::RetryPolicy {
+    let Some(setting) = setting else {
+        return dm_download::RetryPolicy::default();
+    };
+
+    dm_download::RetryPolicy {
+        max_retries: setting.max_retries,
+        backoff: match setting.backoff {
+            RetryBackoffSetting::Constant => dm_download::BackoffCurve::Constant,
+            RetryBackoffSetting::Linear => dm_download::BackoffCurve::Linear,
+            RetryBackoffSetting::Exponential => dm_download::BackoffCurve::Exponential,
+        },
+        initial_delay: Duration::from_secs(setting.initial_delay_seconds.into()),
+        max_delay: Duration::from_secs(setting.max_delay_seconds.into()),
+        jitter_percent: setting.jitter_percent,
+        retryable: dm_download::RetryableErrors {
+            network: setting.retry_network,
+            stalled: setting.retry_stalled,
+            incomplete: setting.retry_incomplete,
+            invalid_response: setting.retry_invalid_response,
+        },
+        deadline: setting
+            .deadline_seconds
+            .map(|seconds| Duration::from_secs(seconds.into())),
+    }
+}
+
+fn download_stall_policy(settings: &AppSettings) -> dm_download::StallPolicy {
+    let default = dm_download::StallPolicy::default();
+
+    dm_download::StallPolicy {
+        idle_timeout: settings
+            .download_stall_timeout_seconds
+            .map_or(default.idle_timeout, |seconds| {
+                Duration::from_secs(seconds.into())
+            }),
+        min_bytes_per_second: settings.download_min_speed_bytes_per_second,
+        ..default
+    }
+}
+
+fn download_parallel_files(settings: &AppSettings) -> usize {
+    settings
+        .download_parallel_files
+        .map_or(dm_download::DEFAULT_PARALLEL_FILES, |files| files as usize)
+}
+
+fn download_connections_per_file(settings: &AppSettings) -> usize {
+    settings
+        .download_connections_per_file
+        .map_or(dm_download::DEFAULT_CONNECTIONS_PER_FILE, |connections| {
+            connections as usize
+        })
+}
+
 fn required_library_root(settings: &AppSettings) -> Result<PathBuf, String> {
     settings
         .library_root
@@ -3216,6 +4615,26 @@ fn command_error(error: impl ToString) -> String {
     error.to_string()
 }
 
+fn local_archive_import_report_details(report: &LocalArchiveImportReport) -> Value {
+    let count_action = |action: &str| {
+        report
+            .works
+            .iter()
+            .filter(|item| item.action.as_str() == action)
+            .count()
+    };
+
+    json!({
+        "dryRun": report.dry_run,
+        "scannedFiles": report.scanned_files,
+        "workCount": report.works.len(),
+        "extractCount": count_action("extract"),
+        "skippedNoId": report.skipped_no_id,
+        "skippedAmbiguous": report.skipped_ambiguous,
+        "skippedNonUtf8": report.skipped_non_utf8,
+    })
+}
+
 fn local_work_import_report_details(report: &LocalWorkImportReport) -> Value {
     json!({
         "scannedDirectories": report.scanned_directories,
@@ -3281,6 +4700,7 @@ fn job_audit_operation(kind: &str) -> String {
         "workDownload" => "work.download".to_owned(),
         "bulkWorkDownload" => "work.bulkDownload".to_owned(),
         "bulkWorkDownloadPreview" => "work.bulkDownload.preview".to_owned(),
+        "libraryVerify" => "library.verify".to_owned(),
         _ => format!("job.{kind}"),
     }
 }
@@ -3297,6 +4717,24 @@ fn unpack_policy_label(policy: dm_download::UnpackPolicy) -> &'static str {
     match policy {
         dm_download::UnpackPolicy::KeepArchives => "keepArchives",
         dm_download::UnpackPolicy::UnpackWhenRecognized => "unpackWhenRecognized",
+        dm_download::UnpackPolicy::UnpackAndKeepArchives => "unpackAndKeepArchives",
+    }
+}
+
+fn archive_name_encoding_label(name_encoding: ArchiveNameEncoding) -> &'static str {
+    match name_encoding {
+        ArchiveNameEncoding::Auto => "auto",
+        ArchiveNameEncoding::Utf8 => "utf8",
+        ArchiveNameEncoding::Cp932 => "cp932",
+        ArchiveNameEncoding::Cp437 => "cp437",
+    }
+}
+
+fn previous_version_label(policy: PreviousVersionPolicy) -> &'static str {
+    match policy {
+        PreviousVersionPolicy::Discard => "discard",
+        PreviousVersionPolicy::Keep => "keep",
+        PreviousVersionPolicy::Archive => "archive",
     }
 }
 
@@ -3325,7 +4763,12 @@ async fn bulk_download_candidates(
         let page_len = page.products.len();
 
         for product in page.products {
-            if skip_downloaded && product.download.status == WorkDownloadStatus::Downloaded {
+            if skip_downloaded
+                && matches!(
+                    product.download.status,
+                    WorkDownloadStatus::Downloaded | WorkDownloadStatus::Corrupted
+                )
+            {
                 skipped_downloaded_count += 1;
                 continue;
             }
@@ -3349,6 +4792,180 @@ async fn bulk_download_candidates(
     })
 }
 
+/// Runs the enabled post-download hooks for one finished work. A failing hook is logged and
+/// audited but never fails the download job.
+async fn run_post_download_hooks(
+    context: &JobContext,
+    library: &Library,
+    audit: &AuditLogger,
+    hooks: &[PostDownloadHookSetting],
+    work_id: &str,
+    local_path: &Path,
+) -> Vec<PostDownloadHookOutcome> {
+    let hooks = hooks.iter().filter(|hook| hook.enabled).collect::<Vec<_>>();
+
+    if hooks.is_empty() {
+        return Vec::new();
+    }
+
+    let hook_context = match library
+        .post_download_hook_context(work_id, local_path)
+        .await
+    {
+        Ok(hook_context) => hook_context,
+        Err(error) => {
+            context.warn(format!(
+                "Skipped post-download hooks for {work_id}: {}",
+                error.support_message()
+            ));
+            return Vec::new();
+        }
+    };
+    let mut outcomes = Vec::with_capacity(hooks.len());
+
+    context.set_phase("postProcessing");
+    for hook in hooks {
+        if context.is_cancelled() {
+            break;
+        }
+
+        context.set_detail(format!("Running {} for {work_id}", hook.name));
+        let sink = JobPostDownloadHookSink {
+            context: context.clone(),
+            hook_name: hook.name.clone(),
+        };
+        let outcome = dm_library::run_post_download_hook(
+            hook,
+            &hook_context,
+            Some(context.cancellation_token()),
+            &sink,
+        )
+        .await;
+        let details = json!({
+            "workId": work_id,
+            "hook": outcome.name,
+            "program": outcome.program,
+            "exitCode": outcome.exit_code,
+        });
+
+        if outcome.succeeded() {
+            context.info(format!("Hook {} finished for {work_id}", outcome.name));
+            record_audit(
+                audit,
+                AuditEvent::succeeded("work.download.hook", "Post-download hook finished")
+                    .with_details(details),
+            )
+            .await;
+        } else {
+            let error = outcome.error.clone().unwrap_or_default();
+
+            context.warn(format!(
+                "Hook {} failed for {work_id}: {error}",
+                outcome.name
+            ));
+            record_audit(
+                audit,
+                AuditEvent::failed("work.download.hook", "Post-download hook failed")
+                    .with_error(Some("hook"), error)
+                    .with_details(details),
+            )
+            .await;
+        }
+
+        outcomes.push(outcome);
+    }
+    context.clear_detail();
+
+    outcomes
+}
+
+fn post_download_hook_output(outcomes: &[PostDownloadHookOutcome]) -> Value {
+    json!(outcomes
+        .iter()
+        .map(|outcome| json!({
+            "name": outcome.name,
+            "exitCode": outcome.exit_code,
+            "succeeded": outcome.succeeded(),
+            "error": outcome.error,
+        }))
+        .collect::<Vec<_>>())
+}
+
+fn nested_archive_output(nested: &[NestedArchive]) -> Value {
+    json!(nested
+        .iter()
+        .map(|archive| {
+            let sources = archive
+                .sources
+                .iter()
+                .map(|source| source.to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+
+            match &archive.outcome {
+                NestedArchiveOutcome::Extracted(extraction) => json!({
+                    "sources": sources,
+                    "outcome": "extracted",
+                    "outputDir": extraction.output_dir.to_string_lossy(),
+                    "nested": nested_archive_output(&extraction.nested),
+                }),
+                NestedArchiveOutcome::DepthLimit => json!({
+                    "sources": sources,
+                    "outcome": "depthLimit",
+                }),
+                NestedArchiveOutcome::SizeLimit {
+                    required,
+                    remaining,
+                } => json!({
+                    "sources": sources,
+                    "outcome": "sizeLimit",
+                    "requiredBytes": required,
+                    "remainingBytes": remaining,
+                }),
+                NestedArchiveOutcome::Failed { error } => json!({
+                    "sources": sources,
+                    "outcome": "failed",
+                    "error": error,
+                }),
+            }
+        })
+        .collect::<Vec<_>>())
+}
+
+fn count_nested_archives_left_packed(nested: &[NestedArchive]) -> usize {
+    nested
+        .iter()
+        .map(|archive| match &archive.outcome {
+            NestedArchiveOutcome::Extracted(extraction) => {
+                count_nested_archives_left_packed(&extraction.nested)
+            }
+            NestedArchiveOutcome::DepthLimit | NestedArchiveOutcome::SizeLimit { .. } => 1,
+            NestedArchiveOutcome::Failed { .. } => 0,
+        })
+        .sum()
+}
+
+/// `"<first source>: <error>"` for every nested archive that failed to unpack.
+fn nested_archive_failures(nested: &[NestedArchive]) -> Vec<String> {
+    nested
+        .iter()
+        .flat_map(|archive| match &archive.outcome {
+            NestedArchiveOutcome::Extracted(extraction) => {
+                nested_archive_failures(&extraction.nested)
+            }
+            NestedArchiveOutcome::Failed { error } => {
+                let source = archive
+                    .sources
+                    .first()
+                    .map(|source| source.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+
+                vec![format!("{source}: {error}")]
+            }
+            NestedArchiveOutcome::DepthLimit | NestedArchiveOutcome::SizeLimit { .. } => Vec::new(),
+        })
+        .collect()
+}
+
 fn bulk_download_output(
     report: &BulkWorkDownloadReport,
     skipped_queued_count: usize,
@@ -3397,6 +5014,46 @@ fn bulk_download_output(
     output
 }
 
+fn library_verify_output(report: &LibraryVerifyReport) -> JobMetadata {
+    let mut output = JobMetadata::new();
+
+    output.insert("verifiedCount".to_owned(), json!(report.verified_count));
+    output.insert("corruptedCount".to_owned(), json!(report.corrupted_count));
+    output.insert("restoredCount".to_owned(), json!(report.restored_count));
+    output.insert(
+        "issues".to_owned(),
+        json!(report
+            .issues
+            .iter()
+            .map(|issue| json!({
+                "workId": issue.work_id,
+                "localPath": issue.local_path.to_string_lossy(),
+                "corrupted": issue.is_corrupted(),
+                "missing": issue.missing,
+                "changed": issue.changed,
+                "extra": issue.extra,
+                "missingArchiveVault": issue
+                    .missing_archive_vault
+                    .as_ref()
+                    .map(|path| path.to_string_lossy()),
+            }))
+            .collect::<Vec<_>>()),
+    );
+    output
+}
+
+fn local_archive_import_output(report: &LocalArchiveImportReport) -> JobMetadata {
+    let mut output = JobMetadata::new();
+
+    output.insert("importedCount".to_owned(), json!(report.imported_count));
+    output.insert("failedCount".to_owned(), json!(report.failed_count));
+    output.insert(
+        "metadataUpdatedCount".to_owned(),
+        json!(report.metadata_updated_count),
+    );
+    output
+}
+
 fn bulk_download_preview_output(preview: &BulkWorkDownloadPreviewDto) -> JobMetadata {
     let mut output = JobMetadata::new();
 
@@ -3424,6 +5081,10 @@ fn bulk_download_preview_output(preview: &BulkWorkDownloadPreviewDto) -> JobMeta
         "unknownSizeCount".to_owned(),
         json!(preview.unknown_size_count),
     );
+    output.insert(
+        "diskSpaceShortfallBytes".to_owned(),
+        json!(preview.disk_space_shortfall_bytes),
+    );
 
     output
 }
@@ -3536,6 +5197,10 @@ fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     let storage = tauri::async_runtime::block_on(async {
         let storage = Storage::open(&database_path).await?;
         storage.run_migrations().await?;
+        let settings = storage.app_settings().await?;
+        dm_download::BandwidthLimiter::global()
+            .set_policy(bandwidth_policy(&settings))
+            .await;
         dm_storage::Result::Ok(storage)
     })?;
     let credential_vault_path = app_data_dir.join("credentials").join("vault.json");
@@ -3569,6 +5234,8 @@ fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     app.manage(AppState {
         storage,
         library,
+        // One governor for every job so concurrent syncs and downloads share the host budget.
+        api_config: dm_api::DlsiteClientConfig::default(),
         jobs,
         audit,
         download_reservations,
@@ -3582,6 +5249,104 @@ fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parses_and_formats_schedule_times() {
+        assert_eq!(parse_minute_of_day("01:00"), Ok(60));
+        assert_eq!(parse_minute_of_day(" 23:59 "), Ok(1439));
+        assert!(parse_minute_of_day("24:00").is_err());
+        assert!(parse_minute_of_day("7").is_err());
+        assert_eq!(format_minute_of_day(420), "07:00");
+    }
+
+    #[test]
+    fn validates_and_converts_retry_policy_settings() {
+        let setting = RetryPolicySetting {
+            max_retries: 5,
+            backoff: RetryBackoffSetting::Linear,
+            initial_delay_seconds: 10,
+            max_delay_seconds: 60,
+            jitter_percent: 0,
+            retry_network: true,
+            retry_stalled: false,
+            retry_incomplete: true,
+            retry_invalid_response: true,
+            deadline_seconds: Some(0),
+        };
+        let normalized = normalize_retry_policy(Some(setting)).unwrap();
+        let policy = download_retry_policy(normalized);
+
+        assert_eq!(normalized.and_then(|policy| policy.deadline_seconds), None);
+        assert_eq!(policy.backoff_delay(2), Duration::from_secs(20));
+        assert!(!policy.retryable.stalled);
+        assert!(normalize_retry_policy(Some(RetryPolicySetting {
+            initial_delay_seconds: 90,
+            ..setting
+        }))
+        .is_err());
+        assert_eq!(format_retry_delay(Duration::from_millis(29_200)), "30s");
+        assert_eq!(format_retry_delay(Duration::from_secs(125)), "2m 05s");
+    }
+
+    #[test]
+    fn validates_and_converts_nested_archive_settings() {
+        let setting = NestedArchiveSetting {
+            max_depth: 2,
+            max_unpacked_bytes: 1024,
+            keep_inner_archives: true,
+        };
+
+        assert_eq!(normalize_nested_archives(None), Ok(None));
+        assert_eq!(normalize_nested_archives(Some(setting)), Ok(Some(setting)));
+        assert!(normalize_nested_archives(Some(NestedArchiveSetting {
+            max_depth: 0,
+            ..setting
+        }))
+        .is_err());
+        assert!(normalize_nested_archives(Some(NestedArchiveSetting {
+            max_unpacked_bytes: 0,
+            ..setting
+        }))
+        .is_err());
+        assert_eq!(
+            nested_archive_options(Some(setting)),
+            Some(NestedArchiveOptions {
+                max_depth: 2,
+                max_unpacked_bytes: 1024,
+                keep_inner_archives: true,
+            })
+        );
+    }
+
+    #[test]
+    fn normalizes_post_download_hooks() {
+        let hook = PostDownloadHookSetting {
+            name: " ".to_owned(),
+            program: " loudnorm ".to_owned(),
+            args: vec!["{localPath}".to_owned()],
+            enabled: true,
+            timeout_seconds: Some(60),
+        };
+        let normalized = normalize_post_download_hooks(Some(vec![hook.clone()])).unwrap();
+
+        assert_eq!(normalized[0].name, "loudnorm");
+        assert_eq!(normalized[0].program, "loudnorm");
+        assert_eq!(normalized[0].timeout_seconds, Some(60));
+        assert!(
+            normalize_post_download_hooks(Some(vec![PostDownloadHookSetting {
+                program: String::new(),
+                ..hook.clone()
+            }]))
+            .is_err()
+        );
+        assert!(
+            normalize_post_download_hooks(Some(vec![PostDownloadHookSetting {
+                timeout_seconds: Some(0),
+                ..hook
+            }]))
+            .is_err()
+        );
+    }
+
     #[test]
     fn work_download_progress_throttle_limits_steady_download_updates() {
         let start = Instant::now();
@@ -3715,6 +5480,8 @@ pub fn run() {
             list_product_filter_facets,
             get_product_detail,
             set_product_custom_tags,
+            set_product_archive_name_encoding,
+            set_product_archive_password,
             start_account_sync,
             start_work_download,
             start_bulk_work_download,
@@ -3723,6 +5490,9 @@ pub fn run() {
             delete_work_download,
             mark_work_downloaded,
             scan_local_work_downloads,
+            preview_local_archive_import,
+            start_local_archive_import,
+            start_library_verify,
             list_jobs,
             get_job,
             cancel_job,
@@ -1,4 +1,10 @@
-import type { ProductCreditFieldDefinition, ProductTypeCodeDetail } from "./types";
+import type {
+  NestedArchiveMode,
+  NestedArchiveSetting,
+  ProductCreditFieldDefinition,
+  ProductTypeCodeDetail,
+  RetryPolicySetting,
+} from "./types";
 
 export const GITHUB_URL = "https://github.com/AcrylicShrimp/dlsite-manager";
 export const DLSITE_URL = "https://www.dlsite.com/";
@@ -28,6 +34,54 @@ export const SORT_OPTIONS = [
   ["titleAsc", "Title"],
 ] as const;
 
+export const DISPLAY_LANGUAGE_OPTIONS = [
+  ["ja_JP", "Japanese"],
+  ["en_US", "English"],
+  ["ko_KR", "Korean"],
+  ["zh_TW", "Traditional Chinese"],
+  ["zh_CN", "Simplified Chinese"],
+] as const;
+
+export const DOWNLOAD_CONNECTION_OPTIONS = [1, 2, 4, 8] as const;
+
+export const DOWNLOAD_PARALLEL_FILE_OPTIONS = [1, 2, 3, 4] as const;
+
+export const RETRY_BACKOFF_OPTIONS = [
+  ["constant", "Constant"],
+  ["linear", "Linear"],
+  ["exponential", "Exponential"],
+] as const;
+
+export const DEFAULT_RETRY_POLICY: RetryPolicySetting = {
+  maxRetries: 3,
+  backoff: "exponential",
+  initialDelaySeconds: 1,
+  maxDelaySeconds: 60,
+  jitterPercent: 20,
+  retryNetwork: true,
+  retryStalled: true,
+  retryIncomplete: true,
+  retryInvalidResponse: true,
+  deadlineSeconds: null,
+};
+
+export const DEFAULT_NESTED_ARCHIVES: NestedArchiveSetting = {
+  maxDepth: 2,
+  maxUnpackedBytes: 64 * 1024 * 1024 * 1024,
+  keepInnerArchives: false,
+};
+
+export const NESTED_ARCHIVE_OPTIONS = [
+  ["off", "Leave packed"],
+  ["unpack", "Unpack"],
+  ["keep", "Unpack and keep"],
+] as const satisfies readonly (readonly [NestedArchiveMode, string])[];
+
+export const MANIFEST_SIDECAR_OPTIONS = [
+  [false, "Database only"],
+  [true, "Also in folder"],
+] as const;
+
 export const creditFieldDefinitions = [
   { key: "maker", label: "Maker" },
   { key: "voice", label: "CV" },
@@ -1,6 +1,69 @@
 export type AppSettings = {
   libraryRoot: string | null;
   downloadRoot: string | null;
+  archiveVaultRoot: string | null;
+  displayLanguage: string | null;
+  downloadConnectionsPerFile: number | null;
+  downloadParallelFiles: number | null;
+  bandwidthLimitBytesPerSecond: number | null;
+  bandwidthSchedules: BandwidthSchedule[];
+  downloadStallTimeoutSeconds: number | null;
+  downloadMinSpeedBytesPerSecond: number | null;
+  downloadRetryPolicy: RetryPolicySetting | null;
+  manifestSidecar: boolean;
+  nestedArchives: NestedArchiveSetting | null;
+  postDownloadHooks: PostDownloadHook[];
+};
+
+export type NestedArchiveSetting = {
+  maxDepth: number;
+  maxUnpackedBytes: number;
+  keepInnerArchives: boolean;
+};
+
+export type NestedArchiveMode = "off" | "unpack" | "keep";
+
+export type RetryBackoff = "constant" | "linear" | "exponential";
+
+export type RetryPolicySetting = {
+  maxRetries: number;
+  backoff: RetryBackoff;
+  initialDelaySeconds: number;
+  maxDelaySeconds: number;
+  jitterPercent: number;
+  retryNetwork: boolean;
+  retryStalled: boolean;
+  retryIncomplete: boolean;
+  retryInvalidResponse: boolean;
+  deadlineSeconds: number | null;
+};
+
+export type BandwidthSchedule = {
+  start: string;
+  end: string;
+  bytesPerSecond: number | null;
+};
+
+export type BandwidthScheduleDraft = {
+  start: string;
+  end: string;
+  megabytesPerSecond: string;
+};
+
+export type PostDownloadHook = {
+  name: string;
+  program: string;
+  args: string[];
+  enabled: boolean;
+  timeoutSeconds: number | null;
+};
+
+export type PostDownloadHookDraft = {
+  name: string;
+  program: string;
+  args: string;
+  enabled: boolean;
+  timeoutSeconds: string;
 };
 
 export type AppInfo = {
@@ -15,6 +78,7 @@ export type Account = {
   label: string;
   loginName: string | null;
   hasCredential: boolean;
+  proxy: AccountProxy | null;
   enabled: boolean;
   createdAt: string;
   updatedAt: string;
@@ -22,6 +86,12 @@ export type Account = {
   lastSyncAt: string | null;
 };
 
+export type AccountProxy = {
+  url: string;
+  username: string | null;
+  hasPassword: boolean;
+};
+
 export type AccountRemovalReport = {
   accountId: string;
   label: string;
@@ -39,12 +109,14 @@ export type WorkDownloadStatus =
   | "downloading"
   | "downloaded"
   | "failed"
-  | "cancelled";
+  | "cancelled"
+  | "corrupted";
 
 export type ProductDownload = {
   status: WorkDownloadStatus;
   localPath: string | null;
   stagingPath: string | null;
+  archiveVaultPath: string | null;
   unpackPolicy: string | null;
   bytesReceived: number;
   bytesTotal: number | null;
@@ -53,6 +125,7 @@ export type ProductDownload = {
   startedAt: string | null;
   completedAt: string | null;
   updatedAt: string | null;
+  updateAvailable: boolean;
 };
 
 export type ProductCreditGroup = {
@@ -100,6 +173,8 @@ export type ProductListPage = {
 export type ProductFilterFacets = {
   makers: ProductMakerFacet[];
   customTags: ProductCustomTagFacet[];
+  genres: ProductGenreFacet[];
+  series: ProductSeriesFacet[];
 };
 
 export type ProductMakerFacet = {
@@ -112,6 +187,28 @@ export type ProductCustomTagFacet = {
   count: number;
 };
 
+export type ProductGenreFacet = {
+  id: string;
+  name: string;
+  count: number;
+};
+
+export type ProductSeriesFacet = {
+  id: string;
+  name: string;
+  count: number;
+};
+
+export type ProductGenre = {
+  id: string;
+  name: string;
+};
+
+export type ProductSeries = {
+  id: string;
+  name: string;
+};
+
 export type ProductDetail = {
   workId: string;
   title: string;
@@ -132,6 +229,14 @@ export type ProductDetail = {
   creditGroups: ProductCreditGroup[];
   tags: ProductTag[];
   customTags: ProductCustomTag[];
+  archiveNameEncoding: Exclude<ArchiveNameEncoding, "auto"> | null;
+  archivePasswordSaved: boolean;
+  description: string | null;
+  series: ProductSeries | null;
+  genres: ProductGenre[];
+  sampleImageUrls: string[];
+  fileFormats: string[];
+  trackCount: number | null;
   download: ProductDownload;
   owners: ProductOwner[];
 };
@@ -146,6 +251,15 @@ export type BulkWorkDownloadPreview = {
   knownExpectedBytes: number;
   totalExpectedBytes: number | null;
   unknownSizeCount: number;
+  diskSpaceShortfallBytes: number;
+  diskSpace: DiskSpaceCheck[];
+};
+
+export type DiskSpaceCheck = {
+  path: string;
+  requiredBytes: number;
+  availableBytes: number;
+  shortfallBytes: number;
 };
 
 export type LocalWorkImportReport = {
@@ -162,6 +276,42 @@ export type LocalWorkImportReport = {
   importedWorks: { workId: string; localPath: string }[];
 };
 
+export type LocalArchiveImportAction =
+  | "extract"
+  | "imported"
+  | "already_downloaded"
+  | "target_exists"
+  | "unrecognized"
+  | "failed";
+
+export type LocalArchiveImportReport = {
+  dryRun: boolean;
+  scannedFiles: number;
+  skippedNoId: number;
+  skippedAmbiguous: number;
+  skippedNonUtf8: number;
+  importedCount: number;
+  failedCount: number;
+  metadataUpdatedCount: number;
+  metadataError: string | null;
+  works: {
+    workId: string;
+    kind:
+      | "single_zip"
+      | "seven_zip"
+      | "legacy_split_rar"
+      | "multi_volume_rar"
+      | "split_zip"
+      | "unrecognized";
+    archives: string[];
+    targetPath: string;
+    unpackedBytes: number | null;
+    action: LocalArchiveImportAction;
+    errorMessage: string | null;
+    manifestError: string | null;
+  }[];
+};
+
 export type BulkDownloadDialog = {
   kind: "confirm" | "notice";
   preview: BulkWorkDownloadPreview;
@@ -209,6 +359,7 @@ export type JobSnapshot = {
   title: string;
   status: JobStatus;
   phase: string | null;
+  detail: string | null;
   progress: JobProgress | null;
   metadata: Record<string, unknown>;
   output: Record<string, unknown> | null;
@@ -280,9 +431,15 @@ export type ProductActionMenu = {
   top: number;
 };
 
+export type UnpackPolicy = "keepArchives" | "unpackWhenRecognized" | "unpackAndKeepArchives";
+
+export type ArchiveNameEncoding = "auto" | "utf8" | "cp932" | "cp437";
+
 export type StartWorkDownloadOptions = {
-  unpackPolicy?: "keepArchives" | "unpackWhenRecognized";
+  unpackPolicy?: UnpackPolicy;
   replaceExisting?: boolean;
+  resumeUnpack?: boolean;
+  previousVersion?: "discard" | "keep" | "archive";
   queuedMessage?: string;
 };
 
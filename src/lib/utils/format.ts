@@ -75,6 +75,8 @@ export function downloadStatusLabel(status: WorkDownloadStatus) {
       return "Failed";
     case "cancelled":
       return "Cancelled";
+    case "corrupted":
+      return "Corrupted";
   }
 }
 
@@ -83,6 +85,80 @@ export function valueOrNull(value: string) {
   return trimmed.length > 0 ? trimmed : null;
 }
 
+const BYTES_PER_MEGABYTE = 1024 * 1024;
+
+export function megabytesPerSecondToBytes(value: string) {
+  const megabytes = Number.parseFloat(value.trim());
+  return Number.isFinite(megabytes) && megabytes > 0
+    ? Math.round(megabytes * BYTES_PER_MEGABYTE)
+    : null;
+}
+
+export function bytesToMegabytesPerSecond(bytes: number | null) {
+  return bytes ? String(Math.round((bytes / BYTES_PER_MEGABYTE) * 100) / 100) : "";
+}
+
+const BYTES_PER_GIGABYTE = 1024 * 1024 * 1024;
+
+export function gigabytesToBytes(value: string) {
+  const gigabytes = Number.parseFloat(value.trim());
+  return Number.isFinite(gigabytes) && gigabytes > 0
+    ? Math.round(gigabytes * BYTES_PER_GIGABYTE)
+    : null;
+}
+
+export function bytesToGigabytes(bytes: number | null) {
+  return bytes ? String(Math.round((bytes / BYTES_PER_GIGABYTE) * 100) / 100) : "";
+}
+
+export function kilobytesPerSecondToBytes(value: string) {
+  const kilobytes = Number.parseFloat(value.trim());
+  return Number.isFinite(kilobytes) && kilobytes > 0 ? Math.round(kilobytes * 1024) : null;
+}
+
+export function bytesToKilobytesPerSecond(bytes: number | null) {
+  return bytes ? String(Math.round((bytes / 1024) * 100) / 100) : "";
+}
+
+/** Splits on whitespace; double quotes keep an argument with spaces together. */
+export function splitCommandArguments(value: string) {
+  const args: string[] = [];
+  let current = "";
+  let started = false;
+  let quoted = false;
+
+  for (const char of value) {
+    if (char === '"') {
+      quoted = !quoted;
+      started = true;
+    } else if (!quoted && /\s/.test(char)) {
+      if (started) {
+        args.push(current);
+        current = "";
+        started = false;
+      }
+    } else {
+      current += char;
+      started = true;
+    }
+  }
+
+  if (started) {
+    args.push(current);
+  }
+
+  return args;
+}
+
+export function joinCommandArguments(args: string[]) {
+  return args.map((arg) => (arg === "" || /[\s"]/.test(arg) ? `"${arg.replaceAll('"', "")}"` : arg)).join(" ");
+}
+
+export function integerOrNull(value: string) {
+  const number = Number.parseInt(value.trim(), 10);
+  return Number.isFinite(number) ? number : null;
+}
+
 export function shortDate(value: string | null) {
   if (!value) {
     return "";
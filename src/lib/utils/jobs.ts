@@ -87,6 +87,11 @@ export function jobLabel(job: JobSnapshot) {
         : "Downloaded";
     }
 
+    if (job.kind === "localArchiveImport") {
+      const importedCount = jobOutputNumber(job, "importedCount");
+      return typeof importedCount === "number" ? `Imported ${importedCount} works` : "Imported";
+    }
+
     if (job.kind === "bulkWorkDownloadPreview") {
       const plannedCount = jobOutputNumber(job, "plannedCount");
       const failedCount = jobOutputNumber(job, "failedCount");
@@ -131,10 +136,14 @@ export function jobLabel(job: JobSnapshot) {
       return job.kind === "workDownload" ? downloadJobProgressLabel(job) : "Resolving download";
     case "downloading":
       return downloadJobProgressLabel(job);
+    case "retrying":
+      return job.detail ?? "Retrying";
     case "unpacking":
-      return "Decompressing";
+      return job.detail ?? "Decompressing";
     case "finalizing":
       return "Finalizing";
+    case "postProcessing":
+      return job.detail ?? "Running hooks";
     default:
       if (job.kind === "bulkWorkDownload") {
         return "Downloading results";
@@ -172,11 +181,14 @@ export function activeWorkDownloadDetail(job: JobSnapshot) {
       return "Resolving download files";
     case "probingDownload":
     case "downloading":
+    case "retrying":
       return downloadProgressDetail(job);
     case "unpacking":
       return "Decompressing archive";
     case "finalizing":
       return "Moving files into the library";
+    case "postProcessing":
+      return "Running post-download hooks";
     case "completed":
       return "Completing";
     default: